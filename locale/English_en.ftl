@@ -99,6 +99,7 @@ about_check_schema_updates = Check Schema &Updates
 
 update_current_schema_from_asskit = Update currently loaded Schema with Assembly Kit
 generate_schema_diff = Generate Schema Diff
+infer_current_schema_enum_values = Infer Value Sets of currently loaded Schema
 
 ### app_ui_extra.rs localisation
 
@@ -267,7 +268,7 @@ tt_context_menu_new_queek_packed_file = Open the dialog to create a Packedfile b
 tt_context_menu_mass_import_tsv = Import a bunch of TSV files at the same time. It automatically checks if they are DB Tables, Locs or invalid TSVs, and imports them all at once. Existing files will be overwritten!
 tt_context_menu_mass_export_tsv = Export every DB Table and Loc PackedFile from this PackFile as TSV files at the same time. Existing files will be overwritten!
 tt_context_menu_merge_tables = Merge multple DB Tables/Loc PackedFiles into one.
-tt_context_menu_update_tables = Update a table to the last known working version of it for the Current game Selected.
+tt_context_menu_update_tables = Update a table to the last known working version of it for the Current game Selected. If the PackFile is selected, update all its outdated tables.
 tt_context_menu_delete = Delete the selected File/Folder.
 
 tt_context_menu_extract = Extract the selected File/Folder from the PackFile.
@@ -280,7 +281,6 @@ tt_context_menu_open_notes = Open the PackFile's Notes in a secondary view, with
 tt_filter_autoexpand_matches_button = Auto-Expand matches. NOTE: Filtering with all matches expanded in a big PackFile (+10k files, like data.pack) can hang the program for a while. You have been warned.
 tt_filter_case_sensitive_button = Enable/Disable case sensitive filtering for the TreeView.
 
-packedfile_noneditable_sequence = Non-editable Sequence
 
 ### Rename Dialogues
 
@@ -398,6 +398,9 @@ settings_optimize_not_renamed_packedfiles = Optimize Non-Renamed PackedFiles:
 settings_use_dependency_checker = Enable Dependency Checker for DB Tables:
 settings_use_lazy_loading = Use Lazy-Loading for PackFiles:
 settings_disable_uuid_regeneration_tables = Disable UUID Regeneration on DB Tables:
+settings_generate_loc_entries_on_db_edit = Generate Loc Entries on DB Table Edits:
+settings_schema_source_type = Schema Source Type:
+settings_schema_source_location = Schema Source Location:
 
 settings_debug_title = Debug Settings
 settings_debug_missing_table = Check for Missing Table Definitions
@@ -432,8 +435,14 @@ tt_extra_packfile_use_dependency_checker_tip = If you enable this, when opening
 tt_extra_packfile_use_lazy_loading_tip = If you enable this, PackFiles will load their data on-demand from the disk instead of loading the entire PackFile to Ram. This reduces Ram usage by a lot, but if something else changes/deletes the PackFile while it's open, the PackFile will likely be unrecoverable and you'll lose whatever is in it.
     If you mainly mod in Warhammer 2's /data folder LEAVE THIS DISABLED, as a bug in the Assembly Kit causes PackFiles to become broken/be deleted when you have this enabled.
 tt_extra_disable_uuid_regeneration_on_db_tables_label_tip = Check this if you plan to put your binary tables under Git/Svn/any kind of version control software.
-
-tt_debug_check_for_missing_table_definitions_tip = If you enable this, RPFM will try to decode EVERY TABLE in the current PackFile when opening it or when changing the Game Selected, and it'll output all the tables without an schema to a \"missing_table_definitions.txt\" file.
+tt_extra_generate_loc_entries_on_db_edit_tip = If you enable this, when saving a DB Table with localised fields RPFM will add placeholder entries for its new keys to a Loc PackedFile at 'text/db/', creating it if needed.
+    If you rename the key of a row, the keys of its Loc entries will be renamed too.
+tt_extra_network_schema_source_tip = Where RPFM gets the schemas from when checking for schema updates and updating them:
+    - 'url': a folder with the schemas served over HTTP(S), like a mirror or a fork of RPFM's repository. Leave the location empty to use RPFM's repository.
+    - 'folder': a local folder with the schemas and their 'versions.ron' file.
+    - 'git': a local git checkout of RPFM's repository (or a fork of it). RPFM will run 'git pull' on it before reading its 'schemas' folder.
+
+tt_debug_check_for_missing_table_definitions_tip = If you enable this, RPFM will try to decode EVERY TABLE in the current PackFile when opening it or when changing the Game Selected, and it'll output all the tables without an schema, the ones that fail to decode, and the ones with bytes left after decoding to a \"table_decoding_report.json\" file.
     DEBUG FEATURE, VERY SLOW. DON'T ENABLE IT UNLESS YOU REALLY WANT TO USE IT.
 
 ### CA_VP8 Videos
@@ -491,7 +500,11 @@ game_selected_unsupported_operation = This operation is not supported for the Ga
 
 optimize_packfile_success = PackFile optimized.
 update_current_schema_from_asskit_success = Currently loaded schema updated.
+update_current_schema_from_asskit_success_unmatched = Currently loaded schema updated. The fields of {"{"}{"}"} tables couldn't be matched with the Assembly Kit, so they may need a review:
+update_current_schema_from_asskit_unmatched_schema_fields = Only in the schema
+update_current_schema_from_asskit_unmatched_raw_fields = Only in the Assembly Kit
 generate_schema_diff_success = Diff generated succesfully.
+infer_current_schema_enum_values_success = Currently loaded schema updated. {"{"}{"}"} fields have been inferred as fields with a fixed set of values:
 settings_font_title = Font Settings
 
 title_success = Success!
@@ -505,14 +518,23 @@ rename_instructions = It's easy, but you'll not understand it without an example
     And, in case you ask, works with numeric cells too, as long as the resulting text is a valid number.
 
 update_table_success = Table updated from version '{"{"}{"}"}' to version '{"{"}{"}"}'.
+update_all_tables_success = {"{"}{"}"} tables have been updated. {"{"}{"}"} of them may need a review:
+update_all_tables_up_to_date = All the tables are already up to date.
 no_errors_detected = No errors detected.
 original_data = Original Data: '{"{"}{"}"}'
 column_tooltip_1 = This column is a reference to:
 column_tooltip_2 = And many more. Exactly, {"{"}{"}"} more. Too many to show them here.
 column_tooltip_3 = Fields that reference this column:
+column_tooltip_default_value = Default value:
+column_tooltip_filename = This column contains paths of files in:
+column_tooltip_filename_unknown = This column contains paths of files.
+column_tooltip_enum_values = This column only takes these values:
 
 tsv_select_title = Select TSV File to Import...
 tsv_export_title = Export TSV File...
+import_tsv_extra_columns = The TSV file has been imported, but the following columns are not in this table's definition, and have been ignored: {"{"}{"}"}.
+mass_import_tsv_extra_columns = The files have been imported, but the following columns are not in their table's definition, and have been ignored: {"{"}{"}"}
+loc_placeholder_text = PLACEHOLDER
 
 rewrite_selection_title = Rewrite Selection
 rewrite_selection_instructions_title = Instructions
@@ -524,6 +546,36 @@ rewrite_selection_is_math = Is a math operation?
 rewrite_selection_placeholder = Write here whatever you want.
 rewrite_selection_accept = Accept
 
+apply_expression_title = Apply Expression
+apply_expression_instructions_title = Instructions
+apply_expression_instructions = Sets the chosen column, in every row matching the filter, to the result of the expression:
+     - Columns are used by their name, like: cost * 1.1
+     - Text goes between double quotes, like: "prefix_" + key
+     - Operators: + - * / % == != < <= > >= && || !
+     - Functions: if, min, max, abs, round, floor, ceil, upper, lower, trim, len, concat, replace, contains, starts_with, ends_with.
+    If the filter is empty, the expression is applied to all rows.
+apply_expression_column = Column:
+apply_expression_expression = Expression:
+apply_expression_expression_placeholder = cost * 1.1
+apply_expression_filter = Filter:
+apply_expression_filter_placeholder = category == "cavalry"
+apply_expression_accept = Accept
+
+rename_key_cascade_title = Rename Key Everywhere
+rename_key_cascade_instructions_title = Instructions
+rename_key_cascade_instructions = Renames the selected key in every table of this type, in every column of every table referencing it,
+    and in the Loc entries of the renamed rows. All the changes can be undone at once with "Undo Key Rename".
+rename_key_cascade_new_key = New key:
+rename_key_cascade_rename_on_texts = Also rename it in text files, like scripts.
+rename_key_cascade_accept = Accept
+rename_key_cascade_nothing_renamed = The key '{"{"}{"}"}' has not been found, so nothing has been renamed.
+find_references_none = No rows reference the key '{"{"}{"}"}'.
+find_references_found = Rows referencing the key '{"{"}{"}"}':
+find_references_entry = {"{"}{"}"}, column '{"{"}{"}"}', row {"{"}{"}"}.
+find_references_entry_game_files = {"{"}{"}"}, column '{"{"}{"}"}', row {"{"}{"}"} (game files).
+key_rename_nothing_to_undo = There is no key rename to undo.
+key_rename_nothing_to_redo = There is no key rename to redo.
+
 context_menu_apply_submenu = A&pply...
 context_menu_clone_submenu = &Clone...
 context_menu_copy_submenu = &Copy...
@@ -531,6 +583,9 @@ context_menu_add_rows = &Add Row
 context_menu_insert_rows = &Insert Row
 context_menu_delete_rows = &Delete Row
 context_menu_rewrite_selection = &Rewrite Selection
+context_menu_apply_expression = Apply &Expression
+context_menu_rename_key_cascade = Rename &Key Everywhere
+context_menu_find_references = &Find References
 context_menu_clone_and_insert = &Clone and Insert
 context_menu_clone_and_append = Clone and &Append
 context_menu_copy = &Copy
@@ -545,6 +600,8 @@ context_menu_reset_selection = Reset &Selection
 context_menu_resize_columns = Resize Columns
 context_menu_undo = &Undo
 context_menu_redo = &Redo
+context_menu_undo_key_rename = Undo Key Rename
+context_menu_redo_key_rename = Redo Key Rename
 
 header_column = <b><i>Column Name</i></b>
 header_hidden = <b><i>Hidden</i></b>
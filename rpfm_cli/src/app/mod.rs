@@ -149,7 +149,7 @@ pub fn initialize_app<'a, 'b>() -> App<'a, 'b> {
                 .short("i")
                 .long("import")
                 .value_name("TSV FILE - DESTINATION FILE")
-                .help("Import a compatible TSV file as a DB/LOC table.")
+                .help("Import a compatible TSV file as a DB/LOC table. CSV, JSON and RON files are also supported, depending on their extension.")
                 .takes_value(true)
                 .min_values(1)
                 .max_values(2))
@@ -159,10 +159,106 @@ pub fn initialize_app<'a, 'b>() -> App<'a, 'b> {
                 .short("e")
                 .long("export")
                 .value_name("DB FILE - DESTINATION FILE")
-                .help("Export a DB/LOC Table's data to a TSV file.")
+                .help("Export a DB/LOC Table's data to a TSV file, or to the format chosen with '--format'.")
                 .takes_value(true)
                 .min_values(1)
-                .max_values(2)))
+                .max_values(2))
+
+            // `Format` option. To choose the format used when exporting DB/Loc `PackedFiles`.
+            .arg(Arg::with_name("format")
+                .long("format")
+                .value_name("FORMAT")
+                .help("Format to export the tables to. If not provided, they're exported to TSV.")
+                .possible_values(&["tsv", "csv", "json", "ron"])
+                .requires("export")
+                .takes_value(true))
+
+            // `Rename Key` option. To rename a key in a DB Table and everything in the PackFile referencing it.
+            .arg(Arg::with_name("rename-key")
+                .short("r")
+                .long("rename-key")
+                .value_name("TABLE NAME - COLUMN - OLD KEY - NEW KEY")
+                .help("Rename a key of a DB Table (like 'land_units_tables'), updating every table column and Loc entry of the PackFile that references it.")
+                .takes_value(true)
+                .min_values(4)
+                .max_values(4))
+
+            // `Clone Key` option. To copy a row of a DB Table, and all the rows referencing it, with a new key.
+            .arg(Arg::with_name("clone-key")
+                .short("c")
+                .long("clone-key")
+                .value_name("TABLE NAME - KEY - NEW KEY")
+                .help("Copy a row of a DB Table (like 'main_units_tables') into the PackFile with a new key, along with every row and Loc entry referencing it, from the PackFile or the game files.")
+                .takes_value(true)
+                .min_values(3)
+                .max_values(3))
+
+            // `Find References` option. To list everything referencing a row of a DB Table.
+            .arg(Arg::with_name("find-references")
+                .short("f")
+                .long("find-references")
+                .value_name("TABLE NAME - KEY")
+                .help("List every row of every DB Table, in the PackFile and in the game files, referencing the provided key of a DB Table (like 'main_units_tables').")
+                .takes_value(true)
+                .min_values(2)
+                .max_values(2))
+
+            // `Apply Patches` option. To generate the DB Tables described in a table patch file.
+            .arg(Arg::with_name("apply-patches")
+                .short("a")
+                .long("apply-patches")
+                .value_name("PATCH FILE")
+                .help("Apply a table patch file (.ron) against the game files, adding the DB Tables it generates to the PackFile. Re-apply it after a game update to regenerate them.")
+                .takes_value(true))
+
+            // `Apply Expression` option. To bulk-edit a column of a DB Table with an expression.
+            .arg(Arg::with_name("apply-expression")
+                .short("x")
+                .long("apply-expression")
+                .value_name("TABLE NAME - COLUMN - EXPRESSION - FILTER")
+                .help("Set a column of every DB Table of a type (like 'land_units_tables') in the PackFile to the result of an expression, like 'cost * 1.1'. If a filter expression is provided, like 'category == \"cavalry\"', only the rows matching it are edited.")
+                .takes_value(true)
+                .min_values(3)
+                .max_values(4))
+
+            // `Update Tables` flag. To update all the outdated DB Tables of a PackFile to the versions in the game files.
+            .arg(Arg::with_name("update-tables")
+                .short("u")
+                .long("update-tables")
+                .help("Update every outdated DB Table of the PackFile to the version of it in the game files, printing what changed in each table and which tables may need a review.")
+                .takes_value(false))
+
+            // `Rename on Texts` flag. To also rename the key on text files when using `Rename Key`.
+            .arg(Arg::with_name("rename-on-texts")
+                .short("t")
+                .long("rename-on-texts")
+                .help("When renaming a key, rename it also on Text files, like scripts.")
+                .requires("rename-key")
+                .takes_value(false)))
+
+        // `Translation` Subcommand. To move the Loc entries of a PackFile to/from standard translation formats (PO/XLIFF).
+        .subcommand(SubCommand::with_name("translation")
+            .about("Allows you to export the Loc entries of a PackFile for translation, and to import them back once translated.")
+
+            // `Export` option. To export the Loc entries of a PackFile to a PO/XLIFF file.
+            .arg(Arg::with_name("export")
+                .short("e")
+                .long("export")
+                .value_name("LANGUAGE - DESTINATION FILE")
+                .help("Export all the Loc entries of the PackFile to a PO (.po) or XLIFF (.xlf) file, to be translated to the provided language (en, ge, sp,...).")
+                .takes_value(true)
+                .min_values(2)
+                .max_values(2))
+
+            // `Import` option. To import a translated PO/XLIFF file as a per-language Loc file.
+            .arg(Arg::with_name("import")
+                .short("i")
+                .long("import")
+                .value_name("TRANSLATION FILE")
+                .help("Import a translated PO (.po) or XLIFF (.xlf) file into the PackFile, as a Loc file under 'text/translations/'. The game loads it for every language, so import each language into its own copy of the PackFile.")
+                .takes_value(true)
+                .min_values(1)
+                .max_values(1)))
 
         // `Schema` Subcommand. Basically, here goes commands destined to keep schemas up-to-date.
         .subcommand(SubCommand::with_name("schema")
@@ -170,6 +266,84 @@ pub fn initialize_app<'a, 'b>() -> App<'a, 'b> {
             .arg(Arg::with_name("update")
                 .short("u")
                 .long("update")
+                .takes_value(false))
+
+            // `Test Decoding` option. To check which tables of the game files the current schema cannot decode.
+            .arg(Arg::with_name("test-decoding")
+                .short("t")
+                .long("test-decoding")
+                .value_name("REPORT FILE")
+                .help("Try to decode every DB and Loc table of the game files with the current schema, saving a JSON report of the tables without definition, the tables that fail to decode, and the tables that leave bytes undecoded.")
+                .takes_value(true))
+
+            // `Diff` option. To print the differences between two schema files.
+            .arg(Arg::with_name("diff")
+                .short("d")
+                .long("diff")
+                .value_name("OLD SCHEMA FILE - NEW SCHEMA FILE")
+                .help("Print the differences between the DB Tables of two schema files (.ron), like new tables, new versions and corrected fields, as MarkDown.")
+                .takes_value(true)
+                .min_values(2)
+                .max_values(2))
+
+            // `JSON` flag. To print the schema diff as JSON instead of MarkDown.
+            .arg(Arg::with_name("json")
+                .long("json")
+                .help("Print the schema diff as JSON instead of MarkDown.")
+                .requires("diff")
+                .takes_value(false))
+
+            // `Lint` option. To check a schema file for common errors.
+            .arg(Arg::with_name("lint")
+                .short("l")
+                .long("lint")
+                .value_name("SCHEMA FILE")
+                .help("Check a schema file (.ron) for common errors, like duplicated fields, references to tables or columns that don't exist, or invalid default values.")
+                .takes_value(true))
+
+            // `Export Type Definitions` option. To generate type definitions of the tables exported to JSON, for other tools.
+            .arg(Arg::with_name("export-type-definitions")
+                .short("e")
+                .long("export-type-definitions")
+                .value_name("FORMAT - SCHEMA FILE - DESTINATION FOLDER")
+                .help("Generate type definitions for every version of every table of a schema file (.ron), to validate or use the tables exported to JSON in other tools. The format can be 'json-schema', 'typescript' or 'python'.")
+                .takes_value(true)
+                .min_values(3)
+                .max_values(3))
+
+            // `Merge` option. To merge two schema files into a new one.
+            .arg(Arg::with_name("merge")
+                .short("m")
+                .long("merge")
+                .value_name("BASE SCHEMA FILE - OTHER SCHEMA FILE - DESTINATION SCHEMA FILE")
+                .help("Merge the definitions of two schema files (.ron) into a new schema file, printing the versions defined differently in both of them.")
+                .takes_value(true)
+                .min_values(3)
+                .max_values(3))
+
+            // `Strategy` option. To choose what to do with the conflicts when merging schemas.
+            .arg(Arg::with_name("strategy")
+                .long("strategy")
+                .value_name("STRATEGY")
+                .help("What to do when both schemas have different definitions for the same version of a table: keep the base one (ours), keep the other one (theirs), or don't merge (abort).")
+                .possible_values(&["ours", "theirs", "abort"])
+                .default_value("abort")
+                .takes_value(true))
+
+            // `Migrate` option. To migrate a schema file from an old structural version to the current one.
+            .arg(Arg::with_name("migrate")
+                .long("migrate")
+                .value_name("SCHEMA FILE - DESTINATION SCHEMA FILE")
+                .help("Migrate a schema file of any old structural version (.json or .ron) to the current one, printing the changes done. Both paths can be the same file.")
+                .takes_value(true)
+                .min_values(2)
+                .max_values(2))
+
+            // `Dry Run` flag. To see what a migration would change, without saving anything.
+            .arg(Arg::with_name("dry-run")
+                .long("dry-run")
+                .help("Print the changes the migration would do, and the differences with the schema already in the destination, without saving anything.")
+                .requires("migrate")
                 .takes_value(false)))
 
 }
@@ -14,12 +14,16 @@
 use clap::ArgMatches;
 
 use rpfm_error::{ErrorKind, Result};
+use rpfm_lib::packedfile::table::TableFormat;
+use rpfm_lib::schema::merge::MergeStrategy;
+use rpfm_lib::schema::type_definitions::TypeDefinitionFormat;
 
 use crate::config::Config;
 
 mod table;
 mod packfile;
 mod schema;
+mod translation;
 
 //---------------------------------------------------------------------------//
 // 								Command Variants
@@ -105,7 +109,7 @@ pub fn command_packfile(config: &Config, matches: &ArgMatches, packfile: Option<
 }
 
 /// This function triggers functions that require the `Table` command.
-pub fn command_table(config: &Config, matches: &ArgMatches, _packfile: Option<&str>) -> Result<()> {
+pub fn command_table(config: &Config, matches: &ArgMatches, packfile: Option<&str>) -> Result<()> {
     if matches.is_present("import") {
 		match matches.values_of("import") {
 			Some(values) => {
@@ -120,20 +124,190 @@ pub fn command_table(config: &Config, matches: &ArgMatches, _packfile: Option<&s
 		match matches.values_of("export") {
 			Some(values) => {
                 let packed_file_paths = values.map(|y| y).collect::<Vec<&str>>();
-                table::export_tsv(&config, &packed_file_paths)
+                let format = TableFormat::from_extension(matches.value_of("format").unwrap_or("tsv"))?;
+                table::export_tsv(&config, &packed_file_paths, format)
             },
 			None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
 		}
     }
 
+    else if matches.is_present("rename-key") {
+        match packfile {
+            Some(packfile_path) => match matches.values_of("rename-key") {
+                Some(mut values) => {
+                    let table_name = values.next().unwrap();
+                    let column_name = values.next().unwrap();
+                    let old_key = values.next().unwrap();
+                    let new_key = values.next().unwrap();
+                    let rename_on_texts = matches.is_present("rename-on-texts");
+                    table::rename_key(config, packfile_path, table_name, column_name, old_key, new_key, rename_on_texts)
+                },
+                None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+            },
+            None => Err(ErrorKind::NoHTMLError("No PackFile provided.".to_owned()).into()),
+        }
+    }
+
+    else if matches.is_present("clone-key") {
+        match packfile {
+            Some(packfile_path) => match matches.values_of("clone-key") {
+                Some(mut values) => {
+                    let table_name = values.next().unwrap();
+                    let old_key = values.next().unwrap();
+                    let new_key = values.next().unwrap();
+                    table::clone_key(config, packfile_path, table_name, old_key, new_key)
+                },
+                None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+            },
+            None => Err(ErrorKind::NoHTMLError("No PackFile provided.".to_owned()).into()),
+        }
+    }
+
+    else if matches.is_present("apply-patches") {
+        match packfile {
+            Some(packfile_path) => match matches.value_of("apply-patches") {
+                Some(patches_path) => table::apply_patches(config, packfile_path, patches_path),
+                None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+            },
+            None => Err(ErrorKind::NoHTMLError("No PackFile provided.".to_owned()).into()),
+        }
+    }
+
+    else if matches.is_present("apply-expression") {
+        match packfile {
+            Some(packfile_path) => match matches.values_of("apply-expression") {
+                Some(mut values) => {
+                    let table_name = values.next().unwrap();
+                    let column_name = values.next().unwrap();
+                    let expression = values.next().unwrap();
+                    let filter = values.next();
+                    table::apply_expression(config, packfile_path, table_name, column_name, expression, filter)
+                },
+                None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+            },
+            None => Err(ErrorKind::NoHTMLError("No PackFile provided.".to_owned()).into()),
+        }
+    }
+
+    else if matches.is_present("update-tables") {
+        match packfile {
+            Some(packfile_path) => table::update_tables(config, packfile_path),
+            None => Err(ErrorKind::NoHTMLError("No PackFile provided.".to_owned()).into()),
+        }
+    }
+
+    else if matches.is_present("find-references") {
+        match packfile {
+            Some(packfile_path) => match matches.values_of("find-references") {
+                Some(mut values) => {
+                    let table_name = values.next().unwrap();
+                    let key = values.next().unwrap();
+                    table::find_references(config, packfile_path, table_name, key)
+                },
+                None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+            },
+            None => Err(ErrorKind::NoHTMLError("No PackFile provided.".to_owned()).into()),
+        }
+    }
+
 	else { Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into()) }
 }
 
+/// This function triggers functions that require the `Translation` command.
+pub fn command_translation(config: &Config, matches: &ArgMatches, packfile: Option<&str>) -> Result<()> {
+    match packfile {
+        Some(packfile_path) => {
+            if matches.is_present("export") {
+                match matches.values_of("export") {
+                    Some(mut values) => {
+                        let language = values.next().unwrap();
+                        let destination_path = values.next().unwrap();
+                        translation::export(config, packfile_path, language, destination_path)
+                    },
+                    None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+                }
+            }
+
+            else if matches.is_present("import") {
+                match matches.value_of("import") {
+                    Some(source_path) => translation::import(config, packfile_path, source_path),
+                    None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+                }
+            }
+
+            else { Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into()) }
+        },
+        None => Err(ErrorKind::NoHTMLError("No PackFile provided.".to_owned()).into()),
+    }
+}
+
 /// This function triggers functions that require the `Schema` command.
 pub fn command_schema(config: &Config, matches: &ArgMatches) -> Result<()> {
     if matches.is_present("update") {
 		schema::update(config)
     }
 
+    else if matches.is_present("diff") {
+        match matches.values_of("diff") {
+            Some(mut values) => {
+                let old_schema_path = values.next().unwrap();
+                let new_schema_path = values.next().unwrap();
+                schema::diff(config, old_schema_path, new_schema_path, matches.is_present("json"))
+            },
+            None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+        }
+    }
+
+    else if matches.is_present("lint") {
+        match matches.value_of("lint") {
+            Some(schema_path) => schema::lint(config, schema_path),
+            None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+        }
+    }
+
+    else if matches.is_present("export-type-definitions") {
+        match matches.values_of("export-type-definitions") {
+            Some(mut values) => match TypeDefinitionFormat::from_name(values.next().unwrap()) {
+                Some(format) => {
+                    let schema_path = values.next().unwrap();
+                    let destination_path = values.next().unwrap();
+                    schema::export_type_definitions(config, format, schema_path, destination_path)
+                },
+                None => Err(ErrorKind::NoHTMLError("Invalid format provided. Valid formats are 'json-schema', 'typescript' and 'python'.".to_owned()).into())
+            },
+            None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+        }
+    }
+
+    else if matches.is_present("merge") {
+        match (matches.values_of("merge"), matches.value_of("strategy").and_then(MergeStrategy::from_name)) {
+            (Some(mut values), Some(strategy)) => {
+                let base_schema_path = values.next().unwrap();
+                let other_schema_path = values.next().unwrap();
+                let destination_path = values.next().unwrap();
+                schema::merge(config, base_schema_path, other_schema_path, destination_path, strategy)
+            },
+            _ => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+        }
+    }
+
+    else if matches.is_present("migrate") {
+        match matches.values_of("migrate") {
+            Some(mut values) => {
+                let schema_path = values.next().unwrap();
+                let destination_path = values.next().unwrap();
+                schema::migrate(config, schema_path, destination_path, matches.is_present("dry-run"))
+            },
+            None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+        }
+    }
+
+    else if matches.is_present("test-decoding") {
+        match matches.value_of("test-decoding") {
+            Some(report_path) => schema::test_decoding(config, report_path),
+            None => Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into())
+        }
+    }
+
 	else { Err(ErrorKind::NoHTMLError("No valid argument provided.".to_owned()).into()) }
 }
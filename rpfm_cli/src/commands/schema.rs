@@ -8,10 +8,17 @@
 // https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
 //---------------------------------------------------------------------------//
 
-use log::info;
+use log::{info, warn};
 
-use rpfm_error::Result;
+use std::path::PathBuf;
+
+use rpfm_error::{ErrorKind, Result};
+use rpfm_lib::schema::Schema;
+use rpfm_lib::schema::decoding_report::DecodingReport;
+use rpfm_lib::schema::merge::{MergeConflict, MergeStrategy};
+use rpfm_lib::schema::type_definitions::TypeDefinitionFormat;
 use rpfm_lib::schema::versions::VersionsFile;
+use rpfm_lib::{GAME_SELECTED, SUPPORTED_GAMES};
 
 use crate::config::Config;
 
@@ -30,3 +37,133 @@ pub fn update(config: &Config) -> Result<()> {
     }
     result
 }
+
+/// This function tries to decode every table of the game files with the current schema, saving a report of the ones that failed.
+pub fn test_decoding(config: &Config, report_path: &str) -> Result<()> {
+    match &config.game_selected {
+        Some(game_selected) => {
+            if config.verbosity_level > 0 {
+                info!("Decoding all the tables of the game files of {}.", game_selected);
+            }
+
+            *GAME_SELECTED.write().unwrap() = game_selected.to_owned();
+            let schema = Schema::load(&SUPPORTED_GAMES[&**game_selected].schema)?;
+            let report = DecodingReport::new_from_game_selected(&schema)?;
+            report.save_json(&PathBuf::from(report_path))?;
+
+            info!("{} of {} tables decoded without problems.", report.decoded_tables, report.total_tables);
+            if !report.issues.is_empty() {
+                warn!("{} tables without definition, {} tables that failed to decode, {} tables with bytes left. Full report saved to: {}",
+                    report.get_issues_by_kind(true, false, false).len(),
+                    report.get_issues_by_kind(false, true, false).len(),
+                    report.get_issues_by_kind(false, false, true).len(),
+                    report_path
+                );
+            }
+            Ok(())
+        },
+        None => Err(ErrorKind::NoHTMLError("No Game Selected provided.".to_owned()).into()),
+    }
+}
+
+/// This function prints the differences between two schema files, as MarkDown or JSON.
+pub fn diff(config: &Config, old_schema_path: &str, new_schema_path: &str, as_json: bool) -> Result<()> {
+    if config.verbosity_level > 0 {
+        info!("Comparing schemas {} and {}.", old_schema_path, new_schema_path);
+    }
+
+    let old_schema = Schema::load_from_path(&PathBuf::from(old_schema_path))?;
+    let new_schema = Schema::load_from_path(&PathBuf::from(new_schema_path))?;
+    let diff = Schema::diff(&old_schema, &new_schema);
+
+    if as_json { println!("{}", diff.to_json()?); }
+    else if diff.is_empty() { info!("Both schemas are equal."); }
+    else { println!("{}", diff.to_markdown()); }
+    Ok(())
+}
+
+/// This function checks a schema file for common errors, printing the ones found.
+pub fn lint(config: &Config, schema_path: &str) -> Result<()> {
+    if config.verbosity_level > 0 {
+        info!("Checking schema {}.", schema_path);
+    }
+
+    let schema = Schema::load_from_path(&PathBuf::from(schema_path))?;
+    let issues = schema.lint();
+    issues.iter().for_each(|x| warn!("{}", x));
+
+    if issues.is_empty() { info!("No errors found."); }
+    else { warn!("{} errors found.", issues.len()); }
+    Ok(())
+}
+
+/// This function generates type definitions for every table of a schema file, in the provided format.
+pub fn export_type_definitions(config: &Config, format: TypeDefinitionFormat, schema_path: &str, destination_path: &str) -> Result<()> {
+    if config.verbosity_level > 0 {
+        info!("Generating type definitions of schema {}.", schema_path);
+    }
+
+    let schema = Schema::load_from_path(&PathBuf::from(schema_path))?;
+    schema.export_type_definitions(format, &PathBuf::from(destination_path))?;
+
+    if config.verbosity_level > 0 {
+        info!("Type definitions saved to: {}", destination_path);
+    }
+    Ok(())
+}
+
+/// This function merges two schema files into a new one, printing the conflicts found.
+pub fn merge(config: &Config, base_schema_path: &str, other_schema_path: &str, destination_path: &str, strategy: MergeStrategy) -> Result<()> {
+    if config.verbosity_level > 0 {
+        info!("Merging schema {} into {}.", other_schema_path, base_schema_path);
+    }
+
+    let mut schema = Schema::load_from_path(&PathBuf::from(base_schema_path))?;
+    let other_schema = Schema::load_from_path(&PathBuf::from(other_schema_path))?;
+    let conflicts = match schema.merge(&other_schema, strategy) {
+        Ok(conflicts) => conflicts,
+
+        // On abort, we still want to know what the conflicts were.
+        Err(error) => {
+            let mut schema = schema.clone();
+            if let Ok(conflicts) = schema.merge(&other_schema, MergeStrategy::Ours) {
+                print_merge_conflicts(&conflicts);
+            }
+            return Err(error);
+        }
+    };
+
+    print_merge_conflicts(&conflicts);
+    schema.save_to_path(&PathBuf::from(destination_path))?;
+
+    if config.verbosity_level > 0 {
+        info!("Schemas merged with {} conflicts. Merged schema saved to: {}", conflicts.len(), destination_path);
+    }
+    Ok(())
+}
+
+/// This function prints the provided merge conflicts as MarkDown.
+fn print_merge_conflicts(conflicts: &[MergeConflict]) {
+    for conflict in conflicts {
+        let mut changes = vec![];
+        conflict.diff.push_markdown(&format!("{} (version {})", conflict.versioned_file, conflict.version), &mut changes);
+        if changes.is_empty() { changes.push("  - Only their localised fields are different.".to_owned()); }
+        warn!("Conflicting definitions for {}, version {}:\n{}", conflict.versioned_file, conflict.version, changes.join("\n"));
+    }
+}
+
+/// This function migrates a schema file from any old structural version to the current one, printing the changes done.
+pub fn migrate(config: &Config, schema_path: &str, destination_path: &str, dry_run: bool) -> Result<()> {
+    if config.verbosity_level > 0 {
+        info!("Migrating schema {}.", schema_path);
+    }
+
+    let report = Schema::migrate_file(&PathBuf::from(schema_path), &PathBuf::from(destination_path), dry_run)?;
+    println!("{}", report.to_markdown());
+
+    if config.verbosity_level > 0 {
+        if dry_run { info!("Dry run finished. Nothing has been saved."); }
+        else { info!("Schema migrated from V{}. Migrated schema saved to: {}", report.source_version, destination_path); }
+    }
+    Ok(())
+}
@@ -8,12 +8,18 @@
 // https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
 //---------------------------------------------------------------------------//
 
-use log::info;
+use log::{info, warn};
+use prettytable::{Table, row, cell};
 use std::path::PathBuf;
 
 use rpfm_error::{ErrorKind, Result};
+use rpfm_lib::packedfile::table::TableFormat;
 use rpfm_lib::packedfile::table::db::DB;
+use rpfm_lib::packfile::PackFile;
+use rpfm_lib::packfile::table_update::TableUpdateReport;
 use rpfm_lib::schema::Schema;
+use rpfm_lib::table_patches::TablePatchSet;
+use rpfm_lib::{DEPENDENCY_DATABASE, GAME_SELECTED, SCHEMA};
 use rpfm_lib::SUPPORTED_GAMES;
 
 use crate::config::Config;
@@ -22,9 +28,9 @@ use crate::config::Config;
 // 							DB/Loc Command Variants
 //---------------------------------------------------------------------------//
 
-/// This function imports a TSV file into a binary DB/Loc file.
+/// This function imports a TSV/CSV/JSON/RON file into a binary DB/Loc file.
 ///
-/// If no destination path was provided, it leaves the DB/Loc File in the same place as the tsv file, with the same name.
+/// The format of each file is deduced from its extension. The DB/Loc File is left in the same place as the source file, with the same name.
 pub fn import_tsv(
     config: &Config,
     source_paths: &[&str],
@@ -38,33 +44,246 @@ pub fn import_tsv(
         Some(game_selected) => {
             let schema = Schema::load(&SUPPORTED_GAMES[&**game_selected].schema)?;
         	let source_paths = source_paths.iter().map(PathBuf::from).collect::<Vec<PathBuf>>();
-        	let result = DB::import_tsv_to_binary_file(&schema, &source_paths);
+        	let extra_columns = DB::import_to_binary_file(&schema, &source_paths)?;
+            for (path, columns) in &extra_columns {
+                warn!("Columns ignored in {}, as they're not in the table's definition: {}", path.to_string_lossy(), columns.join(", "));
+            }
             info!("All TSV files imported to binary.");
-            result
+            Ok(())
         },
         None => Err(ErrorKind::NoHTMLError("No Game Selected provided.".to_owned()).into()),
     }
 }
 
-/// This function imports a TSV file into a binary DB/Loc file.
+/// This function exports a binary DB/Loc file into a TSV/CSV/JSON/RON file.
 ///
-/// If no destination path was provided, it leaves the DB/Loc File in the same place as the tsv file, with the same name.
+/// The exported file is left in the same place as the DB/Loc file, with the same name and the extension of the format.
 pub fn export_tsv(
     config: &Config,
     source_paths: &[&str],
+    format: TableFormat,
 ) -> Result<()> {
 	if config.verbosity_level > 0 {
-		source_paths.iter().for_each(|x| info!("Export Binary File as TSV: {}", x));
+		source_paths.iter().for_each(|x| info!("Export Binary File as {}: {}", format.get_extension().to_uppercase(), x));
 	}
 
     match &config.game_selected {
         Some(game_selected) => {
             let schema = Schema::load(&SUPPORTED_GAMES[&**game_selected].schema)?;
             let source_paths = source_paths.iter().map(PathBuf::from).collect::<Vec<PathBuf>>();
-            let result = DB::export_tsv_from_binary_file(&schema, &source_paths);
-            info!("All binary files exported to TSV.");
+            let result = DB::export_from_binary_file(&schema, &source_paths, format);
+            info!("All binary files exported to {}.", format.get_extension().to_uppercase());
+            result
+        },
+        None => Err(ErrorKind::NoHTMLError("No Game Selected provided.".to_owned()).into()),
+    }
+}
+
+/// This function renames a key of a DB Table within a PackFile, updating everything in the PackFile that references it, then saves the PackFile.
+pub fn rename_key(
+    config: &Config,
+    packfile: &str,
+    table_name: &str,
+    column_name: &str,
+    old_key: &str,
+    new_key: &str,
+    rename_on_texts: bool,
+) -> Result<()> {
+    if config.verbosity_level > 0 {
+        info!("Renaming key {} to {} in column {} of {} in the PackFile: {}", old_key, new_key, column_name, table_name, packfile);
+    }
+
+    match &config.game_selected {
+        Some(game_selected) => {
+            *GAME_SELECTED.write().unwrap() = game_selected.to_owned();
+            *SCHEMA.write().unwrap() = Some(Schema::load(&SUPPORTED_GAMES[&**game_selected].schema)?);
+            let packfile_path = PathBuf::from(packfile);
+            let mut packfile = PackFile::open_packfiles(&[packfile_path], true, false, false)?;
+
+            let change_set = packfile.rename_key_cascade(table_name, column_name, old_key, new_key, rename_on_texts)?;
+            let result = packfile.save(None);
+
+            if config.verbosity_level > 0 {
+                change_set.get_paths().iter().for_each(|x| info!("PackedFile changed: {}", x.join("/")));
+                info!("Key renamed in {} PackedFiles.", change_set.get_paths().len());
+            }
+
+            result
+        },
+        None => Err(ErrorKind::NoHTMLError("No Game Selected provided.".to_owned()).into()),
+    }
+}
+
+/// This function copies a row of a DB Table, and all the rows referencing it, into a PackFile with a new key, then saves the PackFile.
+///
+/// The rows can come from the PackFile itself, or from the game files.
+pub fn clone_key(
+    config: &Config,
+    packfile: &str,
+    table_name: &str,
+    old_key: &str,
+    new_key: &str,
+) -> Result<()> {
+    if config.verbosity_level > 0 {
+        info!("Cloning key {} of {} as {} in the PackFile: {}", old_key, table_name, new_key, packfile);
+    }
+
+    match &config.game_selected {
+        Some(game_selected) => {
+            *GAME_SELECTED.write().unwrap() = game_selected.to_owned();
+            let schema = Schema::load(&SUPPORTED_GAMES[&**game_selected].schema)?;
+            let packfile_path = PathBuf::from(packfile);
+            let mut packfile = PackFile::open_packfiles(&[packfile_path], true, false, false)?;
+            let mut dependencies = PackFile::load_all_dependency_packfiles(packfile.get_packfiles_list());
+
+            let (added_paths, edited_paths) = packfile.clone_key_with_dependents(&schema, &mut dependencies, table_name, old_key, new_key)?;
+            let result = packfile.save(None);
+
+            if config.verbosity_level > 0 {
+                added_paths.iter().for_each(|x| info!("PackedFile added: {}", x.join("/")));
+                edited_paths.iter().for_each(|x| info!("PackedFile changed: {}", x.join("/")));
+            }
+
             result
         },
         None => Err(ErrorKind::NoHTMLError("No Game Selected provided.".to_owned()).into()),
     }
 }
+
+/// This function applies a table patch file against the game files, adding the generated DB Tables to a PackFile, then saves the PackFile.
+pub fn apply_patches(
+    config: &Config,
+    packfile: &str,
+    patches_path: &str,
+) -> Result<()> {
+    if config.verbosity_level > 0 {
+        info!("Applying table patches {} to the PackFile: {}", patches_path, packfile);
+    }
+
+    match &config.game_selected {
+        Some(game_selected) => {
+            *GAME_SELECTED.write().unwrap() = game_selected.to_owned();
+            let schema = Schema::load(&SUPPORTED_GAMES[&**game_selected].schema)?;
+            let patch_set = TablePatchSet::load(&PathBuf::from(patches_path))?;
+            let packfile_path = PathBuf::from(packfile);
+            let mut packfile = PackFile::open_packfiles(&[packfile_path], true, false, false)?;
+            *DEPENDENCY_DATABASE.lock().unwrap() = PackFile::load_all_dependency_packfiles(packfile.get_packfiles_list());
+
+            let (added_paths, removed_paths) = patch_set.apply_to_packfile(&mut packfile, &schema)?;
+            let result = packfile.save(None);
+
+            if config.verbosity_level > 0 {
+                added_paths.iter().for_each(|x| info!("PackedFile added: {}", x.join("/")));
+                removed_paths.iter().for_each(|x| info!("PackedFile removed: {}", x.join("/")));
+            }
+
+            result
+        },
+        None => Err(ErrorKind::NoHTMLError("No Game Selected provided.".to_owned()).into()),
+    }
+}
+
+/// This function sets a column of the DB Tables of a type in a PackFile to the result of an expression, then saves the PackFile.
+pub fn apply_expression(
+    config: &Config,
+    packfile: &str,
+    table_name: &str,
+    column_name: &str,
+    expression: &str,
+    filter: Option<&str>,
+) -> Result<()> {
+    if config.verbosity_level > 0 {
+        info!("Applying expression {} to the column {} of {} in the PackFile: {}", expression, column_name, table_name, packfile);
+    }
+
+    match &config.game_selected {
+        Some(game_selected) => {
+            *GAME_SELECTED.write().unwrap() = game_selected.to_owned();
+            *SCHEMA.write().unwrap() = Some(Schema::load(&SUPPORTED_GAMES[&**game_selected].schema)?);
+            let packfile_path = PathBuf::from(packfile);
+            let mut packfile = PackFile::open_packfiles(&[packfile_path], true, false, false)?;
+
+            let edited_paths = packfile.apply_expression_to_tables(table_name, column_name, expression, filter)?;
+            let result = packfile.save(None);
+
+            if config.verbosity_level > 0 {
+                edited_paths.iter().for_each(|x| info!("PackedFile changed: {}", x.join("/")));
+            }
+
+            result
+        },
+        None => Err(ErrorKind::NoHTMLError("No Game Selected provided.".to_owned()).into()),
+    }
+}
+
+/// This function updates all the outdated DB Tables of a PackFile to the versions in the game files, then saves the PackFile.
+///
+/// The report of the changes done to each table is always printed, as it's the only way to know what needs a review.
+pub fn update_tables(
+    config: &Config,
+    packfile: &str,
+) -> Result<()> {
+    if config.verbosity_level > 0 {
+        info!("Updating the tables of the PackFile: {}", packfile);
+    }
+
+    match &config.game_selected {
+        Some(game_selected) => {
+            *GAME_SELECTED.write().unwrap() = game_selected.to_owned();
+            *SCHEMA.write().unwrap() = Some(Schema::load(&SUPPORTED_GAMES[&**game_selected].schema)?);
+            let packfile_path = PathBuf::from(packfile);
+            let mut packfile = PackFile::open_packfiles(&[packfile_path], true, false, false)?;
+            *DEPENDENCY_DATABASE.lock().unwrap() = PackFile::load_all_dependency_packfiles(packfile.get_packfiles_list());
+
+            let reports = packfile.update_all_tables()?;
+            if reports.is_empty() {
+                info!("All the tables are up to date.");
+                return Ok(());
+            }
+
+            let result = packfile.save(None);
+            println!("{}", TableUpdateReport::to_markdown(&reports));
+
+            let reports_with_warnings = reports.iter().filter(|x| x.has_warnings()).count();
+            if reports_with_warnings > 0 {
+                warn!("{} of {} updated tables may need a review.", reports_with_warnings, reports.len());
+            }
+
+            result
+        },
+        None => Err(ErrorKind::NoHTMLError("No Game Selected provided.".to_owned()).into()),
+    }
+}
+
+/// This function prints all the rows of the DB Tables of a PackFile and the game files referencing the provided key of a DB Table.
+pub fn find_references(
+    config: &Config,
+    packfile: &str,
+    table_name: &str,
+    key: &str,
+) -> Result<()> {
+    if config.verbosity_level > 0 {
+        info!("Finding references to key {} of {} in the PackFile: {}", key, table_name, packfile);
+    }
+
+    match &config.game_selected {
+        Some(game_selected) => {
+            *GAME_SELECTED.write().unwrap() = game_selected.to_owned();
+            let schema = Schema::load(&SUPPORTED_GAMES[&**game_selected].schema)?;
+            let packfile_path = PathBuf::from(packfile);
+            let mut packfile = PackFile::open_packfiles(&[packfile_path], true, false, false)?;
+            let mut dependency_database = PackFile::load_all_dependency_packfiles(packfile.get_packfiles_list());
+
+            let references = DB::get_references_to_key(&mut packfile, &schema, table_name, key, &mut dependency_database)?;
+            let mut table = Table::new();
+            table.add_row(row!["PackedFile Path", "Column", "Row", "From Game Files"]);
+            for reference in &references {
+                table.add_row(row![reference.path.join("/"), reference.column_name, reference.row_number + 1, reference.is_from_game_files]);
+            }
+
+            table.printstd();
+            Ok(())
+        },
+        None => Err(ErrorKind::NoHTMLError("No Game Selected provided.".to_owned()).into()),
+    }
+}
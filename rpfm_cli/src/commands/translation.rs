@@ -0,0 +1,105 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+//! This module contains the `Translation` command's functions.
+
+use log::info;
+
+use std::path::PathBuf;
+
+use rpfm_error::{ErrorKind, Result};
+use rpfm_lib::GAME_SELECTED;
+use rpfm_lib::packfile::PackFile;
+use rpfm_lib::schema::Schema;
+use rpfm_lib::SUPPORTED_GAMES;
+use rpfm_lib::translations::Translation;
+
+use crate::config::Config;
+
+//---------------------------------------------------------------------------//
+// 							Translation Command Variants
+//---------------------------------------------------------------------------//
+
+/// This function exports all the Loc entries of a PackFile to a translation file, to be translated to the provided language.
+pub fn export(
+    config: &Config,
+    packfile: &str,
+    language: &str,
+    destination_path: &str,
+) -> Result<()> {
+    if config.verbosity_level > 0 {
+        info!("Exporting Loc entries of the PackFile {} for translation to: {}", packfile, language);
+    }
+
+    match &config.game_selected {
+        Some(game_selected) => {
+            *GAME_SELECTED.write().unwrap() = game_selected.to_owned();
+            if !Translation::get_supported_languages().iter().any(|x| x == language) {
+                return Err(ErrorKind::TranslationUnsupportedLanguage(language.to_owned()).into());
+            }
+
+            let schema = Schema::load(&SUPPORTED_GAMES[&**game_selected].schema)?;
+            let packfile_path = PathBuf::from(packfile);
+            let mut packfile = PackFile::open_packfiles(&[packfile_path], true, false, false)?;
+
+            let vanilla_texts = Translation::get_vanilla_texts(language, &schema);
+            let translation = Translation::new_from_packfile(&mut packfile, language, &schema, &vanilla_texts);
+            let result = translation.save(&PathBuf::from(destination_path));
+
+            if config.verbosity_level > 0 {
+                info!("{} Loc entries exported for translation.", translation.entries.len());
+            }
+
+            result
+        },
+        None => Err(ErrorKind::NoHTMLError("No Game Selected provided.".to_owned()).into()),
+    }
+}
+
+/// This function imports a translation file into a PackFile, as the Loc file of its language, then saves the PackFile.
+///
+/// The game loads that Loc file no matter the language it's running in, so the PackFile should only contain one translation.
+pub fn import(
+    config: &Config,
+    packfile: &str,
+    source_path: &str,
+) -> Result<()> {
+    if config.verbosity_level > 0 {
+        info!("Importing translation file {} into the PackFile: {}", source_path, packfile);
+    }
+
+    match &config.game_selected {
+        Some(game_selected) => {
+            *GAME_SELECTED.write().unwrap() = game_selected.to_owned();
+            let schema = Schema::load(&SUPPORTED_GAMES[&**game_selected].schema)?;
+            let translation = Translation::load(&PathBuf::from(source_path))?;
+            if translation.language.is_empty() {
+                return Err(ErrorKind::TranslationDecode("The translation file doesn't specify its language.".to_owned()).into());
+            }
+
+            // The language is used in the path of the new Loc file, so only accept the ones of the game.
+            if !Translation::get_supported_languages().iter().any(|x| x == &translation.language) {
+                return Err(ErrorKind::TranslationUnsupportedLanguage(translation.language.to_owned()).into());
+            }
+
+            let packfile_path = PathBuf::from(packfile);
+            let mut packfile = PackFile::open_packfiles(&[packfile_path], true, false, false)?;
+            let path = translation.import_to_packfile(&mut packfile, &schema)?;
+            let result = packfile.save(None);
+
+            if config.verbosity_level > 0 {
+                info!("Translation imported successfully as: {}", path.join("/"));
+            }
+
+            result
+        },
+        None => Err(ErrorKind::NoHTMLError("No Game Selected provided.".to_owned()).into()),
+    }
+}
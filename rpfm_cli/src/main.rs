@@ -67,6 +67,7 @@ fn main() {
     let result = match matches.subcommand() {
         ("packfile", Some(matches)) => commands::command_packfile(&config, matches, packfile),
         ("table", Some(matches)) => commands::command_table(&config, matches, packfile),
+        ("translation", Some(matches)) => commands::command_translation(&config, matches, packfile),
         ("schema", Some(matches)) => commands::command_schema(&config, matches),
         _ => { Ok(()) }
     };
@@ -95,8 +95,14 @@ pub enum ErrorKind {
     /// Error for when the second field of a TSV file is not a valid number.
     ImportTSVInvalidVersion,
 
-    /// Error for when the version of a TSV file is not the one we're trying to import to.
-    ImportTSVWrongVersion,
+    /// Error for when a TSV file has no row of column headers, or none of them match a column of the table.
+    ImportTSVMissingHeader,
+
+    /// Error for when a field of an imported JSON/RON file is not valid for its column. It contains the row and the name of the field.
+    ImportTableIncorrectField(usize, String),
+
+    /// Error for when we try to import/export a table from/to an unsupported format. It contains the format.
+    TableUnsupportedFormat(String),
 
     /// Generic TSV import/export error.
     TSVErrorGeneric,
@@ -243,6 +249,9 @@ pub enum ErrorKind {
     /// Error for when we cannot open a PackedFile due to not being decodeable on the lib.
     PackedFileTypeUnknown,
 
+    /// Error for when we try to undo/redo a change to multiple PackedFiles, but some of them have been edited since then. Contains their paths.
+    ChangeSetOutdated(Vec<Vec<String>>),
+
     /// Error for when we replace the binary data of a PackedFile with another data that's not decodeable in the same way as the old data.
     NewDataIsNotDecodeableTheSameWayAsOldDAta,
 
@@ -259,6 +268,9 @@ pub enum ErrorKind {
     /// Error for when a Table is empty and it doesn't have an `Definition`, so it's undecodeable.
     TableEmptyWithNoDefinition,
 
+    /// Error for when the text representation of a Sequence field is not valid for its definition. Contains the cause of the error.
+    TableSequenceInvalidData(String),
+
     //--------------------------------//
     // DB Table Errors
     //--------------------------------//
@@ -266,7 +278,7 @@ pub enum ErrorKind {
     /// Error for when we try to decode something as a DB Table and it fails.
     DBTableIsNotADBTable,
 
-    /// Error for when we try to open a table with a List field on it.
+    /// Error for when we try to open a table with a List field on it, but our definition for it has no Sequence field.
     DBTableContainsListField,
 
     /// Error for when we are trying to use "Search&Replace" to place invalid data into a cell.
@@ -278,6 +290,12 @@ pub enum ErrorKind {
     /// Error for when we find missing references when checking a DB Table. Contains a list with the tables with missing references.
     DBMissingReferences(Vec<String>),
 
+    /// Error for when we cannot find a key in a DB Table, neither in our PackFile nor in the game files. Contains the key.
+    DBTableKeyNotFound(String),
+
+    /// Error for when a key we want to add to a DB Table already exists on it. Contains the key.
+    DBTableKeyAlreadyExists(String),
+
     /// Error for when we found no newer version of a table than the one we have.
     NoDefinitionUpdateAvailable,
 
@@ -296,6 +314,21 @@ pub enum ErrorKind {
     /// Error for when we don't have a `Definition` for a specific version of a `VersionedFile`.
     SchemaDefinitionNotFound,
 
+    /// Error for when the schema source set in the settings is not valid. Contains the type of source set.
+    SchemaSourceInvalid(String),
+
+    /// Error for when we failed to update the local git checkout used as schema source. Contains the error message.
+    SchemaSourceGitPullFailed(String),
+
+    /// Error for when we aborted a schema merge due to conflicts. Contains the amount of conflicts.
+    SchemaMergeConflicts(usize),
+
+    /// Error for when we try to migrate a file that's not a schema of any known structural version.
+    SchemaMigrationUnknownFormat,
+
+    /// Error for when we try to migrate a schema with a structural version we don't support. Contains the structural version.
+    SchemaMigrationUnsupportedVersion(u16),
+
     //--------------------------------//
     // RigidModel Errors
     //--------------------------------//
@@ -350,6 +383,51 @@ pub enum ErrorKind {
     /// Error for when we try to decode a Loc PackedFile and fails for corruption.
     LocPackedFileCorrupted,
 
+    //--------------------------------//
+    // Translation Errors
+    //--------------------------------//
+
+    /// Error for when we try to export/import a translation file with an unsupported format. Contains the extension of the file.
+    TranslationUnsupportedFormat(String),
+
+    /// Error for when we try to export a translation to a language the game selected doesn't support. Contains the language.
+    TranslationUnsupportedLanguage(String),
+
+    /// Error for when a translation file fails to decode. Contains the error message.
+    TranslationDecode(String),
+
+    /// Error for when we try to import a translation whose language is not valid as a file name. Contains the language.
+    TranslationInvalidLanguage(String),
+
+    //--------------------------------//
+    // Table Patch Errors
+    //--------------------------------//
+
+    /// Error for when a table patch uses a field that doesn't exist in its table. Contains the table and the field.
+    TablePatchFieldNotFound(String, String),
+
+    /// Error for when a table patch tries to set a value not valid for its field. Contains the table, the field and the value.
+    TablePatchInvalidValue(String, String, String),
+
+    /// Error for when a table patch tries to remove rows, but all the rows it matches are vanilla rows. Contains the table.
+    TablePatchRemoveVanillaRows(String),
+
+    //--------------------------------//
+    // Table Expression Errors
+    //--------------------------------//
+
+    /// Error for when a table expression fails to parse. Contains the cause.
+    TableExpressionParse(String),
+
+    /// Error for when a table expression fails to evaluate. Contains the cause.
+    TableExpressionEvaluation(String),
+
+    /// Error for when a table expression targets a column that doesn't exist. Contains the column.
+    TableExpressionColumnNotFound(String),
+
+    /// Error for when a column mapping file uses a field that doesn't exist in the table. Contains the table and the field.
+    TableMappingFieldNotFound(String, String),
+
     //--------------------------------//
     // Image Errors
     //--------------------------------//
@@ -371,6 +449,9 @@ pub enum ErrorKind {
     /// Error for when we try to get the PAK file of a game for which we have no support for PAK files.
     PAKFileNotSupportedForThisGame,
 
+    /// Error for when the PAK file of a game is from an older version of RPFM, or it cannot be read.
+    PAKFileOutdated,
+
     //-----------------------------------------------------//
     //                Decoding Errors
     //-----------------------------------------------------//
@@ -503,6 +584,9 @@ pub enum ErrorKind {
     /// Error for when we try to open in the decoder an incompatible PackedFile.
     PackedFileNotDecodeableWithDecoder,
 
+    /// Error for when we try to move fields of the decoder into a Sequence, but they're not right after one.
+    DecoderSequenceInvalidSelection,
+
     /// Error for when we try to launch a game with no steam ID.
     LaunchNotSupportedForThisGame,
 
@@ -563,8 +647,10 @@ impl Display for ErrorKind {
             ErrorKind::JsonErrorEOF => write!(f,"<p>Error while trying to read JSON data:</p><p>Unexpected EOF found.</p>"),
             ErrorKind::ImportTSVIncorrectRow(row, column) => write!(f, "<p>This TSV file has an error in the <b>row <i>{}</i></b>, <b>field <i>{}</i></b> (both starting at 1). Please, check it and make sure the value in that field is a valid value for that column.</p>", row + 1, column + 1),
             ErrorKind::ImportTSVWrongTypeTable => write!(f, "<p>This TSV file either belongs to another table, to a localisation PackedFile, it's broken or it's incompatible with RPFM.</p>"),
-            ErrorKind::ImportTSVWrongVersion => write!(f, "<p>This TSV file belongs to another version of this table. If you want to use it, consider creating a new empty table, fill it with enough empty rows, open this file in a TSV editor, like Excel or LibreOffice, and copy column by column.</p><p>A more automatic solution is on the way, but not yet there.</p>"),
+            ErrorKind::ImportTSVMissingHeader => write!(f, "<p>This TSV file has no column headers at line 2, or none of them match a column of this table. Please, make sure the second line contains the names of the columns.</p>"),
             ErrorKind::ImportTSVInvalidVersion => write!(f, "<p>This TSV file has an invalid version value at line 1.</p>"),
+            ErrorKind::ImportTableIncorrectField(row, field) => write!(f, "<p>This file has an error in the <b>row <i>{}</i></b> (starting at 1), <b>field <i>{}</i></b>. Please, check it and make sure the value in that field is a valid value for that column.</p>", row + 1, field),
+            ErrorKind::TableUnsupportedFormat(format) => write!(f, "<p>The table format <i>'{}'</i> is not supported. Supported formats are <i>'tsv'</i>, <i>'csv'</i>, <i>'json'</i> and <i>'ron'</i>.</p>", format),
             ErrorKind::TSVErrorGeneric => write!(f, "<p>Error while trying to import/export a TSV file.</p>"),
             ErrorKind::FluentParsingError => write!(f, "<p>Error while trying to parse a fluent sentence.</p>"),
             ErrorKind::FluentResourceLoadingError => write!(f, "<p>Error while trying to load a fluent resource.</p>"),
@@ -643,6 +729,7 @@ impl Display for ErrorKind {
             ErrorKind::PackedFileCouldNotBeImported(paths) => write!(f, "<p>The following failed to be imported:<ul>{}</ul></p>", paths.iter().map(|x| format!("<li>{}<li>", x)).collect::<String>()),
             ErrorKind::PackedFileSaveError(path) => write!(f, "<p>The following PackedFile failed to be saved: {}</p>", path.join("/")),
             ErrorKind::PackedFileTypeUnknown => write!(f, "<p>The PackedFile could not be opened.</p>"),
+            ErrorKind::ChangeSetOutdated(paths) => write!(f, "<p>The following PackedFiles have been edited or removed since this change, so it cannot be undone/redone without losing those edits:</p><ul>{}</ul>", paths.iter().map(|x| format!("<li>{}</li>", x.join("/"))).collect::<String>()),
 
             //--------------------------------//
             // Table Errors
@@ -650,21 +737,29 @@ impl Display for ErrorKind {
             ErrorKind::TableRowWrongFieldCount(expected, real) => write!(f, "<p>Error while trying to save a row from a table:</p><p>We expected a row with \"{}\" fields, but we got a row with \"{}\" fields instead.</p>", expected, real),
             ErrorKind::TableWrongFieldType(expected, real) => write!(f, "<p>Error while trying to save a row from a table:</p><p>We expected a field of type \"{}\", but we got a field of type \"{}\".</p>", expected, real),
             ErrorKind::TableEmptyWithNoDefinition => write!(f, "<p>This table is empty and there is not a Definition for it. That means is undecodeable.</p>"),
+            ErrorKind::TableSequenceInvalidData(cause) => write!(f, "<p>The data of a Sequence field is not valid:</p><p>{}</p><p>Sequences must be written as a list of rows, with each row being a list of values, like this: <i>[[\"value\", 1, true], [\"value_2\", 2, false]]</i>.</p>", cause),
 
             //--------------------------------//
             // DB Table Errors
             //--------------------------------//
             ErrorKind::DBTableIsNotADBTable => write!(f, "<p>This is either not a DB Table, or it's a DB Table but it's corrupted.</p>"),
-            ErrorKind::DBTableContainsListField => write!(f, "<p>This specific table version uses a currently unimplemented type (List), so is undecodeable, for now.</p>"),
+            ErrorKind::DBTableContainsListField => write!(f, "<p>This specific table version uses a List field, but the definition in your schema doesn't have it. Update your schemas and try again.</p>"),
             ErrorKind::DBTableReplaceInvalidData => write!(f, "<p>Error while trying to replace the data of a Cell.</p><p>This means you tried to replace a number cell with text, or used a too big, too low or invalid number. Don't do it. It wont end well.</p>"),
             ErrorKind::DBTableDecode(cause) => write!(f, "<p>Error while trying to decode the DB Table:</p><p>{}</p><p>Before anything else, please check your game selected is really the one this PackFile is for! If it isn't, change your game selected and try again.</p>", cause),
             ErrorKind::DBMissingReferences(references) => write!(f, "<p>The currently open PackFile has reference errors in the following tables:<ul>{}</ul></p>", references.iter().map(|x| format!("<li>{}<li>", x)).collect::<String>()),
+            ErrorKind::DBTableKeyNotFound(key) => write!(f, "<p>The key '{}' has not been found in the table, neither in the currently open PackFile nor in the game files.</p>", key),
+            ErrorKind::DBTableKeyAlreadyExists(key) => write!(f, "<p>The key '{}' already exists in the table.</p>", key),
             ErrorKind::NoDefinitionUpdateAvailable => write!(f, "<p>This table already has the newer definition available.</p>"),
             ErrorKind::NoTableInGameFilesToCompare => write!(f, "<p>This table cannot be found in the Game Files, so it cannot be automatically updated (yet).</p>"),
             ErrorKind::SchemaNotFoundAndNotDownloaded => write!(f, "<p>There is no Schema file to load on the disk, and the tries to download one have failed.</p>"),
             ErrorKind::SchemaNotFound => write!(f, "<p>There is no Schema for the Game Selected.</p>"),
             ErrorKind::SchemaVersionedFileNotFound => write!(f, "<p>There is no Definition of the table in the Schema.</p>"),
             ErrorKind::SchemaDefinitionNotFound => write!(f, "<p>There is no Definition for this specific version of the table in the Schema.</p>"),
+            ErrorKind::SchemaSourceInvalid(source_type) => write!(f, "<p>The schema source type <i>'{}'</i> is not valid. Valid types are <i>'url'</i>, <i>'folder'</i> and <i>'git'</i>.</p>", source_type),
+            ErrorKind::SchemaSourceGitPullFailed(error) => write!(f, "<p>Error while updating the git checkout used as schema source:</p><p>{}</p>", error),
+            ErrorKind::SchemaMergeConflicts(conflicts) => write!(f, "<p>The schemas have not been merged, as they have {} conflicting definitions.</p>", conflicts),
+            ErrorKind::SchemaMigrationUnknownFormat => write!(f, "<p>The file is not a schema of any known structural version.</p>"),
+            ErrorKind::SchemaMigrationUnsupportedVersion(version) => write!(f, "<p>The schema has the structural version {}, which is not supported by this version of RPFM.</p>", version),
 
             //--------------------------------//
             // RigidModel Errors
@@ -692,6 +787,29 @@ impl Display for ErrorKind {
             ErrorKind::LocPackedFileIsNotALocPackedFile => write!(f, "<p>This is either not a Loc PackedFile, or it's a Loc PackedFile but it's corrupted.</p>"),
             ErrorKind::LocPackedFileCorrupted => write!(f, "<p>This Loc PackedFile seems to be corrupted.</p>"),
 
+            //--------------------------------//
+            // Translation Errors
+            //--------------------------------//
+            ErrorKind::TranslationUnsupportedFormat(extension) => write!(f, "<p>The translation file format <i>'{}'</i> is not supported. Supported formats are <i>'po'</i> and <i>'xlf'</i>/<i>'xliff'</i>.</p>", extension),
+            ErrorKind::TranslationUnsupportedLanguage(language) => write!(f, "<p>The language <i>'{}'</i> is not supported by the Game Selected.</p>", language),
+            ErrorKind::TranslationDecode(cause) => write!(f, "<p>Error while trying to decode the translation file:</p><p>{}</p>", cause),
+            ErrorKind::TranslationInvalidLanguage(language) => write!(f, "<p>The language <i>'{}'</i> of the translation file is not valid. It cannot be empty, or contain path separators or <i>'..'</i>.</p>", language),
+
+            //--------------------------------//
+            // Table Patch Errors
+            //--------------------------------//
+            ErrorKind::TablePatchFieldNotFound(table, field) => write!(f, "<p>The field <i>'{}'</i> used in the patch doesn't exist in the table <i>'{}'</i>.</p>", field, table),
+            ErrorKind::TablePatchInvalidValue(table, field, value) => write!(f, "<p>The value <i>'{}'</i> used in the patch is not valid for the field <i>'{}'</i> of the table <i>'{}'</i>.</p>", value, field, table),
+            ErrorKind::TablePatchRemoveVanillaRows(table) => write!(f, "<p>A <i>Remove</i> operation of the patch for the table <i>'{}'</i> only matches vanilla rows. Mods cannot remove vanilla rows, so it would do nothing.</p>", table),
+
+            //--------------------------------//
+            // Table Expression Errors
+            //--------------------------------//
+            ErrorKind::TableExpressionParse(cause) => write!(f, "<p>Error while trying to parse the expression:</p><p>{}</p>", cause),
+            ErrorKind::TableExpressionEvaluation(cause) => write!(f, "<p>Error while trying to evaluate the expression:</p><p>{}</p>", cause),
+            ErrorKind::TableExpressionColumnNotFound(column) => write!(f, "<p>The column <i>'{}'</i> doesn't exist in the table.</p>", column),
+            ErrorKind::TableMappingFieldNotFound(table, field) => write!(f, "<p>The field <i>'{}'</i> used in the column mapping file of the table <i>'{}'</i> doesn't exist in the versions of the table it maps.</p>", field, table),
+
             //--------------------------------//
             // Image Errors
             //--------------------------------//
@@ -709,6 +827,9 @@ impl Display for ErrorKind {
             // Error for when we try to get the PAK file of a game for which we have no support for PAK files.
             ErrorKind::PAKFileNotSupportedForThisGame => write!(f, "<p>The currently selected game doesn't have support for PAK files.</p>"),
 
+            // Error for when the PAK file of a game is from an older version of RPFM, or it cannot be read.
+            ErrorKind::PAKFileOutdated => write!(f, "<p>The PAK file of the currently selected game was generated by an older version of RPFM, or it's corrupted. Please, regenerate it from the <i>Special Stuff</i> menu.</p>"),
+
             //-----------------------------------------------------//
             //                Decoding Errors
             //-----------------------------------------------------//
@@ -768,6 +889,7 @@ impl Display for ErrorKind {
             ErrorKind::DependencyManagerDecode(cause) => write!(f, "<p>Error while trying to decode the Dependency PackFile List:</p><p>{}</p>", cause),
             ErrorKind::DecoderDecode(cause) => write!(f, "<p>Error while trying to load the following PackedFile to the decoder:</p><p>{}</p>", cause),
             ErrorKind::PackedFileNotDecodeableWithDecoder => write!(f, "<p>This PackedFile cannot be decoded using the PackedFile Decoder.</p>"),
+            ErrorKind::DecoderSequenceInvalidSelection => write!(f, "<p>Only contiguous fields right after a <i>Sequence</i> field can be moved into it, and only a single <i>Sequence</i> field can have its fields extracted.</p>"),
             ErrorKind::LaunchNotSupportedForThisGame => write!(f, "<p>The currently selected game cannot be launched from Steam.</p>"),
             ErrorKind::ConfigFolderCouldNotBeOpened => write!(f, "<p>RPFM's config folder couldn't be open (maybe it doesn't exists?).</p>"),
         }
@@ -61,6 +61,33 @@ const RAW_DEFINITION_EXTENSION_V0: &str = ".xsd";
 
 const BLACKLISTED_TABLES: [&str; 1] = ["translated_texts.xml"];
 
+/// Signature at the start of every PAK file, followed by its format version as an u16.
+pub const PAK_HEADER: &[u8] = b"RPFMPAK";
+
+/// Current format version of the PAK files. Bump it when the serialized tables change, so older PAK files get regenerated instead of misread.
+pub const PAK_VERSION: u16 = 1;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct represents the fields of a table that couldn't be matched between the Schema and the Assembly Kit when updating the Schema.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct UnmatchedFields {
+
+    /// Name of the table, like `land_units_tables`.
+    pub table_name: String,
+
+    /// Version of the definition that has been updated.
+    pub version: i32,
+
+    /// Fields of the definition without a field in the Assembly Kit. These fields have not been updated.
+    pub schema_fields: Vec<String>,
+
+    /// Fields of the Assembly Kit without a field, regular or localised, in the definition.
+    pub raw_fields: Vec<String>,
+}
+
 //---------------------------------------------------------------------------//
 // Functions to process the Raw DB Tables from the Assembly Kit.
 //---------------------------------------------------------------------------//
@@ -71,6 +98,8 @@ const BLACKLISTED_TABLES: [&str; 1] = ["translated_texts.xml"];
 /// as fake tables with version -1. That will allow us to use them for dependency checking and for populating combos.
 ///
 /// To keep things fast, only undecoded or missing (from the game files) tables will be included into the PAK file.
+///
+/// The tables are saved serialized with bincode, after the `PAK_HEADER` and the `PAK_VERSION`.
 pub fn generate_pak_file(
     raw_db_path: &PathBuf,
     version: i16,
@@ -89,6 +118,8 @@ pub fn generate_pak_file(
 
     let mut file = File::create(pak_path)?;
     let serialized_data = bincode::serialize(&tables)?;
+    file.write_all(PAK_HEADER)?;
+    file.write_all(&PAK_VERSION.to_le_bytes())?;
     file.write_all(&serialized_data)?;
 
     // If we reach this point, return success.
@@ -100,8 +131,10 @@ pub fn generate_pak_file(
 /// Some notes:
 /// - This works only over already decoded tables (no new definitions are created).
 /// - This decodes localisable fields as proper localisable fiels, separating them from the rest.
-/// - This only updates the current versions of the tables, not older ones.
-pub fn update_schema_from_raw_files(ass_kit_path: Option<PathBuf>) -> Result<()> {
+/// - This only updates the current versions of the tables. Older ones only get the descriptions and filename data they're missing.
+///
+/// It returns the fields of the current versions of the tables it couldn't match with the Assembly Kit, sorted by table name.
+pub fn update_schema_from_raw_files(ass_kit_path: Option<PathBuf>) -> Result<Vec<UnmatchedFields>> {
     let mut schema_writable = SCHEMA.write().unwrap();
     let schema_referenced: &mut Option<Schema> = schema_writable.borrow_mut();
     if let Some(ref mut schema) = schema_referenced {
@@ -132,30 +165,51 @@ pub fn update_schema_from_raw_files(ass_kit_path: Option<PathBuf>) -> Result<()>
                         } else { None };
 
                     let (raw_definitions, _) = RawDefinition::read_all(&ass_kit_schemas_path, raw_db_version, false)?;
-                    schema.get_ref_mut_versioned_file_db_all().par_iter_mut().for_each(|versioned_file| {
+                    let mut unmatched_fields = schema.get_ref_mut_versioned_file_db_all().par_iter_mut().filter_map(|versioned_file| {
                         if let VersionedFile::DB(table_name, definitions) = versioned_file {
                             let name = &table_name[0..table_name.len() - 7];
                             if let Some(raw_definition) = raw_definitions.iter().filter(|x| x.name.is_some()).find(|x| &(x.name.as_ref().unwrap())[0..x.name.as_ref().unwrap().len() - 4] == name) {
                                 let mut vanilla_tables = packfile_db.get_packed_files_by_path_start(&["db".to_owned(), table_name.to_owned()]);
-                                if !vanilla_tables.is_empty() {
-                                    let vanilla_table = &mut vanilla_tables[0];
-                                    if let Ok(vanilla_table_data) = vanilla_table.get_raw_data() {
-                                        if let Ok((version, _, _, _, _)) = DB::read_header(&vanilla_table_data) {
-                                            if let Some(ref mut definition) = definitions.iter_mut().find(|x| x.version == version) {
-                                                definition.update_from_raw_definition(&raw_definition);
-                                                if let Some(ref raw_localisable_fields) = raw_localisable_fields {
-                                                    definition.update_from_raw_localisable_fields(&raw_definition, &raw_localisable_fields.fields)
-                                                }
-                                            }
+                                let version = vanilla_tables.get_mut(0)
+                                    .and_then(|vanilla_table| vanilla_table.get_raw_data().ok())
+                                    .and_then(|vanilla_table_data| DB::read_header(&vanilla_table_data).ok())
+                                    .map(|(version, _, _, _, _)| version);
+
+                                let mut unmatched_fields = None;
+                                for definition in definitions.iter_mut() {
+                                    if Some(definition.version) == version {
+                                        let schema_fields = definition.update_from_raw_definition(raw_definition);
+                                        if let Some(ref raw_localisable_fields) = raw_localisable_fields {
+                                            definition.update_from_raw_localisable_fields(raw_definition, &raw_localisable_fields.fields)
+                                        }
+
+                                        let raw_fields = raw_definition.fields.iter()
+                                            .filter(|x| !definition.fields.iter().chain(definition.localised_fields.iter()).any(|y| y.name.eq_ignore_ascii_case(&x.name)))
+                                            .map(|x| x.name.to_owned())
+                                            .collect::<Vec<String>>();
+
+                                        if !schema_fields.is_empty() || !raw_fields.is_empty() {
+                                            unmatched_fields = Some(UnmatchedFields {
+                                                table_name: table_name.to_owned(),
+                                                version: definition.version,
+                                                schema_fields,
+                                                raw_fields,
+                                            });
                                         }
                                     }
+                                    else {
+                                        definition.update_docs_from_raw_definition(raw_definition);
+                                    }
                                 }
+                                return unmatched_fields;
                             }
                         }
-                    });
+                        None
+                    }).collect::<Vec<UnmatchedFields>>();
+                    unmatched_fields.sort_by(|x, y| x.table_name.cmp(&y.table_name));
                     schema.save(&SUPPORTED_GAMES[&**GAME_SELECTED.read().unwrap()].schema)?;
 
-                    Ok(())
+                    Ok(unmatched_fields)
                 }
                 else { Err(ErrorKind::GamePathNotConfigured.into()) }
             }
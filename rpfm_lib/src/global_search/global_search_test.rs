@@ -0,0 +1,100 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module containing test for the `GlobalSearch` module, to make sure searches respect their case sensitivity, and replaces end.
+!*/
+
+use crate::SCHEMA;
+use crate::packedfile::DecodedPackedFile;
+use crate::packedfile::text::Text;
+use crate::packfile::{PackFile, PFHVersion};
+use crate::packfile::packedfile::PackedFile;
+use crate::schema::Schema;
+
+use super::GlobalSearch;
+
+/// This function returns a `PackFile` with a Text PackedFile for each one of the provided contents.
+fn get_test_pack_file(contents: &[&str]) -> PackFile {
+
+    // Searches don't decode anything without a schema, even if the files are already decoded.
+    let mut schema = SCHEMA.write().unwrap();
+    if schema.is_none() {
+        *schema = Some(Schema::default());
+    }
+
+    let mut pack_file = PackFile::new_with_name("test.pack", PFHVersion::PFH5);
+    for (index, contents) in contents.iter().enumerate() {
+        let mut text = Text::new();
+        text.set_contents(contents);
+        let packed_file = PackedFile::new_from_decoded(&DecodedPackedFile::Text(text), vec!["text".to_owned(), format!("test_{}.txt", index)]);
+        pack_file.add_packed_file(&packed_file, true).unwrap();
+    }
+    pack_file
+}
+
+/// This function returns the contents of the Text PackedFile with the provided path.
+fn get_text(pack_file: &PackFile, path: &[String]) -> String {
+    match pack_file.get_ref_packed_file_by_path(path).unwrap().get_decoded() {
+        DecodedPackedFile::Text(text) => text.get_ref_contents().to_owned(),
+        _ => panic!("Not a text file."),
+    }
+}
+
+#[test]
+fn test_global_search_case_sensitivity() {
+    let mut pack_file = get_test_pack_file(&["Unit_Key, unit_key and UNIT_KEY."]);
+    for use_regex in &[false, true] {
+        let mut global_search = GlobalSearch {
+            pattern: "unit_key".to_owned(),
+            case_sensitive: true,
+            use_regex: *use_regex,
+            ..Default::default()
+        };
+
+        global_search.search(&mut pack_file);
+        assert_eq!(global_search.matches_text[0].matches.len(), 1, "regex: {}", use_regex);
+
+        global_search.case_sensitive = false;
+        global_search.search(&mut pack_file);
+        assert_eq!(global_search.matches_text[0].matches.len(), 3, "regex: {}", use_regex);
+    }
+}
+
+#[test]
+fn test_global_search_replace_text() {
+    let mut pack_file = get_test_pack_file(&["local key = \"key\"\r\nkeys[key] = 1\r\n", "nothing here"]);
+    let paths = pack_file.get_ref_packed_files_all().iter().map(|x| x.get_path().to_vec()).collect::<Vec<Vec<String>>>();
+
+    // Replacing a pattern with a text containing it must end, and replace each match only once.
+    let mut global_search = GlobalSearch {
+        pattern: "key".to_owned(),
+        replace_text: "key_2".to_owned(),
+        case_sensitive: true,
+        ..Default::default()
+    };
+    global_search.search(&mut pack_file);
+    assert!(global_search.replace_all(&mut pack_file).is_empty());
+    assert_eq!(get_text(&pack_file, &paths[0]), "local key_2 = \"key_2\"\r\nkey_2s[key_2] = 1\r\n");
+    assert_eq!(get_text(&pack_file, &paths[1]), "nothing here");
+
+    // Regex replaces must only touch whole words if asked to, and keep the line endings.
+    let mut global_search = GlobalSearch {
+        pattern: r"\bkey_2\b".to_owned(),
+        replace_text: "unit_key".to_owned(),
+        case_sensitive: true,
+        use_regex: true,
+        ..Default::default()
+    };
+    global_search.search(&mut pack_file);
+    let matches = global_search.matches_text.iter().map(|x| super::MatchHolder::Text(x.clone())).collect::<Vec<super::MatchHolder>>();
+    assert!(global_search.replace_matches(&mut pack_file, &matches).is_empty());
+    assert_eq!(get_text(&pack_file, &paths[0]), "local unit_key = \"unit_key\"\r\nkey_2s[unit_key] = 1\r\n");
+}
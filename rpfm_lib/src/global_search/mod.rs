@@ -35,6 +35,9 @@ pub mod schema;
 pub mod table;
 pub mod text;
 
+#[cfg(test)]
+mod global_search_test;
+
 //-------------------------------------------------------------------------------//
 //                              Enums & Structs
 //-------------------------------------------------------------------------------//
@@ -128,7 +131,7 @@ impl GlobalSearch {
 
         // If we want to use regex and the pattern is invalid, don't search.
         let matching_mode = if self.use_regex {
-            if let Ok(regex) = RegexBuilder::new(&self.pattern).case_insensitive(self.case_sensitive).build() {
+            if let Ok(regex) = RegexBuilder::new(&self.pattern).case_insensitive(!self.case_sensitive).build() {
                 MatchingMode::Regex(regex)
             }
             else { MatchingMode::Pattern }
@@ -200,7 +203,7 @@ impl GlobalSearch {
 
         // If we want to use regex and the pattern is invalid, don't search.
         let matching_mode = if self.use_regex {
-            if let Ok(regex) = RegexBuilder::new(&self.pattern).case_insensitive(self.case_sensitive).build() {
+            if let Ok(regex) = RegexBuilder::new(&self.pattern).case_insensitive(!self.case_sensitive).build() {
                 MatchingMode::Regex(regex)
             }
             else { MatchingMode::Pattern }
@@ -280,7 +283,7 @@ impl GlobalSearch {
 
         // If we want to use regex and the pattern is invalid, don't search.
         let matching_mode = if self.use_regex {
-            if let Ok(regex) = RegexBuilder::new(&self.pattern).case_insensitive(self.case_sensitive).build() {
+            if let Ok(regex) = RegexBuilder::new(&self.pattern).case_insensitive(!self.case_sensitive).build() {
                 MatchingMode::Regex(regex)
             }
             else { MatchingMode::Pattern }
@@ -334,9 +337,12 @@ impl GlobalSearch {
                         }
                     }
 
-                    // TODO.
-                    MatchHolder::Text(_) => {
-
+                    MatchHolder::Text(match_text) => {
+                        if let Some(packed_file) = pack_file.get_ref_mut_packed_file_by_path(&match_text.path) {
+                            if let Ok(DecodedPackedFile::Text(ref mut text)) = packed_file.decode_return_ref_mut_no_locks(schema) {
+                                self.replace_match_text(text, &mut changed_files, match_text, &matching_mode);
+                            }
+                        }
                     }
                     MatchHolder::Schema(_) => continue,
                 }
@@ -354,7 +360,7 @@ impl GlobalSearch {
 
         // If we want to use regex and the pattern is invalid, don't search.
         let matching_mode = if self.use_regex {
-            if let Ok(regex) = RegexBuilder::new(&self.pattern).case_insensitive(self.case_sensitive).build() {
+            if let Ok(regex) = RegexBuilder::new(&self.pattern).case_insensitive(!self.case_sensitive).build() {
                 MatchingMode::Regex(regex)
             }
             else { MatchingMode::Pattern }
@@ -410,6 +416,14 @@ impl GlobalSearch {
                 }
             }
 
+            for match_text in &self.matches_text {
+                if let Some(packed_file) = pack_file.get_ref_mut_packed_file_by_path(&match_text.path) {
+                    if let Ok(DecodedPackedFile::Text(ref mut text)) = packed_file.decode_return_ref_mut_no_locks(schema) {
+                        self.replace_match_text(text, &mut changed_files, match_text, &matching_mode);
+                    }
+                }
+            }
+
             let changed_files = changed_files.iter().map(|x| PathType::File(x.to_vec())).collect::<Vec<PathType>>();
             self.update(pack_file, &changed_files);
         }
@@ -473,14 +487,33 @@ impl GlobalSearch {
                 }
             }
             MatchingMode::Pattern => {
-                while let Some(start) = text.find(&self.pattern) {
-                    let end = start + self.pattern.len();
-                    text.replace_range(start..end, &self.replace_text);
+                if !self.pattern.is_empty() && text.contains(&self.pattern) {
+                    *text = text.replace(&self.pattern, &self.replace_text);
                 }
             }
         }
     }
 
+    /// This function replaces all the matches in a Text PackedFile.
+    ///
+    /// We replace on the full contents instead of per-match, so line endings and anything not matched are kept intact.
+    fn replace_match_text(
+        &self,
+        data: &mut Text,
+        changed_files: &mut Vec<Vec<String>>,
+        match_text: &TextMatches,
+        matching_mode: &MatchingMode,
+    ) {
+        let mut contents = data.get_ref_contents().to_owned();
+        self.replace_match(&mut contents, matching_mode);
+        if contents != data.get_ref_contents() {
+            data.set_contents(&contents);
+            if !changed_files.contains(&match_text.path) {
+                changed_files.push(match_text.path.to_vec());
+            }
+        }
+    }
+
     /// This function performs a search over the provided DB Table.
     fn search_on_db(&self, path: &[String], table_data: &DB, matching_mode: &MatchingMode) -> TableMatches {
         let mut matches = TableMatches::new(path);
@@ -39,6 +39,8 @@ pub mod packedfile;
 pub mod packfile;
 pub mod schema;
 pub mod settings;
+pub mod table_patches;
+pub mod translations;
 
 // Statics, so we don't need to pass them everywhere to use them.
 lazy_static! {
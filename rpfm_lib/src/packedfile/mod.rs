@@ -31,6 +31,7 @@ use crate::packedfile::table::{db::DB, loc::Loc};
 use crate::packedfile::text::{Text, TextType};
 use crate::packedfile::rigidmodel::RigidModel;
 use crate::packfile::packedfile::{PackedFile, RawPackedFile};
+use crate::packfile::table_update::TableUpdateReport;
 use crate::schema::Schema;
 use crate::SCHEMA;
 
@@ -208,8 +209,8 @@ impl DecodedPackedFile {
 
     /// This function updates a DB Table to its latest valid version, being the latest valid version the one in the data.pack or equivalent of the game.
     ///
-    /// It returns both, old and new versions, or an error.
-    pub fn update_table(&mut self) -> Result<(i32, i32)> {
+    /// It returns the report of the update, with the values it couldn't carry over to the new version, or an error.
+    pub fn update_table(&mut self, path: &[String]) -> Result<TableUpdateReport> {
         match self {
             DecodedPackedFile::DB(data) => {
                 let mut dep_db = DEPENDENCY_DATABASE.lock().unwrap();
@@ -221,10 +222,8 @@ impl DecodedPackedFile {
                         .max_by(|x, y| x.get_ref_definition().version.cmp(&y.get_ref_definition().version)) {
 
                         let definition_new = vanilla_db.get_definition();
-                        let definition_old = data.get_definition();
-                        if definition_old != definition_new {
-                            data.set_definition(&definition_new);
-                            Ok((definition_old.version, definition_new.version))
+                        if data.get_ref_definition() != &definition_new {
+                            TableUpdateReport::update_table(path, data, &definition_new)
                         }
                         else {
                             Err(ErrorKind::NoDefinitionUpdateAvailable.into())
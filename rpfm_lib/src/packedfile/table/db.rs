@@ -26,18 +26,21 @@ use std::path::PathBuf;
 
 use rpfm_error::{ErrorKind, Result};
 
+use crate::assembly_kit::{PAK_HEADER, PAK_VERSION};
 use crate::assembly_kit::table_data::RawTable;
 use crate::common::{decoder::Decoder, encoder::Encoder};
 use crate::common::get_game_selected_pak_file;
 use crate::GAME_SELECTED;
 use crate::games::*;
-use crate::packedfile::DecodedPackedFile;
+use crate::packedfile::{DecodedPackedFile, PackedFileType};
 use crate::packfile::PackFile;
 use crate::packfile::packedfile::PackedFile;
 use crate::schema::*;
 use crate::SETTINGS;
 use super::DecodedData;
-use super::Table;
+use super::{Table, TableFormat};
+use super::guesser::DefinitionGuess;
+use super::mapping::{ColumnMapping, ColumnSource, LostValue};
 
 /// If this sequence is found, the DB Table has a GUID after it.
 const GUID_MARKER: &[u8] = &[253, 254, 252, 255];
@@ -67,6 +70,26 @@ pub struct DB {
     table: Table,
 }
 
+/// This struct represents a cell of a DB Table referencing a key of another DB Table.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct DBReference {
+
+    /// The path of the DB Table containing the reference.
+    pub path: Vec<String>,
+
+    /// The name of the column containing the reference.
+    pub column_name: String,
+
+    /// The index of the column containing the reference.
+    pub column_number: u32,
+
+    /// The row containing the reference.
+    pub row_number: i64,
+
+    /// If the DB Table is from the game files, instead of from the open PackFile.
+    pub is_from_game_files: bool,
+}
+
 //---------------------------------------------------------------------------//
 //                           Implementation of DB
 //---------------------------------------------------------------------------//
@@ -135,6 +158,25 @@ impl DB {
         self.table.set_definition(new_definition);
     }
 
+    /// This function replaces the definition of this table with the one provided, taking the data of each new column from the provided source.
+    ///
+    /// It returns the values that couldn't be carried over to the new definition.
+    pub fn set_definition_with_sources(&mut self, new_definition: &Definition, sources: &[ColumnSource]) -> Vec<LostValue> {
+        self.table.set_definition_with_sources(new_definition, sources)
+    }
+
+    /// This function updates this table to the provided definition, trying to not lose data from renamed columns.
+    ///
+    /// Columns are mapped using the user's mapping file for this table and versions, if any, and then by name, similar name and position.
+    /// It returns where the data of each new column came from, and the values that couldn't be carried over.
+    pub fn update_definition(&mut self, new_definition: &Definition) -> Result<(Vec<ColumnSource>, Vec<LostValue>)> {
+        let old_definition = self.get_ref_definition();
+        let user_mapping = ColumnMapping::load_from_config(&self.name, old_definition.version, new_definition.version)?;
+        let sources = ColumnMapping::get_column_sources(old_definition, new_definition, user_mapping.as_ref())?;
+        let lost_values = self.set_definition_with_sources(new_definition, &sources);
+        Ok((sources, lost_values))
+    }
+
     /// This function replaces the data of this table with the one provided.
     ///
     /// This can (and will) fail if the data is not of the format defined by the definition of the table.
@@ -153,14 +195,19 @@ impl DB {
         // Get the header of the `DB`.
         let (version, mysterious_byte, uuid, entry_count, mut index) = Self::read_header(&packed_file_data)?;
 
-        // These tables use the not-yet-implemented type "List" in the following versions:
+        // Try to get the table_definition for this table, if exists.
+        let versioned_file = schema.get_ref_versioned_file_db(&name);
+        if versioned_file.is_err() && entry_count == 0 { return Err(ErrorKind::TableEmptyWithNoDefinition.into()) }
+        let definition = versioned_file?.get_version(version);
+        if definition.is_err() && entry_count == 0 { return Err(ErrorKind::TableEmptyWithNoDefinition.into()) }
+
+        // These tables use the "List" type, decoded as a Sequence, in the following versions:
         // - models_artillery: 0,
         // - models_artilleries: 0,
         // - models_building: 0, 3, 7.
         // - models_naval: 0, 6, 11.
         // - models_sieges: 2, 3.
-        // So we disable everything for any problematic version of these tables.
-        // TODO: Implement the needed type for these tables.
+        // If the definition we have for them doesn't have the Sequence field yet (old schemas), we cannot decode them.
         if (name == "models_artillery_tables" && version == 0) ||
             (name == "models_artilleries_tables" && version == 0) ||
             (name == "models_building_tables" && (version == 0 ||
@@ -170,14 +217,13 @@ impl DB {
                                                     version == 6 ||
                                                     version == 11)) ||
             (name == "models_sieges_tables" && (version == 2 ||
-                                                    version == 3))
-        { return Err(ErrorKind::DBTableContainsListField.into()) }
-
-        // Try to get the table_definition for this table, if exists.
-        let versioned_file = schema.get_ref_versioned_file_db(&name);
-        if versioned_file.is_err() && entry_count == 0 { return Err(ErrorKind::TableEmptyWithNoDefinition.into()) }
-        let definition = versioned_file?.get_version(version);
-        if definition.is_err() && entry_count == 0 { return Err(ErrorKind::TableEmptyWithNoDefinition.into()) }
+                                                    version == 3)) {
+            if let Ok(definition) = definition {
+                if !definition.fields.iter().any(|x| matches!(x.field_type, FieldType::Sequence(_))) {
+                    return Err(ErrorKind::DBTableContainsListField.into())
+                }
+            }
+        }
 
         // Then try to decode all the entries.
         let mut table = Table::new(definition?);
@@ -259,29 +305,40 @@ impl DB {
         Ok((version, mysterious_byte, uuid, entry_count, index))
     }
 
+    /// This function guesses the definitions that can decode the provided `DB` data, best guesses first.
+    ///
+    /// If a schema is provided, the definitions of the table in it are used to guide the search and to rank the guesses.
+    pub fn guess_definitions(packed_file_data: &[u8], name: &str, schema: Option<&Schema>, max_guesses: usize) -> Result<Vec<DefinitionGuess>> {
+        let (_, _, _, entry_count, index) = Self::read_header(packed_file_data)?;
+        if entry_count == 0 { return Err(ErrorKind::TableEmptyWithNoDefinition.into()) }
+
+        let reference_definitions = match schema.map(|x| x.get_ref_versioned_file_db(name)) {
+            Some(Ok(versioned_file)) => versioned_file.get_version_list().to_vec(),
+            _ => vec![],
+        };
+
+        Ok(DefinitionGuess::guess(packed_file_data, index, entry_count, &reference_definitions, max_guesses))
+    }
+
     /// This function loads the PAK file of the game selected (if exists) into memory.
     ///
-    /// This is useful to help resolving dependencies.
-    pub fn read_pak_file() -> Vec<Self> {
-
-        // Create the empty list.
-        let mut db_files = vec![];
-
-        // Get all the paths we need.
-        if let Ok(pak_file) = get_game_selected_pak_file() {
-            if let Ok(pak_file) = File::open(pak_file) {
-                let mut pak_file = BufReader::new(pak_file);
-                let mut data = vec![];
-                if pak_file.read_to_end(&mut data).is_ok() {
-                    if let Ok(pak_file) = deserialize(&data) {
-                        db_files = pak_file;
-                    }
-                }
-            }
+    /// This is useful to help resolving dependencies. If the game has no PAK file, the list is empty. If it has one,
+    /// but it's from an older version of the format or it cannot be read, an error asking to regenerate it is returned.
+    pub fn read_pak_file() -> Result<Vec<Self>> {
+        let pak_file = match get_game_selected_pak_file() {
+            Ok(pak_file) if pak_file.is_file() => pak_file,
+            _ => return Ok(vec![]),
+        };
+
+        let mut data = vec![];
+        BufReader::new(File::open(pak_file)?).read_to_end(&mut data)?;
+
+        let header_len = PAK_HEADER.len();
+        if data.len() < header_len + 2 || &data[..header_len] != PAK_HEADER || data[header_len..header_len + 2] != PAK_VERSION.to_le_bytes() {
+            return Err(ErrorKind::PAKFileOutdated.into());
         }
 
-        // Return the fake DB Table list.
-        db_files
+        deserialize(&data[header_len + 2..]).map_err(|_| ErrorKind::PAKFileOutdated.into())
     }
 
     /// This function is used to optimize the size of a DB Table.
@@ -312,6 +369,195 @@ impl DB {
         self.table.get_ref_table_data().is_empty()
     }
 
+    /// This function returns the name of the table without the `_tables` suffix, as used in the keys of Loc entries.
+    pub fn get_loc_table_name(&self) -> &str {
+        if self.name.ends_with("_tables") { &self.name[..self.name.len() - 7] } else { &self.name }
+    }
+
+    /// This function returns the key of the provided row, as used in the keys of Loc entries, made by joining the data of all its key columns.
+    ///
+    /// Different rows can have the same joined key (like `a` + `bc` and `ab` + `c`), so to compare rows use `get_row_keys` instead.
+    pub fn get_row_key(&self, row: &[DecodedData]) -> String {
+        self.get_row_keys(row).concat()
+    }
+
+    /// This function returns the data of all the key columns of the provided row, in order. It's empty if the table has no key columns.
+    pub fn get_row_keys(&self, row: &[DecodedData]) -> Vec<String> {
+        self.get_ref_definition().fields.iter()
+            .zip(row.iter())
+            .filter(|(field, _)| field.is_key)
+            .map(|(_, data)| data.data_to_string())
+            .collect()
+    }
+
+    /// This function returns the Loc key of a localised field for the provided row key.
+    ///
+    /// The format of the key is `<table_name_without_tables>_<field_name>_<row_key>`.
+    pub fn get_loc_key(&self, field_name: &str, row_key: &str) -> String {
+        format!("{}_{}_{}", self.get_loc_table_name(), field_name, row_key)
+    }
+
+    /// This function returns all the Loc keys this table needs, one per localised field per row.
+    pub fn get_loc_keys(&self) -> Vec<String> {
+        let localised_fields = &self.get_ref_definition().localised_fields;
+        self.get_ref_table_data().iter()
+            .map(|row| self.get_row_key(row))
+            .filter(|row_key| !row_key.is_empty())
+            .flat_map(|row_key| localised_fields.iter().map(|field| self.get_loc_key(&field.name, &row_key)).collect::<Vec<String>>())
+            .collect()
+    }
+
+    /// This function returns the list of keys that got renamed between the provided old version of this table and this one.
+    ///
+    /// As editing a table doesn't reorder its rows, a rename is detected when a row keeps its position, but its key
+    /// changed to one that didn't exist before. Keys are compared column by column, but the returned list contains
+    /// the joined keys, as used in Loc entries, in the format `(old_key, new_key)`.
+    pub fn get_renamed_keys(&self, old_table: &Self) -> Vec<(String, String)> {
+        let old_keys = old_table.get_ref_table_data().iter().map(|row| old_table.get_row_keys(row)).collect::<Vec<Vec<String>>>();
+        let new_keys = self.get_ref_table_data().iter().map(|row| self.get_row_keys(row)).collect::<Vec<Vec<String>>>();
+
+        old_keys.iter().zip(new_keys.iter())
+            .filter(|(old_key, new_key)| old_key != new_key && !old_key.is_empty() && !new_key.is_empty())
+            .filter(|(old_key, new_key)| !new_keys.contains(old_key) && !old_keys.contains(new_key))
+            .map(|(old_key, new_key)| (old_key.concat(), new_key.concat()))
+            .filter(|(old_key, new_key)| old_key != new_key)
+            .collect()
+    }
+
+    /// This function replaces the provided key with a new one in every column of this table that is, or references, the provided table and column.
+    ///
+    /// The table name is expected without the `_tables` suffix, like in the references of the schema. It returns if any cell has been changed.
+    pub fn rename_references(&mut self, ref_table: &str, ref_column: &str, old_key: &str, new_key: &str) -> Result<bool> {
+        let columns = self.get_reference_columns(ref_table, ref_column);
+        let mut renamed = false;
+        for row in &mut self.table.entries {
+            for column in &columns {
+                if let Some(cell) = row.get_mut(*column) {
+                    if cell.data_to_string() != old_key { continue; }
+                    match cell {
+                        DecodedData::Float(ref mut data) => *data = new_key.parse::<f32>()?,
+                        DecodedData::Integer(ref mut data) => *data = new_key.parse::<i32>()?,
+                        DecodedData::LongInteger(ref mut data) => *data = new_key.parse::<i64>()?,
+                        DecodedData::StringU8(ref mut data) |
+                        DecodedData::StringU16(ref mut data) |
+                        DecodedData::OptionalStringU8(ref mut data) |
+                        DecodedData::OptionalStringU16(ref mut data) => *data = new_key.to_owned(),
+                        DecodedData::Boolean(_) |
+                        DecodedData::Sequence(_) => continue,
+                    }
+                    renamed = true;
+                }
+            }
+        }
+
+        Ok(renamed)
+    }
+
+    /// This function returns all the rows of this table with the provided key in a column that is, or references, the provided table and column.
+    ///
+    /// The table name is expected without the `_tables` suffix, like in the references of the schema.
+    pub fn get_rows_referencing(&self, ref_table: &str, ref_column: &str, key: &str) -> Vec<Vec<DecodedData>> {
+        let columns = self.get_reference_columns(ref_table, ref_column);
+        self.get_ref_table_data().iter()
+            .filter(|row| columns.iter().any(|column| row.get(*column).map(|cell| cell.data_to_string()).as_deref() == Some(key)))
+            .cloned()
+            .collect()
+    }
+
+    /// This function returns the indexes of the columns of this table that are, or reference, the provided table and column.
+    fn get_reference_columns(&self, ref_table: &str, ref_column: &str) -> Vec<usize> {
+        let is_referenced_table = self.get_loc_table_name() == ref_table;
+        self.get_ref_definition().fields.iter()
+            .enumerate()
+            .filter(|(_, field)| (is_referenced_table && field.name == ref_column) || match field.is_reference {
+                Some((ref table, ref column)) => table == ref_table && column == ref_column,
+                None => false,
+            })
+            .map(|(column, _)| column)
+            .collect()
+    }
+
+    /// This function returns all the cells of the DB Tables in the `PackFile` and in the dependency database referencing the provided key of a table.
+    ///
+    /// It's the reverse of `get_dependency_data`: instead of what a column can reference, it returns what references a row.
+    /// Only references to the key columns of the table are taken into account, and only the tables the schema says reference it are decoded.
+    /// Tables of the dependency database with the same path as a table in the `PackFile` are ignored, as the game doesn't use them.
+    pub fn get_references_to_key(
+        pack_file: &mut PackFile,
+        schema: &Schema,
+        table_name: &str,
+        key: &str,
+        real_dep_db: &mut [PackedFile],
+    ) -> Result<Vec<DBReference>> {
+
+        // References in the schema use the name of the table without the `_tables` suffix.
+        let ref_table = table_name.strip_suffix("_tables").unwrap_or(table_name);
+        let ref_columns = schema.get_ref_last_definition_db(table_name)?.fields.iter()
+            .filter(|x| x.is_key)
+            .map(|x| x.name.to_owned())
+            .collect::<Vec<String>>();
+
+        let table_names = schema.get_tables_referencing(ref_table);
+        let is_table_referencing = |path: &[String]| match path.get(1) {
+            Some(name) => table_names.contains(name),
+            None => false,
+        };
+
+        let mut references = vec![];
+        for packed_file in pack_file.get_ref_mut_packed_files_by_type(PackedFileType::DB, false) {
+            let path = packed_file.get_path().to_vec();
+            if !is_table_referencing(&path) { continue; }
+            if let Ok(DecodedPackedFile::DB(table)) = packed_file.decode_return_ref_no_locks(schema) {
+                references.append(&mut table.get_references_to_key_in_table(&path, ref_table, &ref_columns, key, false));
+            }
+        }
+
+        for packed_file in real_dep_db.iter_mut().filter(|x| x.get_packed_file_type_by_path() == PackedFileType::DB) {
+            let path = packed_file.get_path().to_vec();
+            if !is_table_referencing(&path) || pack_file.packedfile_exists(&path) { continue; }
+            if let Ok(DecodedPackedFile::DB(table)) = packed_file.decode_return_ref_no_locks(schema) {
+                references.append(&mut table.get_references_to_key_in_table(&path, ref_table, &ref_columns, key, true));
+            }
+        }
+
+        Ok(references)
+    }
+
+    /// This function returns all the cells of this table referencing the provided key of the provided table and columns.
+    fn get_references_to_key_in_table(
+        &self,
+        path: &[String],
+        ref_table: &str,
+        ref_columns: &[String],
+        key: &str,
+        is_from_game_files: bool,
+    ) -> Vec<DBReference> {
+        let columns = self.get_ref_definition().fields.iter()
+            .enumerate()
+            .filter(|(_, field)| match field.is_reference {
+                Some((ref table, ref column)) => table == ref_table && ref_columns.contains(column),
+                None => false,
+            })
+            .collect::<Vec<(usize, &Field)>>();
+
+        let mut references = vec![];
+        for (row_number, row) in self.get_ref_table_data().iter().enumerate() {
+            for (column_number, field) in &columns {
+                if row[*column_number].data_to_string() == key {
+                    references.push(DBReference {
+                        path: path.to_vec(),
+                        column_name: field.name.to_owned(),
+                        column_number: *column_number as u32,
+                        row_number: row_number as i64,
+                        is_from_game_files,
+                    });
+                }
+            }
+        }
+
+        references
+    }
+
     /// This function returns the dependency/lookup data of a column from the dependency database.
     fn get_dependency_data_from_real_dependencies(
         references: &mut BTreeMap<String, String>,
@@ -490,6 +736,8 @@ impl DB {
 
     /// This function returns the dependency/lookup data of each column of a DB Table.
     ///
+    /// Columns that are not references, but have a set of values, return their values instead.
+    ///
     /// The returned references are in the following format:
     /// ```BTreeMap<column_index, Vec<(referenced_value, lookup_value)>```.
     pub fn get_dependency_data(
@@ -516,21 +764,47 @@ impl DB {
                     data.insert(column as i32, references);
                 }
             }
+
+            // Fields with a value set use it as reference data, with the labels as lookups.
+            else if let Some(ref enum_values) = field.enum_values {
+                data.insert(column as i32, enum_values.clone());
+            }
         }
 
         data
     }
 
+    /// This function sets the result of the provided expression as the value of the provided column, in every row matching the provided filter.
+    ///
+    /// It returns the rows edited.
+    pub fn apply_expression(&mut self, column_name: &str, expression: &str, filter: Option<&str>) -> Result<Vec<usize>> {
+        self.table.apply_expression(column_name, expression, filter)
+    }
+
     /// This function imports a TSV file into a decoded table.
+    ///
+    /// It also returns the columns of the TSV file that don't exist in the provided definition, and have been ignored.
     pub fn import_tsv(
         definition: &Definition,
         path: &PathBuf,
         name: &str,
-    ) -> Result<Self> {
-        let table = Table::import_tsv(definition, path, name)?;
+    ) -> Result<(Self, Vec<String>)> {
+        Self::import(definition, path, name, TableFormat::TSV)
+    }
+
+    /// This function imports a file in the provided format into a decoded table.
+    ///
+    /// It also returns the columns of the file that don't exist in the provided definition, and have been ignored.
+    pub fn import(
+        definition: &Definition,
+        path: &PathBuf,
+        name: &str,
+        format: TableFormat,
+    ) -> Result<(Self, Vec<String>)> {
+        let (table, extra_columns) = Table::import(definition, path, name, format)?;
         let mut db = DB::from(table);
         db.name = name.to_owned();
-        Ok(db)
+        Ok((db, extra_columns))
     }
 
     /// This function exports the provided data to a TSV file.
@@ -539,32 +813,49 @@ impl DB {
         path: &PathBuf,
         table_name: &str,
     ) -> Result<()> {
-        self.table.export_tsv(path, table_name)
+        self.export(path, table_name, TableFormat::TSV)
     }
 
-    /// This function imports a TSV file into a binary file on disk.
-    pub fn import_tsv_to_binary_file(
+    /// This function exports the provided data to a file in the provided format.
+    pub fn export(
+        &self,
+        path: &PathBuf,
+        table_name: &str,
+        format: TableFormat,
+    ) -> Result<()> {
+        self.table.export(path, table_name, format)
+    }
+
+    /// This function imports TSV/CSV/JSON/RON files into binary files on disk, deducing their format from their extension.
+    ///
+    /// It returns, for each file with columns that don't exist in the definition, the path of the file and the ignored columns.
+    pub fn import_to_binary_file(
         schema: &Schema,
         source_paths: &[PathBuf],
-    ) -> Result<()> {
+    ) -> Result<Vec<(PathBuf, Vec<String>)>> {
+        let mut extra_columns = vec![];
         for path in source_paths {
             let mut destination = path.clone();
             destination.set_extension("");
-            Table::import_tsv_to_binary_file(&schema, &path, &destination)?;
+            let extra_columns_file = Table::import_to_binary_file(&schema, &path, &destination)?;
+            if !extra_columns_file.is_empty() {
+                extra_columns.push((path.to_path_buf(), extra_columns_file));
+            }
         }
 
-        Ok(())
+        Ok(extra_columns)
     }
 
-    /// This function exports to TSV a binary file on disk.
-    pub fn export_tsv_from_binary_file(
+    /// This function exports binary files on disk to files in the provided format.
+    pub fn export_from_binary_file(
         schema: &Schema,
         source_paths: &[PathBuf],
+        format: TableFormat,
     ) -> Result<()> {
         for path in source_paths {
             let mut destination = path.clone();
-            destination.set_extension("tsv");
-            Table::export_tsv_from_binary_file(&schema, &path, &destination)?;
+            destination.set_extension(format.get_extension());
+            Table::export_from_binary_file(&schema, &path, &destination, format)?;
         }
 
         Ok(())
@@ -0,0 +1,616 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with the code to evaluate expressions over the rows of a table.
+
+This is used to do bulk operations over tables, like "multiply every `cost` by 1.1 where `category` is cavalry".
+An expression can use:
+- Columns, by the name of their field: `cost`, `category`...
+- Literals: numbers (`1`, `1.5`), strings (`"cavalry"`) and booleans (`true`, `false`).
+- Arithmetic operators: `+`, `-`, `*`, `/` and `%`. `+` concatenates if any of its sides is a string.
+- Comparison operators: `==`, `!=`, `<`, `<=`, `>` and `>=`.
+- Logical operators: `&&`, `||` and `!`.
+- Functions: `if(condition, then, else)`, `min(...)`, `max(...)`, `abs(x)`, `round(x)`, `floor(x)`, `ceil(x)`,
+  `upper(s)`, `lower(s)`, `trim(s)`, `len(s)`, `concat(...)`, `replace(s, from, to)`, `contains(s, x)`,
+  `starts_with(s, x)` and `ends_with(s, x)`.
+
+!*/
+
+use std::convert::TryFrom;
+use std::fmt;
+
+use rpfm_error::{ErrorKind, Result};
+
+use crate::schema::{Definition, FieldType};
+
+use super::DecodedData;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct represents a parsed expression, ready to be evaluated over the rows of a table with a specific definition.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Expression {
+
+    /// Root node of the expression.
+    root: Node,
+}
+
+/// This enum represents the values an expression can return.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ExpressionValue {
+    Boolean(bool),
+    Integer(i64),
+    Float(f64),
+    String(String),
+}
+
+/// This enum represents a node of a parsed expression.
+#[derive(Clone, Debug, PartialEq)]
+enum Node {
+    Literal(ExpressionValue),
+    Column(usize),
+    Negate(Box<Node>),
+    Not(Box<Node>),
+    Binary(Operator, Box<Node>, Box<Node>),
+    Function(String, Vec<Node>),
+}
+
+/// This enum represents the binary operators an expression can use.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    And,
+    Or,
+}
+
+/// This enum represents the tokens an expression is made of.
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(String),
+    String(String),
+    Identifier(String),
+    Symbol(&'static str),
+}
+
+/// This struct is used to parse an expression from its tokens.
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    index: usize,
+    definition: &'a Definition,
+}
+
+/// List of functions supported in expressions, with their min and max amount of arguments.
+const FUNCTIONS: [(&str, usize, usize); 16] = [
+    ("if", 3, 3),
+    ("min", 1, usize::MAX),
+    ("max", 1, usize::MAX),
+    ("abs", 1, 1),
+    ("round", 1, 1),
+    ("floor", 1, 1),
+    ("ceil", 1, 1),
+    ("upper", 1, 1),
+    ("lower", 1, 1),
+    ("trim", 1, 1),
+    ("len", 1, 1),
+    ("concat", 1, usize::MAX),
+    ("replace", 3, 3),
+    ("contains", 2, 2),
+    ("starts_with", 2, 2),
+    ("ends_with", 2, 2),
+];
+
+/// List of symbols supported in expressions. Longer symbols go first, so they're not tokenized as two shorter ones.
+const SYMBOLS: [&str; 19] = ["==", "!=", "<=", ">=", "&&", "||", "+", "-", "*", "/", "%", "<", ">", "!", "(", ")", ",", "=", "&"];
+
+//---------------------------------------------------------------------------//
+//                       Implementation of Expression
+//---------------------------------------------------------------------------//
+
+/// Implementation of `Expression`.
+impl Expression {
+
+    /// This function parses an expression for tables with the provided definition.
+    ///
+    /// Columns are resolved here, so using a column that doesn't exist in the definition is a parsing error.
+    pub fn parse(source: &str, definition: &Definition) -> Result<Self> {
+        let mut parser = Parser {
+            tokens: Self::tokenize(source)?,
+            index: 0,
+            definition,
+        };
+
+        let root = parser.parse_or()?;
+        match parser.tokens.get(parser.index) {
+            Some(token) => Err(ErrorKind::TableExpressionParse(format!("Unexpected <i>{}</i>.", token)).into()),
+            None => Ok(Self { root }),
+        }
+    }
+
+    /// This function evaluates the expression over the provided row.
+    pub fn evaluate(&self, row: &[DecodedData]) -> Result<ExpressionValue> {
+        self.root.evaluate(row)
+    }
+
+    /// This function evaluates the expression over the provided row, and turns the result into data for a field of the provided type.
+    pub fn evaluate_as(&self, row: &[DecodedData], field_type: &FieldType) -> Result<DecodedData> {
+        self.evaluate(row)?.to_decoded_data(field_type)
+    }
+
+    /// This function evaluates the expression over the provided row, expecting a boolean. Used for filters.
+    pub fn is_match(&self, row: &[DecodedData]) -> Result<bool> {
+        match self.evaluate(row)? {
+            ExpressionValue::Boolean(value) => Ok(value),
+            value => Err(ErrorKind::TableExpressionEvaluation(format!("The filter returned <i>{}</i> instead of true or false.", value)).into()),
+        }
+    }
+
+    /// This function splits the source of an expression into tokens.
+    fn tokenize(source: &str) -> Result<Vec<Token>> {
+        let chars = source.chars().collect::<Vec<char>>();
+        let mut tokens = vec![];
+        let mut index = 0;
+        while index < chars.len() {
+            let character = chars[index];
+            if character.is_whitespace() {
+                index += 1;
+            }
+
+            else if character.is_ascii_digit() || (character == '.' && chars.get(index + 1).map(|x| x.is_ascii_digit()) == Some(true)) {
+                let start = index;
+                while index < chars.len() && (chars[index].is_ascii_digit() || chars[index] == '.') { index += 1; }
+                tokens.push(Token::Number(chars[start..index].iter().collect()));
+            }
+
+            else if character.is_alphabetic() || character == '_' {
+                let start = index;
+                while index < chars.len() && (chars[index].is_alphanumeric() || chars[index] == '_') { index += 1; }
+                tokens.push(Token::Identifier(chars[start..index].iter().collect()));
+            }
+
+            // Strings use double quotes, and support escaping quotes and backslashes with a backslash.
+            else if character == '"' {
+                let mut string = String::new();
+                index += 1;
+                loop {
+                    match chars.get(index) {
+                        Some('"') => break,
+                        Some('\\') if index + 1 < chars.len() => {
+                            string.push(chars[index + 1]);
+                            index += 2;
+                        }
+                        Some(character) => {
+                            string.push(*character);
+                            index += 1;
+                        }
+                        None => return Err(ErrorKind::TableExpressionParse("Unclosed string.".to_owned()).into()),
+                    }
+                }
+                index += 1;
+                tokens.push(Token::String(string));
+            }
+
+            else {
+                let rest = chars[index..].iter().take(2).collect::<String>();
+                match SYMBOLS.iter().find(|symbol| rest.starts_with(*symbol)) {
+
+                    // Single `=` and `&` are not valid operators, but they're common mistakes, so we give a nicer error for them.
+                    Some(symbol) if *symbol == "=" || *symbol == "&" => return Err(ErrorKind::TableExpressionParse(format!("Unknown operator <i>{}</i>. Did you mean <i>{}{}</i>?", symbol, symbol, symbol)).into()),
+                    Some(symbol) => {
+                        index += symbol.len();
+                        tokens.push(Token::Symbol(symbol));
+                    }
+                    None => return Err(ErrorKind::TableExpressionParse(format!("Unexpected character <i>{}</i>.", character)).into()),
+                }
+            }
+        }
+
+        Ok(tokens)
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                         Implementation of Parser
+//---------------------------------------------------------------------------//
+
+/// Implementation of `Parser`. Each level of precedence has its own function, from the lowest to the highest one.
+impl<'a> Parser<'a> {
+
+    /// This function parses `||` operations.
+    fn parse_or(&mut self) -> Result<Node> {
+        let mut node = self.parse_and()?;
+        while self.next_symbol_is("||") {
+            node = Node::Binary(Operator::Or, Box::new(node), Box::new(self.parse_and()?));
+        }
+        Ok(node)
+    }
+
+    /// This function parses `&&` operations.
+    fn parse_and(&mut self) -> Result<Node> {
+        let mut node = self.parse_comparison()?;
+        while self.next_symbol_is("&&") {
+            node = Node::Binary(Operator::And, Box::new(node), Box::new(self.parse_comparison()?));
+        }
+        Ok(node)
+    }
+
+    /// This function parses comparisons.
+    fn parse_comparison(&mut self) -> Result<Node> {
+        let mut node = self.parse_sum()?;
+        loop {
+            let operator = match self.tokens.get(self.index) {
+                Some(Token::Symbol("==")) => Operator::Equal,
+                Some(Token::Symbol("!=")) => Operator::NotEqual,
+                Some(Token::Symbol("<")) => Operator::Less,
+                Some(Token::Symbol("<=")) => Operator::LessOrEqual,
+                Some(Token::Symbol(">")) => Operator::Greater,
+                Some(Token::Symbol(">=")) => Operator::GreaterOrEqual,
+                _ => return Ok(node),
+            };
+            self.index += 1;
+            node = Node::Binary(operator, Box::new(node), Box::new(self.parse_sum()?));
+        }
+    }
+
+    /// This function parses additions and subtractions.
+    fn parse_sum(&mut self) -> Result<Node> {
+        let mut node = self.parse_product()?;
+        loop {
+            let operator = match self.tokens.get(self.index) {
+                Some(Token::Symbol("+")) => Operator::Add,
+                Some(Token::Symbol("-")) => Operator::Subtract,
+                _ => return Ok(node),
+            };
+            self.index += 1;
+            node = Node::Binary(operator, Box::new(node), Box::new(self.parse_product()?));
+        }
+    }
+
+    /// This function parses multiplications, divisions and remainders.
+    fn parse_product(&mut self) -> Result<Node> {
+        let mut node = self.parse_unary()?;
+        loop {
+            let operator = match self.tokens.get(self.index) {
+                Some(Token::Symbol("*")) => Operator::Multiply,
+                Some(Token::Symbol("/")) => Operator::Divide,
+                Some(Token::Symbol("%")) => Operator::Remainder,
+                _ => return Ok(node),
+            };
+            self.index += 1;
+            node = Node::Binary(operator, Box::new(node), Box::new(self.parse_unary()?));
+        }
+    }
+
+    /// This function parses `-` and `!` operations.
+    fn parse_unary(&mut self) -> Result<Node> {
+        if self.next_symbol_is("-") { Ok(Node::Negate(Box::new(self.parse_unary()?))) }
+        else if self.next_symbol_is("!") { Ok(Node::Not(Box::new(self.parse_unary()?))) }
+        else { self.parse_primary() }
+    }
+
+    /// This function parses literals, columns, function calls and parenthesis.
+    fn parse_primary(&mut self) -> Result<Node> {
+        let token = self.tokens.get(self.index).cloned();
+        self.index += 1;
+        match token {
+            Some(Token::Number(number)) => {
+                if number.contains('.') {
+                    number.parse::<f64>()
+                        .map(|x| Node::Literal(ExpressionValue::Float(x)))
+                        .map_err(|_| ErrorKind::TableExpressionParse(format!("Invalid number <i>{}</i>.", number)).into())
+                } else {
+                    number.parse::<i64>()
+                        .map(|x| Node::Literal(ExpressionValue::Integer(x)))
+                        .map_err(|_| ErrorKind::TableExpressionParse(format!("Invalid number <i>{}</i>.", number)).into())
+                }
+            }
+
+            Some(Token::String(string)) => Ok(Node::Literal(ExpressionValue::String(string))),
+
+            Some(Token::Identifier(identifier)) => {
+                if self.next_symbol_is("(") {
+                    let mut arguments = vec![];
+                    if !self.next_symbol_is(")") {
+                        loop {
+                            arguments.push(self.parse_or()?);
+                            if self.next_symbol_is(")") { break; }
+                            if !self.next_symbol_is(",") {
+                                return Err(ErrorKind::TableExpressionParse(format!("Expected <i>,</i> or <i>)</i> in the arguments of <i>{}</i>.", identifier)).into());
+                            }
+                        }
+                    }
+
+                    match FUNCTIONS.iter().find(|(name, _, _)| *name == identifier) {
+                        Some((_, min, max)) => if arguments.len() < *min || arguments.len() > *max {
+                            Err(ErrorKind::TableExpressionParse(format!("Wrong amount of arguments for <i>{}</i>.", identifier)).into())
+                        } else {
+                            Ok(Node::Function(identifier, arguments))
+                        },
+                        None => Err(ErrorKind::TableExpressionParse(format!("Unknown function <i>{}</i>.", identifier)).into()),
+                    }
+                }
+
+                else if identifier == "true" { Ok(Node::Literal(ExpressionValue::Boolean(true))) }
+                else if identifier == "false" { Ok(Node::Literal(ExpressionValue::Boolean(false))) }
+                else {
+                    match self.definition.fields.iter().position(|x| x.name == identifier) {
+                        Some(column) => Ok(Node::Column(column)),
+                        None => Err(ErrorKind::TableExpressionParse(format!("Unknown column <i>{}</i>.", identifier)).into()),
+                    }
+                }
+            }
+
+            Some(Token::Symbol("(")) => {
+                let node = self.parse_or()?;
+                if self.next_symbol_is(")") { Ok(node) }
+                else { Err(ErrorKind::TableExpressionParse("Unclosed parenthesis.".to_owned()).into()) }
+            }
+
+            Some(token) => Err(ErrorKind::TableExpressionParse(format!("Unexpected <i>{}</i>.", token)).into()),
+            None => Err(ErrorKind::TableExpressionParse("Unexpected end of the expression.".to_owned()).into()),
+        }
+    }
+
+    /// This function checks if the next token is the provided symbol. If it is, it's consumed.
+    fn next_symbol_is(&mut self, symbol: &str) -> bool {
+        match self.tokens.get(self.index) {
+            Some(Token::Symbol(next)) if *next == symbol => {
+                self.index += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                          Implementation of Node
+//---------------------------------------------------------------------------//
+
+/// Implementation of `Node`.
+impl Node {
+
+    /// This function evaluates this node over the provided row.
+    fn evaluate(&self, row: &[DecodedData]) -> Result<ExpressionValue> {
+        match self {
+            Node::Literal(value) => Ok(value.clone()),
+            Node::Column(column) => match row.get(*column) {
+                Some(data) => Ok(ExpressionValue::from(data)),
+                None => Err(ErrorKind::TableExpressionEvaluation("The row doesn't match the definition of the expression.".to_owned()).into()),
+            },
+            Node::Negate(node) => match node.evaluate(row)? {
+                ExpressionValue::Integer(value) => value.checked_neg().map(ExpressionValue::Integer).ok_or_else(|| ErrorKind::TableExpressionEvaluation("Integer overflow.".to_owned()).into()),
+                ExpressionValue::Float(value) => Ok(ExpressionValue::Float(-value)),
+                value => Err(ErrorKind::TableExpressionEvaluation(format!("<i>{}</i> cannot be negated.", value)).into()),
+            },
+            Node::Not(node) => Ok(ExpressionValue::Boolean(!node.evaluate(row)?.as_bool()?)),
+            Node::Binary(operator, left, right) => {
+
+                // Logical operators only evaluate their right side when needed.
+                match operator {
+                    Operator::And => Ok(ExpressionValue::Boolean(left.evaluate(row)?.as_bool()? && right.evaluate(row)?.as_bool()?)),
+                    Operator::Or => Ok(ExpressionValue::Boolean(left.evaluate(row)?.as_bool()? || right.evaluate(row)?.as_bool()?)),
+                    _ => Self::evaluate_binary(*operator, left.evaluate(row)?, right.evaluate(row)?),
+                }
+            }
+            Node::Function(name, arguments) => {
+
+                // `if` only evaluates the branch it returns.
+                if name == "if" {
+                    return if arguments[0].evaluate(row)?.as_bool()? { arguments[1].evaluate(row) } else { arguments[2].evaluate(row) };
+                }
+
+                let arguments = arguments.iter().map(|x| x.evaluate(row)).collect::<Result<Vec<ExpressionValue>>>()?;
+                Self::evaluate_function(name, &arguments)
+            }
+        }
+    }
+
+    /// This function evaluates a binary operation.
+    fn evaluate_binary(operator: Operator, left: ExpressionValue, right: ExpressionValue) -> Result<ExpressionValue> {
+        use ExpressionValue::*;
+        let error = |left: &ExpressionValue, right: &ExpressionValue| Err(ErrorKind::TableExpressionEvaluation(format!("Invalid operation between <i>{}</i> and <i>{}</i>.", left, right)).into());
+        match operator {
+            Operator::Add => match (&left, &right) {
+                (String(_), _) | (_, String(_)) => Ok(String(format!("{}{}", left, right))),
+                (Integer(x), Integer(y)) => x.checked_add(*y).map(Integer).ok_or_else(|| ErrorKind::TableExpressionEvaluation("Integer overflow.".to_owned()).into()),
+                _ => Ok(Float(left.as_f64()? + right.as_f64()?)),
+            },
+            Operator::Subtract => match (&left, &right) {
+                (Integer(x), Integer(y)) => x.checked_sub(*y).map(Integer).ok_or_else(|| ErrorKind::TableExpressionEvaluation("Integer overflow.".to_owned()).into()),
+                _ => Ok(Float(left.as_f64()? - right.as_f64()?)),
+            },
+            Operator::Multiply => match (&left, &right) {
+                (Integer(x), Integer(y)) => x.checked_mul(*y).map(Integer).ok_or_else(|| ErrorKind::TableExpressionEvaluation("Integer overflow.".to_owned()).into()),
+                _ => Ok(Float(left.as_f64()? * right.as_f64()?)),
+            },
+
+            // Divisions always return floats, so `cost / 2` doesn't silently lose the decimals.
+            Operator::Divide => {
+                let divisor = right.as_f64()?;
+                if divisor == 0.0 { Err(ErrorKind::TableExpressionEvaluation("Division by zero.".to_owned()).into()) }
+                else { Ok(Float(left.as_f64()? / divisor)) }
+            },
+            Operator::Remainder => match (&left, &right) {
+                (Integer(_), Integer(0)) => Err(ErrorKind::TableExpressionEvaluation("Division by zero.".to_owned()).into()),
+                (Integer(x), Integer(y)) => x.checked_rem(*y).map(Integer).ok_or_else(|| ErrorKind::TableExpressionEvaluation("Integer overflow.".to_owned()).into()),
+                _ => Ok(Float(left.as_f64()? % right.as_f64()?)),
+            },
+            Operator::Equal | Operator::NotEqual => {
+                let is_equal = match (&left, &right) {
+                    (Boolean(x), Boolean(y)) => x == y,
+                    (String(x), String(y)) => x == y,
+                    (Integer(x), Integer(y)) => x == y,
+                    (Integer(_), Float(_)) | (Float(_), Integer(_)) | (Float(_), Float(_)) => (left.as_f64()? - right.as_f64()?).abs() < f64::EPSILON,
+                    _ => return error(&left, &right),
+                };
+                Ok(Boolean(if operator == Operator::Equal { is_equal } else { !is_equal }))
+            },
+            Operator::Less | Operator::LessOrEqual | Operator::Greater | Operator::GreaterOrEqual => {
+                let ordering = match (&left, &right) {
+                    (String(x), String(y)) => x.partial_cmp(y),
+                    (Integer(x), Integer(y)) => x.partial_cmp(y),
+                    (Boolean(_), _) | (_, Boolean(_)) | (String(_), _) | (_, String(_)) => return error(&left, &right),
+                    _ => left.as_f64()?.partial_cmp(&right.as_f64()?),
+                };
+
+                match ordering {
+                    Some(ordering) => Ok(Boolean(match operator {
+                        Operator::Less => ordering == std::cmp::Ordering::Less,
+                        Operator::LessOrEqual => ordering != std::cmp::Ordering::Greater,
+                        Operator::Greater => ordering == std::cmp::Ordering::Greater,
+                        _ => ordering != std::cmp::Ordering::Less,
+                    })),
+                    None => error(&left, &right),
+                }
+            },
+            Operator::And | Operator::Or => Ok(Boolean(if operator == Operator::And { left.as_bool()? && right.as_bool()? } else { left.as_bool()? || right.as_bool()? })),
+        }
+    }
+
+    /// This function evaluates a function call. The amount of arguments is checked when parsing.
+    fn evaluate_function(name: &str, arguments: &[ExpressionValue]) -> Result<ExpressionValue> {
+        use ExpressionValue::*;
+        match name {
+            "min" | "max" => {
+                let mut result = arguments[0].clone();
+                for argument in &arguments[1..] {
+                    let is_less = Self::evaluate_binary(Operator::Less, argument.clone(), result.clone())?.as_bool()?;
+                    if is_less == (name == "min") { result = argument.clone(); }
+                }
+                Ok(result)
+            },
+            "abs" => match &arguments[0] {
+                Integer(value) => value.checked_abs().map(Integer).ok_or_else(|| ErrorKind::TableExpressionEvaluation("Integer overflow.".to_owned()).into()),
+                value => Ok(Float(value.as_f64()?.abs())),
+            },
+            "round" => Ok(Float(arguments[0].as_f64()?.round())),
+            "floor" => Ok(Float(arguments[0].as_f64()?.floor())),
+            "ceil" => Ok(Float(arguments[0].as_f64()?.ceil())),
+            "upper" => Ok(String(arguments[0].to_string().to_uppercase())),
+            "lower" => Ok(String(arguments[0].to_string().to_lowercase())),
+            "trim" => Ok(String(arguments[0].to_string().trim().to_owned())),
+            "len" => Ok(Integer(arguments[0].to_string().chars().count() as i64)),
+            "concat" => Ok(String(arguments.iter().map(|x| x.to_string()).collect())),
+            "replace" => Ok(String(arguments[0].to_string().replace(&arguments[1].to_string(), &arguments[2].to_string()))),
+            "contains" => Ok(Boolean(arguments[0].to_string().contains(&arguments[1].to_string()))),
+            "starts_with" => Ok(Boolean(arguments[0].to_string().starts_with(&arguments[1].to_string()))),
+            "ends_with" => Ok(Boolean(arguments[0].to_string().ends_with(&arguments[1].to_string()))),
+            _ => Err(ErrorKind::TableExpressionParse(format!("Unknown function <i>{}</i>.", name)).into()),
+        }
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                    Implementation of ExpressionValue
+//---------------------------------------------------------------------------//
+
+/// Implementation of `ExpressionValue`.
+impl ExpressionValue {
+
+    /// This function returns the value as a boolean, if it's one.
+    pub fn as_bool(&self) -> Result<bool> {
+        match self {
+            ExpressionValue::Boolean(value) => Ok(*value),
+            value => Err(ErrorKind::TableExpressionEvaluation(format!("<i>{}</i> is not true or false.", value)).into()),
+        }
+    }
+
+    /// This function returns the value as a float, if it's a number.
+    pub fn as_f64(&self) -> Result<f64> {
+        match self {
+            ExpressionValue::Integer(value) => Ok(*value as f64),
+            ExpressionValue::Float(value) => Ok(*value),
+            value => Err(ErrorKind::TableExpressionEvaluation(format!("<i>{}</i> is not a number.", value)).into()),
+        }
+    }
+
+    /// This function turns the value into data for a field of the provided type.
+    ///
+    /// Floats are rounded when turned into integers, and any value can be turned into a string.
+    pub fn to_decoded_data(&self, field_type: &FieldType) -> Result<DecodedData> {
+        let error = || ErrorKind::TableExpressionEvaluation(format!("<i>{}</i> is not a valid value for a column of type {}.", self, DecodedData::default(field_type)));
+        match field_type {
+            FieldType::Boolean => Ok(DecodedData::Boolean(self.as_bool()?)),
+            FieldType::Float => Ok(DecodedData::Float(self.as_f64()? as f32)),
+            FieldType::Integer => match self {
+                ExpressionValue::Integer(value) => i32::try_from(*value).map(DecodedData::Integer).map_err(|_| error().into()),
+                _ => {
+                    let value = self.as_f64()?.round();
+                    if (f64::from(i32::MIN)..=f64::from(i32::MAX)).contains(&value) { Ok(DecodedData::Integer(value as i32)) }
+                    else { Err(error().into()) }
+                }
+            },
+            FieldType::LongInteger => match self {
+                ExpressionValue::Integer(value) => Ok(DecodedData::LongInteger(*value)),
+                _ => Ok(DecodedData::LongInteger(self.as_f64()?.round() as i64)),
+            },
+            FieldType::StringU8 => Ok(DecodedData::StringU8(self.to_string())),
+            FieldType::StringU16 => Ok(DecodedData::StringU16(self.to_string())),
+            FieldType::OptionalStringU8 => Ok(DecodedData::OptionalStringU8(self.to_string())),
+            FieldType::OptionalStringU16 => Ok(DecodedData::OptionalStringU16(self.to_string())),
+            FieldType::Sequence(_) => Err(error().into()),
+        }
+    }
+}
+
+/// Implementation to create an `ExpressionValue` from a `DecodedData`.
+impl From<&DecodedData> for ExpressionValue {
+    fn from(data: &DecodedData) -> Self {
+        match data {
+            DecodedData::Boolean(data) => ExpressionValue::Boolean(*data),
+
+            // Floats go through their string representation, so we don't get precision garbage when turning them into f64.
+            DecodedData::Float(data) => ExpressionValue::Float(data.to_string().parse::<f64>().unwrap_or_default()),
+            DecodedData::Integer(data) => ExpressionValue::Integer(i64::from(*data)),
+            DecodedData::LongInteger(data) => ExpressionValue::Integer(*data),
+            DecodedData::StringU8(data) |
+            DecodedData::StringU16(data) |
+            DecodedData::OptionalStringU8(data) |
+            DecodedData::OptionalStringU16(data) => ExpressionValue::String(data.to_owned()),
+            DecodedData::Sequence(_) => ExpressionValue::String(data.data_to_string()),
+        }
+    }
+}
+
+/// Display implementation of `ExpressionValue`.
+impl fmt::Display for ExpressionValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExpressionValue::Boolean(value) => write!(f, "{}", value),
+            ExpressionValue::Integer(value) => write!(f, "{}", value),
+            ExpressionValue::Float(value) => write!(f, "{}", value),
+            ExpressionValue::String(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// Display implementation of `Token`.
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Token::Number(value) | Token::Identifier(value) => write!(f, "{}", value),
+            Token::String(value) => write!(f, "\"{}\"", value),
+            Token::Symbol(value) => write!(f, "{}", value),
+        }
+    }
+}
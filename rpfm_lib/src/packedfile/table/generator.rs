@@ -0,0 +1,232 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with the code to generate random tables from their definition, for tests and fuzzing.
+
+The generated tables are random, but valid:
+- Every cell has the type of its field, and strings respect the `max_length` of their field.
+- Rows have unique keys. If the keys cannot be unique (like a table with only a boolean key), fewer rows are generated.
+- Sequences are populated with their own random rows, including empty ones.
+- References and fields with a set of values take their values from the reference data, if provided.
+
+Values are biased towards the edge cases that tend to break encoders: empty strings, non-ASCII characters,
+UTF-16 surrogate pairs, escaped tabs and newlines, and the limits of each numeric type.
+
+The same seed always generates the same tables, so failing cases can be reproduced.
+!*/
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::packedfile::table::db::DB;
+use crate::packedfile::table::loc::Loc;
+use crate::packfile::PackFile;
+use crate::schema::{Definition, FieldType, Schema};
+
+use super::{DecodedData, Table};
+
+/// Default max amount of rows of a generated table.
+const DEFAULT_MAX_ROWS: usize = 32;
+
+/// Default max amount of rows of a generated sequence.
+const DEFAULT_MAX_SEQUENCE_ROWS: usize = 4;
+
+/// Default max length of a generated string, in characters.
+const DEFAULT_MAX_STRING_LENGTH: usize = 24;
+
+/// Max amount of tries to generate a row with unique keys before giving up on it.
+const MAX_KEY_TRIES: usize = 16;
+
+/// Max depth of nested sequences. Deeper sequences are left empty.
+const MAX_SEQUENCE_DEPTH: usize = 4;
+
+/// Pieces used to build random strings. The escaped ones are how the decoder stores tabs and newlines.
+const STRING_PIECES: [&str; 16] = [
+    "a", "Z", "0", "_", " ", "\"", "'", "[", "]", ",", "\\\\t", "\\\\n", "é", "ß", "ł", "字",
+];
+
+/// Characters outside the Basic Multilingual Plane, encoded as surrogate pairs in UTF-16.
+const SURROGATE_PAIRS: [&str; 3] = ["😀", "𝄞", "𐍈"];
+
+/// Floats used as edge cases. NaN is not included, as it's never equal to itself, so tables with it never match after a round trip.
+const EDGE_FLOATS: [f32; 8] = [0.0, -1.0, 0.5, f32::MAX, f32::MIN, f32::MIN_POSITIVE, f32::INFINITY, f32::NEG_INFINITY];
+
+/// Integers used as edge cases.
+const EDGE_INTEGERS: [i32; 4] = [0, -1, i32::MAX, i32::MIN];
+
+/// Long integers used as edge cases.
+const EDGE_LONG_INTEGERS: [i64; 4] = [0, -1, i64::MAX, i64::MIN];
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct represents a generator of random tables.
+#[derive(Clone, Debug)]
+pub struct TableGenerator {
+
+    /// State of the random number generator.
+    state: u64,
+
+    /// Max amount of rows of a generated table.
+    pub max_rows: usize,
+
+    /// Max amount of rows of a generated sequence.
+    pub max_sequence_rows: usize,
+
+    /// Max length of a generated string, in characters. Fields with a lower `max_length` use theirs.
+    pub max_string_length: usize,
+
+    /// Valid values for each column, in the format returned by `DB::get_dependency_data`.
+    reference_data: BTreeMap<i32, BTreeMap<String, String>>,
+}
+
+//---------------------------------------------------------------------------//
+//                       Implementation of TableGenerator
+//---------------------------------------------------------------------------//
+
+/// Implementation of `TableGenerator`.
+impl TableGenerator {
+
+    /// This function creates a new `TableGenerator` with the provided seed.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            state: seed,
+            max_rows: DEFAULT_MAX_ROWS,
+            max_sequence_rows: DEFAULT_MAX_SEQUENCE_ROWS,
+            max_string_length: DEFAULT_MAX_STRING_LENGTH,
+            reference_data: BTreeMap::new(),
+        }
+    }
+
+    /// This function sets the valid values of each column, in the format returned by `DB::get_dependency_data`.
+    ///
+    /// Columns with values only take them, and columns without them take random values. Empty columns are ignored.
+    pub fn set_reference_data(&mut self, reference_data: BTreeMap<i32, BTreeMap<String, String>>) {
+        self.reference_data = reference_data;
+    }
+
+    /// This function takes the valid values of the references and sets of values of the provided definition from the provided `PackFile`.
+    pub fn set_reference_data_from_packfile(&mut self, pack_file: &mut PackFile, schema: &Schema, definition: &Definition) {
+        self.reference_data = DB::get_dependency_data(pack_file, schema, definition, &mut vec![], &[], &[]);
+    }
+
+    /// This function generates a random `Table` with the provided definition.
+    pub fn generate_table(&mut self, definition: &Definition) -> Table {
+        let row_count = self.next_index(self.max_rows + 1);
+        self.generate_table_with_rows(definition, row_count, 0)
+    }
+
+    /// This function generates a random `DB` with the provided name and definition.
+    pub fn generate_db(&mut self, name: &str, definition: &Definition) -> DB {
+        let mut db = DB::new(name, None, definition);
+        db.set_table_data(self.generate_table(definition).get_ref_table_data()).unwrap();
+        db
+    }
+
+    /// This function generates a random `Loc` with the provided definition.
+    pub fn generate_loc(&mut self, definition: &Definition) -> Loc {
+        let mut loc = Loc::new(definition);
+        loc.set_table_data(self.generate_table(definition).get_ref_table_data()).unwrap();
+        loc
+    }
+
+    /// This function generates a `Table` with up to the provided amount of rows, and unique keys.
+    ///
+    /// Reference data is only used for the top table, as its column indexes don't apply to sequences.
+    fn generate_table_with_rows(&mut self, definition: &Definition, row_count: usize, depth: usize) -> Table {
+        let key_columns = definition.fields.iter().enumerate().filter(|(_, x)| x.is_key).map(|(x, _)| x).collect::<Vec<usize>>();
+        let mut keys = BTreeSet::new();
+        let mut rows = vec![];
+
+        for _ in 0..row_count {
+            for _ in 0..MAX_KEY_TRIES {
+                let row = definition.fields.iter().enumerate().map(|(column, field)| {
+                    let reference_data = if depth == 0 { self.reference_data.get(&(column as i32)).filter(|x| !x.is_empty()).cloned() } else { None };
+                    match reference_data {
+                        Some(reference_data) => self.generate_reference(&field.field_type, &reference_data),
+                        None => self.generate_data(&field.field_type, field.max_length, depth),
+                    }
+                }).collect::<Vec<DecodedData>>();
+
+                if key_columns.is_empty() || keys.insert(key_columns.iter().map(|x| row[*x].data_to_string()).collect::<Vec<String>>()) {
+                    rows.push(row);
+                    break;
+                }
+            }
+        }
+
+        let mut table = Table::new(definition);
+        table.set_table_data(&rows).unwrap();
+        table
+    }
+
+    /// This function returns one of the provided reference values, or random data if none of them is valid for the field type.
+    fn generate_reference(&mut self, field_type: &FieldType, reference_data: &BTreeMap<String, String>) -> DecodedData {
+        let value = reference_data.keys().nth(self.next_index(reference_data.len())).unwrap();
+        DecodedData::new_from_type_and_string(field_type, value).unwrap_or_else(|_| self.generate_data(field_type, 0, 0))
+    }
+
+    /// This function generates random data of the provided type.
+    fn generate_data(&mut self, field_type: &FieldType, max_length: i32, depth: usize) -> DecodedData {
+        match field_type {
+            FieldType::Boolean => DecodedData::Boolean(self.next_index(2) == 0),
+            FieldType::Float => DecodedData::Float(if self.next_index(4) == 0 { EDGE_FLOATS[self.next_index(EDGE_FLOATS.len())] }
+                else { (self.next() as i32) as f32 / 1024.0 }),
+            FieldType::Integer => DecodedData::Integer(if self.next_index(4) == 0 { EDGE_INTEGERS[self.next_index(EDGE_INTEGERS.len())] }
+                else { self.next() as i32 }),
+            FieldType::LongInteger => DecodedData::LongInteger(if self.next_index(4) == 0 { EDGE_LONG_INTEGERS[self.next_index(EDGE_LONG_INTEGERS.len())] }
+                else { self.next() as i64 }),
+            FieldType::StringU8 => DecodedData::StringU8(self.generate_string(max_length)),
+            FieldType::StringU16 => DecodedData::StringU16(self.generate_string(max_length)),
+            FieldType::OptionalStringU8 => DecodedData::OptionalStringU8(self.generate_string(max_length)),
+            FieldType::OptionalStringU16 => DecodedData::OptionalStringU16(self.generate_string(max_length)),
+            FieldType::Sequence(definition) => {
+                let row_count = if depth < MAX_SEQUENCE_DEPTH { self.next_index(self.max_sequence_rows + 1) } else { 0 };
+                DecodedData::Sequence(self.generate_table_with_rows(definition, row_count, depth + 1))
+            }
+        }
+    }
+
+    /// This function generates a random string, empty one of each four times, with up to the provided length in characters.
+    fn generate_string(&mut self, max_length: i32) -> String {
+        let max_length = if max_length > 0 { (max_length as usize).min(self.max_string_length) } else { self.max_string_length };
+        if max_length == 0 || self.next_index(4) == 0 {
+            return String::new();
+        }
+
+        // Escaped characters count as more than one character, so we count the characters as we go.
+        let length = 1 + self.next_index(max_length);
+        let mut string = String::new();
+        while string.chars().count() < length {
+            let piece = if self.next_index(8) == 0 { SURROGATE_PAIRS[self.next_index(SURROGATE_PAIRS.len())] }
+                else { STRING_PIECES[self.next_index(STRING_PIECES.len())] };
+
+            if string.chars().count() + piece.chars().count() > length { break; }
+            string.push_str(piece);
+        }
+
+        if string.is_empty() { "a".to_owned() } else { string }
+    }
+
+    /// This function returns a random index lower than the provided one. The provided one must be greater than zero.
+    fn next_index(&mut self, max: usize) -> usize {
+        (self.next() % max as u64) as usize
+    }
+
+    /// This function returns the next random number, using SplitMix64.
+    fn next(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut value = self.state;
+        value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        value ^ (value >> 31)
+    }
+}
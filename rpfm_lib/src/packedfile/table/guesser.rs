@@ -0,0 +1,456 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with the code to guess the structure of a DB Table we don't have a definition for.
+
+After a game patch, tables may change their structure, and finding the new one by hand in the decoder is slow.
+This module searches for sequences of field types that decode the entire table, without bytes left, and ranks them:
+- By plausibility of the decoded data, like floats with sane values or strings with data in them.
+- By similarity to the older versions of the same table in the schema, if any.
+
+The older versions are also used as starting points for the search, as new versions usually add, remove or change
+only a few fields. Sequences are not guessed, as they're too rare to be worth the extra search time.
+!*/
+
+use byteorder::{ByteOrder, LittleEndian};
+
+use std::collections::BTreeSet;
+
+use crate::schema::{Definition, Field, FieldType};
+
+/// Max amount of fields a guessed definition can have.
+const MAX_FIELDS: usize = 128;
+
+/// Max amount of nodes the search can visit before giving up.
+const MAX_SEARCH_STEPS: usize = 50_000;
+
+/// Max amount of complete candidates the search can find before giving up.
+const MAX_CANDIDATES: usize = 256;
+
+/// Biggest absolute value we consider a plausible integer.
+const MAX_PLAUSIBLE_INTEGER: f64 = 10_000_000.0;
+
+/// Smallest absolute value, not counting zero, we consider a plausible float.
+const MIN_PLAUSIBLE_FLOAT: f64 = 0.000_01;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct represents a guessed definition for a DB Table.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DefinitionGuess {
+
+    /// Fields of the guessed definition. Fields matching a field of the most similar older version take its name and data.
+    pub fields: Vec<Field>,
+
+    /// How plausible the data decoded with this definition is, from 0 to 1.
+    pub plausibility: f64,
+
+    /// Version of the most similar older definition, if any.
+    pub similar_version: Option<i32>,
+
+    /// How similar this definition is to the most similar older definition, from 0 to 1.
+    pub similarity: f64,
+
+    /// Final score used to rank the guesses, from 0 to 1.
+    pub score: f64,
+}
+
+/// This enum represents the raw shapes a field can have in the data.
+///
+/// Integers and floats have the same shape, so we decide which one they are after the search, looking at all their values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Slot {
+    Boolean,
+    Four,
+    LongInteger,
+    StringU8,
+    StringU16,
+    OptionalStringU8,
+    OptionalStringU16,
+}
+
+/// This struct holds the state of the search.
+struct Search<'a> {
+    data: &'a [u8],
+    index: usize,
+    entry_count: usize,
+    steps: usize,
+    candidates: BTreeSet<Vec<Slot>>,
+}
+
+//---------------------------------------------------------------------------//
+//                    Implementation of DefinitionGuess
+//---------------------------------------------------------------------------//
+
+/// Implementation of `DefinitionGuess`.
+impl DefinitionGuess {
+
+    /// This function guesses the definitions that can decode the data of a DB Table, best guesses first.
+    ///
+    /// It requires the data of the table, the index where its first row starts (after the header), its amount of rows,
+    /// and the older definitions of the table, if any. It returns up to `max_guesses` guesses.
+    pub fn guess(
+        data: &[u8],
+        index: usize,
+        entry_count: u32,
+        reference_definitions: &[Definition],
+        max_guesses: usize,
+    ) -> Vec<Self> {
+        if entry_count == 0 { return vec![]; }
+        let mut search = Search {
+            data,
+            index,
+            entry_count: entry_count as usize,
+            steps: 0,
+            candidates: BTreeSet::new(),
+        };
+
+        // Older versions first, as they're our best bet.
+        for definition in reference_definitions {
+            if let Some(slots) = definition.fields.iter().map(|x| Slot::from_field_type(&x.field_type)).collect::<Option<Vec<Slot>>>() {
+                search.check_around(&slots);
+            }
+        }
+
+        search.search(&mut vec![], index);
+
+        let mut guesses = search.candidates.iter()
+            .filter_map(|slots| Self::new(&search, slots, reference_definitions))
+            .collect::<Vec<Self>>();
+
+        guesses.sort_by(|x, y| y.score.partial_cmp(&x.score).unwrap_or(std::cmp::Ordering::Equal).then(x.fields.len().cmp(&y.fields.len())));
+        guesses.truncate(max_guesses);
+        guesses
+    }
+
+    /// This function returns a `Definition` with the fields of this guess.
+    pub fn get_definition(&self, version: i32) -> Definition {
+        let mut definition = Definition::new(version);
+        definition.fields = self.fields.to_vec();
+        definition
+    }
+
+    /// This function creates a guess from a sequence of slots that decodes the entire table.
+    fn new(search: &Search, slots: &[Slot], reference_definitions: &[Definition]) -> Option<Self> {
+        let offsets = search.get_cell_offsets(slots)?;
+        let mut field_types = Vec::with_capacity(slots.len());
+        let mut plausibility = 0.0;
+        for (column, slot) in slots.iter().enumerate() {
+            let cells = offsets.iter().map(|row| row[column]).collect::<Vec<usize>>();
+            let (field_type, column_plausibility) = slot.get_field_type(search.data, &cells);
+            field_types.push(field_type);
+            plausibility += column_plausibility;
+        }
+        plausibility /= slots.len() as f64;
+
+        // Find the most similar older version, and take the names of the fields that match.
+        let mut fields = field_types.iter().enumerate()
+            .map(|(column, field_type)| Field { name: format!("unknown_{}", column), field_type: field_type.clone(), ..Default::default() })
+            .collect::<Vec<Field>>();
+
+        let mut similar_version = None;
+        let mut similarity = 0.0;
+        let mut matches = vec![];
+        for definition in reference_definitions {
+            let old_field_types = definition.fields.iter().map(|x| x.field_type.clone()).collect::<Vec<FieldType>>();
+            let definition_matches = get_longest_common_subsequence(&field_types, &old_field_types);
+            let definition_similarity = 2.0 * definition_matches.len() as f64 / (field_types.len() + old_field_types.len()) as f64;
+            if similar_version.is_none() || definition_similarity > similarity {
+                similar_version = Some(definition.version);
+                similarity = definition_similarity;
+                matches = definition_matches.iter().map(|(new, old)| (*new, definition.fields[*old].clone())).collect();
+            }
+        }
+
+        for (column, field) in matches {
+            fields[column] = field;
+        }
+
+        let score = if similar_version.is_some() { (plausibility + similarity) / 2.0 } else { plausibility };
+        Some(Self {
+            fields,
+            plausibility,
+            similar_version,
+            similarity,
+            score,
+        })
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                        Implementation of Search
+//---------------------------------------------------------------------------//
+
+/// Implementation of `Search`.
+impl<'a> Search<'a> {
+
+    /// This function searches for slot sequences decoding the entire table, following the data of the first row.
+    fn search(&mut self, slots: &mut Vec<Slot>, offset: usize) {
+        if self.steps >= MAX_SEARCH_STEPS || self.candidates.len() >= MAX_CANDIDATES { return; }
+        self.steps += 1;
+
+        // If the rest of the rows cannot fit in the data left, there is no need to keep going.
+        let min_row_size = slots.iter().map(|x| x.get_min_size()).sum::<usize>();
+        if offset + self.entry_count.saturating_sub(1) * min_row_size > self.data.len() { return; }
+
+        if !slots.is_empty() && self.is_complete(slots) {
+            self.candidates.insert(slots.to_vec());
+        }
+
+        if slots.len() >= MAX_FIELDS { return; }
+        for slot in Slot::get_search_order(self.data, offset) {
+            if let Some(next_offset) = slot.read(self.data, offset) {
+                slots.push(slot);
+                self.search(slots, next_offset);
+                slots.pop();
+            }
+        }
+    }
+
+    /// This function checks the provided slot sequence, and the ones with a slot more or less than it.
+    fn check_around(&mut self, slots: &[Slot]) {
+        let mut variants = vec![slots.to_vec()];
+        for position in 0..=slots.len() {
+            if position < slots.len() {
+                let mut variant = slots.to_vec();
+                variant.remove(position);
+                variants.push(variant);
+            }
+
+            for slot in &Slot::ALL {
+                let mut variant = slots.to_vec();
+                variant.insert(position, *slot);
+                variants.push(variant);
+            }
+        }
+
+        for variant in variants {
+            if !variant.is_empty() && self.is_complete(&variant) {
+                self.candidates.insert(variant);
+            }
+        }
+    }
+
+    /// This function returns if the provided slot sequence decodes all the rows, ending exactly at the end of the data.
+    fn is_complete(&self, slots: &[Slot]) -> bool {
+
+        // Tables without strings have a fixed row size, so we can check the size before decoding anything.
+        if slots.iter().all(|x| x.get_fixed_size().is_some()) {
+            let row_size = slots.iter().filter_map(|x| x.get_fixed_size()).sum::<usize>();
+            if self.index + row_size * self.entry_count != self.data.len() { return false; }
+        }
+
+        let mut offset = self.index;
+        for _ in 0..self.entry_count {
+            for slot in slots {
+                match slot.read(self.data, offset) {
+                    Some(next_offset) => offset = next_offset,
+                    None => return false,
+                }
+            }
+        }
+        offset == self.data.len()
+    }
+
+    /// This function returns the offset of each cell of each row decoded with the provided slot sequence.
+    fn get_cell_offsets(&self, slots: &[Slot]) -> Option<Vec<Vec<usize>>> {
+        let mut offset = self.index;
+        let mut rows = Vec::with_capacity(self.entry_count);
+        for _ in 0..self.entry_count {
+            let mut row = Vec::with_capacity(slots.len());
+            for slot in slots {
+                row.push(offset);
+                offset = slot.read(self.data, offset)?;
+            }
+            rows.push(row);
+        }
+        if offset == self.data.len() { Some(rows) } else { None }
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                         Implementation of Slot
+//---------------------------------------------------------------------------//
+
+/// Implementation of `Slot`.
+impl Slot {
+
+    /// All the slots, in the order we try them after the most likely ones.
+    const ALL: [Slot; 7] = [
+        Slot::Four,
+        Slot::Boolean,
+        Slot::StringU8,
+        Slot::OptionalStringU8,
+        Slot::LongInteger,
+        Slot::StringU16,
+        Slot::OptionalStringU16,
+    ];
+
+    /// This function returns the slot of a field type, if it can be guessed.
+    fn from_field_type(field_type: &FieldType) -> Option<Self> {
+        match field_type {
+            FieldType::Boolean => Some(Slot::Boolean),
+            FieldType::Float | FieldType::Integer => Some(Slot::Four),
+            FieldType::LongInteger => Some(Slot::LongInteger),
+            FieldType::StringU8 => Some(Slot::StringU8),
+            FieldType::StringU16 => Some(Slot::StringU16),
+            FieldType::OptionalStringU8 => Some(Slot::OptionalStringU8),
+            FieldType::OptionalStringU16 => Some(Slot::OptionalStringU16),
+            FieldType::Sequence(_) => None,
+        }
+    }
+
+    /// This function returns the slots in the order we should try them at the provided offset.
+    ///
+    /// Strings with text in them are rarely a coincidence, so they go first.
+    fn get_search_order(data: &[u8], offset: usize) -> Vec<Self> {
+        let mut slots = Self::ALL.to_vec();
+        slots.sort_by_key(|slot| if slot.get_text_size(data, offset).unwrap_or(0) > 0 { 0 } else { 1 });
+        slots
+    }
+
+    /// This function returns the size of the slot, if it doesn't depend on its data.
+    fn get_fixed_size(self) -> Option<usize> {
+        match self {
+            Slot::Boolean => Some(1),
+            Slot::Four => Some(4),
+            Slot::LongInteger => Some(8),
+            _ => None,
+        }
+    }
+
+    /// This function returns the minimum size the slot can have.
+    fn get_min_size(self) -> usize {
+        match self {
+            Slot::StringU8 | Slot::StringU16 => 2,
+            Slot::OptionalStringU8 | Slot::OptionalStringU16 => 1,
+            _ => self.get_fixed_size().unwrap_or(0),
+        }
+    }
+
+    /// This function returns the size of the text of the slot at the provided offset, if it's a valid string slot.
+    fn get_text_size(self, data: &[u8], offset: usize) -> Option<usize> {
+        let header_size = match self {
+            Slot::StringU8 | Slot::StringU16 => 2,
+            Slot::OptionalStringU8 | Slot::OptionalStringU16 => if *data.get(offset)? == 0 { 1 } else { 3 },
+            _ => return None,
+        };
+        Some(self.read(data, offset)? - offset - header_size)
+    }
+
+    /// This function returns where the next slot starts if the data at the provided offset is valid for this slot.
+    ///
+    /// Strings with control characters in them are considered invalid, as they're almost always misaligned data.
+    fn read(self, data: &[u8], offset: usize) -> Option<usize> {
+        match self {
+            Slot::Boolean => if *data.get(offset)? <= 1 { Some(offset + 1) } else { None },
+            Slot::Four | Slot::LongInteger => {
+                let next_offset = offset + self.get_fixed_size()?;
+                if next_offset <= data.len() { Some(next_offset) } else { None }
+            }
+            Slot::StringU8 => {
+                let size = LittleEndian::read_u16(data.get(offset..offset + 2)?) as usize;
+                let bytes = data.get(offset + 2..offset + 2 + size)?;
+                let string = std::str::from_utf8(bytes).ok()?;
+                if string.chars().any(is_invalid_char) { None } else { Some(offset + 2 + size) }
+            }
+            Slot::StringU16 => {
+                let size = LittleEndian::read_u16(data.get(offset..offset + 2)?) as usize * 2;
+                let bytes = data.get(offset + 2..offset + 2 + size)?;
+                let units = bytes.chunks_exact(2).map(LittleEndian::read_u16);
+                for character in std::char::decode_utf16(units) {
+                    if is_invalid_char(character.ok()?) { return None; }
+                }
+                Some(offset + 2 + size)
+            }
+            Slot::OptionalStringU8 | Slot::OptionalStringU16 => match data.get(offset)? {
+                0 => Some(offset + 1),
+                1 => if self == Slot::OptionalStringU8 { Slot::StringU8.read(data, offset + 1) } else { Slot::StringU16.read(data, offset + 1) },
+                _ => None,
+            }
+        }
+    }
+
+    /// This function returns the field type of a column of this slot, and how plausible its data is, from 0 to 1.
+    fn get_field_type(self, data: &[u8], cells: &[usize]) -> (FieldType, f64) {
+        match self {
+            Slot::Boolean => {
+                let is_constant = cells.iter().all(|x| data[*x] == data[cells[0]]);
+                (FieldType::Boolean, if is_constant { 0.7 } else { 1.0 })
+            }
+
+            // Floats read as integers give huge numbers, and integers read as floats give tiny or invalid numbers.
+            Slot::Four => {
+                let integers = cells.iter().map(|x| LittleEndian::read_i32(&data[*x..]) as f64).collect::<Vec<f64>>();
+                let floats = cells.iter().map(|x| LittleEndian::read_f32(&data[*x..]) as f64).collect::<Vec<f64>>();
+                let plausible_integers = integers.iter().filter(|x| x.abs() <= MAX_PLAUSIBLE_INTEGER).count();
+                let plausible_floats = floats.iter().filter(|x| **x == 0.0 || (x.is_finite() && x.abs() >= MIN_PLAUSIBLE_FLOAT && x.abs() <= MAX_PLAUSIBLE_INTEGER)).count();
+                if plausible_floats == cells.len() && plausible_integers < cells.len() {
+                    (FieldType::Float, 1.0)
+                }
+                else {
+                    let is_constant = integers.iter().all(|x| (x - integers[0]).abs() < f64::EPSILON);
+                    (FieldType::Integer, if is_constant { 0.8 } else { plausible_integers as f64 / cells.len() as f64 })
+                }
+            }
+            Slot::LongInteger => {
+                let plausible = cells.iter().filter(|x| (LittleEndian::read_i64(&data[**x..]) as f64).abs() <= MAX_PLAUSIBLE_INTEGER).count();
+                (FieldType::LongInteger, 0.8 * plausible as f64 / cells.len() as f64)
+            }
+
+            // For strings, we only care if there is text in them. Otherwise, they're probably something else.
+            Slot::StringU8 | Slot::StringU16 | Slot::OptionalStringU8 | Slot::OptionalStringU16 => {
+                let has_text = cells.iter().any(|x| self.get_text_size(data, *x).unwrap_or(0) > 0);
+                match self {
+                    Slot::StringU8 => (FieldType::StringU8, if has_text { 1.0 } else { 0.4 }),
+                    Slot::StringU16 => (FieldType::StringU16, if has_text { 0.8 } else { 0.3 }),
+                    Slot::OptionalStringU8 => (FieldType::OptionalStringU8, if has_text { 0.9 } else { 0.4 }),
+                    _ => (FieldType::OptionalStringU16, if has_text { 0.7 } else { 0.3 }),
+                }
+            }
+        }
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                              Utility functions
+//---------------------------------------------------------------------------//
+
+/// This function returns if a character is not expected in the text of a table.
+fn is_invalid_char(character: char) -> bool {
+    character.is_control() && !matches!(character, '\t' | '\n' | '\r')
+}
+
+/// This function returns the matching positions `(position in a, position in b)` of the longest common subsequence of two lists of field types.
+fn get_longest_common_subsequence(a: &[FieldType], b: &[FieldType]) -> Vec<(usize, usize)> {
+    let mut lengths = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for index_a in (0..a.len()).rev() {
+        for index_b in (0..b.len()).rev() {
+            lengths[index_a][index_b] = if a[index_a] == b[index_b] { lengths[index_a + 1][index_b + 1] + 1 }
+            else { lengths[index_a + 1][index_b].max(lengths[index_a][index_b + 1]) };
+        }
+    }
+
+    let mut matches = vec![];
+    let (mut index_a, mut index_b) = (0, 0);
+    while index_a < a.len() && index_b < b.len() {
+        if a[index_a] == b[index_b] {
+            matches.push((index_a, index_b));
+            index_a += 1;
+            index_b += 1;
+        }
+        else if lengths[index_a + 1][index_b] >= lengths[index_a][index_b + 1] { index_a += 1; }
+        else { index_b += 1; }
+    }
+    matches
+}
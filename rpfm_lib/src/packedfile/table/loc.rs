@@ -15,13 +15,14 @@ Loc Tables are the files which contain all the localisation strings used by the
 They're just tables with a key, a text, and a boolean column.
 !*/
 
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 use rpfm_error::{ErrorKind, Result};
 
 use crate::common::{decoder::Decoder, encoder::Encoder};
 use super::DecodedData;
-use super::Table;
+use super::{Table, TableFormat};
 
 use crate::schema::*;
 
@@ -40,6 +41,12 @@ pub const TSV_NAME_LOC: &str = "Loc PackedFile";
 /// Extension used by Loc PackedFiles.
 pub const EXTENSION: &str = ".loc";
 
+/// Name of the column containing the key of each Loc entry.
+const COLUMN_KEY: &str = "key";
+
+/// Name of the column containing the text of each Loc entry.
+const COLUMN_TEXT: &str = "text";
+
 //---------------------------------------------------------------------------//
 //                              Enum & Structs
 //---------------------------------------------------------------------------//
@@ -113,6 +120,58 @@ impl Loc {
         self.table.set_table_data(data)
     }
 
+    /// This function returns the keys of all the entries of this Loc Table.
+    pub fn get_keys(&self) -> Vec<String> {
+        let column = self.get_column_index(COLUMN_KEY, 0);
+        self.table.entries.iter().filter_map(|row| row.get(column)).map(|data| data.data_to_string()).collect()
+    }
+
+    /// This function renames the keys of this Loc Table following the provided `old_key -> new_key` map.
+    ///
+    /// It returns if any key has been renamed.
+    pub fn rename_keys(&mut self, renamed_keys: &BTreeMap<String, String>) -> bool {
+        let column = self.get_column_index(COLUMN_KEY, 0);
+        let mut renamed = false;
+        for row in &mut self.table.entries {
+            if let Some(DecodedData::StringU16(ref mut key)) = row.get_mut(column) {
+                if let Some(new_key) = renamed_keys.get(key) {
+                    *key = new_key.to_owned();
+                    renamed = true;
+                }
+            }
+        }
+        renamed
+    }
+
+    /// This function returns the key and text of all the entries of this Loc Table.
+    pub fn get_texts(&self) -> Vec<(String, String)> {
+        let column_key = self.get_column_index(COLUMN_KEY, 0);
+        let column_text = self.get_column_index(COLUMN_TEXT, 1);
+        self.table.entries.iter()
+            .filter_map(|row| Some((row.get(column_key)?.data_to_string(), row.get(column_text)?.data_to_string())))
+            .collect()
+    }
+
+    /// This function adds a new entry to this Loc Table for each one of the provided `(key, text)` pairs.
+    ///
+    /// The rest of the columns of the new entries are filled with their default values.
+    pub fn add_entries(&mut self, entries: &[(String, String)]) {
+        let column_key = self.get_column_index(COLUMN_KEY, 0);
+        let column_text = self.get_column_index(COLUMN_TEXT, 1);
+        let default_row = self.table.definition.fields.iter().map(|field| DecodedData::default(&field.field_type)).collect::<Vec<DecodedData>>();
+        for (key, text) in entries {
+            let mut row = default_row.to_vec();
+            if let Some(DecodedData::StringU16(ref mut data)) = row.get_mut(column_key) { *data = key.to_owned(); }
+            if let Some(DecodedData::StringU16(ref mut data)) = row.get_mut(column_text) { *data = text.to_owned(); }
+            self.table.entries.push(row);
+        }
+    }
+
+    /// This function returns the index of the column with the provided name, or the fallback index if there is no column with that name.
+    fn get_column_index(&self, name: &str, fallback: usize) -> usize {
+        self.table.definition.fields.iter().position(|field| field.name == name).unwrap_or(fallback)
+    }
+
     /// This function creates a new `Loc` from a `Vec<u8>`.
     pub fn read(packed_file_data: &[u8], schema: &Schema, return_incomplete: bool) -> Result<Self> {
 
@@ -202,14 +261,35 @@ impl Loc {
         self.table.get_ref_table_data().is_empty()
     }
 
+    /// This function sets the result of the provided expression as the value of the provided column, in every row matching the provided filter.
+    ///
+    /// It returns the rows edited.
+    pub fn apply_expression(&mut self, column_name: &str, expression: &str, filter: Option<&str>) -> Result<Vec<usize>> {
+        self.table.apply_expression(column_name, expression, filter)
+    }
+
     /// This function imports a TSV file into a decoded table.
+    ///
+    /// It also returns the columns of the TSV file that don't exist in the provided definition, and have been ignored.
     pub fn import_tsv(
         definition: &Definition,
         path: &PathBuf,
         name: &str,
-    ) -> Result<Self> {
-        let table = Table::import_tsv(definition, path, name)?;
-        Ok(Loc::from(table))
+    ) -> Result<(Self, Vec<String>)> {
+        Self::import(definition, path, name, TableFormat::TSV)
+    }
+
+    /// This function imports a file in the provided format into a decoded table.
+    ///
+    /// It also returns the columns of the file that don't exist in the provided definition, and have been ignored.
+    pub fn import(
+        definition: &Definition,
+        path: &PathBuf,
+        name: &str,
+        format: TableFormat,
+    ) -> Result<(Self, Vec<String>)> {
+        let (table, extra_columns) = Table::import(definition, path, name, format)?;
+        Ok((Loc::from(table), extra_columns))
     }
 
     /// This function exports the provided data to a TSV file.
@@ -218,7 +298,17 @@ impl Loc {
         path: &PathBuf,
         table_name: &str,
     ) -> Result<()> {
-        self.table.export_tsv(path, table_name)
+        self.export(path, table_name, TableFormat::TSV)
+    }
+
+    /// This function exports the provided data to a file in the provided format.
+    pub fn export(
+        &self,
+        path: &PathBuf,
+        table_name: &str,
+        format: TableFormat,
+    ) -> Result<()> {
+        self.table.export(path, table_name, format)
     }
 }
 
@@ -0,0 +1,285 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with the code to map the columns of a table between two versions of its definition.
+
+When a table is updated to a newer definition, the data of each column has to be moved to its new column.
+Columns with the same name are easy, but sometimes columns are renamed between versions. To not lose their data,
+columns are mapped in this order:
+- Using the mapping file provided by the user for the table and the versions, if any.
+- By name.
+- By similar name, if both columns have the same type.
+- By position, if both columns have the same type.
+
+Mapping files are RON files in the `table_mappings` folder of the config folder, named `{table_name}_{from_version}_{to_version}.ron`,
+and they map new fields to the old fields they take their data from, like this:
+
+```ron
+(
+    table_name: "land_units_tables",
+    from_version: 5,
+    to_version: 6,
+    columns: {
+        "recruitment_cost": Some("cost"),
+        "upkeep": None,
+    },
+)
+```
+!*/
+
+use ron::de::from_reader;
+use ron::ser::{to_string_pretty, PrettyConfig};
+use serde_derive::{Serialize, Deserialize};
+
+use std::collections::BTreeMap;
+use std::fs::{DirBuilder, File};
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use rpfm_error::{ErrorKind, Result};
+
+use crate::config::get_config_path;
+use crate::schema::Definition;
+
+/// Name of the folder containing the column mapping files.
+const MAPPING_FOLDER: &str = "table_mappings";
+
+/// Minimum similarity two column names must have to be considered the same column.
+const MIN_NAME_SIMILARITY: f64 = 0.6;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct represents a mapping between the columns of two versions of a table, provided by the user.
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct ColumnMapping {
+
+    /// Name of the table this mapping is for, like `land_units_tables`.
+    pub table_name: String,
+
+    /// Version of the table we're updating from.
+    pub from_version: i32,
+
+    /// Version of the table we're updating to.
+    pub to_version: i32,
+
+    /// Map of new fields and the old fields they take their data from. `None` means the new field starts with default values.
+    pub columns: BTreeMap<String, Option<String>>,
+}
+
+/// This enum represents where the data of a column comes from when updating a table to a new definition.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColumnSource {
+
+    /// The column has no source, so it's filled with default values.
+    Default,
+
+    /// The column takes its data from the old column with the same name.
+    Name(usize),
+
+    /// The column takes its data from the old column set in the user's mapping file.
+    User(usize),
+
+    /// The column takes its data from an old column with a similar name and the same type.
+    SimilarName(usize),
+
+    /// The column takes its data from the old column in the same position and with the same type.
+    Position(usize),
+}
+
+/// This struct represents a value that couldn't be carried over when updating a table to a new definition.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LostValue {
+
+    /// Row of the value.
+    pub row: usize,
+
+    /// Name of the old field of the value.
+    pub field_name: String,
+
+    /// The value, as text.
+    pub value: String,
+
+    /// Name of the new field the value couldn't be converted to. `None` if its field has been removed.
+    pub new_field_name: Option<String>,
+}
+
+//---------------------------------------------------------------------------//
+//                       Implementation of ColumnMapping
+//---------------------------------------------------------------------------//
+
+/// Implementation of `ColumnMapping`.
+impl ColumnMapping {
+
+    /// This function loads a `ColumnMapping` from a RON file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let file = BufReader::new(File::open(path)?);
+        from_reader(file).map_err(From::from)
+    }
+
+    /// This function saves a `ColumnMapping` to a RON file.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        let config = PrettyConfig::default();
+        file.write_all(to_string_pretty(&self, config)?.as_bytes())?;
+        Ok(())
+    }
+
+    /// This function saves a `ColumnMapping` to the mapping folder, so it's used when updating its table.
+    pub fn save_to_config(&self) -> Result<()> {
+        let path = Self::get_path(&self.table_name, self.from_version, self.to_version)?;
+        if let Some(folder) = path.parent() {
+            DirBuilder::new().recursive(true).create(folder)?;
+        }
+        self.save(&path)
+    }
+
+    /// This function returns the path of the mapping file for the provided table and versions.
+    pub fn get_path(table_name: &str, from_version: i32, to_version: i32) -> Result<PathBuf> {
+        Ok(get_config_path()?.join(MAPPING_FOLDER).join(format!("{}_{}_{}.ron", table_name, from_version, to_version)))
+    }
+
+    /// This function loads the mapping file for the provided table and versions, if there is one.
+    pub fn load_from_config(table_name: &str, from_version: i32, to_version: i32) -> Result<Option<Self>> {
+        let path = Self::get_path(table_name, from_version, to_version)?;
+        if path.is_file() { Self::load(&path).map(Some) } else { Ok(None) }
+    }
+
+    /// This function returns where the data of each column of the new definition comes from.
+    ///
+    /// The user mapping is checked before anything else. It fails if it uses fields not in their definitions.
+    pub fn get_column_sources(
+        old_definition: &Definition,
+        new_definition: &Definition,
+        user_mapping: Option<&Self>,
+    ) -> Result<Vec<ColumnSource>> {
+        let mut sources: Vec<Option<ColumnSource>> = vec![None; new_definition.fields.len()];
+        let mut used = vec![false; old_definition.fields.len()];
+
+        if let Some(user_mapping) = user_mapping {
+            for (new_field_name, old_field_name) in &user_mapping.columns {
+                let new_column = new_definition.fields.iter()
+                    .position(|x| &x.name == new_field_name)
+                    .ok_or_else(|| ErrorKind::TableMappingFieldNotFound(user_mapping.table_name.to_owned(), new_field_name.to_owned()))?;
+
+                sources[new_column] = match old_field_name {
+                    Some(old_field_name) => {
+                        let old_column = old_definition.fields.iter()
+                            .position(|x| &x.name == old_field_name)
+                            .ok_or_else(|| ErrorKind::TableMappingFieldNotFound(user_mapping.table_name.to_owned(), old_field_name.to_owned()))?;
+                        used[old_column] = true;
+                        Some(ColumnSource::User(old_column))
+                    }
+                    None => Some(ColumnSource::Default),
+                };
+            }
+        }
+
+        // By name.
+        for (new_column, new_field) in new_definition.fields.iter().enumerate() {
+            if sources[new_column].is_some() { continue; }
+            if let Some(old_column) = old_definition.fields.iter().position(|x| x.name == new_field.name) {
+                if !used[old_column] {
+                    used[old_column] = true;
+                    sources[new_column] = Some(ColumnSource::Name(old_column));
+                }
+            }
+        }
+
+        // By similar name. The most similar pairs go first, so a column doesn't steal the data of a better match.
+        let mut candidates = vec![];
+        for (new_column, new_field) in new_definition.fields.iter().enumerate() {
+            if sources[new_column].is_some() { continue; }
+            for (old_column, old_field) in old_definition.fields.iter().enumerate() {
+                if used[old_column] || old_field.field_type != new_field.field_type { continue; }
+                let similarity = Self::get_name_similarity(&old_field.name, &new_field.name);
+                if similarity >= MIN_NAME_SIMILARITY {
+                    candidates.push((similarity, new_column, old_column));
+                }
+            }
+        }
+
+        candidates.sort_by(|x, y| y.0.partial_cmp(&x.0).unwrap_or(std::cmp::Ordering::Equal));
+        for (_, new_column, old_column) in candidates {
+            if sources[new_column].is_none() && !used[old_column] {
+                used[old_column] = true;
+                sources[new_column] = Some(ColumnSource::SimilarName(old_column));
+            }
+        }
+
+        // By position.
+        for (new_column, new_field) in new_definition.fields.iter().enumerate() {
+            if sources[new_column].is_some() { continue; }
+            if let Some(old_field) = old_definition.fields.get(new_column) {
+                if !used[new_column] && old_field.field_type == new_field.field_type {
+                    used[new_column] = true;
+                    sources[new_column] = Some(ColumnSource::Position(new_column));
+                }
+            }
+        }
+
+        Ok(sources.into_iter().map(|x| x.unwrap_or(ColumnSource::Default)).collect())
+    }
+
+    /// This function returns how similar two column names are, from 0 (nothing in common) to 1 (same name).
+    ///
+    /// Names containing the other one, like `cost` and `recruitment_cost`, are always considered similar.
+    fn get_name_similarity(name_a: &str, name_b: &str) -> f64 {
+        let name_a = name_a.to_lowercase();
+        let name_b = name_b.to_lowercase();
+        let chars_a = name_a.chars().collect::<Vec<char>>();
+        let chars_b = name_b.chars().collect::<Vec<char>>();
+        let max_len = chars_a.len().max(chars_b.len());
+        if max_len == 0 { return 1.0; }
+
+        if !name_a.is_empty() && !name_b.is_empty() && (name_a.contains(&name_b) || name_b.contains(&name_a)) {
+            return MIN_NAME_SIMILARITY.max(chars_a.len().min(chars_b.len()) as f64 / max_len as f64);
+        }
+
+        // Levenshtein distance, keeping only the previous row.
+        let mut previous = (0..=chars_b.len()).collect::<Vec<usize>>();
+        for (index_a, char_a) in chars_a.iter().enumerate() {
+            let mut current = vec![index_a + 1; chars_b.len() + 1];
+            for (index_b, char_b) in chars_b.iter().enumerate() {
+                let cost = if char_a == char_b { 0 } else { 1 };
+                current[index_b + 1] = (previous[index_b] + cost).min(previous[index_b + 1] + 1).min(current[index_b] + 1);
+            }
+            previous = current;
+        }
+
+        1.0 - previous[chars_b.len()] as f64 / max_len as f64
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                       Implementation of ColumnSource
+//---------------------------------------------------------------------------//
+
+/// Implementation of `ColumnSource`.
+impl ColumnSource {
+
+    /// This function returns the old column the data comes from, if any.
+    pub fn get_column(self) -> Option<usize> {
+        match self {
+            ColumnSource::Default => None,
+            ColumnSource::Name(column) |
+            ColumnSource::User(column) |
+            ColumnSource::SimilarName(column) |
+            ColumnSource::Position(column) => Some(column),
+        }
+    }
+
+    /// This function returns if the source has been guessed, so it may be wrong.
+    pub fn is_guessed(self) -> bool {
+        matches!(self, ColumnSource::SimilarName(_) | ColumnSource::Position(_))
+    }
+}
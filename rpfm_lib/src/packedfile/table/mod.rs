@@ -15,13 +15,15 @@ This module contains the struct `Table`, used to manage the decoded data of a ta
 !*/
 
 use bincode::serialize;
-use csv::{QuoteStyle, ReaderBuilder, WriterBuilder};
+use csv::{QuoteStyle, Reader, ReaderBuilder, StringRecord, WriterBuilder};
 use serde_derive::{Serialize, Deserialize};
+use serde_json::{Map, Value};
 
 use std::{fmt, fmt::Display};
+use std::convert::TryFrom;
 use std::fs::File;
 use std::io::{BufReader, BufWriter, Read, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use rpfm_error::{Error, ErrorKind, Result};
 
@@ -29,8 +31,21 @@ use crate::assembly_kit::table_data::RawTable;
 use crate::common::{decoder::Decoder, encoder::Encoder};
 use crate::schema::*;
 
+use self::expression::Expression;
+use self::mapping::{ColumnSource, LostValue};
+
 pub mod db;
+pub mod expression;
+pub mod generator;
+pub mod guesser;
 pub mod loc;
+pub mod mapping;
+
+#[cfg(test)]
+mod table_test;
+
+/// Indentation used when writing tables as RON, the same RON uses by default when pretty-printing.
+const RON_INDENT: &str = "    ";
 
 //---------------------------------------------------------------------------//
 //                              Enum & Structs
@@ -49,6 +64,37 @@ pub struct Table {
     entries: Vec<Vec<DecodedData>>,
 }
 
+/// This enum represents the formats a table can be exported to/imported from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TableFormat {
+
+    /// RPFM's own TSV format. The first line contains the name and version of the table, and the second one the column names.
+    TSV,
+
+    /// Same as the TSV format, but comma-separated and with proper quoting.
+    CSV,
+
+    /// JSON object with the name and version of the table, and the rows as an array of objects keyed by field name.
+    JSON,
+
+    /// Same as the JSON format, but in RON.
+    RON,
+}
+
+/// This struct represents a table exported to a serialized format, like JSON or RON.
+#[derive(Serialize, Deserialize)]
+struct SerializedTable {
+
+    /// The name of the table ("table_folder_name" for DB tables, `TSV_NAME_LOC` for Locs).
+    table_name: String,
+
+    /// The version of the table.
+    version: i32,
+
+    /// The rows of the table, with their values keyed by field name.
+    entries: Vec<Map<String, Value>>,
+}
+
 /// This enum is used to store different types of data in a unified way. Used, for example, to store the data from each field in a DB Table.
 ///
 /// NOTE: `Sequence` it's a recursive type. A Sequence/List means you got a repeated sequence of fields
@@ -137,6 +183,123 @@ impl DecodedData {
             DecodedData::Sequence(_) => if let FieldType::Sequence(_) = field_type { true } else { false },
         }
     }
+
+    /// This function returns the data contained in the provided `DecodedData` as a `String`.
+    ///
+    /// Sequences are returned as a JSON list with one object per row, keyed by field name, so they can be edited as text.
+    pub fn data_to_string(&self) -> String {
+        match self {
+            DecodedData::Boolean(data) => data.to_string(),
+            DecodedData::Float(data) => data.to_string(),
+            DecodedData::Integer(data) => data.to_string(),
+            DecodedData::LongInteger(data) => data.to_string(),
+            DecodedData::StringU8(data) |
+            DecodedData::StringU16(data) |
+            DecodedData::OptionalStringU8(data) |
+            DecodedData::OptionalStringU16(data) => data.to_owned(),
+            DecodedData::Sequence(data) => data.to_json_value().to_string(),
+        }
+    }
+
+    /// This function creates a `DecodedData` of the provided type from its `String` representation, as returned by `data_to_string`.
+    pub fn new_from_type_and_string(field_type: &FieldType, value: &str) -> Result<Self> {
+        Ok(match field_type {
+            FieldType::Boolean => {
+                let value = value.to_lowercase();
+                if value == "true" || value == "1" { DecodedData::Boolean(true) }
+                else if value == "false" || value == "0" { DecodedData::Boolean(false) }
+                else { return Err(ErrorKind::NotABooleanValue.into()) }
+            }
+            FieldType::Float => DecodedData::Float(value.parse::<f32>()?),
+            FieldType::Integer => DecodedData::Integer(value.parse::<i32>()?),
+            FieldType::LongInteger => DecodedData::LongInteger(value.parse::<i64>()?),
+            FieldType::StringU8 => DecodedData::StringU8(value.to_owned()),
+            FieldType::StringU16 => DecodedData::StringU16(value.to_owned()),
+            FieldType::OptionalStringU8 => DecodedData::OptionalStringU8(value.to_owned()),
+            FieldType::OptionalStringU16 => DecodedData::OptionalStringU16(value.to_owned()),
+            FieldType::Sequence(definition) => {
+                let value = serde_json::from_str(value).map_err(|error| Error::from(ErrorKind::TableSequenceInvalidData(error.to_string())))?;
+                DecodedData::Sequence(Table::new_from_json_value(definition, &value, TableFormat::JSON)?)
+            }
+        })
+    }
+
+    /// This function returns the data contained in the provided `DecodedData` as a JSON value.
+    ///
+    /// Strings are unescaped, so they contain real tabs and newlines. NaN and infinite floats, which JSON cannot represent,
+    /// are returned as the strings `NaN`, `inf` and `-inf`.
+    fn to_json_value(&self) -> Value {
+        match self {
+            DecodedData::Boolean(data) => Value::from(*data),
+
+            // Floats go through their string representation, so we don't get precision garbage when turning them into f64.
+            DecodedData::Float(data) => if data.is_finite() { Value::from(data.to_string().parse::<f64>().unwrap_or_default()) } else { Value::from(data.to_string()) },
+            DecodedData::Integer(data) => Value::from(*data),
+            DecodedData::LongInteger(data) => Value::from(*data),
+            DecodedData::StringU8(data) |
+            DecodedData::StringU16(data) |
+            DecodedData::OptionalStringU8(data) |
+            DecodedData::OptionalStringU16(data) => Value::from(Table::unescape_special_chars(data)),
+            DecodedData::Sequence(data) => data.to_json_value(),
+        }
+    }
+
+    /// This function creates a `DecodedData` of the provided type from a JSON value, as returned by `to_json_value`.
+    ///
+    /// Long integers are also read from integral floats in RON, as that's how `i64::MIN` is written there.
+    fn new_from_type_and_json_value(field_type: &FieldType, value: &Value, format: TableFormat) -> Result<Self> {
+        let error = || Error::from(ErrorKind::TableSequenceInvalidData(format!("<i>{}</i> is not a valid {} value.", value, DecodedData::default(field_type))));
+        let string = || value.as_str().map(Table::escape_special_chars).ok_or_else(error);
+        Ok(match field_type {
+            FieldType::Boolean => DecodedData::Boolean(value.as_bool().ok_or_else(error)?),
+            FieldType::Float => DecodedData::Float(value.as_f64().map(|x| x as f32)
+                .or_else(|| value.as_str().and_then(|x| x.parse::<f32>().ok()).filter(|x| !x.is_finite()))
+                .ok_or_else(error)?),
+            FieldType::Integer => DecodedData::Integer(value.as_i64().and_then(|x| i32::try_from(x).ok()).ok_or_else(error)?),
+            FieldType::LongInteger => DecodedData::LongInteger(value.as_i64()
+                .or_else(|| value.as_f64().filter(|x| format == TableFormat::RON && x.fract() == 0.0 && *x >= i64::MIN as f64 && *x < i64::MAX as f64).map(|x| x as i64))
+                .ok_or_else(error)?),
+            FieldType::StringU8 => DecodedData::StringU8(string()?),
+            FieldType::StringU16 => DecodedData::StringU16(string()?),
+            FieldType::OptionalStringU8 => DecodedData::OptionalStringU8(string()?),
+            FieldType::OptionalStringU16 => DecodedData::OptionalStringU16(string()?),
+            FieldType::Sequence(definition) => DecodedData::Sequence(Table::new_from_json_value(definition, value, format)?),
+        })
+    }
+}
+
+//----------------------------------------------------------------//
+// Implementations for `TableFormat`.
+//----------------------------------------------------------------//
+
+/// Implementation of `TableFormat`.
+impl TableFormat {
+
+    /// This function returns the format with the provided extension/name (tsv, csv, json or ron).
+    pub fn from_extension(extension: &str) -> Result<Self> {
+        match &*extension.to_lowercase() {
+            "tsv" => Ok(TableFormat::TSV),
+            "csv" => Ok(TableFormat::CSV),
+            "json" => Ok(TableFormat::JSON),
+            "ron" => Ok(TableFormat::RON),
+            _ => Err(ErrorKind::TableUnsupportedFormat(extension.to_owned()).into()),
+        }
+    }
+
+    /// This function returns the format of a file, based on its extension.
+    pub fn from_path(path: &Path) -> Result<Self> {
+        Self::from_extension(&path.extension().map(|x| x.to_string_lossy().to_string()).unwrap_or_default())
+    }
+
+    /// This function returns the extension used by files of this format.
+    pub fn get_extension(self) -> &'static str {
+        match self {
+            TableFormat::TSV => "tsv",
+            TableFormat::CSV => "csv",
+            TableFormat::JSON => "json",
+            TableFormat::RON => "ron",
+        }
+    }
 }
 
 //----------------------------------------------------------------//
@@ -184,48 +347,83 @@ impl Table {
     /// This updates the table's data to follow the format marked by the new definition, so you can use it to *update* the version of your table.
     pub fn set_definition(&mut self, new_definition: &Definition) {
 
-        // It's simple: we compare both schemas, and get the original and final positions of each column.
-        // If a row is new, his original position is -1. If has been removed, his final position is -1.
-        let mut positions: Vec<(i32, i32)> = vec![];
-        for (new_pos, new_field) in new_definition.fields.iter().enumerate() {
-            if let Some(old_pos) = self.definition.fields.iter().position(|x| x.name == new_field.name) {
-                positions.push((old_pos as i32, new_pos as i32))
-            } else { positions.push((-1, new_pos as i32)); }
-        }
+        // It's simple: each column of the new definition takes its data from the column with the same name in the old one.
+        let sources = new_definition.fields.iter()
+            .map(|new_field| match self.definition.fields.iter().position(|x| x.name == new_field.name) {
+                Some(old_column) => ColumnSource::Name(old_column),
+                None => ColumnSource::Default,
+            })
+            .collect::<Vec<ColumnSource>>();
 
-        // Then, for each field in the old definition, check if exists in the new one.
-        for (old_pos, old_field) in self.definition.fields.iter().enumerate() {
-            if !new_definition.fields.iter().any(|x| x.name == old_field.name) { positions.push((old_pos as i32, -1)); }
-        }
-
-        // We sort the columns by their destination.
-        positions.sort_by_key(|x| x.1);
-
-        // Then, we create the new data using the old one and the column changes.
-        let mut new_entries: Vec<Vec<DecodedData>> = vec![];
-        for row in &mut self.entries {
-            let mut entry = vec![];
-            for (old_pos, new_pos) in &positions {
+        self.set_definition_with_sources(new_definition, &sources);
+    }
 
-                // If the new position is -1, it means the column got removed. We skip it.
-                if *new_pos == -1 { continue; }
+    /// This function replaces the definition of this table with the one provided, taking the data of each new column from the provided source.
+    ///
+    /// Values that cannot be converted to the type of their new column, and values of columns that are not the source of any new column,
+    /// are lost. Those values are returned, except if they were default values.
+    pub fn set_definition_with_sources(&mut self, new_definition: &Definition, sources: &[ColumnSource]) -> Vec<LostValue> {
+        let mut lost_values = vec![];
+        let mut new_entries: Vec<Vec<DecodedData>> = Vec::with_capacity(self.entries.len());
+        for (row, entry) in self.entries.iter().enumerate() {
+            let mut new_entry = Vec::with_capacity(new_definition.fields.len());
+            for (new_field, source) in new_definition.fields.iter().zip(sources) {
+                let field_type = &new_field.field_type;
+                match source.get_column() {
+
+                    // If the column has no source, it's a new column. We need to get his type and create a `Default` field with it.
+                    None => new_entry.push(DecodedData::default(field_type)),
+
+                    // Otherwise, we got a moved column. Grab his field from the old data and put it in his new place.
+                    // If it's a Sequence, its nested table needs to be updated to the new definition too.
+                    Some(old_column) => {
+                        let mut cell = entry[old_column].clone();
+                        if let (DecodedData::Sequence(ref mut sub_table), FieldType::Sequence(ref definition)) = (&mut cell, field_type) {
+                            sub_table.set_definition(definition);
+                        }
 
-                // If the old position is -1, it means we got a new column. We need to get his type and create a `Default` field with it.
-                else if *old_pos == -1 {
-                    entry.push(DecodedData::default(&new_definition.fields[*new_pos as usize].field_type));
+                        // If the column changed his type, we try to convert his data. If that's not possible, we reset it.
+                        else if !cell.is_field_type_correct(field_type.clone()) {
+                            cell = match DecodedData::new_from_type_and_string(field_type, &cell.data_to_string()) {
+                                Ok(new_cell) => new_cell,
+                                Err(_) => {
+                                    let old_field = &self.definition.fields[old_column];
+                                    if cell != DecodedData::default(&old_field.field_type) {
+                                        lost_values.push(LostValue {
+                                            row,
+                                            field_name: old_field.name.to_owned(),
+                                            value: cell.data_to_string(),
+                                            new_field_name: Some(new_field.name.to_owned()),
+                                        });
+                                    }
+                                    DecodedData::default(field_type)
+                                }
+                            };
+                        }
+                        new_entry.push(cell);
+                    }
                 }
+            }
 
-                // Otherwise, we got a moved column. Grab his field from the old data and put it in his new place.
-                else {
-                    entry.push(row[*old_pos as usize].clone());
+            // The data of the columns that got removed is lost.
+            for (old_column, old_field) in self.definition.fields.iter().enumerate() {
+                if !sources.iter().any(|x| x.get_column() == Some(old_column)) && entry[old_column] != DecodedData::default(&old_field.field_type) {
+                    lost_values.push(LostValue {
+                        row,
+                        field_name: old_field.name.to_owned(),
+                        value: entry[old_column].data_to_string(),
+                        new_field_name: None,
+                    });
                 }
             }
-            new_entries.push(entry);
+
+            new_entries.push(new_entry);
         }
 
         // Then, we finally replace our definition and our data.
         self.definition = new_definition.clone();
         self.entries = new_entries;
+        lost_values
     }
 
     /// This function replaces the data of this table with the one provided.
@@ -250,6 +448,58 @@ impl Table {
         Ok(())
     }
 
+    /// This function returns the entries of this table as a JSON list, with one object per row keyed by field name, like exported rows.
+    fn to_json_value(&self) -> Value {
+        Value::Array(self.entries.iter().map(|row| Value::Object(Self::get_json_row(&self.definition, row))).collect())
+    }
+
+    /// This function returns the provided row as a JSON object, keyed by field name.
+    fn get_json_row(definition: &Definition, row: &[DecodedData]) -> Map<String, Value> {
+        definition.fields.iter()
+            .zip(row.iter())
+            .map(|(field, cell)| (field.name.to_owned(), cell.to_json_value()))
+            .collect()
+    }
+
+    /// This function creates a new table with the provided definition from a JSON list, with one object per row keyed by field name.
+    ///
+    /// Missing fields take their default value. Rows as lists of values, in the order of the definition, are also accepted.
+    fn new_from_json_value(definition: &Definition, value: &Value, format: TableFormat) -> Result<Self> {
+        let rows = value.as_array().ok_or_else(|| Error::from(ErrorKind::TableSequenceInvalidData("The data is not a list of rows.".to_owned())))?;
+        let mut table = Self::new(definition);
+        for row in rows {
+            let entry = match row {
+                Value::Object(cells) => {
+                    if let Some(column) = cells.keys().find(|column| !definition.fields.iter().any(|field| &field.name == *column)) {
+                        return Err(ErrorKind::TableSequenceInvalidData(format!("The row <i>{}</i> has the field <i>{}</i>, which is not in the definition.", row, column)).into());
+                    }
+
+                    definition.fields.iter().map(|field| match cells.get(&field.name) {
+                        Some(cell) => DecodedData::new_from_type_and_json_value(&field.field_type, cell, format),
+                        None => Ok(Self::get_field_default_value(field)),
+                    }).collect::<Result<Vec<DecodedData>>>()?
+                }
+
+                Value::Array(cells) => {
+                    if cells.len() != definition.fields.len() {
+                        return Err(ErrorKind::TableSequenceInvalidData(format!("The row <i>{}</i> has {} values, but {} were expected.", row, cells.len(), definition.fields.len())).into());
+                    }
+
+                    cells.iter()
+                        .zip(definition.fields.iter())
+                        .map(|(cell, field)| DecodedData::new_from_type_and_json_value(&field.field_type, cell, format))
+                        .collect::<Result<Vec<DecodedData>>>()?
+                }
+
+                _ => return Err(ErrorKind::TableSequenceInvalidData(format!("<i>{}</i> is not a row.", row)).into()),
+            };
+
+            table.entries.push(entry);
+        }
+
+        Ok(table)
+    }
+
     /// This function decodes all the fields of a table from raw bytes.
     ///
     /// If return_incomplete == true, this function will return an error with the incompletely decoded table when it fails.
@@ -318,6 +568,23 @@ impl Table {
         Ok(())
     }
 
+    /// This function decodes a Sequence with the provided definition from raw bytes, starting at the provided index.
+    ///
+    /// Sequences start with their entry count, as an u32, followed by their entries. This is for tools that need to decode
+    /// a single Sequence, like the decoder. Tables decode their Sequences on their own.
+    pub fn decode_sequence(definition: &Definition, data: &[u8], index: &mut usize) -> Result<Self> {
+        let entry_count = data.decode_packedfile_integer_u32(*index, index)?;
+
+        // Every entry takes at least a byte, so a bigger count means we're not decoding a Sequence.
+        if entry_count as usize > data.len() - *index {
+            return Err(ErrorKind::HelperDecodingEncodingError(format!("<p>Error trying to decode a <b><i>Sequence</b></i>: its entry count ({}) is bigger than the amount of bytes left.</p>", entry_count)).into());
+        }
+
+        let mut table = Self::new(definition);
+        table.decode(data, entry_count, index, false)?;
+        Ok(table)
+    }
+
     /// This function encodes all the fields of a table to raw bytes.
     fn encode(&self, mut packed_file: &mut Vec<u8>) -> Result<()> {
         let fields = &self.definition.fields;
@@ -356,158 +623,136 @@ impl Table {
     }
 
     //----------------------------------------------------------------//
-    // TSV Functions for PackedFiles.
+    // Import/Export Functions for PackedFiles.
     //----------------------------------------------------------------//
 
-    /// This function imports a TSV file into a decoded table.
-    fn import_tsv(
+    /// This function imports a file in the provided format into a decoded table.
+    ///
+    /// Columns are matched with the fields of the definition by their names, so files from other versions of the table
+    /// can be imported too. Fields without a column get their default value. Along with the table, this returns the names
+    /// of the columns of the file that don't exist in the definition, and have been ignored.
+    fn import(
         definition: &Definition,
         path: &PathBuf,
         name: &str,
-    ) -> Result<Self> {
+        format: TableFormat,
+    ) -> Result<(Self, Vec<String>)> {
+        match format {
+            TableFormat::TSV | TableFormat::CSV => Self::import_delimited(definition, path, name, format),
+            TableFormat::JSON | TableFormat::RON => Self::import_serialized(definition, path, name, format),
+        }
+    }
 
-        // We want the reader to have no quotes, tab as delimiter and custom headers, because otherwise
-        // Excel, Libreoffice and all the programs that edit this kind of files break them on save.
-        let mut reader = ReaderBuilder::new()
-            .delimiter(b'\t')
-            .quoting(false)
-            .has_headers(false)
-            .flexible(true)
-            .from_path(&path)?;
+    /// This function imports a TSV/CSV file into a decoded table.
+    fn import_delimited(
+        definition: &Definition,
+        path: &PathBuf,
+        name: &str,
+        format: TableFormat,
+    ) -> Result<(Self, Vec<String>)> {
+        let mut reader = Self::get_delimited_reader(path, format)?;
 
-        // If we succesfully load the TSV file into a reader, check the first two lines to ensure
-        // it's a valid TSV for our specific table.
+        // If we succesfully load the file into a reader, check the first two lines to ensure
+        // it's a valid file for our specific table.
         let mut entries = vec![];
+        let mut columns = vec![];
+        let mut column_count = 0;
+        let mut extra_columns = vec![];
         for (row, record) in reader.records().enumerate() {
             if let Ok(record) = record {
 
                 // The first line should contain the "table_folder_name"/"Loc PackedFile/PackFile List", and the version (1 for Locs).
-                // If it doesn't match with the name we provided, return an error.
+                // If it doesn't match with the name we provided, return an error. The version doesn't need to match, but it has to be valid.
                 if row == 0 {
                     if record.get(0).unwrap_or("error") != name { return Err(ErrorKind::ImportTSVWrongTypeTable.into()); }
-                    if record.get(1).unwrap_or("-1").parse::<i32>().map_err(|_| Error::from(ErrorKind::ImportTSVInvalidVersion))? != definition.version {
-                        return Err(ErrorKind::ImportTSVWrongVersion.into());
-                    }
+                    record.get(1).unwrap_or("-1").parse::<i32>().map_err(|_| Error::from(ErrorKind::ImportTSVInvalidVersion))?;
                 }
 
-                // The second line contains the column headers. Is just to help people in other programs, so we skip it.
-                else if row == 1 { continue }
-
-                // Then read the rest of the rows as a normal TSV.
-                else if record.len() == definition.fields.len() {
-                    let mut entry = vec![];
-                    for (column, field) in record.iter().enumerate() {
-                        match definition.fields[column].field_type {
-                            FieldType::Boolean => {
-                                let value = field.to_lowercase();
-                                if value == "true" || value == "1" { entry.push(DecodedData::Boolean(true)); }
-                                else if value == "false" || value == "0" { entry.push(DecodedData::Boolean(false)); }
-                                else { return Err(ErrorKind::ImportTSVIncorrectRow(row, column).into()); }
-                            }
-                            FieldType::Float => entry.push(DecodedData::Float(field.parse::<f32>().map_err(|_| Error::from(ErrorKind::ImportTSVIncorrectRow(row, column)))?)),
-                            FieldType::Integer => entry.push(DecodedData::Integer(field.parse::<i32>().map_err(|_| Error::from(ErrorKind::ImportTSVIncorrectRow(row, column)))?)),
-                            FieldType::LongInteger => entry.push(DecodedData::LongInteger(field.parse::<i64>().map_err(|_| Error::from(ErrorKind::ImportTSVIncorrectRow(row, column)))?)),
-                            FieldType::StringU8 => entry.push(DecodedData::StringU8(field.to_owned())),
-                            FieldType::StringU16 => entry.push(DecodedData::StringU16(field.to_owned())),
-                            FieldType::OptionalStringU8 => entry.push(DecodedData::OptionalStringU8(field.to_owned())),
-                            FieldType::OptionalStringU16 => entry.push(DecodedData::OptionalStringU16(field.to_owned())),
-
-                            // For now fail on Sequences. These are a bit special and I don't know if the're even possible in TSV.
-                            FieldType::Sequence(_) => return Err(ErrorKind::ImportTSVIncorrectRow(row, column).into())
-                        }
-                    }
-                    entries.push(entry);
+                // The second line contains the column headers. We use them to know what field goes in each column.
+                // If none of them match a field, the file either has no header row, or it belongs to something else.
+                else if row == 1 {
+                    let (mapping, extra) = Self::get_tsv_column_mapping(definition, &record);
+                    if !mapping.is_empty() && mapping.iter().all(Option::is_none) { return Err(ErrorKind::ImportTSVMissingHeader.into()); }
+                    columns = mapping;
+                    column_count = record.len();
+                    extra_columns = extra;
                 }
 
-                // If it fails here, return an error with the len of the record instead a field.
-                else { return Err(ErrorKind::ImportTSVIncorrectRow(row, record.len()).into()); }
+                // Then read the rest of the rows as a normal TSV.
+                else { entries.push(Self::import_tsv_row(definition, &columns, column_count, &record, row, format)?); }
             }
             else { return Err(ErrorKind::ImportTSVIncorrectRow(row, 0).into()); }
         }
 
+        // A file with only the table name and version has no header row to map its columns with.
+        if column_count == 0 { return Err(ErrorKind::ImportTSVMissingHeader.into()); }
+
         // If we reached this point without errors, we replace the old data with the new one and return success.
         let mut table = Table::new(definition);
         table.entries = entries;
-        Ok(table)
+        Ok((table, extra_columns))
     }
 
-    /// This function imports a TSV file into a new Table File.
-    fn import_tsv_to_binary_file(
-        schema: &Schema,
-        source_path: &PathBuf,
-        destination_path: &PathBuf,
-    ) -> Result<()> {
-
-        // We want the reader to have no quotes, tab as delimiter and custom headers, because otherwise
-        // Excel, Libreoffice and all the programs that edit this kind of files break them on save.
-        let mut reader = ReaderBuilder::new()
-            .delimiter(b'\t')
-            .quoting(false)
-            .has_headers(true)
-            .flexible(true)
-            .from_path(&source_path)?;
-
-        // If we succesfully load the TSV file into a reader, check the first line to ensure it's a valid TSV file.
-        let table_type;
-        let table_version;
-        {
-            let headers = reader.headers()?;
-            table_type = if let Some(table_type) = headers.get(0) { table_type.to_owned() } else { return Err(ErrorKind::ImportTSVWrongTypeTable.into()) };
-            table_version = if let Some(table_version) = headers.get(1) { table_version.parse::<i32>().map_err(|_| Error::from(ErrorKind::ImportTSVInvalidVersion))? } else { return Err(ErrorKind::ImportTSVInvalidVersion.into()) };
-        }
-
-        // Get his definition depending on his first line's contents.
-        let definition = if table_type == loc::TSV_NAME_LOC { schema.get_ref_versioned_file_loc()?.get_version(table_version)?.clone() }
-        else { schema.get_ref_versioned_file_db(&table_type)?.get_version(table_version)?.clone() };
+    /// This function imports a JSON/RON file into a decoded table.
+    fn import_serialized(
+        definition: &Definition,
+        path: &PathBuf,
+        name: &str,
+        format: TableFormat,
+    ) -> Result<(Self, Vec<String>)> {
+        let serialized_table = Self::read_serialized(path, format)?;
+        if serialized_table.table_name != name { return Err(ErrorKind::ImportTSVWrongTypeTable.into()); }
 
-        // Try to import the entries of the file.
         let mut entries = vec![];
-        for (row, record) in reader.records().enumerate() {
-            if let Ok(record) = record {
+        let mut extra_columns: Vec<String> = vec![];
+        for (row, entry) in serialized_table.entries.iter().enumerate() {
+            entries.push(definition.fields.iter().map(|field| match entry.get(&field.name) {
+                Some(value) => DecodedData::new_from_type_and_json_value(&field.field_type, value, format)
+                    .map_err(|_| Error::from(ErrorKind::ImportTableIncorrectField(row, field.name.to_owned()))),
+                None => Ok(Self::get_field_default_value(field)),
+            }).collect::<Result<Vec<DecodedData>>>()?);
+
+            for column in entry.keys() {
+                if !definition.fields.iter().any(|field| &field.name == column) && !extra_columns.contains(column) {
+                    extra_columns.push(column.to_owned());
+                }
+            }
+        }
 
-                // The second line contains the column headers. Is just to help people in other programs, not needed to be check.
-                if row == 0 { continue }
+        let mut table = Table::new(definition);
+        table.entries = entries;
+        Ok((table, extra_columns))
+    }
 
-                // Then read the rest of the rows as a normal TSV.
-                else if record.len() == definition.fields.len() {
-                    let mut entry = vec![];
-                    for (column, field) in record.iter().enumerate() {
-                        match definition.fields[column].field_type {
-                            FieldType::Boolean => {
-                                let value = field.to_lowercase();
-                                if value == "true" || value == "1" { entry.push(DecodedData::Boolean(true)); }
-                                else if value == "false" || value == "0" { entry.push(DecodedData::Boolean(false)); }
-                                else { return Err(ErrorKind::ImportTSVIncorrectRow(row, column).into()); }
-                            }
-                            FieldType::Float => entry.push(DecodedData::Float(field.parse::<f32>().map_err(|_| Error::from(ErrorKind::ImportTSVIncorrectRow(row, column)))?)),
-                            FieldType::Integer => entry.push(DecodedData::Integer(field.parse::<i32>().map_err(|_| Error::from(ErrorKind::ImportTSVIncorrectRow(row, column)))?)),
-                            FieldType::LongInteger => entry.push(DecodedData::LongInteger(field.parse::<i64>().map_err(|_| Error::from(ErrorKind::ImportTSVIncorrectRow(row, column)))?)),
-                            FieldType::StringU8 => entry.push(DecodedData::StringU8(field.to_owned())),
-                            FieldType::StringU16 => entry.push(DecodedData::StringU16(field.to_owned())),
-                            FieldType::OptionalStringU8 => entry.push(DecodedData::OptionalStringU8(field.to_owned())),
-                            FieldType::OptionalStringU16 => entry.push(DecodedData::OptionalStringU16(field.to_owned())),
-                            FieldType::Sequence(_) => return Err(ErrorKind::ImportTSVIncorrectRow(row, column).into())
-                        }
-                    }
-                    entries.push(entry);
-                }
+    /// This function imports a file in any of the supported formats into a new Table File.
+    ///
+    /// The format is deduced from the extension of the file. The new file is always created with the last version
+    /// of its definition, no matter what version the imported file has. It returns the names of the columns of the
+    /// imported file that don't exist in the definition, and have been ignored.
+    fn import_to_binary_file(
+        schema: &Schema,
+        source_path: &PathBuf,
+        destination_path: &PathBuf,
+    ) -> Result<Vec<String>> {
 
-                // If it fails here, return an error with the len of the record instead a field.
-                else { return Err(ErrorKind::ImportTSVIncorrectRow(row, record.len()).into()); }
-            }
+        // Get the last definition of the table, depending on the type of the table.
+        let format = TableFormat::from_path(source_path)?;
+        let table_type = Self::get_table_name_from_file(source_path, format)?;
+        let definition = if table_type == loc::TSV_NAME_LOC { schema.get_ref_last_definition_loc()? }
+        else { schema.get_ref_last_definition_db(&table_type)? };
 
-            else { return Err(ErrorKind::ImportTSVIncorrectRow(row, 0).into()); }
-        }
+        // Try to import the entries of the file.
+        let (table, extra_columns) = Self::import(definition, source_path, &table_type, format)?;
 
         // If we reached this point without errors, we create the File in memory and add the entries to it.
         let data = if table_type == loc::TSV_NAME_LOC {
-            let mut file = loc::Loc::new(&definition);
-            file.set_table_data(&entries)?;
+            let mut file = loc::Loc::new(definition);
+            file.set_table_data(&table.entries)?;
             file.save()
         }
         else {
-            let mut file = db::DB::new(&table_type, None, &definition);
-            file.set_table_data(&entries)?;
+            let mut file = db::DB::new(&table_type, None, definition);
+            file.set_table_data(&table.entries)?;
             file.save()
         }?;
 
@@ -515,56 +760,28 @@ impl Table {
         let mut file = BufWriter::new(File::create(&destination_path)?);
         file.write_all(&data)?;
 
-        // If all worked, return success.
-        Ok(())
+        // If all worked, return the columns we ignored.
+        Ok(extra_columns)
     }
 
-    /// This function exports the provided data to a TSV file.
-    fn export_tsv(
+    /// This function exports the table to a file in the provided format.
+    fn export(
         &self,
         path: &PathBuf,
         table_name: &str,
+        format: TableFormat,
     ) -> Result<()> {
-
-        // We want the writer to have no quotes, tab as delimiter and custom headers, because otherwise
-        // Excel, Libreoffice and all the programs that edit this kind of files break them on save.
-        let mut writer = WriterBuilder::new()
-            .delimiter(b'\t')
-            .quote_style(QuoteStyle::Never)
-            .has_headers(false)
-            .flexible(true)
-            .from_writer(vec![]);
-
-        // We serialize the info of the table (name and version) in the first line, and the column names in the second one.
-        writer.serialize((table_name, self.definition.version))?;
-        writer.serialize(self.definition.fields.iter().map(|x| x.name.to_owned()).collect::<Vec<String>>())?;
-
-        // Then we serialize each entry in the DB Table.
-        for entry in &self.entries { writer.serialize(&entry)?; }
-
-        // Then, we try to write it on disk. If there is an error, report it.
-        let mut file = File::create(&path)?;
-        file.write_all(String::from_utf8(writer.into_inner().unwrap())?.as_bytes())?;
-
-        Ok(())
+        Self::export_entries(&self.definition, &self.entries, path, table_name, format)
     }
 
-    /// This function exports the provided file to a TSV file..
-    fn export_tsv_from_binary_file(
+    /// This function exports the provided binary file to a file in the provided format.
+    fn export_from_binary_file(
         schema: &Schema,
         source_path: &PathBuf,
-        destination_path: &PathBuf
+        destination_path: &PathBuf,
+        format: TableFormat,
     ) -> Result<()> {
 
-        // We want the writer to have no quotes, tab as delimiter and custom headers, because otherwise
-        // Excel, Libreoffice and all the programs that edit this kind of files break them on save.
-        let mut writer = WriterBuilder::new()
-            .delimiter(b'\t')
-            .quote_style(QuoteStyle::Never)
-            .has_headers(false)
-            .flexible(true)
-            .from_path(destination_path)?;
-
         // We don't know what type this file is, so we try to decode it as a Loc. If that fails, we try
         // to decode it as a DB using the name of his parent folder. If that fails too, run before it explodes!
         let mut file = BufReader::new(File::open(source_path)?);
@@ -583,13 +800,256 @@ impl Table {
         let definition = if table_type == loc::TSV_NAME_LOC { schema.get_ref_versioned_file_loc()?.get_version(version)?.clone() }
         else { schema.get_ref_versioned_file_db(&table_type)?.get_version(version)?.clone() };
 
-        // We serialize the info of the table (name and version) in the first line, and the column names in the second one.
-        writer.serialize((&table_type, version))?;
-        writer.serialize(definition.fields.iter().map(|x| x.name.to_owned()).collect::<Vec<String>>())?;
+        Self::export_entries(&definition, &entries, destination_path, table_type, format)
+    }
 
-        // Then we serialize each entry in the DB Table.
-        for entry in entries { writer.serialize(&entry)?; }
-        writer.flush().map_err(From::from)
+    /// This function exports the provided entries to a file in the provided format.
+    fn export_entries(
+        definition: &Definition,
+        entries: &[Vec<DecodedData>],
+        path: &PathBuf,
+        table_name: &str,
+        format: TableFormat,
+    ) -> Result<()> {
+        match format {
+            TableFormat::TSV | TableFormat::CSV => {
+
+                // For TSV, we want the writer to have no quotes, tab as delimiter and custom headers, because otherwise
+                // Excel, Libreoffice and all the programs that edit this kind of files break them on save.
+                let (delimiter, quote_style) = if format == TableFormat::CSV { (b',', QuoteStyle::Necessary) } else { (b'\t', QuoteStyle::Never) };
+                let mut writer = WriterBuilder::new()
+                    .delimiter(delimiter)
+                    .quote_style(quote_style)
+                    .has_headers(false)
+                    .flexible(true)
+                    .from_path(path)?;
+
+                // We serialize the info of the table (name and version) in the first line, and the column names in the second one.
+                writer.serialize((table_name, definition.version))?;
+                writer.serialize(definition.fields.iter().map(|x| x.name.to_owned()).collect::<Vec<String>>())?;
+
+                // Then we serialize each entry in the DB Table.
+                for entry in entries { writer.serialize(Self::export_tsv_row(entry, format))?; }
+                writer.flush().map_err(From::from)
+            }
+
+            TableFormat::JSON | TableFormat::RON => {
+                let serialized_table = SerializedTable {
+                    table_name: table_name.to_owned(),
+                    version: definition.version,
+                    entries: entries.iter().map(|entry| Self::get_json_row(definition, entry)).collect(),
+                };
+
+                let data = if format == TableFormat::RON { Self::to_ron_string(&serialized_table)? }
+                else { serde_json::to_string_pretty(&serialized_table)? };
+
+                let mut file = BufWriter::new(File::create(&path)?);
+                file.write_all(data.as_bytes()).map_err(From::from)
+            }
+        }
+    }
+
+    /// This function returns the name of the table contained in a file in the provided format.
+    ///
+    /// It also checks that the version of the table in the file is valid.
+    pub fn get_table_name_from_file(path: &PathBuf, format: TableFormat) -> Result<String> {
+        match format {
+            TableFormat::TSV | TableFormat::CSV => {
+                let mut reader = Self::get_delimited_reader(path, format)?;
+                match reader.records().next() {
+                    Some(Ok(record)) => {
+                        let table_name = record.get(0).ok_or_else(|| Error::from(ErrorKind::ImportTSVWrongTypeTable))?;
+                        record.get(1).unwrap_or("-1").parse::<i32>().map_err(|_| Error::from(ErrorKind::ImportTSVInvalidVersion))?;
+                        Ok(table_name.to_owned())
+                    }
+                    _ => Err(ErrorKind::ImportTSVWrongTypeTable.into()),
+                }
+            }
+            TableFormat::JSON | TableFormat::RON => Ok(Self::read_serialized(path, format)?.table_name),
+        }
+    }
+
+    /// This function returns a reader for a TSV/CSV file.
+    fn get_delimited_reader(path: &PathBuf, format: TableFormat) -> Result<Reader<File>> {
+
+        // For TSV, we want the reader to have no quotes, tab as delimiter and custom headers, because otherwise
+        // Excel, Libreoffice and all the programs that edit this kind of files break them on save.
+        ReaderBuilder::new()
+            .delimiter(if format == TableFormat::CSV { b',' } else { b'\t' })
+            .quoting(format == TableFormat::CSV)
+            .has_headers(false)
+            .flexible(true)
+            .from_path(&path)
+            .map_err(From::from)
+    }
+
+    /// This function reads a JSON/RON file into a `SerializedTable`.
+    fn read_serialized(path: &PathBuf, format: TableFormat) -> Result<SerializedTable> {
+        let mut data = String::new();
+        BufReader::new(File::open(path)?).read_to_string(&mut data)?;
+        if format == TableFormat::RON { ron::de::from_str(&data).map_err(From::from) }
+        else { serde_json::from_str(&data).map_err(From::from) }
+    }
+
+    /// This function returns, for each field of the provided definition, the column of the TSV file it's in, using the header row.
+    ///
+    /// It also returns the names of the columns that don't match any field.
+    fn get_tsv_column_mapping(definition: &Definition, headers: &StringRecord) -> (Vec<Option<usize>>, Vec<String>) {
+        let mapping = definition.fields.iter().map(|field| headers.iter().position(|header| header == field.name)).collect();
+        let extra_columns = headers.iter().filter(|header| !definition.fields.iter().any(|field| field.name == *header)).map(|header| header.to_owned()).collect();
+        (mapping, extra_columns)
+    }
+
+    /// This function decodes a row of a TSV file, using the provided mapping between fields and columns.
+    ///
+    /// The row must have the same amount of columns as the header row. Fields with no column get their default value.
+    fn import_tsv_row(definition: &Definition, columns: &[Option<usize>], column_count: usize, record: &StringRecord, row: usize, format: TableFormat) -> Result<Vec<DecodedData>> {
+        if record.len() != column_count { return Err(ErrorKind::ImportTSVIncorrectRow(row, record.len()).into()); }
+
+        definition.fields.iter().zip(columns).map(|(field, column)| match column {
+            Some(column) => Self::import_tsv_field(&field.field_type, &record[*column], row, *column, format),
+            None => Ok(Self::get_field_default_value(field)),
+        }).collect()
+    }
+
+    /// This function sets the result of the provided expression as the value of the provided column, in every row matching the provided filter.
+    ///
+    /// The filter is another expression, which must return true or false. If there is no filter, the expression is applied to all rows.
+    /// Nothing is changed if the expression fails in any row. It returns the rows edited.
+    pub fn apply_expression(&mut self, column_name: &str, expression: &str, filter: Option<&str>) -> Result<Vec<usize>> {
+        let column = self.definition.fields.iter()
+            .position(|x| x.name == column_name)
+            .ok_or_else(|| Error::from(ErrorKind::TableExpressionColumnNotFound(column_name.to_owned())))?;
+        let field_type = &self.definition.fields[column].field_type;
+        let expression = Expression::parse(expression, &self.definition)?;
+        let filter = match filter {
+            Some(filter) if !filter.trim().is_empty() => Some(Expression::parse(filter, &self.definition)?),
+            _ => None,
+        };
+
+        let mut values = vec![];
+        for (index, row) in self.entries.iter().enumerate() {
+            if let Some(ref filter) = filter {
+                if !filter.is_match(row)? { continue; }
+            }
+            values.push((index, expression.evaluate_as(row, field_type)?));
+        }
+
+        let mut edited_rows = vec![];
+        for (index, value) in values {
+            if self.entries[index][column] != value {
+                self.entries[index][column] = value;
+                edited_rows.push(index);
+            }
+        }
+
+        Ok(edited_rows)
+    }
+
+    /// This function returns the default value of the provided field, or the default value of its type if it doesn't have one.
+    pub fn get_field_default_value(field: &Field) -> DecodedData {
+        field.default_value.as_ref()
+            .and_then(|default_value| DecodedData::new_from_type_and_string(&field.field_type, default_value).ok())
+            .unwrap_or_else(|| DecodedData::default(&field.field_type))
+    }
+
+    /// This function decodes a field of a TSV/CSV file, returning an error with its position if it's not valid for its type.
+    ///
+    /// Sequences are expected inline, in the same format `DecodedData::data_to_string` returns them.
+    /// Strings in CSV files contain real tabs and newlines, so they're escaped like the decoded ones.
+    fn import_tsv_field(field_type: &FieldType, field: &str, row: usize, column: usize, format: TableFormat) -> Result<DecodedData> {
+        let is_string = matches!(field_type, FieldType::StringU8 | FieldType::StringU16 | FieldType::OptionalStringU8 | FieldType::OptionalStringU16);
+        let field = if format == TableFormat::CSV && is_string { Self::escape_special_chars(field) } else { field.to_owned() };
+        DecodedData::new_from_type_and_string(field_type, &field).map_err(|_| Error::from(ErrorKind::ImportTSVIncorrectRow(row, column)))
+    }
+
+    /// This function prepares a row of a table to be written in a TSV/CSV file.
+    ///
+    /// Sequences are written inline, as JSON. As JSON escapes tabs and newlines, they don't break the TSV format.
+    /// TSV files have no quoting, so their strings keep tabs and newlines escaped. CSV files quote them, so theirs are unescaped.
+    fn export_tsv_row(row: &[DecodedData], format: TableFormat) -> Vec<String> {
+        row.iter().map(|x| match x {
+            DecodedData::StringU8(data) |
+            DecodedData::StringU16(data) |
+            DecodedData::OptionalStringU8(data) |
+            DecodedData::OptionalStringU16(data) if format == TableFormat::CSV => Self::unescape_special_chars(data),
+            _ => x.data_to_string(),
+        }).collect()
+    }
+
+    /// This function serializes a `SerializedTable` to pretty-printed RON.
+    ///
+    /// We don't use RON's serializer for this, because it writes floats without fractional part as integers, and then fails to read back
+    /// the ones too big for an `i64`, like `f32::MAX`. Here every float keeps its fractional part. `i64::MIN` is written as a float too,
+    /// as RON fails to read it as an integer, and long integers are read back from integral floats.
+    fn to_ron_string(serialized_table: &SerializedTable) -> Result<String> {
+        let mut output = format!("(\n    table_name: {},\n    version: {},\n    entries: [", ron::ser::to_string(&serialized_table.table_name)?, serialized_table.version);
+        if !serialized_table.entries.is_empty() {
+            output.push('\n');
+            for entry in &serialized_table.entries {
+                output.push_str(&RON_INDENT.repeat(2));
+                Self::write_ron_map(&mut output, entry, 2)?;
+                output.push_str(",\n");
+            }
+            output.push_str(RON_INDENT);
+        }
+
+        output.push_str("],\n)");
+        Ok(output)
+    }
+
+    /// This function writes a JSON object as a RON map, indented with the provided level.
+    fn write_ron_map(output: &mut String, map: &Map<String, Value>, indent: usize) -> Result<()> {
+        output.push('{');
+        if !map.is_empty() {
+            output.push('\n');
+            for (key, value) in map {
+                output.push_str(&RON_INDENT.repeat(indent + 1));
+                output.push_str(&ron::ser::to_string(key)?);
+                output.push_str(": ");
+                Self::write_ron_value(output, value, indent + 1)?;
+                output.push_str(",\n");
+            }
+            output.push_str(&RON_INDENT.repeat(indent));
+        }
+
+        output.push('}');
+        Ok(())
+    }
+
+    /// This function writes a JSON value as RON, indented with the provided level.
+    fn write_ron_value(output: &mut String, value: &Value, indent: usize) -> Result<()> {
+        match value {
+            Value::Null => output.push_str("()"),
+            Value::Bool(_) | Value::String(_) => output.push_str(&ron::ser::to_string(value)?),
+            Value::Number(number) => match number.as_i64() {
+                Some(number) if number == i64::MIN => output.push_str(&format!("{}.0", number)),
+                Some(number) => output.push_str(&number.to_string()),
+                None => match number.as_u64() {
+                    Some(number) => output.push_str(&number.to_string()),
+                    None => {
+                        let number = number.as_f64().unwrap_or_default().to_string();
+                        output.push_str(&number);
+                        if !number.contains('.') { output.push_str(".0"); }
+                    }
+                }
+            },
+            Value::Array(values) => {
+                output.push('[');
+                if !values.is_empty() {
+                    output.push('\n');
+                    for value in values {
+                        output.push_str(&RON_INDENT.repeat(indent + 1));
+                        Self::write_ron_value(output, value, indent + 1)?;
+                        output.push_str(",\n");
+                    }
+                    output.push_str(&RON_INDENT.repeat(indent));
+                }
+                output.push(']');
+            }
+            Value::Object(map) => Self::write_ron_map(output, map, indent)?,
+        }
+        Ok(())
     }
 
     /// This function escapes certain characters of the provided string.
@@ -0,0 +1,542 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module containing test for the `Table` module, to make sure nested tables (Sequences) survive the trip, and bulk edits do what they should.
+
+It also contains property tests, with random tables made by the `TableGenerator`, to make sure any valid table survives encoding and all the import/export formats.
+!*/
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::env::temp_dir;
+use std::path::PathBuf;
+
+use crate::schema::{Definition, Field, FieldType, Schema, VersionedFile};
+use crate::schema::provenance::{DefinitionProvenance, DefinitionSource};
+
+use crate::packedfile::DecodedPackedFile;
+use crate::packfile::{PackFile, PFHVersion};
+use crate::packfile::packedfile::PackedFile;
+
+use super::{DecodedData, Table, TableFormat};
+use super::db::{DB, DBReference};
+use super::generator::TableGenerator;
+use super::loc::Loc;
+
+/// This function returns a `Table` with a Sequence field, containing another Sequence field.
+fn get_test_table() -> Table {
+    let mut nested_definition = Definition::new(-1);
+    nested_definition.fields.push(Field { name: "value".to_owned(), field_type: FieldType::Float, ..Default::default() });
+    nested_definition.fields.push(Field { name: "enabled".to_owned(), field_type: FieldType::Boolean, ..Default::default() });
+
+    let mut sequence_definition = Definition::new(-1);
+    sequence_definition.fields.push(Field { name: "name".to_owned(), field_type: FieldType::StringU8, ..Default::default() });
+    sequence_definition.fields.push(Field { name: "values".to_owned(), field_type: FieldType::Sequence(nested_definition.clone()), ..Default::default() });
+
+    let mut definition = Definition::new(0);
+    definition.fields.push(Field { name: "key".to_owned(), field_type: FieldType::StringU8, is_key: true, ..Default::default() });
+    definition.fields.push(Field { name: "sequence".to_owned(), field_type: FieldType::Sequence(sequence_definition.clone()), ..Default::default() });
+
+    let mut nested_table = Table::new(&nested_definition);
+    nested_table.set_table_data(&[vec![DecodedData::Float(0.5), DecodedData::Boolean(true)]]).unwrap();
+
+    let mut sequence_table = Table::new(&sequence_definition);
+    sequence_table.set_table_data(&[
+        vec![DecodedData::StringU8("with \"quotes\" and [brackets]".to_owned()), DecodedData::Sequence(nested_table)],
+        vec![DecodedData::StringU8("empty".to_owned()), DecodedData::Sequence(Table::new(&nested_definition))],
+    ]).unwrap();
+
+    let mut table = Table::new(&definition);
+    table.set_table_data(&[vec![DecodedData::StringU8("key".to_owned()), DecodedData::Sequence(sequence_table)]]).unwrap();
+    table
+}
+
+#[test]
+fn test_table_sequence_encode_decode() {
+    let table = get_test_table();
+    let mut data = vec![];
+    table.encode(&mut data).unwrap();
+
+    let mut decoded_table = Table::new(table.get_ref_definition());
+    let mut index = 0;
+    decoded_table.decode(&data, 1, &mut index, false).unwrap();
+    assert_eq!(index, data.len());
+    assert_eq!(decoded_table, table);
+}
+
+#[test]
+fn test_table_decode_sequence() {
+    let table = get_test_table();
+    let mut data = vec![];
+    table.encode(&mut data).unwrap();
+
+    // The sequence goes right after the key, which is a StringU8 with its length before it.
+    let sequence = &table.get_ref_table_data()[0][1];
+    let sequence_definition = match &table.get_ref_definition().fields[1].field_type {
+        FieldType::Sequence(definition) => definition.clone(),
+        _ => unreachable!(),
+    };
+
+    let mut index = 5;
+    let decoded_sequence = Table::decode_sequence(&sequence_definition, &data, &mut index).unwrap();
+    assert_eq!(index, data.len());
+    assert_eq!(&DecodedData::Sequence(decoded_sequence), sequence);
+
+    // Counts bigger than the data left are not Sequences.
+    let mut index = 0;
+    assert!(Table::decode_sequence(&sequence_definition, &[255, 255, 255, 255, 0, 0], &mut index).is_err());
+}
+
+#[test]
+fn test_table_sequence_from_pack() {
+
+    // The example PackFile has a key-value table. Decode it, then nest its rows in a Sequence and make sure they survive a full save and read.
+    let name = "_kv_battle_ai_ability_usage_variables_tables";
+    let mut pack_file = PackFile::read(&PathBuf::from("../test_files/example_list_tables.pack"), false).unwrap();
+    let data = pack_file.get_ref_mut_packed_file_by_path(&["db".to_owned(), name.to_owned(), "data__".to_owned()]).unwrap().get_raw_data().unwrap();
+
+    let mut kv_definition = Definition::new(0);
+    kv_definition.fields.push(Field { name: "key".to_owned(), field_type: FieldType::StringU8, is_key: true, ..Default::default() });
+    kv_definition.fields.push(Field { name: "value".to_owned(), field_type: FieldType::Float, ..Default::default() });
+
+    let mut sequence_definition = Definition::new(1);
+    sequence_definition.fields.push(Field { name: "key".to_owned(), field_type: FieldType::StringU8, is_key: true, ..Default::default() });
+    sequence_definition.fields.push(Field { name: "variables".to_owned(), field_type: FieldType::Sequence(kv_definition.clone()), ..Default::default() });
+
+    let mut schema = Schema::default();
+    schema.add_versioned_file(&VersionedFile::DB(name.to_owned(), vec![sequence_definition.clone(), kv_definition.clone()]));
+
+    let kv_table = DB::read(&data, name, &schema, false).unwrap();
+    assert_eq!(kv_table.get_ref_table_data().len(), 3);
+    assert_eq!(kv_table.get_ref_table_data()[1], vec![DecodedData::StringU8("mana_saving_threshold".to_owned()), DecodedData::Float(0.4)]);
+
+    let mut variables = Table::new(&kv_definition);
+    variables.set_table_data(kv_table.get_ref_table_data()).unwrap();
+    let mut sequence_table = DB::new(name, None, &sequence_definition);
+    sequence_table.set_table_data(&[vec![DecodedData::StringU8("battle_ai".to_owned()), DecodedData::Sequence(variables)]]).unwrap();
+    let decoded_table = DB::read(&sequence_table.save().unwrap(), name, &schema, false).unwrap();
+    assert_eq!(decoded_table.get_ref_table_data(), sequence_table.get_ref_table_data());
+}
+
+#[test]
+fn test_table_bincode() {
+
+    // Tables are bincode-serialized for incomplete decodings and the Assembly Kit PAK files, so optional metadata must survive it.
+    let mut table = get_test_table();
+    let mut definition = table.get_definition();
+    definition.provenance = Some(DefinitionProvenance::new(DefinitionSource::AssemblyKit));
+    definition.fields[0].enum_values = Some(vec![("key".to_owned(), "Key".to_owned())].into_iter().collect());
+    table.set_definition(&definition);
+
+    let data = bincode::serialize(&table).unwrap();
+    assert_eq!(bincode::deserialize::<Table>(&data).unwrap(), table);
+
+    let mut db = DB::new("test_tables", None, &definition);
+    db.set_table_data(table.get_ref_table_data()).unwrap();
+    let tables = vec![db, DB::new("empty_tables", None, &Definition::new(1))];
+    let data = bincode::serialize(&tables).unwrap();
+    assert_eq!(bincode::deserialize::<Vec<DB>>(&data).unwrap(), tables);
+}
+
+#[test]
+fn test_table_sequence_string() {
+    let table = get_test_table();
+    let field_type = &table.get_ref_definition().fields[1].field_type;
+    let sequence = &table.get_ref_table_data()[0][1];
+    let string = sequence.data_to_string();
+    assert_eq!(&DecodedData::new_from_type_and_string(field_type, &string).unwrap(), sequence);
+    assert!(DecodedData::new_from_type_and_string(field_type, "[[\"missing_values\"]]").is_err());
+
+    // Rows are keyed by field name at every level, like exported rows. Missing fields take their default value, and unknown ones fail.
+    assert_eq!(string, r#"[{"name":"with \"quotes\" and [brackets]","values":[{"enabled":true,"value":0.5}]},{"name":"empty","values":[]}]"#);
+    let sequence = DecodedData::new_from_type_and_string(field_type, r#"[{"name":"only_name"}]"#).unwrap();
+    assert_eq!(sequence.data_to_string(), r#"[{"name":"only_name","values":[]}]"#);
+    assert!(DecodedData::new_from_type_and_string(field_type, r#"[{"name":"typo","valeus":[]}]"#).is_err());
+}
+
+#[test]
+fn test_table_sequence_tsv() {
+    let table = get_test_table();
+    let path = temp_dir().join("rpfm_test_table_sequence.tsv");
+    table.export(&path, "test_table", TableFormat::TSV).unwrap();
+    let imported_table = Table::import(table.get_ref_definition(), &path, "test_table", TableFormat::TSV);
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(imported_table.unwrap(), (table, vec![]));
+}
+
+#[test]
+fn test_table_tsv_other_version() {
+    let path = temp_dir().join("rpfm_test_table_other_version.tsv");
+    std::fs::write(&path, "test_table\t1\nremoved\tkey\tvalue\nold\tkey_1\t2.5\n").unwrap();
+
+    let mut definition = Definition::new(2);
+    definition.fields.push(Field { name: "key".to_owned(), field_type: FieldType::StringU8, is_key: true, ..Default::default() });
+    definition.fields.push(Field { name: "added".to_owned(), field_type: FieldType::Integer, default_value: Some("5".to_owned()), ..Default::default() });
+    definition.fields.push(Field { name: "new".to_owned(), field_type: FieldType::Boolean, ..Default::default() });
+    definition.fields.push(Field { name: "value".to_owned(), field_type: FieldType::Float, ..Default::default() });
+
+    let imported_table = Table::import(&definition, &path, "test_table", TableFormat::TSV);
+    let _ = std::fs::remove_file(&path);
+    let (table, extra_columns) = imported_table.unwrap();
+    assert_eq!(table.get_ref_table_data(), &[vec![
+        DecodedData::StringU8("key_1".to_owned()),
+        DecodedData::Integer(5),
+        DecodedData::Boolean(false),
+        DecodedData::Float(2.5),
+    ]]);
+    assert_eq!(extra_columns, vec!["removed".to_owned()]);
+}
+
+#[test]
+fn test_table_tsv_missing_header() {
+    let mut definition = Definition::new(0);
+    definition.fields.push(Field { name: "key".to_owned(), field_type: FieldType::StringU8, is_key: true, ..Default::default() });
+    definition.fields.push(Field { name: "value".to_owned(), field_type: FieldType::Float, ..Default::default() });
+
+    let path = temp_dir().join("rpfm_test_table_missing_header.tsv");
+    for contents in &["test_table\t0\n", "test_table\t0\nkey_1\t2.5\nkey_2\t3.5\n"] {
+        std::fs::write(&path, contents).unwrap();
+        assert!(Table::import(&definition, &path, "test_table", TableFormat::TSV).is_err());
+    }
+
+    // A header row with no data rows is just an empty table.
+    std::fs::write(&path, "test_table\t0\nkey\tvalue\n").unwrap();
+    let imported_table = Table::import(&definition, &path, "test_table", TableFormat::TSV);
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(imported_table.unwrap(), (Table::new(&definition), vec![]));
+}
+
+#[test]
+fn test_db_renamed_keys() {
+    let mut definition = Definition::new(0);
+    definition.fields.push(Field { name: "first".to_owned(), field_type: FieldType::StringU8, is_key: true, ..Default::default() });
+    definition.fields.push(Field { name: "second".to_owned(), field_type: FieldType::StringU8, is_key: true, ..Default::default() });
+    definition.fields.push(Field { name: "value".to_owned(), field_type: FieldType::Integer, ..Default::default() });
+
+    let row = |first: &str, second: &str| vec![DecodedData::StringU8(first.to_owned()), DecodedData::StringU8(second.to_owned()), DecodedData::Integer(0)];
+    let mut old_table = DB::new("test_tables", None, &definition);
+    old_table.set_table_data(&[row("x", "y"), row("a", "bc"), row("p", "1"), row("q", "1"), row("k", "1")]).unwrap();
+
+    // Joined keys can collide, but the keys of each column can't.
+    assert_eq!(old_table.get_row_key(&row("a", "bc")), old_table.get_row_key(&row("ab", "c")));
+    assert_ne!(old_table.get_row_keys(&row("a", "bc")), old_table.get_row_keys(&row("ab", "c")));
+
+    // Changing a key is a rename, swapping keys between rows isn't, and changing a key without changing its joined key has nothing to rename.
+    let mut new_table = old_table.clone();
+    new_table.set_table_data(&[row("x", "z"), row("ab", "c"), row("q", "1"), row("p", "1"), row("k", "1")]).unwrap();
+    assert_eq!(new_table.get_renamed_keys(&old_table), vec![("xy".to_owned(), "xz".to_owned())]);
+}
+
+#[test]
+fn test_db_references_to_key() {
+    let mut units_definition = Definition::new(1);
+    units_definition.fields.push(Field { name: "key".to_owned(), field_type: FieldType::StringU8, is_key: true, ..Default::default() });
+
+    let mut references_definition = Definition::new(1);
+    references_definition.fields.push(Field { name: "unit".to_owned(), field_type: FieldType::StringU8, is_reference: Some(("ref_units".to_owned(), "key".to_owned())), ..Default::default() });
+    references_definition.fields.push(Field { name: "ability".to_owned(), field_type: FieldType::StringU8, ..Default::default() });
+
+    let mut schema = Schema::default();
+    schema.add_versioned_file(&VersionedFile::DB("ref_units_tables".to_owned(), vec![units_definition]));
+    schema.add_versioned_file(&VersionedFile::DB("ref_abilities_tables".to_owned(), vec![references_definition.clone()]));
+
+    let reference = |unit: &str, ability: &str| vec![DecodedData::StringU8(unit.to_owned()), DecodedData::StringU8(ability.to_owned())];
+    let table = |rows: &[Vec<DecodedData>]| {
+        let mut table = DB::new("ref_abilities_tables", None, &references_definition);
+        table.set_table_data(rows).unwrap();
+        DecodedPackedFile::DB(table)
+    };
+
+    // The game files have a table replaced by the one of our PackFile, whose references must be ignored, and another one.
+    let mod_path = vec!["db".to_owned(), "ref_abilities_tables".to_owned(), "mod".to_owned()];
+    let vanilla_path = vec!["db".to_owned(), "ref_abilities_tables".to_owned(), "data__".to_owned()];
+    let mut dependencies = vec![
+        PackedFile::new_from_decoded(&table(&[reference("unit_a", "replaced")]), mod_path.to_vec()),
+        PackedFile::new_from_decoded(&table(&[reference("unit_c", "vanilla"), reference("unit_a", "vanilla")]), vanilla_path.to_vec()),
+    ];
+
+    let mut pack_file = PackFile::new_with_name("test.pack", PFHVersion::PFH5);
+    pack_file.add_packed_file(&PackedFile::new_from_decoded(&table(&[reference("unit_b", "mod"), reference("unit_a", "mod")]), mod_path.to_vec()), true).unwrap();
+
+    let reference_to = |path: &[String], row_number, is_from_game_files| DBReference {
+        path: path.to_vec(),
+        column_name: "unit".to_owned(),
+        column_number: 0,
+        row_number,
+        is_from_game_files,
+    };
+
+    let references = DB::get_references_to_key(&mut pack_file, &schema, "ref_units_tables", "unit_a", &mut dependencies).unwrap();
+    assert_eq!(references, vec![reference_to(&mod_path, 1, false), reference_to(&vanilla_path, 1, true)]);
+    assert!(DB::get_references_to_key(&mut pack_file, &schema, "ref_units_tables", "unit_d", &mut dependencies).unwrap().is_empty());
+}
+
+#[test]
+fn test_table_formats() {
+    let table = get_test_table();
+    for format in &[TableFormat::CSV, TableFormat::JSON, TableFormat::RON] {
+        let path = temp_dir().join(format!("rpfm_test_table_formats.{}", format.get_extension()));
+        table.export(&path, "test_table", *format).unwrap();
+        assert_eq!(TableFormat::from_path(&path).unwrap(), *format);
+        assert_eq!(Table::get_table_name_from_file(&path, *format).unwrap(), "test_table");
+
+        let imported_table = Table::import(table.get_ref_definition(), &path, "test_table", *format);
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(imported_table.unwrap(), (table.clone(), vec![]));
+    }
+}
+
+#[test]
+fn test_table_export_text() {
+    let mut definition = Definition::new(1);
+    definition.fields.push(Field { name: "text".to_owned(), field_type: FieldType::StringU8, ..Default::default() });
+    definition.fields.push(Field { name: "nan".to_owned(), field_type: FieldType::Float, ..Default::default() });
+    definition.fields.push(Field { name: "infinity".to_owned(), field_type: FieldType::Float, ..Default::default() });
+    definition.fields.push(Field { name: "integral".to_owned(), field_type: FieldType::Float, ..Default::default() });
+    definition.fields.push(Field { name: "min".to_owned(), field_type: FieldType::LongInteger, ..Default::default() });
+
+    // Strings are stored with their tabs and newlines escaped, the same way the decoder leaves them.
+    let mut table = Table::new(&definition);
+    table.set_table_data(&[vec![
+        DecodedData::StringU8("line\\\\nnext\\\\tend".to_owned()),
+        DecodedData::Float(f32::NAN),
+        DecodedData::Float(f32::NEG_INFINITY),
+        DecodedData::Float(1.0),
+        DecodedData::LongInteger(i64::MIN),
+    ]]).unwrap();
+
+    let expected: [(TableFormat, &[&str]); 4] = [
+        (TableFormat::TSV, &["line\\\\nnext\\\\tend\tNaN\t-inf\t1\t-9223372036854775808"]),
+        (TableFormat::CSV, &["\"line\nnext\tend\",NaN,-inf,1,-9223372036854775808"]),
+        (TableFormat::JSON, &[r#""text": "line\nnext\tend""#, r#""nan": "NaN""#, r#""infinity": "-inf""#, r#""integral": 1.0"#, r#""min": -9223372036854775808"#]),
+        (TableFormat::RON, &[r#""text": "line\nnext\tend""#, r#""nan": "NaN""#, r#""infinity": "-inf""#, r#""integral": 1.0"#, r#""min": -9223372036854775808.0"#]),
+    ];
+
+    for (format, lines) in &expected {
+        let path = temp_dir().join(format!("rpfm_test_table_export_text.{}", format.get_extension()));
+        table.export(&path, "test_table", *format).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        let imported_table = Table::import(&definition, &path, "test_table", *format);
+        let _ = std::fs::remove_file(&path);
+
+        for line in *lines {
+            assert!(text.contains(line), "format {:?}, expected {:?} in:\n{}", format, line, text);
+        }
+
+        // NaN is never equal to itself, so it's checked apart.
+        let imported_row = imported_table.unwrap().0.get_table_data().remove(0);
+        assert!(matches!(imported_row[1], DecodedData::Float(x) if x.is_nan()), "format {:?}", format);
+        assert_eq!(&imported_row[2..], &table.get_ref_table_data()[0][2..], "format {:?}", format);
+        assert_eq!(imported_row[0], table.get_ref_table_data()[0][0], "format {:?}", format);
+    }
+
+    assert!(TableFormat::from_path(&PathBuf::from("table.txt")).is_err());
+    assert!(TableFormat::from_path(&PathBuf::from("table")).is_err());
+}
+
+#[test]
+fn test_table_apply_expression() {
+    let mut definition = Definition::new(1);
+    definition.fields.push(Field { name: "key".to_owned(), field_type: FieldType::StringU8, is_key: true, ..Default::default() });
+    definition.fields.push(Field { name: "category".to_owned(), field_type: FieldType::StringU8, ..Default::default() });
+    definition.fields.push(Field { name: "cost".to_owned(), field_type: FieldType::Integer, ..Default::default() });
+
+    let mut table = Table::new(&definition);
+    table.set_table_data(&[
+        vec![DecodedData::StringU8("knights".to_owned()), DecodedData::StringU8("cavalry".to_owned()), DecodedData::Integer(1000)],
+        vec![DecodedData::StringU8("swordsmen".to_owned()), DecodedData::StringU8("infantry".to_owned()), DecodedData::Integer(500)],
+    ]).unwrap();
+
+    // Only the filtered rows must change, and floats must be rounded back into integers.
+    assert_eq!(table.apply_expression("cost", "cost * 1.15", Some("category == \"cavalry\"")).unwrap(), vec![0]);
+    assert_eq!(table.get_ref_table_data()[0][2], DecodedData::Integer(1150));
+    assert_eq!(table.get_ref_table_data()[1][2], DecodedData::Integer(500));
+
+    // String functions and conditionals.
+    assert_eq!(table.apply_expression("key", "if(cost > 600, upper(key), concat(key, \"_\", len(key)))", None).unwrap(), vec![0, 1]);
+    assert_eq!(table.get_ref_table_data()[0][0], DecodedData::StringU8("KNIGHTS".to_owned()));
+    assert_eq!(table.get_ref_table_data()[1][0], DecodedData::StringU8("swordsmen_9".to_owned()));
+
+    // Errors must not leave the table half-edited.
+    let data = table.get_table_data();
+    assert!(table.apply_expression("cost", "missing_column + 1", None).is_err());
+    assert!(table.apply_expression("missing_column", "1", None).is_err());
+    assert!(table.apply_expression("cost", "(cost + 1", None).is_err());
+    assert!(table.apply_expression("cost", "cost - 501", Some("cost")).is_err());
+    assert!(table.apply_expression("cost", "1000 / (cost - 500)", None).is_err());
+    assert_eq!(table.get_table_data(), data);
+}
+
+#[test]
+fn test_table_apply_expression_overflow() {
+    let mut definition = Definition::new(1);
+    definition.fields.push(Field { name: "key".to_owned(), field_type: FieldType::StringU8, is_key: true, ..Default::default() });
+    definition.fields.push(Field { name: "value".to_owned(), field_type: FieldType::LongInteger, ..Default::default() });
+
+    let mut table = Table::new(&definition);
+    table.set_table_data(&[vec![DecodedData::StringU8("min".to_owned()), DecodedData::LongInteger(i64::MIN)]]).unwrap();
+
+    // Operations that overflow with the smallest integer must fail instead of panicking or wrapping.
+    let data = table.get_table_data();
+    for expression in &["-value", "value % -1", "abs(value)", "value - 1", "value * -1"] {
+        let error = table.apply_expression("value", expression, None).unwrap_err();
+        assert!(error.to_string().contains("Integer overflow."), "{}", expression);
+    }
+    assert_eq!(table.get_table_data(), data);
+
+    // The same operations are fine with other values.
+    assert_eq!(table.apply_expression("value", "abs(value + 1) % 10", None).unwrap(), vec![0]);
+    assert_eq!(table.get_ref_table_data()[0][1], DecodedData::LongInteger(7));
+    assert_eq!(table.apply_expression("value", "-value", None).unwrap(), vec![0]);
+    assert_eq!(table.get_ref_table_data()[0][1], DecodedData::LongInteger(-7));
+}
+
+#[test]
+fn test_table_guess_definitions() {
+    let mut definition = Definition::new(2);
+    definition.fields.push(Field { name: "key".to_owned(), field_type: FieldType::StringU8, is_key: true, ..Default::default() });
+    definition.fields.push(Field { name: "cost".to_owned(), field_type: FieldType::Integer, ..Default::default() });
+    definition.fields.push(Field { name: "speed".to_owned(), field_type: FieldType::Float, ..Default::default() });
+    definition.fields.push(Field { name: "is_naval".to_owned(), field_type: FieldType::Boolean, ..Default::default() });
+    definition.fields.push(Field { name: "icon".to_owned(), field_type: FieldType::OptionalStringU8, ..Default::default() });
+
+    let mut table = DB::new("land_units_tables", None, &definition);
+    table.set_table_data(&(0..20).map(|x| vec![
+        DecodedData::StringU8(format!("unit_{}", x)),
+        DecodedData::Integer(100 * x),
+        DecodedData::Float(1.5 + x as f32),
+        DecodedData::Boolean(x % 3 == 0),
+        DecodedData::OptionalStringU8(if x % 2 == 0 { format!("icon_{}.png", x) } else { String::new() }),
+    ]).collect::<Vec<Vec<DecodedData>>>()).unwrap();
+    let data = table.save().unwrap();
+
+    // Without a schema, the field types should be guessed from the data alone.
+    let field_types = definition.fields.iter().map(|x| x.field_type.clone()).collect::<Vec<FieldType>>();
+    let guesses = DB::guess_definitions(&data, "land_units_tables", None, 5).unwrap();
+    assert_eq!(guesses[0].fields.iter().map(|x| x.field_type.clone()).collect::<Vec<FieldType>>(), field_types);
+    assert_eq!(guesses[0].similar_version, None);
+
+    // With an older version without the new field, the guess should take the names of the old fields.
+    let mut old_definition = definition.clone();
+    old_definition.version = 1;
+    old_definition.fields.remove(3);
+
+    let mut schema = Schema::default();
+    schema.add_versioned_file(&VersionedFile::DB("land_units_tables".to_owned(), vec![old_definition]));
+    let guesses = DB::guess_definitions(&data, "land_units_tables", Some(&schema), 5).unwrap();
+    assert_eq!(guesses[0].fields.iter().map(|x| x.field_type.clone()).collect::<Vec<FieldType>>(), field_types);
+    assert_eq!(guesses[0].similar_version, Some(1));
+    assert_eq!(guesses[0].fields.iter().map(|x| &*x.name).collect::<Vec<&str>>(), vec!["key", "cost", "speed", "unknown_3", "icon"]);
+}
+
+/// This function returns a definition with a field of each type, including nested sequences.
+fn get_generator_test_definition() -> Definition {
+    let mut nested_definition = Definition::new(-1);
+    nested_definition.fields.push(Field { name: "value".to_owned(), field_type: FieldType::Float, ..Default::default() });
+    nested_definition.fields.push(Field { name: "label".to_owned(), field_type: FieldType::StringU16, ..Default::default() });
+
+    let mut sequence_definition = Definition::new(-1);
+    sequence_definition.fields.push(Field { name: "id".to_owned(), field_type: FieldType::Integer, is_key: true, ..Default::default() });
+    sequence_definition.fields.push(Field { name: "values".to_owned(), field_type: FieldType::Sequence(nested_definition), ..Default::default() });
+
+    let mut definition = Definition::new(3);
+    definition.fields.push(Field { name: "key".to_owned(), field_type: FieldType::StringU8, is_key: true, max_length: 12, ..Default::default() });
+    definition.fields.push(Field { name: "enabled".to_owned(), field_type: FieldType::Boolean, ..Default::default() });
+    definition.fields.push(Field { name: "cost".to_owned(), field_type: FieldType::Integer, ..Default::default() });
+    definition.fields.push(Field { name: "big_cost".to_owned(), field_type: FieldType::LongInteger, ..Default::default() });
+    definition.fields.push(Field { name: "speed".to_owned(), field_type: FieldType::Float, ..Default::default() });
+    definition.fields.push(Field { name: "name".to_owned(), field_type: FieldType::StringU16, ..Default::default() });
+    definition.fields.push(Field { name: "icon".to_owned(), field_type: FieldType::OptionalStringU8, ..Default::default() });
+    definition.fields.push(Field { name: "tooltip".to_owned(), field_type: FieldType::OptionalStringU16, ..Default::default() });
+    definition.fields.push(Field { name: "sequence".to_owned(), field_type: FieldType::Sequence(sequence_definition), ..Default::default() });
+    definition
+}
+
+#[test]
+fn test_table_generator_encode_decode() {
+    let definition = get_generator_test_definition();
+    for seed in 0..32 {
+        let table = TableGenerator::new(seed).generate_table(&definition);
+        let mut data = vec![];
+        table.encode(&mut data).unwrap();
+
+        let mut decoded_table = Table::new(&definition);
+        let mut index = 0;
+        decoded_table.decode(&data, table.get_entry_count() as u32, &mut index, false).unwrap();
+        assert_eq!(index, data.len(), "seed {}", seed);
+        assert_eq!(decoded_table, table, "seed {}", seed);
+    }
+}
+
+#[test]
+fn test_table_generator_formats() {
+    let definition = get_generator_test_definition();
+    for seed in 0..8 {
+        let table = TableGenerator::new(seed).generate_table(&definition);
+        for format in &[TableFormat::TSV, TableFormat::CSV, TableFormat::JSON, TableFormat::RON] {
+            let path = temp_dir().join(format!("rpfm_test_table_generator_{}.{}", seed, format.get_extension()));
+            table.export(&path, "test_table", *format).unwrap();
+            let imported_table = Table::import(&definition, &path, "test_table", *format);
+            let _ = std::fs::remove_file(&path);
+            assert_eq!(imported_table.unwrap(), (table.clone(), vec![]), "seed {}, format {:?}", seed, format);
+        }
+    }
+}
+
+#[test]
+fn test_table_generator_db_and_loc() {
+    let definition = get_generator_test_definition();
+    let mut schema = Schema::default();
+    schema.add_versioned_file(&VersionedFile::DB("test_tables".to_owned(), vec![definition.clone()]));
+
+    let mut generator = TableGenerator::new(7);
+    let table = generator.generate_db("test_tables", &definition);
+    let decoded_table = DB::read(&table.save().unwrap(), "test_tables", &schema, false).unwrap();
+    assert_eq!(decoded_table.get_ref_table_data(), table.get_ref_table_data());
+
+    let mut loc_definition = Definition::new(1);
+    loc_definition.fields.push(Field { name: "key".to_owned(), field_type: FieldType::StringU16, is_key: true, ..Default::default() });
+    loc_definition.fields.push(Field { name: "text".to_owned(), field_type: FieldType::StringU16, ..Default::default() });
+    loc_definition.fields.push(Field { name: "tooltip".to_owned(), field_type: FieldType::Boolean, ..Default::default() });
+    schema.add_versioned_file(&VersionedFile::Loc(vec![loc_definition.clone()]));
+
+    let loc = generator.generate_loc(&loc_definition);
+    let decoded_loc = Loc::read(&loc.save().unwrap(), &schema, false).unwrap();
+    assert_eq!(decoded_loc.get_ref_table_data(), loc.get_ref_table_data());
+}
+
+#[test]
+fn test_table_generator_valid_data() {
+    let definition = get_generator_test_definition();
+    let mut references = BTreeMap::new();
+    references.insert(2, vec!["10", "20", "30"].into_iter().map(|x| (x.to_owned(), String::new())).collect::<BTreeMap<String, String>>());
+
+    let mut generator = TableGenerator::new(42);
+    generator.set_reference_data(references);
+    for _ in 0..8 {
+        let table = generator.generate_table(&definition);
+        let keys = table.get_ref_table_data().iter().map(|x| x[0].data_to_string()).collect::<BTreeSet<String>>();
+        assert_eq!(keys.len(), table.get_entry_count());
+
+        for row in table.get_ref_table_data() {
+            for (cell, field) in row.iter().zip(definition.fields.iter()) {
+                assert!(cell.is_field_type_correct(field.field_type.clone()));
+            }
+            assert!(row[0].data_to_string().chars().count() <= 12);
+            assert!(matches!(&row[2], DecodedData::Integer(x) if *x == 10 || *x == 20 || *x == 30));
+        }
+    }
+
+    // Booleans can only make two unique keys, so the rest of the rows must be dropped.
+    let mut definition = Definition::new(1);
+    definition.fields.push(Field { name: "key".to_owned(), field_type: FieldType::Boolean, is_key: true, ..Default::default() });
+    generator.max_rows = 16;
+    assert!((0..8).all(|_| generator.generate_table(&definition).get_entry_count() <= 2));
+}
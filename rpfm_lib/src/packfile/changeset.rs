@@ -0,0 +1,122 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with all the code to keep track of changes done to multiple PackedFiles at once.
+
+Some operations, like renaming a key across all the tables that reference it, touch a lot of PackedFiles
+in one go. This module allows to undo/redo all those changes as if they were a single one.
+!*/
+
+use rpfm_error::{ErrorKind, Result};
+
+use crate::packfile::PackFile;
+use crate::packedfile::DecodedPackedFile;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct represents a set of changes done to the `PackedFiles` of a `PackFile` as a single operation.
+#[derive(Clone, Debug, Default)]
+pub struct ChangeSet {
+
+    /// The list of changed `PackedFiles`, in the format `(path, data_before, data_after)`.
+    changes: Vec<(Vec<String>, DecodedPackedFile, DecodedPackedFile)>,
+}
+
+//---------------------------------------------------------------------------//
+//                       Implementation of ChangeSet
+//---------------------------------------------------------------------------//
+
+/// Implementation of `ChangeSet`.
+impl ChangeSet {
+
+    /// This function creates a new empty `ChangeSet`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// This function adds a change to the `ChangeSet`.
+    ///
+    /// If the `PackedFile` was already changed in this `ChangeSet`, only his data after the change is updated.
+    pub fn add_change(&mut self, path: &[String], data_before: DecodedPackedFile, data_after: DecodedPackedFile) {
+        match self.changes.iter_mut().find(|(change_path, _, _)| change_path == path) {
+            Some((_, _, change_after)) => *change_after = data_after,
+            None => self.changes.push((path.to_vec(), data_before, data_after)),
+        }
+    }
+
+    /// This function returns the paths of all the `PackedFiles` changed by this `ChangeSet`.
+    pub fn get_paths(&self) -> Vec<Vec<String>> {
+        self.changes.iter().map(|(path, _, _)| path.to_vec()).collect()
+    }
+
+    /// This function returns if the `ChangeSet` has no changes.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// This function reverts all the changes of this `ChangeSet` in the provided `PackFile`.
+    ///
+    /// If any of the changed `PackedFiles` has been edited or removed since the changes were done, nothing is reverted and it returns an error,
+    /// so we don't lose those edits. Otherwise, it returns the paths of the `PackedFiles` that have been reverted.
+    pub fn undo(&self, pack_file: &mut PackFile) -> Result<Vec<Vec<String>>> {
+        self.check_current_data(pack_file, true)?;
+        Ok(self.undo_unchecked(pack_file))
+    }
+
+    /// This function applies again all the changes of this `ChangeSet` in the provided `PackFile`.
+    ///
+    /// If any of the changed `PackedFiles` has been edited or removed since the changes were undone, nothing is changed and it returns an error,
+    /// so we don't lose those edits. Otherwise, it returns the paths of the `PackedFiles` that have been changed.
+    pub fn redo(&self, pack_file: &mut PackFile) -> Result<Vec<Vec<String>>> {
+        self.check_current_data(pack_file, false)?;
+        Ok(self.changes.iter()
+            .filter_map(|(path, _, data_after)| {
+                let packed_file = pack_file.get_ref_mut_packed_file_by_path(path)?;
+                packed_file.set_decoded(data_after);
+                Some(path.to_vec())
+            })
+            .collect())
+    }
+
+    /// This function reverts all the changes of this `ChangeSet` in the provided `PackFile`, without checking if they have been edited since then.
+    ///
+    /// It's meant to roll back changes that are still being done. It returns the paths of the `PackedFiles` that have been reverted.
+    pub(crate) fn undo_unchecked(&self, pack_file: &mut PackFile) -> Vec<Vec<String>> {
+        self.changes.iter().rev()
+            .filter_map(|(path, data_before, _)| {
+                let packed_file = pack_file.get_ref_mut_packed_file_by_path(path)?;
+                packed_file.set_decoded(data_before);
+                Some(path.to_vec())
+            })
+            .collect()
+    }
+
+    /// This function checks that the changed `PackedFiles` still have the data they had after the changes (or before them, if `is_undo` is false).
+    ///
+    /// `PackedFiles` not decoded, like after saving the `PackFile`, are decoded to compare them. If any of them doesn't match, it returns an error with their paths.
+    fn check_current_data(&self, pack_file: &mut PackFile, is_undo: bool) -> Result<()> {
+        let outdated_paths = self.changes.iter()
+            .filter(|(path, data_before, data_after)| {
+                let expected_data = if is_undo { data_after } else { data_before };
+                match pack_file.get_ref_mut_packed_file_by_path(path) {
+                    Some(packed_file) => packed_file.decode().is_err() || packed_file.get_ref_decoded() != expected_data,
+                    None => true,
+                }
+            })
+            .map(|(path, _, _)| path.to_vec())
+            .collect::<Vec<Vec<String>>>();
+
+        if outdated_paths.is_empty() { Ok(()) }
+        else { Err(ErrorKind::ChangeSetOutdated(outdated_paths).into()) }
+    }
+}
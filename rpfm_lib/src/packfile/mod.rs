@@ -26,6 +26,7 @@ use serde_derive::{Serialize, Deserialize};
 use rayon::prelude::*;
 
 use std::{fmt, fmt::Display};
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs::{DirBuilder, File};
 use std::io::{prelude::*, BufReader, BufWriter, SeekFrom, Read, Write};
 use std::path::{Path, PathBuf};
@@ -40,17 +41,23 @@ use crate::SCHEMA;
 use crate::SETTINGS;
 use crate::SUPPORTED_GAMES;
 use crate::common::{*, decoder::Decoder, encoder::Encoder};
+use crate::global_search::GlobalSearch;
+use crate::packfile::changeset::ChangeSet;
 use crate::packfile::compression::*;
 use crate::packfile::crypto::*;
 use crate::packfile::packedfile::*;
+use crate::packfile::table_update::TableUpdateReport;
 use crate::packedfile::{DecodedPackedFile, PackedFileType};
-use crate::packedfile::table::DecodedData;
+use crate::packedfile::table::{DecodedData, Table, TableFormat};
 use crate::packedfile::table::db::DB;
-use crate::packedfile::table::loc::{Loc, TSV_NAME_LOC};
+use crate::packedfile::table::loc::{self, Loc, TSV_NAME_LOC};
+use crate::schema::{Definition, Schema};
 
+pub mod changeset;
 mod compression;
 mod crypto;
 pub mod packedfile;
+pub mod table_update;
 
 #[cfg(test)]
 mod packfile_test;
@@ -1590,6 +1597,508 @@ impl PackFile {
         self.add_packed_file(&packed_file, true)
     }
 
+    /// This function creates or updates the Loc PackedFile with the localised entries of the DB Table in the provided path.
+    ///
+    /// For each localised field of each row of the table, it ensures there is a Loc entry with the key `<table>_<field>_<key>`,
+    /// either in this PackFile or in the game files, adding an entry with the provided placeholder text to `text/db/<table_file_name>.loc` if there is none.
+    ///
+    /// If the old version of the table is provided, the Loc entries of the rows whose key has been renamed are renamed too,
+    /// in every Loc PackedFile of this PackFile.
+    ///
+    /// It returns the paths of the Loc PackedFiles created and the paths of the ones edited, in that order.
+    pub fn update_loc_from_db(
+        &mut self,
+        db_path: &[String],
+        old_table: Option<&DB>,
+        placeholder_text: &str,
+    ) -> Result<(Vec<Vec<String>>, Vec<Vec<String>>)> {
+
+        // Get the schema, as we'll need it unlocked to decode all the files fast.
+        let schema = SCHEMA.read().unwrap();
+        let schema = if let Some(ref schema) = *schema { schema } else { return Err(ErrorKind::SchemaNotFound.into()) };
+
+        let table = match self.get_ref_mut_packed_file_by_path(db_path) {
+            Some(packed_file) => match packed_file.decode_return_ref_no_locks(schema)? {
+                DecodedPackedFile::DB(table) => table.clone(),
+                _ => return Err(ErrorKind::DBTableIsNotADBTable.into()),
+            }
+            None => return Err(ErrorKind::PackedFileNotFound.into()),
+        };
+
+        let mut added_paths = vec![];
+        let mut edited_paths = vec![];
+        let localised_fields = &table.get_ref_definition().localised_fields;
+        if localised_fields.is_empty() { return Ok((added_paths, edited_paths)) }
+
+        // If we have the old version of the table, first rename the Loc keys of the renamed rows.
+        if let Some(old_table) = old_table {
+            let renamed_loc_keys = table.get_renamed_keys(old_table).iter()
+                .flat_map(|(old_key, new_key)| localised_fields.iter().map(move |field| (field, old_key, new_key)))
+                .map(|(field, old_key, new_key)| (table.get_loc_key(&field.name, old_key), table.get_loc_key(&field.name, new_key)))
+                .collect::<BTreeMap<String, String>>();
+
+            if !renamed_loc_keys.is_empty() {
+                for packed_file in self.get_ref_mut_packed_files_by_type(PackedFileType::Loc, false) {
+                    let path = packed_file.get_path().to_vec();
+                    if let Ok(DecodedPackedFile::Loc(loc)) = packed_file.decode_return_ref_mut_no_locks(schema) {
+                        if loc.rename_keys(&renamed_loc_keys) {
+                            edited_paths.push(path);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Then, get all the Loc keys we already have, in our PackFile and in the game files.
+        let mut existing_keys = BTreeSet::new();
+        for packed_file in self.get_ref_mut_packed_files_by_type(PackedFileType::Loc, false) {
+            if let Ok(DecodedPackedFile::Loc(loc)) = packed_file.decode_return_ref_no_locks(schema) {
+                existing_keys.extend(loc.get_keys());
+            }
+        }
+
+        for packed_file in DEPENDENCY_DATABASE.lock().unwrap().iter_mut().filter(|x| x.get_path().last().unwrap().ends_with(loc::EXTENSION)) {
+            if let Ok(DecodedPackedFile::Loc(loc)) = packed_file.decode_return_ref_no_locks(schema) {
+                existing_keys.extend(loc.get_keys());
+            }
+        }
+
+        // If there are no missing keys, we're done. Inserting them into the existing ones also takes care of duplicated keys.
+        let missing_entries = table.get_loc_keys().into_iter()
+            .filter(|x| existing_keys.insert(x.to_owned()))
+            .map(|x| (x, placeholder_text.to_owned()))
+            .collect::<Vec<(String, String)>>();
+        if missing_entries.is_empty() { return Ok((added_paths, edited_paths)) }
+
+        // Otherwise, add them to the Loc PackedFile of the table, creating it if it doesn't exist.
+        let loc_path = Self::get_loc_path_for_db(db_path);
+        self.add_entries_to_loc(schema, &loc_path, &missing_entries, &mut added_paths, &mut edited_paths)?;
+        Ok((added_paths, edited_paths))
+    }
+
+    /// This function copies a row of a DB Table, and all the rows of other tables referencing it, into this `PackFile`, with a new key.
+    ///
+    /// The rows are searched in both, this `PackFile` and the provided dependency database, through the references of the schema.
+    /// Only the table itself and the tables the schema says reference it are decoded. Rows in the `PackFile` take priority over rows in the game files with the same key, and tables of the game files
+    /// with the same path as a table in the `PackFile` are ignored. Each row is copied only once, even if there are tables
+    /// of different versions with it. The Loc entries of all the copied rows are also copied, with their keys updated to the new ones.
+    ///
+    /// The copied rows are added to a table of the same type and version in the `PackFile`, or to a new table named
+    /// after the new key if there is none. All the edits are prepared before applying them, so if there is an error,
+    /// the `PackFile` is left untouched. It returns the paths of the added and the edited `PackedFiles`, in that order.
+    pub fn clone_key_with_dependents(
+        &mut self,
+        schema: &Schema,
+        dependencies: &mut [PackedFile],
+        table_name: &str,
+        old_key: &str,
+        new_key: &str,
+    ) -> Result<(Vec<Vec<String>>, Vec<Vec<String>>)> {
+
+        // References in the schema use the name of the table without the `_tables` suffix, and we only care about the tables with them.
+        let ref_table = table_name.strip_suffix("_tables").unwrap_or(table_name);
+        let mut table_names = schema.get_tables_referencing(ref_table);
+        table_names.insert(table_name.to_owned());
+        let is_table_needed = |packed_file: &PackedFile| match packed_file.get_packed_file_type_by_path() {
+            PackedFileType::DB => match packed_file.get_path().get(1) {
+                Some(name) => table_names.contains(name),
+                None => false,
+            },
+            PackedFileType::Loc => true,
+            _ => false,
+        };
+
+        // Get all the tables and Loc texts we can copy from. The ones of our PackFile go first, so they take priority.
+        let mut tables = vec![];
+        let mut loc_texts = BTreeMap::new();
+        for packed_file in self.get_ref_mut_packed_files_by_types(&[PackedFileType::DB, PackedFileType::Loc], false) {
+            if !is_table_needed(packed_file) { continue; }
+            match packed_file.decode_return_ref_no_locks(schema) {
+                Ok(DecodedPackedFile::DB(table)) => tables.push(table.clone()),
+                Ok(DecodedPackedFile::Loc(loc)) => loc_texts.extend(loc.get_texts()),
+                _ => continue,
+            }
+        }
+
+        // Files of the game replaced by files of our PackFile are not used by the game, so we ignore them.
+        for packed_file in dependencies.iter_mut() {
+            if !is_table_needed(packed_file) || self.packedfile_exists(packed_file.get_path()) { continue; }
+            match packed_file.get_packed_file_type_by_path() {
+                PackedFileType::DB => if let Ok(DecodedPackedFile::DB(table)) = packed_file.decode_return_ref_no_locks(schema) {
+                    tables.push(table.clone());
+                }
+                PackedFileType::Loc => if let Ok(DecodedPackedFile::Loc(loc)) = packed_file.decode_return_ref_no_locks(schema) {
+                    for (key, text) in loc.get_texts() {
+                        loc_texts.entry(key).or_insert(text);
+                    }
+                }
+                _ => continue,
+            }
+        }
+
+        // References in the schema point to the key columns of the table.
+        let key_columns = match tables.iter().find(|x| x.name == table_name) {
+            Some(table) => table.get_ref_definition().fields.iter().filter(|x| x.is_key).map(|x| x.name.to_owned()).collect::<Vec<String>>(),
+            None => return Err(ErrorKind::DBTableKeyNotFound(old_key.to_owned()).into()),
+        };
+
+        if tables.iter()
+            .filter(|x| x.name == table_name)
+            .any(|x| key_columns.iter().any(|column| !x.get_rows_referencing(ref_table, column, new_key).is_empty())) {
+            return Err(ErrorKind::DBTableKeyAlreadyExists(new_key.to_owned()).into());
+        }
+
+        // Copy the rows referencing the key into new tables, one per table type and version. Rows with a key we already
+        // copied from another table of the same type, of any version, are ignored. Rows without keys are compared whole.
+        let mut copied_tables: BTreeMap<(String, i32), DB> = BTreeMap::new();
+        let mut copied_keys = BTreeSet::new();
+        for table in &tables {
+            let definition = table.get_ref_definition();
+            let mut data = match copied_tables.get(&(table.name.to_owned(), definition.version)) {
+                Some(copied_table) => copied_table.get_table_data(),
+                None => vec![],
+            };
+
+            let mut is_edited = false;
+            for row in key_columns.iter().flat_map(|column| table.get_rows_referencing(ref_table, column, old_key)) {
+                let row_key = table.get_row_keys(&row);
+                let is_copied = if row_key.is_empty() { data.contains(&row) } else { !copied_keys.insert((table.name.to_owned(), row_key)) };
+                if !is_copied {
+                    data.push(row);
+                    is_edited = true;
+                }
+            }
+
+            if is_edited {
+                copied_tables.entry((table.name.to_owned(), definition.version))
+                    .or_insert_with(|| DB::new(&table.name, None, definition))
+                    .set_table_data(&data)?;
+            }
+        }
+
+        if !copied_tables.keys().any(|(name, _)| name == table_name) {
+            return Err(ErrorKind::DBTableKeyNotFound(old_key.to_owned()).into());
+        }
+
+        // Then replace the key in the copies, and prepare the PackedFiles to add or edit with them and their Loc entries.
+        let mut staged_packed_files: BTreeMap<Vec<String>, PackedFile> = BTreeMap::new();
+        let mut added_paths = vec![];
+        let mut edited_paths = vec![];
+        for copied_table in copied_tables.values_mut() {
+            let old_table = copied_table.clone();
+            for column in &key_columns {
+                copied_table.rename_references(ref_table, column, old_key, new_key)?;
+            }
+
+            let localised_fields = &copied_table.get_ref_definition().localised_fields;
+            let loc_entries = copied_table.get_renamed_keys(&old_table).iter()
+                .flat_map(|(old_row_key, new_row_key)| localised_fields.iter().map(move |field| (field, old_row_key, new_row_key)))
+                .filter_map(|(field, old_row_key, new_row_key)| {
+                    let text = loc_texts.get(&copied_table.get_loc_key(&field.name, old_row_key))?;
+                    Some((copied_table.get_loc_key(&field.name, new_row_key), text.to_owned()))
+                })
+                .collect::<Vec<(String, String)>>();
+
+            let version = copied_table.get_ref_definition().version;
+            // All the tables of our PackFile have already been decoded, so we can just check their decoded data.
+            let destination = self.get_ref_mut_packed_files_by_path_start(&["db".to_owned(), copied_table.name.to_owned()]).into_iter()
+                .find(|packed_file| match packed_file.get_ref_decoded() {
+                    DecodedPackedFile::DB(table) => table.get_ref_definition().version == version,
+                    _ => false,
+                });
+
+            let db_path = match destination {
+                Some(packed_file) => {
+                    let mut packed_file = packed_file.clone();
+                    if let DecodedPackedFile::DB(table) = packed_file.decode_return_ref_mut_no_locks(schema)? {
+                        let mut data = table.get_table_data();
+                        data.extend_from_slice(copied_table.get_ref_table_data());
+                        table.set_table_data(&data)?;
+                    }
+
+                    let db_path = packed_file.get_path().to_vec();
+                    edited_paths.push(db_path.to_vec());
+                    staged_packed_files.insert(db_path.to_vec(), packed_file);
+                    db_path
+                }
+                None => {
+
+                    // If that path is already taken, in the PackFile or by another new table, change it using an index.
+                    let mut db_path = vec!["db".to_owned(), copied_table.name.to_owned(), new_key.to_owned()];
+                    let mut index = 1;
+                    while self.packedfile_exists(&db_path) || staged_packed_files.contains_key(&db_path) {
+                        db_path[2] = format!("{}_{}", new_key, index);
+                        index += 1;
+                    }
+
+                    let packed_file = PackedFile::new_from_decoded(&DecodedPackedFile::DB(copied_table.clone()), db_path.to_vec());
+                    added_paths.push(db_path.to_vec());
+                    staged_packed_files.insert(db_path.to_vec(), packed_file);
+                    db_path
+                }
+            };
+
+            if !loc_entries.is_empty() {
+                let loc_path = Self::get_loc_path_for_db(&db_path);
+                let mut packed_file = match staged_packed_files.remove(&loc_path) {
+                    Some(packed_file) => packed_file,
+                    None => match self.get_ref_packed_file_by_path(&loc_path) {
+                        Some(packed_file) => {
+                            edited_paths.push(loc_path.to_vec());
+                            packed_file.clone()
+                        }
+                        None => {
+                            added_paths.push(loc_path.to_vec());
+                            let loc = Loc::new(schema.get_ref_last_definition_loc()?);
+                            PackedFile::new_from_decoded(&DecodedPackedFile::Loc(loc), loc_path.to_vec())
+                        }
+                    }
+                };
+
+                match packed_file.decode_return_ref_mut_no_locks(schema)? {
+                    DecodedPackedFile::Loc(loc) => loc.add_entries(&loc_entries),
+                    _ => return Err(ErrorKind::LocPackedFileIsNotALocPackedFile.into()),
+                }
+                staged_packed_files.insert(loc_path, packed_file);
+            }
+        }
+
+        // If we reached this point without errors, apply all the edits at once.
+        let packed_files = staged_packed_files.values().collect::<Vec<&PackedFile>>();
+        self.add_packed_files(&packed_files, true)?;
+        Ok((added_paths, edited_paths))
+    }
+
+    /// This function returns the path of the Loc PackedFile used to store the Loc entries of the provided DB Table.
+    fn get_loc_path_for_db(db_path: &[String]) -> Vec<String> {
+        vec!["text".to_owned(), "db".to_owned(), format!("{}{}", db_path.last().unwrap(), loc::EXTENSION)]
+    }
+
+    /// This function adds the provided `(key, text)` entries to the Loc PackedFile with the provided path, creating it if it doesn't exist.
+    ///
+    /// The path of the Loc PackedFile is added to the provided added/edited lists, depending on what we did with it.
+    fn add_entries_to_loc(
+        &mut self,
+        schema: &Schema,
+        loc_path: &[String],
+        entries: &[(String, String)],
+        added_paths: &mut Vec<Vec<String>>,
+        edited_paths: &mut Vec<Vec<String>>,
+    ) -> Result<()> {
+        match self.get_ref_mut_packed_file_by_path(loc_path) {
+            Some(packed_file) => match packed_file.decode_return_ref_mut_no_locks(schema)? {
+                DecodedPackedFile::Loc(loc) => {
+                    loc.add_entries(entries);
+                    if !added_paths.iter().any(|x| x == loc_path) && !edited_paths.iter().any(|x| x == loc_path) {
+                        edited_paths.push(loc_path.to_vec());
+                    }
+                }
+                _ => return Err(ErrorKind::LocPackedFileIsNotALocPackedFile.into()),
+            }
+            None => {
+                let mut loc = Loc::new(schema.get_ref_last_definition_loc()?);
+                loc.add_entries(entries);
+                let packed_file = PackedFile::new_from_decoded(&DecodedPackedFile::Loc(loc), loc_path.to_vec());
+                added_paths.push(self.add_packed_file(&packed_file, false)?);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// This function renames a key of a DB Table, propagating the change to everything in the `PackFile` referencing it.
+    ///
+    /// This includes:
+    /// - The column of the key in every DB Table of the same type.
+    /// - Every column of every DB Table referencing that column.
+    /// - The Loc entries of every row whose key has changed because of the rename.
+    /// - If `rename_on_texts` is true, every appearance of the key as a whole word in Text PackedFiles, like scripts.
+    ///
+    /// All the changes are returned as a single `ChangeSet`, so they can be undone in one go. If anything fails,
+    /// the changes already done are reverted before returning the error.
+    pub fn rename_key_cascade(
+        &mut self,
+        table_name: &str,
+        column_name: &str,
+        old_key: &str,
+        new_key: &str,
+        rename_on_texts: bool,
+    ) -> Result<ChangeSet> {
+        let mut change_set = ChangeSet::new();
+        if old_key == new_key { return Ok(change_set) }
+
+        // References in the schema use the name of the table without the `_tables` suffix.
+        let ref_table = table_name.strip_suffix("_tables").unwrap_or(table_name);
+        {
+            let schema = SCHEMA.read().unwrap();
+            let schema = if let Some(ref schema) = *schema { schema } else { return Err(ErrorKind::SchemaNotFound.into()) };
+
+            // First, rename the key in the DB Tables, keeping track of the Loc keys that changed with it.
+            let mut renamed_loc_keys = BTreeMap::new();
+            let mut error = None;
+            for packed_file in self.get_ref_mut_packed_files_by_type(PackedFileType::DB, false) {
+                let path = packed_file.get_path().to_vec();
+                if let Ok(DecodedPackedFile::DB(table)) = packed_file.decode_return_ref_mut_no_locks(schema) {
+                    let old_table = table.clone();
+                    match table.rename_references(ref_table, column_name, old_key, new_key) {
+                        Ok(true) => {
+                            let localised_fields = &table.get_ref_definition().localised_fields;
+                            for (old_row_key, new_row_key) in table.get_renamed_keys(&old_table) {
+                                for field in localised_fields {
+                                    renamed_loc_keys.insert(table.get_loc_key(&field.name, &old_row_key), table.get_loc_key(&field.name, &new_row_key));
+                                }
+                            }
+
+                            let new_table = table.clone();
+                            change_set.add_change(&path, DecodedPackedFile::DB(old_table), DecodedPackedFile::DB(new_table));
+                        }
+                        Ok(false) => {},
+                        Err(new_error) => {
+                            *table = old_table;
+                            error = Some(new_error);
+                            break;
+                        }
+                    }
+                }
+            }
+
+            if let Some(error) = error {
+                change_set.undo_unchecked(self);
+                return Err(error);
+            }
+
+            // Then, rename the Loc keys.
+            if !renamed_loc_keys.is_empty() {
+                for packed_file in self.get_ref_mut_packed_files_by_type(PackedFileType::Loc, false) {
+                    let path = packed_file.get_path().to_vec();
+                    if let Ok(DecodedPackedFile::Loc(loc)) = packed_file.decode_return_ref_mut_no_locks(schema) {
+                        let old_loc = loc.clone();
+                        if loc.rename_keys(&renamed_loc_keys) {
+                            let new_loc = loc.clone();
+                            change_set.add_change(&path, DecodedPackedFile::Loc(old_loc), DecodedPackedFile::Loc(new_loc));
+                        }
+                    }
+                }
+            }
+        }
+
+        // And finally, if we want it, do a replace on the text files. We use a regex to only match the full key, and not keys containing it.
+        if rename_on_texts {
+            let mut global_search = GlobalSearch {
+                pattern: format!(r"\b{}\b", regex::escape(old_key)),
+                replace_text: new_key.to_owned(),
+                case_sensitive: true,
+                use_regex: true,
+                search_on_dbs: false,
+                search_on_locs: false,
+                ..Default::default()
+            };
+            global_search.search(self);
+
+            let old_texts = global_search.matches_text.iter()
+                .filter(|x| !x.matches.is_empty())
+                .filter_map(|x| Some((x.path.to_vec(), self.get_ref_packed_file_by_path(&x.path)?.get_decoded())))
+                .collect::<Vec<(Vec<String>, DecodedPackedFile)>>();
+
+            global_search.replace_all(self);
+            for (path, old_text) in old_texts {
+                if let Some(packed_file) = self.get_ref_packed_file_by_path(&path) {
+                    let new_text = packed_file.get_decoded();
+                    if new_text != old_text {
+                        change_set.add_change(&path, old_text, new_text);
+                    }
+                }
+            }
+        }
+
+        Ok(change_set)
+    }
+
+    /// This function sets the result of the provided expression as the value of the provided column, in every row matching the provided filter,
+    /// in every DB Table of the provided type (like `land_units_tables`) in the `PackFile`.
+    ///
+    /// If the expression fails in any table, no table is changed. It returns the paths of the edited tables.
+    pub fn apply_expression_to_tables(
+        &mut self,
+        table_name: &str,
+        column_name: &str,
+        expression: &str,
+        filter: Option<&str>,
+    ) -> Result<Vec<Vec<String>>> {
+        let schema = SCHEMA.read().unwrap();
+        let schema = if let Some(ref schema) = *schema { schema } else { return Err(ErrorKind::SchemaNotFound.into()) };
+
+        // Apply the expression to copies of the tables first, so we don't leave any table half-edited.
+        let mut new_tables = vec![];
+        for packed_file in self.get_ref_mut_packed_files_by_type(PackedFileType::DB, false) {
+            if packed_file.get_path()[1] != table_name { continue; }
+            let path = packed_file.get_path().to_vec();
+            if let Ok(DecodedPackedFile::DB(table)) = packed_file.decode_return_ref_mut_no_locks(schema) {
+                let mut new_table = table.clone();
+                if !new_table.apply_expression(column_name, expression, filter)?.is_empty() {
+                    new_tables.push((path, new_table));
+                }
+            }
+        }
+
+        let mut edited_paths = vec![];
+        for (path, new_table) in new_tables {
+            if let Some(packed_file) = self.get_ref_mut_packed_file_by_path(&path) {
+                packed_file.set_decoded(&DecodedPackedFile::DB(new_table));
+                edited_paths.push(path);
+            }
+        }
+
+        Ok(edited_paths)
+    }
+
+    /// This function updates every outdated DB Table of the `PackFile` to the latest version of it in the game files.
+    ///
+    /// Tables with a version newer than the one in the game files, or not in the game files, are left untouched.
+    /// It returns a report for each updated table, with the changes done and the data that may need a review.
+    pub fn update_all_tables(&mut self) -> Result<Vec<TableUpdateReport>> {
+        let schema = SCHEMA.read().unwrap();
+        let schema = if let Some(ref schema) = *schema { schema } else { return Err(ErrorKind::SchemaNotFound.into()) };
+
+        // Get the latest definition in the game files of every table we have, decoding only the tables we need.
+        let table_names = self.get_ref_packed_files_by_type(PackedFileType::DB, false).iter()
+            .map(|x| x.get_path()[1].to_owned())
+            .collect::<BTreeSet<String>>();
+
+        let mut definitions: BTreeMap<String, Definition> = BTreeMap::new();
+        {
+            let mut dependencies = DEPENDENCY_DATABASE.lock().unwrap();
+            let vanilla_tables = dependencies.iter_mut()
+                .filter(|x| x.get_packed_file_type_by_path() == PackedFileType::DB && table_names.contains(&x.get_path()[1]))
+                .filter_map(|x| if let Ok(DecodedPackedFile::DB(table)) = x.decode_return_ref_no_locks(schema) { Some(table) } else { None });
+
+            for table in vanilla_tables {
+                let is_newer = match definitions.get(&table.name) {
+                    Some(definition) => definition.version < table.get_ref_definition().version,
+                    None => true,
+                };
+
+                if is_newer {
+                    definitions.insert(table.name.to_owned(), table.get_definition());
+                }
+            }
+        }
+
+        let mut reports = vec![];
+        for packed_file in self.get_ref_mut_packed_files_by_type(PackedFileType::DB, false) {
+            let path = packed_file.get_path().to_vec();
+            if let Ok(DecodedPackedFile::DB(table)) = packed_file.decode_return_ref_mut_no_locks(schema) {
+                if let Some(definition) = definitions.get(&table.name) {
+                    if table.get_ref_definition().version < definition.version {
+                        reports.push(TableUpdateReport::update_table(&path, table, definition)?);
+                    }
+                }
+            }
+        }
+
+        Ok(reports)
+    }
+
     /// This function is used to optimize a `PackFile` by removing extra useless data from it.
     ///
     /// Currently, this function removes:
@@ -1762,118 +2271,115 @@ impl PackFile {
 
 
     /// This function is used to Mass-Import TSV files into a PackFile.
+    ///
+    /// CSV, JSON and RON files are also supported. The format of each file is deduced from its extension.
+    ///
+    /// Along with the paths removed and added, it returns the columns of each file that were not in its table's definition, and got ignored.
     pub fn mass_import_tsv(
         &mut self,
         tsv_paths: &[PathBuf],
         name: Option<String>,
         overwrite: bool
-    ) -> Result<(Vec<Vec<String>>, Vec<Vec<String>>)> {
+    ) -> Result<(Vec<Vec<String>>, Vec<Vec<String>>, Vec<(String, Vec<String>)>)> {
 
         // Create the following lists:
         // - PackedFiles to add.
         // - PackedFiles to remove.
         // - Paths with errors.
+        // - Paths with ignored columns.
         let mut packed_files: Vec<PackedFile> = vec![];
         let mut packed_files_to_remove = vec![];
         let mut error_files = vec![];
+        let mut extra_columns_files = vec![];
 
         // If there is not a schema, don't do anything.
         if let Some(ref schema) = *SCHEMA.read().unwrap() {
             for path in tsv_paths {
 
-                // The first row has the PackedFile Type (or name, in case of tables) and version.
-                // The second row contains the column names, and it can be ignored.
-                let mut tsv = String::new();
-                BufReader::new(File::open(&path)?).read_to_string(&mut tsv)?;
-
-                // We get his first line, if it has it. Otherwise, we return an error in this file.
-                if let Some(line) = tsv.lines().next() {
-
-                    // Split the first line by \t so we can get the info of the table.
-                    // We expect to have 2 items here. If we have more or less, stop.
-                    let tsv_info = line.split('\t').collect::<Vec<&str>>();
-                    if tsv_info.len() == 2 {
-
-                        // Get the type and the version of the table.
-                        let table_type = tsv_info[0];
-                        let table_version = match tsv_info[1].parse::<i32>() {
-                            Ok(version) => version,
-                            Err(_) => {
-                                error_files.push(path.to_string_lossy().to_string());
-                                continue
+                // Get the type of the table from the file, using its extension to know its format.
+                // This also checks the version of the table in the file is valid.
+                let (format, table_type) = match TableFormat::from_path(path).and_then(|format| Ok((format, Table::get_table_name_from_file(path, format)?))) {
+                    Ok(data) => data,
+                    Err(_) => {
+                        error_files.push(path.to_string_lossy().to_string());
+                        continue
+                    }
+                };
+
+                // Get the last definition, depending on the table type. Columns are matched by name, so older versions are updated on import.
+                // If the name is not specific for a type of file, we trat it as a DB Table.
+                match &*table_type {
+                    TSV_NAME_LOC => {
+                        let definition = schema.get_ref_last_definition_loc()?;
+                        if let Ok((table, extra_columns)) = Loc::import(&definition, &path, &table_type, format) {
+                            if !extra_columns.is_empty() {
+                                extra_columns_files.push((path.to_string_lossy().to_string(), extra_columns));
                             }
-                        };
-
-                        // Get the definition, depending on the table type and version.
-                        // If the name is not specific for a type of file, we trat it as a DB Table.
-                        match table_type {
-                            TSV_NAME_LOC => {
-                                let definition = schema.get_ref_versioned_file_loc()?.get_version(table_version)?;
-                                if let Ok(table) = Loc::import_tsv(&definition, &path, &table_type) {
-
-                                    // Depending on the name received, call it one thing or another.
-                                    let name = match name {
-                                        Some(ref name) => name.to_string(),
-                                        None => path.file_stem().unwrap().to_str().unwrap().to_string(),
-                                    };
 
-                                    let mut path = vec!["text".to_owned(), "db".to_owned(), format!("{}.loc", name)];
 
-                                    // If that path already exists in the list of new PackedFiles to add, change it using the index.
-                                    if !overwrite {
-                                        let mut index = 1;
-                                        while packed_files.iter().any(|x| x.get_path() == &*path) {
-                                            path[2] = format!("{}_{}.loc", name, index);
-                                            index += 1;
-                                        }
-                                    }
+                            // Depending on the name received, call it one thing or another.
+                            let name = match name {
+                                Some(ref name) => name.to_string(),
+                                None => path.file_stem().unwrap().to_str().unwrap().to_string(),
+                            };
 
-                                    // If that path already exist in the PackFile, add it to the "remove" list.
-                                    if self.packedfile_exists(&path) { packed_files_to_remove.push(path.to_vec()) }
+                            let mut path = vec!["text".to_owned(), "db".to_owned(), format!("{}.loc", name)];
 
-                                    // Create and add the new PackedFile to the list of PackedFiles to add.
-                                    let mut packed_file = PackedFile::new(path, self.get_file_name());
-                                    packed_file.set_decoded(&DecodedPackedFile::Loc(table));
-                                    packed_files.push(packed_file);
+                            // If that path already exists in the list of new PackedFiles to add, change it using the index.
+                            if !overwrite {
+                                let mut index = 1;
+                                while packed_files.iter().any(|x| x.get_path() == &*path) {
+                                    path[2] = format!("{}_{}.loc", name, index);
+                                    index += 1;
                                 }
-                                else { error_files.push(path.to_string_lossy().to_string()); }
                             }
-                            _ => {
-                                let definition = schema.get_ref_versioned_file_db(&table_type)?.get_version(table_version)?;
-                                if let Ok(table) = DB::import_tsv(&definition, &path, &table_type) {
-
-                                    // Depending on the name received, call it one thing or another.
-                                    let name = match name {
-                                        Some(ref name) => name.to_string(),
-                                        None => path.file_stem().unwrap().to_str().unwrap().to_string(),
-                                    };
 
-                                    let mut path = vec!["db".to_owned(), table_type.to_owned(), name.to_owned()];
+                            // If that path already exist in the PackFile, add it to the "remove" list.
+                            if self.packedfile_exists(&path) { packed_files_to_remove.push(path.to_vec()) }
 
-                                    // If that path already exists in the list of new PackedFiles to add, change it using the index.
-                                    if !overwrite {
-                                        let mut index = 1;
-                                        while packed_files.iter().any(|x| x.get_path() == &*path) {
-                                            path[2] = format!("{}_{}", name, index);
-                                            index += 1;
-                                        }
-                                    }
+                            // Create and add the new PackedFile to the list of PackedFiles to add.
+                            let mut packed_file = PackedFile::new(path, self.get_file_name());
+                            packed_file.set_decoded(&DecodedPackedFile::Loc(table));
+                            packed_files.push(packed_file);
+                        }
+                        else { error_files.push(path.to_string_lossy().to_string()); }
+                    }
+                    _ => {
+                        let definition = schema.get_ref_last_definition_db(&table_type)?;
+                        if let Ok((table, extra_columns)) = DB::import(&definition, &path, &table_type, format) {
+                            if !extra_columns.is_empty() {
+                                extra_columns_files.push((path.to_string_lossy().to_string(), extra_columns));
+                            }
 
-                                    // If that path already exists in the PackFile, add it to the "remove" list.
-                                    if self.packedfile_exists(&path) { packed_files_to_remove.push(path.to_vec()) }
 
-                                    // Create and add the new PackedFile to the list of PackedFiles to add.
-                                    let mut packed_file = PackedFile::new(path, self.get_file_name());
-                                    packed_file.set_decoded(&DecodedPackedFile::DB(table));
-                                    packed_files.push(packed_file);
+                            // Depending on the name received, call it one thing or another.
+                            let name = match name {
+                                Some(ref name) => name.to_string(),
+                                None => path.file_stem().unwrap().to_str().unwrap().to_string(),
+                            };
+
+                            let mut path = vec!["db".to_owned(), table_type.to_owned(), name.to_owned()];
+
+                            // If that path already exists in the list of new PackedFiles to add, change it using the index.
+                            if !overwrite {
+                                let mut index = 1;
+                                while packed_files.iter().any(|x| x.get_path() == &*path) {
+                                    path[2] = format!("{}_{}", name, index);
+                                    index += 1;
                                 }
-                                else { error_files.push(path.to_string_lossy().to_string()); }
                             }
+
+                            // If that path already exists in the PackFile, add it to the "remove" list.
+                            if self.packedfile_exists(&path) { packed_files_to_remove.push(path.to_vec()) }
+
+                            // Create and add the new PackedFile to the list of PackedFiles to add.
+                            let mut packed_file = PackedFile::new(path, self.get_file_name());
+                            packed_file.set_decoded(&DecodedPackedFile::DB(table));
+                            packed_files.push(packed_file);
                         }
+                        else { error_files.push(path.to_string_lossy().to_string()); }
                     }
-                    else { error_files.push(path.to_string_lossy().to_string()); }
                 }
-                else { error_files.push(path.to_string_lossy().to_string()); }
             }
 
             // If any of the files returned error, return error.
@@ -1893,7 +2399,7 @@ impl PackFile {
             // We add all the files to the PackFile, and return success.
             let packed_files_to_add = packed_files.iter().collect::<Vec<&PackedFile>>();
             self.add_packed_files(&packed_files_to_add, true)?;
-            Ok((packed_files_to_remove, tree_path))
+            Ok((packed_files_to_remove, tree_path, extra_columns_files))
         }
         else {
             Err(ErrorKind::SchemaNotFound.into())
@@ -1902,8 +2408,10 @@ impl PackFile {
 
     /// This function is used to Mass-Export TSV files from a PackFile.
     ///
+    /// The tables can also be exported as CSV, JSON or RON, depending on the format provided.
+    ///
     /// NOTE: this will OVERWRITE any existing file that has a name conflict with the TSV files provided.
-    pub fn mass_export_tsv(&mut self, path_types: &[PathType], export_path: &PathBuf) -> Result<String> {
+    pub fn mass_export_tsv(&mut self, path_types: &[PathType], export_path: &PathBuf, format: TableFormat) -> Result<String> {
 
         // Lists of PackedFiles that couldn't be exported for one thing or another and exported PackedFile names,
         // so we make sure we don't overwrite those with the following ones.
@@ -1928,19 +2436,19 @@ impl PackFile {
                         Ok(data) => match data {
                             DecodedPackedFile::DB(data) => {
 
-                                // His name will be "db_name_file_name.tsv". If that's taken, we'll add an index until we find one available.
-                                let mut name = format!("{}_{}.tsv", path[1], path.last().unwrap().to_owned());
+                                // His name will be "db_name_file_name.tsv" (or the extension of the format). If that's taken, we'll add an index until we find one available.
+                                let mut name = format!("{}_{}.{}", path[1], path.last().unwrap().to_owned(), format.get_extension());
                                 let mut export_path = export_path.to_path_buf();
 
                                 // Checks to avoid overwriting exported files go here, in an infinite loop of life and death.
                                 let mut index = 1;
                                 while exported_files.contains(&name) {
-                                    name = format!("{}_{}_{}.tsv", path[1], path.last().unwrap().to_owned(), index);
+                                    name = format!("{}_{}_{}.{}", path[1], path.last().unwrap().to_owned(), index, format.get_extension());
                                     index += 1;
                                 }
 
                                 export_path.push(name.to_owned());
-                                match data.export_tsv(&export_path, &path[1]) {
+                                match data.export(&export_path, &path[1], format) {
                                     Ok(_) => exported_files.push(name),
                                     Err(error) => error_list.push((packed_file.get_path().join("\\"), error)),
                                 }
@@ -1948,19 +2456,19 @@ impl PackFile {
                             }
                             DecodedPackedFile::Loc(data) => {
 
-                                // His name will be "db_name_file_name.tsv". If that's taken, we'll add an index until we find one available.
-                                let mut name = format!("{}.tsv", path.last().unwrap().to_owned());
+                                // His name will be "file_name.tsv" (or the extension of the format). If that's taken, we'll add an index until we find one available.
+                                let mut name = format!("{}.{}", path.last().unwrap().to_owned(), format.get_extension());
                                 let mut export_path = export_path.to_path_buf();
 
                                 // Checks to avoid overwriting exported files go here, in an infinite loop of life and death.
                                 let mut index = 1;
                                 while exported_files.contains(&name) {
-                                    name = format!("{}_{}.tsv", path.last().unwrap().to_owned(), index);
+                                    name = format!("{}_{}.{}", path.last().unwrap().to_owned(), index, format.get_extension());
                                     index += 1;
                                 }
 
                                 export_path.push(name.to_owned());
-                                match data.export_tsv(&export_path, &TSV_NAME_LOC) {
+                                match data.export(&export_path, &TSV_NAME_LOC, format) {
                                     Ok(_) => exported_files.push(name),
                                     Err(error) => error_list.push((packed_file.get_path().join("\\"), error)),
                                 }
@@ -14,7 +14,19 @@ Module containing test for the `PackFile` module, just to make sure we don't bre
 
 use std::path::PathBuf;
 
-use super::PackFile;
+use crate::SCHEMA;
+use crate::packedfile::DecodedPackedFile;
+use crate::packedfile::table::DecodedData;
+use crate::packedfile::table::db::DB;
+use crate::packedfile::table::loc::Loc;
+use crate::packedfile::table::mapping::{ColumnMapping, ColumnSource, LostValue};
+use crate::packedfile::text::Text;
+use crate::schema::{Definition, Field, FieldType, Schema, VersionedFile};
+use crate::schema::decoding_report::{DecodingReport, DecodingIssueKind};
+
+use super::{PackFile, PFHVersion};
+use super::packedfile::PackedFile;
+use super::table_update::TableUpdateReport;
 
 #[test]
 fn test_decode_pfh5() {
@@ -121,3 +133,347 @@ fn test_encode_pfh0() {
 
 	assert_eq!(pack_file_base, pack_file_new);
 }
+
+#[test]
+fn test_table_update_report() {
+    let mut old_definition = Definition::new(1);
+    old_definition.fields.push(Field { name: "key".to_owned(), field_type: FieldType::StringU8, is_key: true, ..Default::default() });
+    old_definition.fields.push(Field { name: "cost".to_owned(), field_type: FieldType::StringU8, ..Default::default() });
+    old_definition.fields.push(Field { name: "old_name".to_owned(), field_type: FieldType::Integer, ..Default::default() });
+
+    let mut new_definition = Definition::new(2);
+    new_definition.fields.push(Field { name: "key".to_owned(), field_type: FieldType::StringU8, is_key: true, ..Default::default() });
+    new_definition.fields.push(Field { name: "cost".to_owned(), field_type: FieldType::Integer, ..Default::default() });
+    new_definition.fields.push(Field { name: "new_name".to_owned(), field_type: FieldType::Integer, ..Default::default() });
+
+    let mut old_table = DB::new("land_units_tables", None, &old_definition);
+    old_table.set_table_data(&[
+        vec![DecodedData::StringU8("unit_1".to_owned()), DecodedData::StringU8("100".to_owned()), DecodedData::Integer(5)],
+        vec![DecodedData::StringU8("unit_2".to_owned()), DecodedData::StringU8("expensive".to_owned()), DecodedData::Integer(0)],
+    ]).unwrap();
+
+    // Retyped fields must be converted when possible, and reset when not. Renamed fields keep their data.
+    let path = vec!["db".to_owned(), "land_units_tables".to_owned(), "test".to_owned()];
+    let mut new_table = old_table.clone();
+    let report = TableUpdateReport::update_table(&path, &mut new_table, &new_definition).unwrap();
+    assert_eq!(new_table.get_ref_table_data()[0][1], DecodedData::Integer(100));
+    assert_eq!(new_table.get_ref_table_data()[1][1], DecodedData::Integer(0));
+    assert_eq!(new_table.get_ref_table_data()[0][2], DecodedData::Integer(5));
+
+    assert_eq!((report.old_version, report.new_version), (1, 2));
+    assert_eq!(report.warnings.len(), 2);
+    assert!(report.warnings[0].contains("'new_name' has taken the data of the old field 'old_name'"));
+    assert!(report.warnings[1].contains("'cost' has been moved to 'cost'"));
+    assert_eq!(report.lost_values, vec![LostValue { row: 1, field_name: "cost".to_owned(), value: "expensive".to_owned(), new_field_name: Some("cost".to_owned()) }]);
+    assert!(TableUpdateReport::to_markdown(&[report]).contains("Row 2, *cost*: `expensive`"));
+
+    // User mappings go before any guess.
+    let mut mapping = ColumnMapping::default();
+    mapping.columns.insert("new_name".to_owned(), None);
+    let sources = ColumnMapping::get_column_sources(&old_definition, &new_definition, Some(&mapping)).unwrap();
+    assert_eq!(sources, vec![ColumnSource::Name(0), ColumnSource::Name(1), ColumnSource::Default]);
+
+    let mut new_table = old_table.clone();
+    let lost_values = new_table.set_definition_with_sources(&new_definition, &sources);
+    assert_eq!(lost_values.len(), 2);
+    assert_eq!(lost_values[0], LostValue { row: 0, field_name: "old_name".to_owned(), value: "5".to_owned(), new_field_name: None });
+
+    mapping.columns.insert("new_name".to_owned(), Some("missing_field".to_owned()));
+    assert!(ColumnMapping::get_column_sources(&old_definition, &new_definition, Some(&mapping)).is_err());
+
+    // Empty tables have nothing to lose.
+    let mut empty_table = DB::new("land_units_tables", None, &old_definition);
+    let old_empty_table = empty_table.clone();
+    let sources = ColumnMapping::get_column_sources(&old_definition, &new_definition, None).unwrap();
+    let lost_values = empty_table.set_definition_with_sources(&new_definition, &sources);
+    assert!(!TableUpdateReport::new(&path, &old_empty_table, &empty_table, &sources, lost_values).has_warnings());
+}
+
+#[test]
+fn test_decoding_report() {
+    let mut definition = Definition::new(1);
+    definition.fields.push(Field { name: "key".to_owned(), field_type: FieldType::StringU8, is_key: true, ..Default::default() });
+    definition.fields.push(Field { name: "value".to_owned(), field_type: FieldType::Integer, ..Default::default() });
+
+    let mut table = DB::new("land_units_tables", None, &definition);
+    table.set_table_data(&[
+        vec![DecodedData::StringU8("unit_1".to_owned()), DecodedData::Integer(1)],
+        vec![DecodedData::StringU8("unit_2".to_owned()), DecodedData::Integer(2)],
+    ]).unwrap();
+    let data = table.save().unwrap();
+    let mut data_leftover = data.to_vec();
+    data_leftover.extend_from_slice(&[0, 0]);
+
+    let mut schema = Schema::default();
+    schema.add_versioned_file(&VersionedFile::DB("land_units_tables".to_owned(), vec![definition]));
+
+    let mut pack_file = PackFile::new_with_name("test.pack", PFHVersion::PFH5);
+    for (path, data) in &[
+        (vec!["db", "land_units_tables", "ok"], &data[..]),
+        (vec!["db", "land_units_tables", "leftover"], &data_leftover[..]),
+        (vec!["db", "land_units_tables", "broken"], &data[..data.len() - 2]),
+        (vec!["db", "unknown_tables", "missing"], &data[..]),
+    ] {
+        let mut packed_file = PackedFile::new(path.iter().map(|x| (*x).to_owned()).collect(), "test.pack".to_owned());
+        packed_file.set_raw_data(data);
+        pack_file.add_packed_file(&packed_file, true).unwrap();
+    }
+
+    let report = DecodingReport::new(&pack_file, &schema);
+    assert_eq!((report.total_tables, report.decoded_tables), (4, 1));
+    assert_eq!(report.issues.iter().map(|x| &*x.path[2]).collect::<Vec<&str>>(), vec!["broken", "leftover", "missing"]);
+    assert!(matches!(report.issues[0].kind, DecodingIssueKind::DecodingFailed(_)));
+    assert_eq!(report.issues[1].kind, DecodingIssueKind::LeftoverBytes(data.len() + 2, data.len()));
+    assert_eq!(report.issues[2].kind, DecodingIssueKind::MissingDefinition);
+    assert_eq!((report.issues[2].version, report.issues[2].entry_count), (1, 2));
+    assert_eq!(report.get_issues_by_kind(true, false, true).len(), 2);
+}
+
+#[test]
+fn test_rename_key_cascade() {
+
+    // The cascade rename needs a schema loaded, even if all the PackedFiles are already decoded.
+    {
+        let mut schema = SCHEMA.write().unwrap();
+        if schema.is_none() {
+            *schema = Some(Schema::default());
+        }
+    }
+
+    let mut units_definition = Definition::new(1);
+    units_definition.fields.push(Field { name: "key".to_owned(), field_type: FieldType::StringU8, is_key: true, ..Default::default() });
+    units_definition.localised_fields.push(Field { name: "name".to_owned(), field_type: FieldType::StringU16, ..Default::default() });
+
+    let mut references_definition = Definition::new(1);
+    references_definition.fields.push(Field { name: "id".to_owned(), field_type: FieldType::Integer, is_key: true, ..Default::default() });
+    references_definition.fields.push(Field { name: "unit".to_owned(), field_type: FieldType::StringU8, is_reference: Some(("cascade_units".to_owned(), "key".to_owned())), ..Default::default() });
+
+    let mut loc_definition = Definition::new(1);
+    loc_definition.fields.push(Field { name: "key".to_owned(), field_type: FieldType::StringU16, is_key: true, ..Default::default() });
+    loc_definition.fields.push(Field { name: "text".to_owned(), field_type: FieldType::StringU16, ..Default::default() });
+    loc_definition.fields.push(Field { name: "tooltip".to_owned(), field_type: FieldType::Boolean, ..Default::default() });
+
+    let mut units = DB::new("cascade_units_tables", None, &units_definition);
+    units.set_table_data(&[
+        vec![DecodedData::StringU8("unit_old".to_owned())],
+        vec![DecodedData::StringU8("unit_other".to_owned())],
+    ]).unwrap();
+
+    let mut references = DB::new("cascade_references_tables", None, &references_definition);
+    references.set_table_data(&[
+        vec![DecodedData::Integer(1), DecodedData::StringU8("unit_old".to_owned())],
+        vec![DecodedData::Integer(2), DecodedData::StringU8("unit_other".to_owned())],
+    ]).unwrap();
+
+    let mut loc = Loc::new(&loc_definition);
+    loc.set_table_data(&[
+        vec![DecodedData::StringU16("cascade_units_name_unit_old".to_owned()), DecodedData::StringU16("Old Unit".to_owned()), DecodedData::Boolean(true)],
+        vec![DecodedData::StringU16("cascade_units_name_unit_other".to_owned()), DecodedData::StringU16("Other Unit".to_owned()), DecodedData::Boolean(true)],
+    ]).unwrap();
+
+    let mut text = Text::new();
+    text.set_contents("spawn(\"unit_old\")\nspawn(\"unit_old_2\")\n");
+
+    let paths = vec![
+        vec!["db".to_owned(), "cascade_units_tables".to_owned(), "test".to_owned()],
+        vec!["db".to_owned(), "cascade_references_tables".to_owned(), "test".to_owned()],
+        vec!["text".to_owned(), "test.loc".to_owned()],
+        vec!["script".to_owned(), "test.lua".to_owned()],
+    ];
+    let old_data = [DecodedPackedFile::DB(units), DecodedPackedFile::DB(references), DecodedPackedFile::Loc(loc), DecodedPackedFile::Text(text)];
+
+    let mut pack_file = PackFile::new_with_name("test.pack", PFHVersion::PFH5);
+    for (path, data) in paths.iter().zip(old_data.iter()) {
+        pack_file.add_packed_file(&PackedFile::new_from_decoded(data, path.to_vec()), true).unwrap();
+    }
+
+    // The key, its references, its Loc entries and its whole-word appearances in texts must be renamed. Nothing else.
+    let change_set = pack_file.rename_key_cascade("cascade_units_tables", "key", "unit_old", "unit_new", true).unwrap();
+    assert_eq!(change_set.get_paths(), paths);
+
+    let new_data = paths.iter().map(|path| pack_file.get_ref_packed_file_by_path(path).unwrap().get_decoded()).collect::<Vec<DecodedPackedFile>>();
+    match (&new_data[0], &new_data[1], &new_data[2], &new_data[3]) {
+        (DecodedPackedFile::DB(units), DecodedPackedFile::DB(references), DecodedPackedFile::Loc(loc), DecodedPackedFile::Text(text)) => {
+            assert_eq!(units.get_ref_table_data()[0][0], DecodedData::StringU8("unit_new".to_owned()));
+            assert_eq!(units.get_ref_table_data()[1][0], DecodedData::StringU8("unit_other".to_owned()));
+            assert_eq!(references.get_ref_table_data()[0][1], DecodedData::StringU8("unit_new".to_owned()));
+            assert_eq!(references.get_ref_table_data()[1][1], DecodedData::StringU8("unit_other".to_owned()));
+            assert_eq!(loc.get_texts(), vec![
+                ("cascade_units_name_unit_new".to_owned(), "Old Unit".to_owned()),
+                ("cascade_units_name_unit_other".to_owned(), "Other Unit".to_owned()),
+            ]);
+            assert_eq!(text.get_ref_contents(), "spawn(\"unit_new\")\nspawn(\"unit_old_2\")\n");
+        }
+        _ => panic!("Wrong PackedFile types after the rename."),
+    }
+
+    // Undoing the ChangeSet must restore every PackedFile, and redoing it must apply the rename again.
+    assert_eq!(change_set.undo(&mut pack_file).unwrap().len(), paths.len());
+    for (path, data) in paths.iter().zip(old_data.iter()) {
+        assert_eq!(&pack_file.get_ref_packed_file_by_path(path).unwrap().get_decoded(), data);
+    }
+
+    assert_eq!(change_set.redo(&mut pack_file).unwrap().len(), paths.len());
+    for (path, data) in paths.iter().zip(new_data.iter()) {
+        assert_eq!(&pack_file.get_ref_packed_file_by_path(path).unwrap().get_decoded(), data);
+    }
+
+    // If a changed PackedFile is edited after the rename, undoing it must fail without touching anything, so the edit is not lost.
+    let edited_text = DecodedPackedFile::Text(Text::new());
+    pack_file.get_ref_mut_packed_file_by_path(&paths[3]).unwrap().set_decoded(&edited_text);
+    assert!(change_set.undo(&mut pack_file).is_err());
+    assert_eq!(pack_file.get_ref_packed_file_by_path(&paths[3]).unwrap().get_decoded(), edited_text);
+    for (path, data) in paths.iter().zip(new_data.iter()).take(3) {
+        assert_eq!(&pack_file.get_ref_packed_file_by_path(path).unwrap().get_decoded(), data);
+    }
+
+    // Renaming a key that doesn't exist changes nothing.
+    assert!(pack_file.rename_key_cascade("cascade_units_tables", "key", "unit_missing", "unit_new_2", true).unwrap().is_empty());
+}
+
+#[test]
+fn test_update_loc_from_db() {
+
+    // Syncing the Loc entries needs a schema loaded, even if all the PackedFiles are already decoded.
+    {
+        let mut schema = SCHEMA.write().unwrap();
+        if schema.is_none() {
+            *schema = Some(Schema::default());
+        }
+    }
+
+    let mut units_definition = Definition::new(1);
+    units_definition.fields.push(Field { name: "faction".to_owned(), field_type: FieldType::StringU8, is_key: true, ..Default::default() });
+    units_definition.fields.push(Field { name: "unit".to_owned(), field_type: FieldType::StringU8, is_key: true, ..Default::default() });
+    units_definition.localised_fields.push(Field { name: "name".to_owned(), field_type: FieldType::StringU16, ..Default::default() });
+
+    let mut loc_definition = Definition::new(1);
+    loc_definition.fields.push(Field { name: "key".to_owned(), field_type: FieldType::StringU16, is_key: true, ..Default::default() });
+    loc_definition.fields.push(Field { name: "text".to_owned(), field_type: FieldType::StringU16, ..Default::default() });
+    loc_definition.fields.push(Field { name: "tooltip".to_owned(), field_type: FieldType::Boolean, ..Default::default() });
+
+    let row = |faction: &str, unit: &str| vec![DecodedData::StringU8(faction.to_owned()), DecodedData::StringU8(unit.to_owned())];
+    let mut old_units = DB::new("loc_sync_units_tables", None, &units_definition);
+    old_units.set_table_data(&[row("x", "y"), row("a", "bc")]).unwrap();
+
+    let mut loc = Loc::new(&loc_definition);
+    loc.set_table_data(&[
+        vec![DecodedData::StringU16("loc_sync_units_name_xy".to_owned()), DecodedData::StringU16("XY".to_owned()), DecodedData::Boolean(true)],
+        vec![DecodedData::StringU16("loc_sync_units_name_abc".to_owned()), DecodedData::StringU16("ABC".to_owned()), DecodedData::Boolean(true)],
+    ]).unwrap();
+
+    // Rename one composite key, change another without changing its joined key, and add a new row.
+    let mut new_units = old_units.clone();
+    new_units.set_table_data(&[row("x", "z"), row("ab", "c"), row("n", "1")]).unwrap();
+
+    // The Loc of the table already exists, as creating it needs a Loc definition in the schema.
+    let db_path = vec!["db".to_owned(), "loc_sync_units_tables".to_owned(), "test".to_owned()];
+    let loc_path = vec!["text".to_owned(), "test.loc".to_owned()];
+    let new_loc_path = vec!["text".to_owned(), "db".to_owned(), "test.loc".to_owned()];
+    let mut pack_file = PackFile::new_with_name("test.pack", PFHVersion::PFH5);
+    pack_file.add_packed_file(&PackedFile::new_from_decoded(&DecodedPackedFile::DB(new_units), db_path.to_vec()), true).unwrap();
+    pack_file.add_packed_file(&PackedFile::new_from_decoded(&DecodedPackedFile::Loc(loc), loc_path.to_vec()), true).unwrap();
+    pack_file.add_packed_file(&PackedFile::new_from_decoded(&DecodedPackedFile::Loc(Loc::new(&loc_definition)), new_loc_path.to_vec()), true).unwrap();
+
+    let (added_paths, edited_paths) = pack_file.update_loc_from_db(&db_path, Some(&old_units), "PLACEHOLDER").unwrap();
+    assert!(added_paths.is_empty());
+    assert_eq!(edited_paths, vec![loc_path.to_vec(), new_loc_path.to_vec()]);
+
+    match pack_file.get_ref_packed_file_by_path(&loc_path).unwrap().get_decoded() {
+        DecodedPackedFile::Loc(loc) => assert_eq!(loc.get_texts(), vec![
+            ("loc_sync_units_name_xz".to_owned(), "XY".to_owned()),
+            ("loc_sync_units_name_abc".to_owned(), "ABC".to_owned()),
+        ]),
+        _ => panic!("Wrong PackedFile type after syncing the Loc entries."),
+    }
+
+    match pack_file.get_ref_packed_file_by_path(&new_loc_path).unwrap().get_decoded() {
+        DecodedPackedFile::Loc(loc) => assert_eq!(loc.get_texts(), vec![("loc_sync_units_name_n1".to_owned(), "PLACEHOLDER".to_owned())]),
+        _ => panic!("Wrong PackedFile type after syncing the Loc entries."),
+    }
+}
+
+#[test]
+fn test_clone_key_with_dependents() {
+    let units_definition = |version| {
+        let mut definition = Definition::new(version);
+        definition.fields.push(Field { name: "key".to_owned(), field_type: FieldType::StringU8, is_key: true, ..Default::default() });
+        definition.fields.push(Field { name: "cost".to_owned(), field_type: FieldType::Integer, ..Default::default() });
+        definition.localised_fields.push(Field { name: "name".to_owned(), field_type: FieldType::StringU16, ..Default::default() });
+        definition
+    };
+
+    // The references table has no keys, so its rows are compared whole.
+    let mut references_definition = Definition::new(1);
+    references_definition.fields.push(Field { name: "unit".to_owned(), field_type: FieldType::StringU8, is_reference: Some(("clone_units".to_owned(), "key".to_owned())), ..Default::default() });
+    references_definition.fields.push(Field { name: "ability".to_owned(), field_type: FieldType::StringU8, ..Default::default() });
+
+    let mut loc_definition = Definition::new(1);
+    loc_definition.fields.push(Field { name: "key".to_owned(), field_type: FieldType::StringU16, is_key: true, ..Default::default() });
+    loc_definition.fields.push(Field { name: "text".to_owned(), field_type: FieldType::StringU16, ..Default::default() });
+    loc_definition.fields.push(Field { name: "tooltip".to_owned(), field_type: FieldType::Boolean, ..Default::default() });
+
+    let unit = |key: &str, cost| vec![DecodedData::StringU8(key.to_owned()), DecodedData::Integer(cost)];
+    let reference = |unit: &str, ability: &str| vec![DecodedData::StringU8(unit.to_owned()), DecodedData::StringU8(ability.to_owned())];
+
+    // The game files have an older version of the units table, and a references table our PackFile replaces.
+    let mut vanilla_units = DB::new("clone_units_tables", None, &units_definition(1));
+    vanilla_units.set_table_data(&[unit("unit_a", 100), unit("unit_b", 200)]).unwrap();
+    let mut vanilla_references = DB::new("clone_references_tables", None, &references_definition);
+    vanilla_references.set_table_data(&[reference("unit_a", "vanilla_ability")]).unwrap();
+    let mut vanilla_loc = Loc::new(&loc_definition);
+    vanilla_loc.set_table_data(&[vec![DecodedData::StringU16("clone_units_name_unit_a".to_owned()), DecodedData::StringU16("Unit A".to_owned()), DecodedData::Boolean(true)]]).unwrap();
+
+    // Only the tables the schema says reference the cloned one are searched, so the unrelated one must be ignored.
+    let mut unrelated = DB::new("clone_unrelated_tables", None, &references_definition);
+    unrelated.set_table_data(&[reference("unit_a", "unrelated_ability")]).unwrap();
+
+    let mut schema = Schema::default();
+    schema.add_versioned_file(&VersionedFile::DB("clone_units_tables".to_owned(), vec![units_definition(2), units_definition(1)]));
+    schema.add_versioned_file(&VersionedFile::DB("clone_references_tables".to_owned(), vec![references_definition.clone()]));
+
+    let references_path = vec!["db".to_owned(), "clone_references_tables".to_owned(), "data__".to_owned()];
+    let mut vanilla_files = vec![
+        PackedFile::new_from_decoded(&DecodedPackedFile::DB(vanilla_units), vec!["db".to_owned(), "clone_units_tables".to_owned(), "data__".to_owned()]),
+        PackedFile::new_from_decoded(&DecodedPackedFile::DB(vanilla_references), references_path.to_vec()),
+        PackedFile::new_from_decoded(&DecodedPackedFile::DB(unrelated), vec!["db".to_owned(), "clone_unrelated_tables".to_owned(), "data__".to_owned()]),
+        PackedFile::new_from_decoded(&DecodedPackedFile::Loc(vanilla_loc), vec!["text".to_owned(), "db".to_owned(), "clone_vanilla.loc".to_owned()]),
+    ];
+
+    let mut units = DB::new("clone_units_tables", None, &units_definition(2));
+    units.set_table_data(&[unit("unit_a", 150)]).unwrap();
+    let mut references = DB::new("clone_references_tables", None, &references_definition);
+    references.set_table_data(&[reference("unit_a", "mod_ability")]).unwrap();
+
+    let units_path = vec!["db".to_owned(), "clone_units_tables".to_owned(), "mod".to_owned()];
+    let loc_path = vec!["text".to_owned(), "db".to_owned(), "mod.loc".to_owned()];
+    let mut pack_file = PackFile::new_with_name("test.pack", PFHVersion::PFH5);
+    pack_file.add_packed_file(&PackedFile::new_from_decoded(&DecodedPackedFile::DB(units), units_path.to_vec()), true).unwrap();
+    pack_file.add_packed_file(&PackedFile::new_from_decoded(&DecodedPackedFile::DB(references), references_path.to_vec()), true).unwrap();
+
+    // Creating the Loc of the table fails with our empty schema, and that must leave the PackFile untouched.
+    let old_pack_file = pack_file.clone();
+    assert!(pack_file.clone_key_with_dependents(&schema, &mut vanilla_files, "clone_units_tables", "unit_a", "unit_c").is_err());
+    assert_eq!(pack_file, old_pack_file);
+
+    // With the Loc already there, the row must be copied only once, from our PackFile, and the replaced references table of the game ignored.
+    pack_file.add_packed_file(&PackedFile::new_from_decoded(&DecodedPackedFile::Loc(Loc::new(&loc_definition)), loc_path.to_vec()), true).unwrap();
+    let (added_paths, edited_paths) = pack_file.clone_key_with_dependents(&schema, &mut vanilla_files, "clone_units_tables", "unit_a", "unit_c").unwrap();
+
+    assert!(added_paths.is_empty());
+    assert_eq!(edited_paths, vec![references_path.to_vec(), units_path.to_vec(), loc_path.to_vec()]);
+    match pack_file.get_ref_packed_file_by_path(&units_path).unwrap().get_decoded() {
+        DecodedPackedFile::DB(table) => assert_eq!(table.get_ref_table_data(), &[unit("unit_a", 150), unit("unit_c", 150)]),
+        _ => panic!("Wrong PackedFile type after cloning the key."),
+    }
+
+    match pack_file.get_ref_packed_file_by_path(&references_path).unwrap().get_decoded() {
+        DecodedPackedFile::DB(table) => assert_eq!(table.get_ref_table_data(), &[reference("unit_a", "mod_ability"), reference("unit_c", "mod_ability")]),
+        _ => panic!("Wrong PackedFile type after cloning the key."),
+    }
+
+    match pack_file.get_ref_packed_file_by_path(&loc_path).unwrap().get_decoded() {
+        DecodedPackedFile::Loc(loc) => assert_eq!(loc.get_texts(), vec![("clone_units_name_unit_c".to_owned(), "Unit A".to_owned())]),
+        _ => panic!("Wrong PackedFile type after cloning the key."),
+    }
+}
@@ -0,0 +1,167 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with all the code to report the changes done when updating DB Tables to a newer definition.
+
+After a game patch, the tables of a PackFile may need to be updated to the new versions of their definitions.
+When doing that, columns can be added, removed or change their type, and the data in them can lose its meaning.
+This module generates a report of what changed in each table, flagging the tables whose data may need a review.
+!*/
+
+use std::collections::BTreeMap;
+
+use rpfm_error::Result;
+
+use crate::packedfile::table::db::DB;
+use crate::packedfile::table::mapping::{ColumnSource, LostValue};
+use crate::schema::Definition;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct represents the changes done to a DB Table when updating it to a newer definition.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct TableUpdateReport {
+
+    /// Path of the updated table in the PackFile.
+    pub path: Vec<String>,
+
+    /// Version of the table before the update.
+    pub old_version: i32,
+
+    /// Version of the table after the update.
+    pub new_version: i32,
+
+    /// MarkDown-encoded list of the fields added, removed or changed by the update.
+    pub changes: Vec<String>,
+
+    /// List of the changes that may have left data without meaning, like removed fields with data in them.
+    pub warnings: Vec<String>,
+
+    /// List of the values that couldn't be carried over to the new definition.
+    pub lost_values: Vec<LostValue>,
+}
+
+//---------------------------------------------------------------------------//
+//                    Implementation of TableUpdateReport
+//---------------------------------------------------------------------------//
+
+/// Implementation of `TableUpdateReport`.
+impl TableUpdateReport {
+
+    /// This function updates the provided table to the provided definition, returning the report of the update.
+    ///
+    /// The columns are mapped as explained in the `mapping` module, so renamed columns keep their data when possible.
+    pub fn update_table(path: &[String], table: &mut DB, new_definition: &Definition) -> Result<Self> {
+        let old_table = table.clone();
+        let (sources, lost_values) = table.update_definition(new_definition)?;
+        Ok(Self::new(path, &old_table, table, &sources, lost_values))
+    }
+
+    /// This function creates the report of the update of a table, from the table before and after the update,
+    /// where the data of each new column came from, and the values lost in the update.
+    pub fn new(path: &[String], old_table: &DB, new_table: &DB, sources: &[ColumnSource], lost_values: Vec<LostValue>) -> Self {
+        let old_definition = old_table.get_ref_definition();
+        let new_definition = new_table.get_ref_definition();
+        let table_name = format!("{} ({} => {})", path.join("/"), old_definition.version, new_definition.version);
+
+        let mut changes = vec![];
+        new_definition.get_pretty_diff(old_definition, &table_name, &mut changes);
+        if changes.is_empty() {
+            changes.push(format!("  - ***{}***: no field changes.", table_name));
+        }
+
+        Self {
+            path: path.to_vec(),
+            old_version: old_definition.version,
+            new_version: new_definition.version,
+            changes,
+            warnings: Self::get_warnings(old_table, new_table, sources, &lost_values),
+            lost_values,
+        }
+    }
+
+    /// This function returns if the data of the updated table may need a review.
+    pub fn has_warnings(&self) -> bool {
+        !self.warnings.is_empty()
+    }
+
+    /// This function returns a MarkDown-encoded version of the provided reports, with the tables that may need a review first.
+    pub fn to_markdown(reports: &[Self]) -> String {
+        let (with_warnings, without_warnings): (Vec<&Self>, Vec<&Self>) = reports.iter().partition(|x| x.has_warnings());
+        let mut lines = vec![];
+
+        if !with_warnings.is_empty() {
+            lines.push("- **Tables that may need a review**:".to_owned());
+            for report in &with_warnings {
+                lines.extend_from_slice(&report.changes);
+                lines.push("    - **Warnings**:".to_owned());
+                report.warnings.iter().for_each(|x| lines.push(format!("      - {}", x)));
+
+                if !report.lost_values.is_empty() {
+                    lines.push("    - **Lost values**:".to_owned());
+                    report.lost_values.iter().for_each(|x| lines.push(format!("      - Row {}, *{}*: `{}`.", x.row + 1, x.field_name, x.value)));
+                }
+            }
+        }
+
+        if !without_warnings.is_empty() {
+            lines.push("- **Updated tables**:".to_owned());
+            without_warnings.iter().for_each(|x| lines.extend_from_slice(&x.changes));
+        }
+
+        lines.join("\n")
+    }
+
+    /// This function checks what data may have lost its meaning in the update of a table.
+    ///
+    /// Tables without rows have nothing to lose, so they never have warnings.
+    fn get_warnings(old_table: &DB, new_table: &DB, sources: &[ColumnSource], lost_values: &[LostValue]) -> Vec<String> {
+        let old_definition = old_table.get_ref_definition();
+        let new_definition = new_table.get_ref_definition();
+        let mut warnings = vec![];
+        if old_table.get_ref_table_data().is_empty() { return warnings; }
+
+        // Guessed columns may be wrong, so they always need a review.
+        for (new_field, source) in new_definition.fields.iter().zip(sources) {
+            if let (true, Some(old_column)) = (source.is_guessed(), source.get_column()) {
+                warnings.push(format!("Field '{}' has taken the data of the old field '{}', as they look like the same field. If they're not, provide a column mapping file for this table and versions.", new_field.name, old_definition.fields[old_column].name));
+            }
+        }
+
+        // Lost values are grouped by field, so we don't get a warning per row.
+        let mut lost_values_by_field: BTreeMap<(&str, Option<&str>), usize> = BTreeMap::new();
+        for lost_value in lost_values {
+            *lost_values_by_field.entry((&lost_value.field_name, lost_value.new_field_name.as_deref())).or_insert(0) += 1;
+        }
+
+        for ((field_name, new_field_name), rows) in &lost_values_by_field {
+            match new_field_name {
+                Some(new_field_name) => warnings.push(format!("Field '{}' has been moved to '{}', but {} rows had values that couldn't be converted to its new type, so they have been reset.", field_name, new_field_name, rows)),
+                None => warnings.push(format!("Field '{}' has been removed, with data in {} rows. That data has been lost.", field_name, rows)),
+            }
+        }
+
+        // Key fields with the same default value in every row may make the keys not unique anymore.
+        for (new_field, source) in new_definition.fields.iter().zip(sources) {
+            if !new_field.is_key { continue; }
+            match source.get_column() {
+                Some(old_column) => if !old_definition.fields[old_column].is_key {
+                    warnings.push(format!("Field '{}' is now part of the key. Rows may have duplicated keys now.", new_field.name));
+                },
+                None => warnings.push(format!("Field '{}' is a new key field, and all rows got the same default value in it. Rows may have duplicated keys now.", new_field.name)),
+            }
+        }
+
+        warnings
+    }
+}
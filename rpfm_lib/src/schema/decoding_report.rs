@@ -0,0 +1,207 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with the code to check how well a `Schema` decodes the tables of a game.
+
+After a game patch, schema maintainers need to know which tables changed. This module tries to decode every DB and Loc table
+of a PackFile (usually, all the data PackFiles of the game selected) with a `Schema`, and reports the ones that failed:
+- Tables without a definition for their version.
+- Tables that failed to decode with their definition.
+- Tables that decoded, but left bytes without decoding.
+
+The report can be saved as JSON, so it can be used by other tools.
+!*/
+
+use rayon::prelude::*;
+use serde_derive::{Serialize, Deserialize};
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use rpfm_error::{ErrorKind, Result};
+
+use crate::GAME_SELECTED;
+use crate::packedfile::PackedFileType;
+use crate::packedfile::table::{db::DB, loc::Loc};
+use crate::packfile::PackFile;
+use crate::packfile::packedfile::PackedFile;
+use crate::schema::Schema;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct represents the result of decoding all the tables of a PackFile with a `Schema`.
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct DecodingReport {
+
+    /// Key of the game the tables are from.
+    pub game: String,
+
+    /// Amount of DB and Loc tables checked.
+    pub total_tables: usize,
+
+    /// Amount of tables decoded without problems.
+    pub decoded_tables: usize,
+
+    /// List of the tables that couldn't be decoded properly, sorted by path.
+    pub issues: Vec<DecodingIssue>,
+}
+
+/// This struct represents a table that couldn't be decoded properly.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DecodingIssue {
+
+    /// Path of the table in the PackFile.
+    pub path: Vec<String>,
+
+    /// Name of the table, like `land_units_tables`. Loc tables are always `loc`.
+    pub table_name: String,
+
+    /// Version of the table, as stored in its header.
+    pub version: i32,
+
+    /// Amount of rows of the table, as stored in its header.
+    pub entry_count: u32,
+
+    /// What went wrong when decoding the table.
+    pub kind: DecodingIssueKind,
+}
+
+/// This enum represents the different ways decoding a table can go wrong.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum DecodingIssueKind {
+
+    /// There is no definition for the version of the table. Empty tables are included, as they cannot be decoded either.
+    MissingDefinition,
+
+    /// The table failed to decode with its definition. Contains the error.
+    DecodingFailed(String),
+
+    /// The table decoded, but not all its bytes were used. Contains the size of the table, and the bytes decoded.
+    LeftoverBytes(usize, usize),
+}
+
+//---------------------------------------------------------------------------//
+//                      Implementation of DecodingReport
+//---------------------------------------------------------------------------//
+
+/// Implementation of `DecodingReport`.
+impl DecodingReport {
+
+    /// This function tries to decode all the DB and Loc tables of the provided `PackFile` with the provided `Schema`, and reports the ones that failed.
+    pub fn new(pack_file: &PackFile, schema: &Schema) -> Self {
+        let mut packed_files = pack_file.get_ref_packed_files_by_type(PackedFileType::DB, false);
+        packed_files.append(&mut pack_file.get_ref_packed_files_by_type(PackedFileType::Loc, false));
+
+        let mut issues = packed_files.par_iter()
+            .filter_map(|packed_file| DecodingIssue::new(packed_file, schema))
+            .collect::<Vec<DecodingIssue>>();
+        issues.sort_by(|x, y| x.path.cmp(&y.path));
+
+        Self {
+            game: GAME_SELECTED.read().unwrap().to_owned(),
+            total_tables: packed_files.len(),
+            decoded_tables: packed_files.len() - issues.len(),
+            issues,
+        }
+    }
+
+    /// This function tries to decode all the DB and Loc tables of the data PackFiles of the game selected with the provided `Schema`.
+    pub fn new_from_game_selected(schema: &Schema) -> Result<Self> {
+        let pack_file = PackFile::open_all_ca_packfiles()?;
+        Ok(Self::new(&pack_file, schema))
+    }
+
+    /// This function returns the issues of the provided kind.
+    pub fn get_issues_by_kind(&self, missing_definition: bool, decoding_failed: bool, leftover_bytes: bool) -> Vec<&DecodingIssue> {
+        self.issues.iter().filter(|x| match x.kind {
+            DecodingIssueKind::MissingDefinition => missing_definition,
+            DecodingIssueKind::DecodingFailed(_) => decoding_failed,
+            DecodingIssueKind::LeftoverBytes(_, _) => leftover_bytes,
+        }).collect()
+    }
+
+    /// This function saves the report to a JSON file.
+    pub fn save_json(&self, path: &Path) -> Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        file.write_all(serde_json::to_string_pretty(&self)?.as_bytes())?;
+        Ok(())
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                      Implementation of DecodingIssue
+//---------------------------------------------------------------------------//
+
+/// Implementation of `DecodingIssue`.
+impl DecodingIssue {
+
+    /// This function tries to decode the provided table, returning what went wrong, if anything.
+    ///
+    /// Files with broken headers are reported as failed, as they cannot be decoded either.
+    fn new(packed_file: &PackedFile, schema: &Schema) -> Option<Self> {
+        let path = packed_file.get_path().to_vec();
+        let is_db = PackedFileType::get_packed_file_type(&path) == PackedFileType::DB;
+        let table_name = if is_db { path.get(1).cloned().unwrap_or_default() } else { "loc".to_owned() };
+
+        let data = match packed_file.get_raw_data() {
+            Ok(data) => data,
+            Err(error) => return Some(Self { path, table_name, version: 0, entry_count: 0, kind: DecodingIssueKind::DecodingFailed(strip_html(&error.to_string())) }),
+        };
+
+        let header = if is_db { DB::read_header(&data).map(|(version, _, _, entry_count, _)| (version, entry_count)) } else { Loc::read_header(&data) };
+        let (version, entry_count) = match header {
+            Ok(header) => header,
+            Err(error) => return Some(Self { path, table_name, version: 0, entry_count: 0, kind: DecodingIssueKind::DecodingFailed(strip_html(&error.to_string())) }),
+        };
+
+        let result = if is_db { DB::read(&data, &table_name, schema, false).map(|_| ()) } else { Loc::read(&data, schema, false).map(|_| ()) };
+        let kind = match result {
+            Ok(_) => return None,
+            Err(error) => match error.kind() {
+                ErrorKind::SchemaVersionedFileNotFound |
+                ErrorKind::SchemaDefinitionNotFound |
+                ErrorKind::TableEmptyWithNoDefinition => DecodingIssueKind::MissingDefinition,
+                ErrorKind::PackedFileSizeIsNotWhatWeExpect(size, decoded) => DecodingIssueKind::LeftoverBytes(*size, *decoded),
+                _ => DecodingIssueKind::DecodingFailed(strip_html(&error.to_string())),
+            }
+        };
+
+        Some(Self {
+            path,
+            table_name,
+            version,
+            entry_count,
+            kind,
+        })
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                              Utility functions
+//---------------------------------------------------------------------------//
+
+/// This function removes the HTML tags of an error message, so it can be used outside the UI.
+fn strip_html(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for character in text.chars() {
+        match character {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => result.push(character),
+            _ => {},
+        }
+    }
+    result
+}
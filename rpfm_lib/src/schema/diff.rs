@@ -0,0 +1,217 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with the structs representing the differences between two schemas, or two definitions of a table.
+
+These diffs are generated offline, from two `Schema`s in memory, and can be rendered as MarkDown (for changelogs) or as JSON (for other tools).
+!*/
+
+use serde_derive::{Serialize, Deserialize};
+
+use rpfm_error::Result;
+
+use super::Field;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct represents the differences between two `Schema`s, from the old one to the new one. Only DB Tables are compared.
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct SchemaDiff {
+
+    /// Tables only in the new schema.
+    pub new_tables: Vec<String>,
+
+    /// Versions of tables that are only in the new schema, compared with the closest older version of them in the old schema.
+    pub new_versions: Vec<TableDiff>,
+
+    /// Versions of tables that are in both schemas, but with differences.
+    pub corrections: Vec<TableDiff>,
+
+    /// Tables only in the old schema.
+    pub removed_tables: Vec<String>,
+
+    /// Versions of tables that are only in the old schema, as `(table_name, version)`. Versions of removed tables are not included.
+    pub removed_versions: Vec<(String, i32)>,
+}
+
+/// This struct represents the differences between two definitions of a table.
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct TableDiff {
+
+    /// Name of the table.
+    pub table_name: String,
+
+    /// Version of the table in the new schema.
+    pub version: i32,
+
+    /// Version of the table we compared it with, if any. New versions older than any other version are compared with nothing.
+    pub compared_version: Option<i32>,
+
+    /// The differences between both definitions.
+    pub diff: DefinitionDiff,
+}
+
+/// This struct represents the differences between two definitions.
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct DefinitionDiff {
+
+    /// Fields only in the new definition.
+    pub new_fields: Vec<Field>,
+
+    /// Fields in both definitions, but with differences.
+    pub changed_fields: Vec<FieldDiff>,
+
+    /// Names of the fields only in the old definition.
+    pub removed_fields: Vec<String>,
+
+    /// Order of the fields in both definitions, as `(old order, new order)`, if it changed. Only full diffs check it.
+    #[serde(default)]
+    pub field_order: Option<(Vec<String>, Vec<String>)>,
+}
+
+/// This struct represents the differences between two versions of a field.
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct FieldDiff {
+
+    /// Name of the field.
+    pub name: String,
+
+    /// List of changes, as `(property, old value, new value)`.
+    pub changes: Vec<(String, String, String)>,
+}
+
+//---------------------------------------------------------------------------//
+//                       Implementation of SchemaDiff
+//---------------------------------------------------------------------------//
+
+/// Implementation of `SchemaDiff`.
+impl SchemaDiff {
+
+    /// This function returns if there are no differences between both schemas.
+    pub fn is_empty(&self) -> bool {
+        self.new_tables.is_empty() &&
+            self.new_versions.is_empty() &&
+            self.corrections.is_empty() &&
+            self.removed_tables.is_empty() &&
+            self.removed_versions.is_empty()
+    }
+
+    /// This function returns a MarkDown-encoded version of the diff, like the ones used in the table changelogs.
+    pub fn to_markdown(&self) -> String {
+        let mut diff = String::new();
+
+        let new_tables = self.new_tables.iter().map(|x| format!("  - *{}*.", x)).collect::<Vec<String>>();
+        Self::push_markdown_section(&mut diff, "New tables decoded", &new_tables);
+
+        let mut new_versions = vec![];
+        self.new_versions.iter().for_each(|x| x.push_markdown(&mut new_versions));
+        Self::push_markdown_section(&mut diff, "Updated Tables", &new_versions);
+
+        let mut corrections = vec![];
+        self.corrections.iter().for_each(|x| x.push_markdown(&mut corrections));
+        Self::push_markdown_section(&mut diff, "Fixed Tables", &corrections);
+
+        let removed_tables = self.removed_tables.iter().map(|x| format!("  - *{}*.", x)).collect::<Vec<String>>();
+        Self::push_markdown_section(&mut diff, "Removed tables", &removed_tables);
+
+        let removed_versions = self.removed_versions.iter().map(|(name, version)| format!("  - *{}*: version *{}*.", name, version)).collect::<Vec<String>>();
+        Self::push_markdown_section(&mut diff, "Removed table versions", &removed_versions);
+
+        diff
+    }
+
+    /// This function returns a JSON-encoded version of the diff.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(&self).map_err(From::from)
+    }
+
+    /// This function adds a section with the provided lines to a MarkDown diff, if there are lines to add.
+    fn push_markdown_section(diff: &mut String, title: &str, lines: &[String]) {
+        if !lines.is_empty() {
+            diff.push_str(&format!("- **{}**:\n", title));
+            lines.iter().for_each(|x| { diff.push_str(x); diff.push('\n'); });
+            diff.push('\n');
+        }
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                       Implementation of TableDiff
+//---------------------------------------------------------------------------//
+
+/// Implementation of `TableDiff`.
+impl TableDiff {
+
+    /// This function adds the MarkDown-encoded version of this diff to the provided changes list.
+    ///
+    /// Unlike `DefinitionDiff`, versions without field changes are listed too, so new versions are never missing from the changelog.
+    pub fn push_markdown(&self, changes: &mut Vec<String>) {
+        if self.diff.is_empty() {
+            changes.push(format!("  - ***{}***: version *{}*, no field changes.", self.table_name, self.version));
+        }
+        else {
+            self.diff.push_markdown(&self.table_name, changes);
+        }
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                     Implementation of DefinitionDiff
+//---------------------------------------------------------------------------//
+
+/// Implementation of `DefinitionDiff`.
+impl DefinitionDiff {
+
+    /// This function returns if there are no differences between both definitions.
+    pub fn is_empty(&self) -> bool {
+        self.new_fields.is_empty() && self.changed_fields.is_empty() && self.removed_fields.is_empty() && self.field_order.is_none()
+    }
+
+    /// This function adds the MarkDown-encoded version of this diff to the provided changes list. If there are no differences, it adds nothing.
+    pub fn push_markdown(&self, table_name: &str, changes: &mut Vec<String>) {
+        if !self.is_empty() {
+            changes.push(format!("  - ***{}***:", table_name));
+        }
+
+        for (index, new_field) in self.new_fields.iter().enumerate() {
+            if index == 0 { changes.push("    - **New fields**:".to_owned()); }
+            changes.push(format!("      - ***{}***:", new_field.name));
+            changes.push(format!("        - **Type**: *{}*.", new_field.field_type));
+            changes.push(format!("        - **Is Key**: *{}*.", new_field.is_key));
+            if let Some((ref_table, ref_column)) = &new_field.is_reference {
+                changes.push(format!("        - **Is Reference**: *{}*/*{}*.", ref_table, ref_column));
+            }
+            if !new_field.description.is_empty() {
+                changes.push(format!("        - **Description**: *{}*.", new_field.description));
+            }
+        }
+
+        for (index, changed_field) in self.changed_fields.iter().enumerate() {
+            if index == 0 { changes.push("    - **Changed fields**:".to_owned()); }
+            changes.push(format!("      - **{}**:", changed_field.name));
+
+            for (property, old_value, new_value) in &changed_field.changes {
+                changes.push(format!("        - ***{}***: *{}* => *{}*.", property, old_value, new_value));
+            }
+        }
+
+        for (index, removed_field) in self.removed_fields.iter().enumerate() {
+            if index == 0 { changes.push("    - **Removed fields**:".to_owned()); }
+            changes.push(format!("      - *{}*.", removed_field));
+        }
+
+        if let Some((old_order, new_order)) = &self.field_order {
+            changes.push(format!("    - **Field order**: *{}* => *{}*.", old_order.join(", "), new_order.join(", ")));
+        }
+    }
+}
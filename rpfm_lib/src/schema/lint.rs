@@ -0,0 +1,239 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with the code to check a `Schema` for common errors.
+
+Schemas are edited by hand or by tools, and some errors in them are not caught until someone tries to use the broken definition. This module checks:
+- Duplicated field names in a definition.
+- References to tables or columns that are not in the schema.
+- Lookup columns that are not in the referenced table.
+- Localised fields with the same name as a regular field.
+- `max_length` set on non-string fields.
+- Default values that are not valid for the type of their field, or not in its set of values.
+- Values of a set of values that are not valid for the type of their field.
+- Duplicated `ca_order` in a definition.
+
+Referenced columns are searched in all the versions of the referenced table, as references are not tied to a specific version.
+!*/
+
+use serde_derive::{Serialize, Deserialize};
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::{fmt, fmt::Display};
+
+use crate::packedfile::table::DecodedData;
+use super::{Definition, FieldType, Schema, VersionedFile};
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct represents an error found in a `Schema`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LintIssue {
+
+    /// Name of the versioned file with the error. For DB Tables, it's the name of the table. Otherwise, it's `dep_manager` or `loc`.
+    pub versioned_file: String,
+
+    /// Version of the definition with the error.
+    pub version: i32,
+
+    /// Name of the field with the error, if the error is in a field. Fields inside sequences are named `sequence_field/field`.
+    pub field_name: Option<String>,
+
+    /// The error found.
+    pub kind: LintIssueKind,
+}
+
+/// This enum represents the different errors we can find in a `Schema`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum LintIssueKind {
+
+    /// The field name is used by more than one field of the definition.
+    DuplicatedField,
+
+    /// The field references a table that's not in the schema. Contains the referenced table.
+    ReferencedTableNotFound(String),
+
+    /// The field references a column that's not in any version of the referenced table. Contains the referenced table and column.
+    ReferencedColumnNotFound(String, String),
+
+    /// The field uses a lookup column that's not in any version of the referenced table. Contains the referenced table and the lookup column.
+    LookupColumnNotFound(String, String),
+
+    /// The localised field has the same name as a regular field of the definition.
+    LocalisedFieldCollision,
+
+    /// The field has a `max_length`, but it's not a string. Contains the `max_length`.
+    MaxLengthOnNonString(i32),
+
+    /// The default value of the field is not valid for its type. Contains the default value.
+    InvalidDefaultValue(String),
+
+    /// The `ca_order` of the field is used by more than one field of the definition. Contains the `ca_order`.
+    DuplicatedCaOrder(i16),
+
+    /// A value of the set of values of the field is not valid for its type. Contains the value.
+    InvalidEnumValue(String),
+
+    /// The default value of the field is not in its set of values. Contains the default value.
+    DefaultValueNotInEnum(String),
+}
+
+//---------------------------------------------------------------------------//
+//                         Implementation of LintIssue
+//---------------------------------------------------------------------------//
+
+/// Implementation of `LintIssue`.
+impl LintIssue {
+
+    /// This function checks the provided `Schema` for errors, returning the ones found.
+    pub fn get_issues(schema: &Schema) -> Vec<Self> {
+
+        // Columns of each table, in any version, including localised fields, for checking the references.
+        let mut columns: BTreeMap<&str, (BTreeSet<&str>, BTreeSet<&str>)> = BTreeMap::new();
+        for versioned_file in &schema.versioned_files {
+            if let VersionedFile::DB(table_name, definitions) = versioned_file {
+                let (fields, localised_fields) = columns.entry(table_name).or_default();
+                for definition in definitions {
+                    fields.extend(definition.fields.iter().map(|x| &*x.name));
+                    localised_fields.extend(definition.localised_fields.iter().map(|x| &*x.name));
+                }
+            }
+        }
+
+        let mut issues = vec![];
+        for versioned_file in &schema.versioned_files {
+            let versioned_file_name = match versioned_file {
+                VersionedFile::DB(table_name, _) => table_name.to_owned(),
+                VersionedFile::DepManager(_) => "dep_manager".to_owned(),
+                VersionedFile::Loc(_) => "loc".to_owned(),
+            };
+
+            for definition in versioned_file.get_version_list() {
+                Self::check_definition(&versioned_file_name, definition.version, definition, "", &columns, &mut issues);
+            }
+        }
+
+        issues
+    }
+
+    /// This function checks the provided `Definition` for errors, including the definitions of its sequences.
+    fn check_definition(
+        versioned_file: &str,
+        version: i32,
+        definition: &Definition,
+        field_prefix: &str,
+        columns: &BTreeMap<&str, (BTreeSet<&str>, BTreeSet<&str>)>,
+        issues: &mut Vec<Self>
+    ) {
+        let mut new_issue = |field_name: &str, kind: LintIssueKind| issues.push(Self {
+            versioned_file: versioned_file.to_owned(),
+            version,
+            field_name: Some(format!("{}{}", field_prefix, field_name)),
+            kind,
+        });
+
+        let mut names = BTreeSet::new();
+        let mut ca_orders = BTreeSet::new();
+        for field in &definition.fields {
+            if !names.insert(&*field.name) {
+                new_issue(&field.name, LintIssueKind::DuplicatedField);
+            }
+
+            // Unknown positions are all -1, so they're not duplicates.
+            if field.ca_order != -1 && !ca_orders.insert(field.ca_order) {
+                new_issue(&field.name, LintIssueKind::DuplicatedCaOrder(field.ca_order));
+            }
+
+            if let Some((ref_table, ref_column)) = &field.is_reference {
+                match columns.get(&*format!("{}_tables", ref_table)) {
+                    Some((ref_fields, ref_localised_fields)) => {
+                        if !ref_fields.contains(&**ref_column) {
+                            new_issue(&field.name, LintIssueKind::ReferencedColumnNotFound(ref_table.to_owned(), ref_column.to_owned()));
+                        }
+
+                        // Lookups are usually the localised fields of the referenced table.
+                        if let Some(lookup) = &field.lookup {
+                            for lookup_column in lookup {
+                                if !ref_fields.contains(&**lookup_column) && !ref_localised_fields.contains(&**lookup_column) {
+                                    new_issue(&field.name, LintIssueKind::LookupColumnNotFound(ref_table.to_owned(), lookup_column.to_owned()));
+                                }
+                            }
+                        }
+                    }
+                    None => new_issue(&field.name, LintIssueKind::ReferencedTableNotFound(ref_table.to_owned())),
+                }
+            }
+
+            let is_string = matches!(field.field_type, FieldType::StringU8 | FieldType::StringU16 | FieldType::OptionalStringU8 | FieldType::OptionalStringU16);
+            if field.max_length != 0 && !is_string {
+                new_issue(&field.name, LintIssueKind::MaxLengthOnNonString(field.max_length));
+            }
+
+            if let Some(default_value) = &field.default_value {
+                if DecodedData::new_from_type_and_string(&field.field_type, default_value).is_err() {
+                    new_issue(&field.name, LintIssueKind::InvalidDefaultValue(default_value.to_owned()));
+                }
+            }
+
+            if let Some(enum_values) = &field.enum_values {
+                for value in enum_values.keys() {
+                    if DecodedData::new_from_type_and_string(&field.field_type, value).is_err() {
+                        new_issue(&field.name, LintIssueKind::InvalidEnumValue(value.to_owned()));
+                    }
+                }
+
+                if let Some(default_value) = &field.default_value {
+                    if !enum_values.contains_key(default_value) {
+                        new_issue(&field.name, LintIssueKind::DefaultValueNotInEnum(default_value.to_owned()));
+                    }
+                }
+            }
+        }
+
+        for localised_field in &definition.localised_fields {
+            if names.contains(&*localised_field.name) {
+                new_issue(&localised_field.name, LintIssueKind::LocalisedFieldCollision);
+            }
+        }
+
+        for field in &definition.fields {
+            if let FieldType::Sequence(sequence_definition) = &field.field_type {
+                let field_prefix = format!("{}{}/", field_prefix, field.name);
+                Self::check_definition(versioned_file, version, sequence_definition, &field_prefix, columns, issues);
+            }
+        }
+    }
+}
+
+/// Display implementation of `LintIssue`.
+impl Display for LintIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}, version {}", self.versioned_file, self.version)?;
+        if let Some(field_name) = &self.field_name {
+            write!(f, ", field '{}'", field_name)?;
+        }
+
+        match &self.kind {
+            LintIssueKind::DuplicatedField => write!(f, ": there is more than one field with this name."),
+            LintIssueKind::ReferencedTableNotFound(ref_table) => write!(f, ": the referenced table '{}' is not in the schema.", ref_table),
+            LintIssueKind::ReferencedColumnNotFound(ref_table, ref_column) => write!(f, ": the referenced column '{}' is not in any version of the table '{}'.", ref_column, ref_table),
+            LintIssueKind::LookupColumnNotFound(ref_table, lookup_column) => write!(f, ": the lookup column '{}' is not in any version of the table '{}'.", lookup_column, ref_table),
+            LintIssueKind::LocalisedFieldCollision => write!(f, ": there is a regular field with the same name as this localised field."),
+            LintIssueKind::MaxLengthOnNonString(max_length) => write!(f, ": the field has a max length of {}, but it's not a string.", max_length),
+            LintIssueKind::InvalidDefaultValue(default_value) => write!(f, ": the default value '{}' is not valid for the type of the field.", default_value),
+            LintIssueKind::DuplicatedCaOrder(ca_order) => write!(f, ": there is more than one field with the CA order {}.", ca_order),
+            LintIssueKind::InvalidEnumValue(value) => write!(f, ": the value '{}' of its set of values is not valid for the type of the field.", value),
+            LintIssueKind::DefaultValueNotInEnum(default_value) => write!(f, ": the default value '{}' is not in the set of values of the field.", default_value),
+        }
+    }
+}
@@ -0,0 +1,70 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with the structs used when merging two schemas.
+
+Merging is done by `Schema::merge`. Versioned files only in one of the schemas, and versions only in one of the sides, are always kept.
+When both schemas have the same version of a versioned file with different definitions, that's a conflict, solved following the `MergeStrategy` used.
+!*/
+
+use serde_derive::{Serialize, Deserialize};
+
+use super::diff::DefinitionDiff;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This enum represents what to do when both schemas have different definitions for the same version of a versioned file.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum MergeStrategy {
+
+    /// Keep the definition of the schema we're merging into.
+    Ours,
+
+    /// Keep the definition of the schema we're merging from.
+    Theirs,
+
+    /// Do not merge anything if there are conflicts.
+    Abort,
+}
+
+/// This struct represents a version of a versioned file defined differently in both schemas.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MergeConflict {
+
+    /// Name of the versioned file. For DB Tables, it's the name of the table. Otherwise, it's `dep_manager` or `loc`.
+    pub versioned_file: String,
+
+    /// Version with different definitions.
+    pub version: i32,
+
+    /// The differences from our definition to theirs.
+    pub diff: DefinitionDiff,
+}
+
+//---------------------------------------------------------------------------//
+//                       Implementation of MergeStrategy
+//---------------------------------------------------------------------------//
+
+/// Implementation of `MergeStrategy`.
+impl MergeStrategy {
+
+    /// This function returns the `MergeStrategy` with the provided name (`ours`, `theirs` or `abort`), if any.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "ours" => Some(Self::Ours),
+            "theirs" => Some(Self::Theirs),
+            "abort" => Some(Self::Abort),
+            _ => None,
+        }
+    }
+}
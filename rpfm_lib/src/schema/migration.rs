@@ -0,0 +1,216 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with the code to migrate schemas from any of their old structural versions to the current one.
+
+Schemas are migrated one structural version at a time, following a chain of steps: V0 -> V1 -> V2. Each step converts the schema
+to the next version, and reports what it changed, so we can know what a migration will do before saving it.
+
+NOTE FOR DEV: If you make a new structural version, move the current one to its own legacy module (like `v1`), add it to `VersionedSchema`,
+add the step from it to the new one in `VersionedSchema::migrate_step`, and add a fixture for it to the tests.
+!*/
+
+use ron::de::from_str;
+use serde_derive::{Serialize, Deserialize};
+
+use rpfm_error::{ErrorKind, Result};
+
+use super::{CURRENT_STRUCTURAL_VERSION, Schema};
+use super::diff::SchemaDiff;
+use super::v0::SchemaV0;
+use super::v1::{FieldTypeV1, DefinitionV1, SchemaV1, VersionedFileV1};
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This enum represents a schema in any of its structural versions.
+pub(crate) enum VersionedSchema {
+
+    /// Json schema, used from 0.X to 1.6.2.
+    V0(SchemaV0),
+
+    /// Ron schema without version, used during the development of 2.0.
+    V1(SchemaV1),
+
+    /// Current schema.
+    V2(Schema),
+}
+
+/// This struct represents a step of a migration, from one structural version to the next one.
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct MigrationStep {
+
+    /// Structural version of the schema before this step.
+    pub from_version: u16,
+
+    /// Structural version of the schema after this step.
+    pub to_version: u16,
+
+    /// List of the changes done by this step.
+    pub changes: Vec<String>,
+}
+
+/// This struct represents what a migration did, or would do in a dry run.
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct MigrationReport {
+
+    /// Structural version of the schema before the migration.
+    pub source_version: u16,
+
+    /// Steps followed to reach the current structural version. Empty if the schema was already in it.
+    pub steps: Vec<MigrationStep>,
+
+    /// Differences from the schema in the destination to the migrated one, if there was a schema there.
+    pub diff: Option<SchemaDiff>,
+}
+
+//---------------------------------------------------------------------------//
+//                      Implementation of VersionedSchema
+//---------------------------------------------------------------------------//
+
+/// Implementation of `VersionedSchema`.
+impl VersionedSchema {
+
+    /// This function reads a schema of any structural version from the contents of its file.
+    pub fn read(data: &str) -> Result<Self> {
+        if let Ok(schema) = from_str::<Schema>(data) {
+            return if schema.version == CURRENT_STRUCTURAL_VERSION { Ok(Self::V2(schema)) }
+            else { Err(ErrorKind::SchemaMigrationUnsupportedVersion(schema.version).into()) };
+        }
+
+        if let Ok(schema) = from_str::<SchemaV1>(data) {
+            return Ok(Self::V1(schema));
+        }
+
+        if let Ok(schema) = serde_json::from_str::<SchemaV0>(data) {
+            return Ok(Self::V0(schema));
+        }
+
+        Err(ErrorKind::SchemaMigrationUnknownFormat.into())
+    }
+
+    /// This function returns the structural version of the schema.
+    pub fn get_structural_version(&self) -> u16 {
+        match self {
+            Self::V0(_) => 0,
+            Self::V1(_) => 1,
+            Self::V2(_) => 2,
+        }
+    }
+
+    /// This function migrates the schema to the current structural version, returning it and the steps followed.
+    pub fn migrate(self) -> (Schema, Vec<MigrationStep>) {
+        let mut schema = self;
+        let mut steps = vec![];
+        loop {
+            match schema {
+                Self::V2(schema) => return (schema, steps),
+                _ => {
+                    let (next, step) = schema.migrate_step();
+                    steps.push(step);
+                    schema = next;
+                }
+            }
+        }
+    }
+
+    /// This function migrates the schema to the next structural version, returning it and the step done.
+    ///
+    /// Schemas in the current structural version are returned unchanged.
+    fn migrate_step(self) -> (Self, MigrationStep) {
+        let from_version = self.get_structural_version();
+        let (next, changes) = match self {
+            Self::V0(schema) => {
+                let tables = schema.tables_definitions.len();
+                let definitions = schema.tables_definitions.iter().map(|x| x.versions.len()).sum::<usize>();
+                (Self::V1(SchemaV1::from(&schema)), vec![
+                    "Converted the schema from Json to Ron.".to_owned(),
+                    format!("Migrated {} DB Tables, with {} definitions.", tables, definitions),
+                    "Added the new field properties 'default_value', 'max_length', 'is_filename', 'filename_relative_path' and 'lookup', empty.".to_owned(),
+                ])
+            }
+
+            Self::V1(schema) => {
+                let definitions = schema.0.iter().map(|x| match x {
+                    VersionedFileV1::DB(_, definitions) |
+                    VersionedFileV1::DepManager(definitions) |
+                    VersionedFileV1::Loc(definitions) => definitions.len(),
+                }).sum::<usize>();
+                let fields = schema.0.iter().map(|x| match x {
+                    VersionedFileV1::DB(_, definitions) |
+                    VersionedFileV1::DepManager(definitions) |
+                    VersionedFileV1::Loc(definitions) => definitions.iter().map(count_fields).sum::<usize>(),
+                }).sum::<usize>();
+                (Self::V2(Schema::from(&schema)), vec![
+                    format!("Added the structural version ({}) to the schema.", CURRENT_STRUCTURAL_VERSION),
+                    format!("Migrated {} versioned files, with {} definitions.", schema.0.len(), definitions),
+                    "Added the new definition property 'localised_fields', empty.".to_owned(),
+                    format!("Added the new field property 'ca_order' to {} fields, as unknown (-1).", fields),
+                ])
+            }
+
+            Self::V2(schema) => (Self::V2(schema), vec![]),
+        };
+
+        let step = MigrationStep {
+            from_version,
+            to_version: next.get_structural_version(),
+            changes,
+        };
+
+        (next, step)
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                      Implementation of MigrationReport
+//---------------------------------------------------------------------------//
+
+/// Implementation of `MigrationReport`.
+impl MigrationReport {
+
+    /// This function returns a MarkDown-encoded version of the report.
+    pub fn to_markdown(&self) -> String {
+        let mut report = String::new();
+        if self.steps.is_empty() {
+            report.push_str(&format!("The schema is already in the current structural version ({}).\n", CURRENT_STRUCTURAL_VERSION));
+        }
+
+        for step in &self.steps {
+            report.push_str(&format!("### From V{} to V{}\n", step.from_version, step.to_version));
+            step.changes.iter().for_each(|x| report.push_str(&format!("  - {}\n", x)));
+        }
+
+        if let Some(diff) = &self.diff {
+            if diff.is_empty() {
+                report.push_str("\nThe migrated schema is equal to the one in the destination.\n");
+            } else {
+                report.push_str("\n## Changes over the schema in the destination\n");
+                report.push_str(&diff.to_markdown());
+            }
+        }
+
+        report
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                              Utility functions
+//---------------------------------------------------------------------------//
+
+/// This function returns the amount of fields of a definition V1, including the ones inside its sequences.
+fn count_fields(definition: &DefinitionV1) -> usize {
+    definition.fields.iter().map(|x| match &x.field_type {
+        FieldTypeV1::Sequence(definition) => 1 + count_fields(definition),
+        _ => 1,
+    }).sum()
+}
@@ -60,16 +60,17 @@ Inside the schema there are `VersionedFile` variants of different types, with a
 !*/
 
 use rayon::prelude::*;
-use reqwest::blocking;
-use ron::de::{from_str, from_reader};
+use ron::de::from_reader;
 use ron::ser::{to_string_pretty, PrettyConfig};
+use serde::ser::{Serializer, SerializeStruct};
 use serde_derive::{Serialize, Deserialize};
 
-use std::collections::BTreeMap;
-use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+use std::cmp::{Ordering, Reverse};
 use std::fs::{DirBuilder, File};
 use std::{fmt, fmt::Display};
 use std::io::{BufReader, Read, Write};
+use std::path::Path;
 
 use rpfm_error::{ErrorKind, Result};
 
@@ -78,14 +79,37 @@ use crate::assembly_kit::table_definition::{RawDefinition, RawField};
 use crate::DEPENDENCY_DATABASE;
 use crate::SUPPORTED_GAMES;
 use crate::config::get_config_path;
+use crate::packfile::packedfile::PackedFile;
+use crate::packedfile::DecodedPackedFile;
+use crate::packedfile::table::DecodedData;
 use crate::packedfile::table::db::DB;
+use crate::packedfile::table::loc::TSV_NAME_LOC;
+use self::diff::{DefinitionDiff, FieldDiff, SchemaDiff, TableDiff};
+use self::lint::LintIssue;
+use self::merge::{MergeConflict, MergeStrategy};
+use self::migration::{MigrationReport, VersionedSchema};
+use self::provenance::{compare_patches, DefinitionProvenance, DefinitionSource};
+use self::source::SchemaSource;
+use self::type_definitions::TypeDefinitionFormat;
 use self::versions::VersionsFile;
 
 // Legacy Schemas, to keep backwards compatibility during updates.
 pub(crate) mod v1;
 pub(crate) mod v0;
+pub mod decoding_report;
+pub mod diff;
+pub mod lint;
+pub mod merge;
+pub mod migration;
+pub mod provenance;
+pub mod source;
+pub mod type_definitions;
+pub mod value_domains;
 pub mod versions;
 
+#[cfg(test)]
+mod schema_test;
+
 /// Name of the schema versions file.
 const SCHEMA_VERSIONS_FILE: &str = "versions.ron";
 
@@ -133,7 +157,9 @@ pub enum VersionedFile {
 }
 
 /// This struct contains all the data needed to decode a specific version of a versioned PackedFile.
-#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+///
+/// Its `Serialize` implementation is manual, to skip the empty optional metadata only in text formats.
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize)]
 pub struct Definition {
 
     /// The version of the PackedFile the definition is for. These versions are:
@@ -147,10 +173,18 @@ pub struct Definition {
 
     /// This is a list of all the fields from this definition that are moved to a Loc PackedFile on exporting.
     pub localised_fields: Vec<Field>,
+
+    /// Optional metadata about where this definition comes from, like the patches of the game it was used in.
+    ///
+    /// It's skipped if empty when serializing to text formats, like the RON schemas.
+    #[serde(default)]
+    pub provenance: Option<DefinitionProvenance>,
 }
 
 /// This struct holds all the relevant data do properly decode a field from a versioned PackedFile.
-#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+///
+/// Its `Serialize` implementation is manual, to skip the empty optional metadata only in text formats.
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize)]
 pub struct Field {
 
     /// Name of the field. Should contain no spaces, using `_` instead.
@@ -185,6 +219,12 @@ pub struct Field {
 
     /// Visual position in CA's Table. `-1` means we don't know its position.
     pub ca_order: i16,
+
+    /// `Some(values)` if the field only takes a fixed set of values, with a label for each one (empty if it has none). `None` otherwise.
+    ///
+    /// It's skipped if empty when serializing to text formats, like the RON schemas.
+    #[serde(default)]
+    pub enum_values: Option<BTreeMap<String, String>>,
 }
 
 /// This enum defines every type of field the lib can encode/decode.
@@ -346,6 +386,40 @@ impl Schema {
         }
     }
 
+    /// This function returns the definition of a DB Table used in the provided patch of the game, if we know it.
+    pub fn get_ref_definition_db_for_patch(&self, table_name: &str, patch: &str) -> Result<&Definition> {
+        self.get_ref_versioned_file_db(table_name)?.get_version_for_patch(patch)
+    }
+
+    /// This function returns the version of each DB Table used in the provided patch of the game, for the tables we know it.
+    pub fn get_table_versions_for_patch(&self, patch: &str) -> BTreeMap<String, i32> {
+        self.versioned_files.iter()
+            .filter_map(|x| match x {
+                VersionedFile::DB(table_name, _) => x.get_version_for_patch(patch).ok().map(|definition| (table_name.to_owned(), definition.version)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// This function returns the names of the DB Tables (like `land_units_tables`) with a column referencing the provided table, in any of their versions.
+    ///
+    /// The referenced table is expected without the `_tables` suffix, like in the references of the schema.
+    pub fn get_tables_referencing(&self, ref_table: &str) -> BTreeSet<String> {
+        self.versioned_files.iter()
+            .filter_map(|x| match x {
+                VersionedFile::DB(table_name, definitions) => Some((table_name, definitions)),
+                _ => None,
+            })
+            .filter(|(_, definitions)| definitions.iter()
+                .flat_map(|definition| definition.fields.iter())
+                .any(|field| match field.is_reference {
+                    Some((ref table, _)) => table == ref_table,
+                    None => false,
+                }))
+            .map(|(table_name, _)| table_name.to_owned())
+            .collect()
+    }
+
     /// This function returns the last compatible definition of a Loc Table.
     pub fn get_ref_last_definition_loc(&self) -> Result<&Definition> {
         let versioned_file = self.get_ref_versioned_file_loc()?;
@@ -358,11 +432,19 @@ impl Schema {
     }
 
     /// This function loads a `Schema` to memory from a file in the `schemas/` folder.
+    ///
+    /// The `Schema` is not checked for errors. Use `Schema::lint` for that.
     pub fn load(schema_file: &str) -> Result<Self> {
         let mut file_path = get_config_path()?.join(SCHEMA_FOLDER);
         file_path.push(schema_file);
+        let schema = Self::load_from_path(&file_path)?;
 
-        let file = BufReader::new(File::open(&file_path)?);
+        Ok(schema)
+    }
+
+    /// This function loads a `Schema` to memory from a file anywhere, like a git checkout of the schemas repository.
+    pub fn load_from_path(file_path: &Path) -> Result<Self> {
+        let file = BufReader::new(File::open(file_path)?);
         from_reader(file).map_err(From::from)
     }
 
@@ -374,7 +456,12 @@ impl Schema {
         DirBuilder::new().recursive(true).create(&file_path)?;
 
         file_path.push(schema_file);
-        let mut file = File::create(&file_path)?;
+        self.save_to_path(&file_path)
+    }
+
+    /// This function saves a `Schema` from memory to a file anywhere.
+    pub fn save_to_path(&mut self, file_path: &Path) -> Result<()> {
+        let mut file = File::create(file_path)?;
         let config = PrettyConfig::default();
 
         self.sort();
@@ -426,14 +513,36 @@ impl Schema {
         Ok(())
     }
 
-    /// This function generates a diff between the local schema files and the remote ones and drops it in the config folder.
+    /// This function generates type definitions in the provided format for every version of every DB and Loc Table of the schema,
+    /// so other tools can use the tables exported to JSON. One file is created per version, in the provided folder.
+    pub fn export_type_definitions(&self, format: TypeDefinitionFormat, folder: &Path) -> Result<()> {
+        DirBuilder::new().recursive(true).create(folder)?;
+        for versioned_file in &self.versioned_files {
+            let (file_name, table_name) = match versioned_file {
+                VersionedFile::DB(table_name, _) => (table_name.to_owned(), table_name.to_owned()),
+                VersionedFile::Loc(_) => ("loc".to_owned(), TSV_NAME_LOC.to_owned()),
+                VersionedFile::DepManager(_) => continue,
+            };
+
+            for definition in versioned_file.get_version_list() {
+                let file_path = folder.join(format!("{}_v{}.{}", file_name, definition.version, format.get_file_extension()));
+                let mut file = File::create(&file_path)?;
+                file.write_all(format.generate(&table_name, definition)?.as_bytes())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// This function generates a diff between the local schema files and the ones in the schema source set in the settings, and drops it in the config folder.
     ///
     /// If it detects that you're using the git repo (debug), it adds the diff to the proper place in the docs_src folder instead.
     pub fn generate_schema_diff() -> Result<()> {
 
         // To avoid doing a lot of useless checking, we only check for schemas with different version.
         let local_schema_versions: VersionsFile = from_reader(BufReader::new(File::open(get_config_path()?.join(SCHEMA_FOLDER).join(SCHEMA_VERSIONS_FILE))?))?;
-        let current_schema_versions: VersionsFile = from_str(&blocking::get(&format!("{}{}", SCHEMA_UPDATE_URL_MASTER, SCHEMA_VERSIONS_FILE))?.text()?)?;
+        let source = SchemaSource::from_settings()?;
+        source.refresh()?;
+        let current_schema_versions = source.get_versions_file()?;
         let mut schemas_to_update = vec![];
 
         // If the game's schema is not in the repo (when adding a new game's support) skip it.
@@ -458,92 +567,11 @@ impl Schema {
             if skip_it { continue; }
 
             // For this, first we get both schemas. Then, compare them table by table looking for differences.
-            // Uncomment and tweak the commented schema_current to test against a local schema.
+            // To compare two local schemas instead, use `Schema::diff` directly.
             let schema_local = Schema::load(schema_name).unwrap();
-            //let schema_current = Schema::load("schema_att.ron").unwrap();
-            let schema_current: Schema = from_reader(blocking::get(&format!("{}/{}", SCHEMA_UPDATE_URL_MASTER, schema_name))?)?;
-
-            // Lists to store the different types of differences.
-            let mut diff = String::new();
-            let mut new_tables = vec![];
-            let mut new_versions: Vec<String> = vec![];
-            let mut new_corrections: Vec<String> = vec![];
-
-            // For each table, we need to check EVERY possible difference.
-            for table_local in schema_local.versioned_files.iter().filter(|x| x.is_db()) {
-                if let VersionedFile::DB(name_local, versions_local) = table_local {
-                    match schema_current.get_ref_versioned_file_db(name_local) {
-
-                        // If we find it, we have to check if it has changes. If it has them, then we analize them.
-                        Ok(table_current) => {
-                            if let VersionedFile::DB(_, versions_current) = table_current {
-                                if table_local != table_current {
-                                    for version_local in versions_local {
-                                        match versions_current.iter().find(|x| x.version == version_local.version) {
-
-                                            // If the version has been found, it's a correction for a current version. So we check every
-                                            // field for references.
-                                            Some(version_current) => version_local.get_pretty_diff(&version_current, &name_local, &mut new_corrections),
-
-                                            // If the version hasn't been found, is a new version. We have to compare it with
-                                            // the old one and get his changes.
-                                            None => {
-
-                                                // If we have more versions, get the highest one before the one we have. Tables are automatically
-                                                // sorted on save, so we can just get the first one of the current list.
-                                                if versions_local.len() > 1 {
-                                                    let old_version = &versions_current[0];
-                                                    version_local.get_pretty_diff(&old_version, &name_local, &mut new_versions);
-                                                }
-                                            },
-                                        }
-                                    }
-                                }
-                            }
-                        }
-
-                        // If the table hasn't been found, it's a new table we decoded.
-                        Err(_) => new_tables.push(name_local.to_owned()),
-                    }
-                }
-            }
-
-            // Here we put together all the differences.
-            for (index, table) in new_tables.iter().enumerate() {
-                if index == 0 {
-                    diff.push_str("- **New tables decoded**:\n");
-                }
-                diff.push_str(&format!("  - *{}*.", table));
-                diff.push_str("\n");
+            let schema_current = source.get_schema(schema_name)?;
 
-                if index == new_tables.len() - 1 {
-                    diff.push_str("\n");
-                }
-            }
-
-            for (index, version) in new_versions.iter().enumerate() {
-                if index == 0 {
-                    diff.push_str("- **Updated Tables**:\n");
-                }
-                diff.push_str(version);
-                diff.push_str("\n");
-
-                if index == new_versions.len() - 1 {
-                    diff.push_str("\n");
-                }
-            }
-
-            for (index, correction) in new_corrections.iter().enumerate() {
-                if index == 0 {
-                    diff.push_str("- **Fixed Tables**:\n");
-                }
-                diff.push_str(correction);
-                diff.push_str("\n");
-
-                if index == new_corrections.len() - 1 {
-                    diff.push_str("\n");
-                }
-            }
+            let mut diff = Self::diff(&schema_current, &schema_local).to_markdown();
 
             // If it's not empty, save it. Otherwise, we just ignore it.
             if !diff.is_empty() {
@@ -591,12 +619,201 @@ impl Schema {
         Ok(())
     }
 
+    /// This function returns the differences between the DB Tables of two schemas, without touching the network or the disk.
+    ///
+    /// New versions of a table are compared with the highest older version of it in the old schema, if any.
+    pub fn diff(old: &Self, new: &Self) -> SchemaDiff {
+        let old_tables = old.get_db_definitions_by_table();
+        let new_tables = new.get_db_definitions_by_table();
+        let mut diff = SchemaDiff::default();
+
+        for (table_name, new_versions) in &new_tables {
+            match old_tables.get(table_name) {
+                Some(old_versions) => {
+                    for new_definition in new_versions.iter() {
+                        match old_versions.iter().find(|x| x.version == new_definition.version) {
+                            Some(old_definition) => {
+                                let definition_diff = new_definition.get_diff(old_definition);
+                                if !definition_diff.is_empty() {
+                                    diff.corrections.push(TableDiff {
+                                        table_name: table_name.to_owned(),
+                                        version: new_definition.version,
+                                        compared_version: Some(old_definition.version),
+                                        diff: definition_diff,
+                                    });
+                                }
+                            }
+
+                            None => {
+                                let old_definition = old_versions.iter()
+                                    .filter(|x| x.version < new_definition.version)
+                                    .max_by_key(|x| x.version);
+
+                                diff.new_versions.push(TableDiff {
+                                    table_name: table_name.to_owned(),
+                                    version: new_definition.version,
+                                    compared_version: old_definition.map(|x| x.version),
+                                    diff: new_definition.get_diff(old_definition.unwrap_or(&Definition::new(-1))),
+                                });
+                            }
+                        }
+                    }
+
+                    for old_definition in old_versions.iter() {
+                        if !new_versions.iter().any(|x| x.version == old_definition.version) {
+                            diff.removed_versions.push((table_name.to_owned(), old_definition.version));
+                        }
+                    }
+                }
+                None => diff.new_tables.push(table_name.to_owned()),
+            }
+        }
+
+        diff.removed_tables = old_tables.keys().filter(|x| !new_tables.contains_key(*x)).cloned().collect();
+        diff
+    }
+
+    /// This function checks the `Schema` for common errors, like duplicated fields or references to columns that don't exist.
+    pub fn lint(&self) -> Vec<LintIssue> {
+        LintIssue::get_issues(self)
+    }
+
+    /// This function infers the value sets of the fields of the tables in the provided dependency database, from their distinct values.
+    ///
+    /// Rows of all the tables with the same name and version are checked together. It returns the fields inferred, as `table_name/field_name`.
+    pub fn infer_enum_values(&mut self, dependency_db: &mut [PackedFile], max_values: usize) -> Vec<String> {
+        let schema = self.clone();
+        let mut tables: BTreeMap<(String, i32), Vec<Vec<DecodedData>>> = BTreeMap::new();
+        for packed_file in dependency_db.iter_mut().filter(|x| x.get_path().len() == 3 && x.get_path()[0] == "db") {
+            let table_name = packed_file.get_path()[1].to_owned();
+            if let Ok(DecodedPackedFile::DB(db)) = packed_file.decode_return_ref_no_locks(&schema) {
+                tables.entry((table_name, db.get_ref_definition().version)).or_default().extend_from_slice(db.get_ref_table_data());
+            }
+        }
+
+        let mut inferred = vec![];
+        for ((table_name, version), rows) in &tables {
+            if let Ok(definition) = self.get_ref_mut_versioned_file_db(table_name).and_then(|x| x.get_ref_mut_version(*version)) {
+                inferred.extend(definition.infer_enum_values(rows, max_values).iter().map(|x| format!("{}/{}", table_name, x)));
+            }
+        }
+        inferred
+    }
+
+    /// This function merges the provided `Schema` into this one, returning the conflicts found.
+    ///
+    /// Versioned files and versions only in one of the schemas are always kept. Versions defined differently in both schemas are conflicts,
+    /// solved with the provided strategy. With `MergeStrategy::Abort`, if there are conflicts this schema is left untouched and an error is returned.
+    pub fn merge(&mut self, other: &Self, strategy: MergeStrategy) -> Result<Vec<MergeConflict>> {
+        let mut merged = self.clone();
+        let mut conflicts = vec![];
+
+        for their_versioned_file in &other.versioned_files {
+            match merged.versioned_files.iter_mut().find(|x| x.conflict(their_versioned_file)) {
+                Some(our_versioned_file) => {
+                    let versioned_file_name = match their_versioned_file {
+                        VersionedFile::DB(table_name, _) => table_name.to_owned(),
+                        VersionedFile::DepManager(_) => "dep_manager".to_owned(),
+                        VersionedFile::Loc(_) => "loc".to_owned(),
+                    };
+
+                    for their_definition in their_versioned_file.get_version_list() {
+                        match our_versioned_file.get_version(their_definition.version).ok().cloned() {
+
+                            // Provenance is not part of the conflict, but it's merged with theirs.
+                            Some(our_definition) => if our_definition.fields != their_definition.fields || our_definition.localised_fields != their_definition.localised_fields {
+                                conflicts.push(MergeConflict {
+                                    versioned_file: versioned_file_name.to_owned(),
+                                    version: their_definition.version,
+                                    diff: their_definition.get_full_diff(&our_definition),
+                                });
+
+                                if strategy == MergeStrategy::Theirs {
+                                    our_versioned_file.add_version(their_definition);
+                                }
+                            } else if let Some(their_provenance) = &their_definition.provenance {
+                                let our_definition = our_versioned_file.get_ref_mut_version(their_definition.version)?;
+                                match our_definition.provenance {
+                                    Some(ref mut our_provenance) => our_provenance.merge(their_provenance),
+                                    None => our_definition.provenance = Some(their_provenance.clone()),
+                                }
+                            }
+                            None => our_versioned_file.add_version(their_definition),
+                        }
+                    }
+
+                    // Keep the newest version first, as that's what we use when we don't know the version of a table.
+                    match our_versioned_file {
+                        VersionedFile::DB(_, versions) |
+                        VersionedFile::DepManager(versions) |
+                        VersionedFile::Loc(versions) => versions.sort_by_key(|x| Reverse(x.version)),
+                    }
+                }
+                None => merged.versioned_files.push(their_versioned_file.clone()),
+            }
+        }
+
+        if strategy == MergeStrategy::Abort && !conflicts.is_empty() {
+            return Err(ErrorKind::SchemaMergeConflicts(conflicts.len()).into());
+        }
+
+        merged.sort();
+        *self = merged;
+        Ok(conflicts)
+    }
+
+    /// This function returns the definitions of every DB Table of the schema, sorted by table name.
+    fn get_db_definitions_by_table(&self) -> BTreeMap<String, &[Definition]> {
+        self.versioned_files.iter()
+            .filter_map(|x| if let VersionedFile::DB(name, definitions) = x { Some((name.to_owned(), &**definitions)) } else { None })
+            .collect()
+    }
+
+    /// This function migrates a schema file from any structural version to the current one, saving it in the destination path.
+    ///
+    /// The source and destination can be the same file. If `dry_run` is true, nothing is saved, but the report is still returned,
+    /// including the differences with the schema already in the destination, if any.
+    pub fn migrate_file(source_path: &Path, destination_path: &Path, dry_run: bool) -> Result<MigrationReport> {
+        let mut data = String::new();
+        File::open(source_path)?.read_to_string(&mut data)?;
+
+        let versioned_schema = VersionedSchema::read(&data)?;
+        let source_version = versioned_schema.get_structural_version();
+        let (mut schema, steps) = versioned_schema.migrate();
+        schema.sort();
+
+        let diff = Self::load_from_path(destination_path).ok().map(|old_schema| Self::diff(&old_schema, &schema));
+        if !dry_run {
+            schema.save_to_path(destination_path)?;
+        }
+
+        Ok(MigrationReport {
+            source_version,
+            steps,
+            diff,
+        })
+    }
+
     /// This function allow us to update all Schemas from any legacy version into the current one.
     ///
-    /// NOTE FOR DEV: If you make a new Schema Version, add its update function here.
+    /// Schemas V0 are taken from the `schemas` folder next to RPFM, if there is no newer schema for their game in the config folder.
+    ///
+    /// NOTE FOR DEV: If you make a new Schema Version, add its migration step to `migration::VersionedSchema`.
     pub fn update() {
-        v0::SchemaV0::update();
-        v1::SchemaV1::update();
+        println!("Updating schemas to structural version {}", CURRENT_STRUCTURAL_VERSION);
+        if let Ok(schema_folder) = get_config_path().map(|x| x.join(SCHEMA_FOLDER)) {
+            for (game, game_info) in SUPPORTED_GAMES.iter() {
+                let destination_path = schema_folder.join(&game_info.schema);
+                let legacy_path = Path::new(SCHEMA_FOLDER).join(game_info.schema.replace(".ron", ".json"));
+                let source_path = if destination_path.is_file() { destination_path.to_owned() } else { legacy_path };
+                if !source_path.is_file() { continue; }
+
+                match Self::migrate_file(&source_path, &destination_path, false) {
+                    Ok(report) => println!("Schema V{} for game {} updated to Schema V{}.", report.source_version, game, CURRENT_STRUCTURAL_VERSION),
+                    Err(error) => println!("Schema for game {} couldn't be updated: {}", game, error),
+                }
+            }
+        }
     }
 }
 
@@ -659,6 +876,17 @@ impl VersionedFile {
     }
 
 
+    /// This function returns the definition used in the provided patch of the game, if we know it.
+    ///
+    /// Only definitions with provenance metadata are checked. If more than one was used in the patch, the one first seen later is returned.
+    pub fn get_version_for_patch(&self, patch: &str) -> Result<&Definition> {
+        self.get_version_list().iter()
+            .filter_map(|x| x.provenance.as_ref().filter(|provenance| provenance.is_used_in_patch(patch)).map(|provenance| (x, provenance)))
+            .max_by(|(_, a), (_, b)| compare_patches(a.first_seen.as_deref().unwrap_or_default(), b.first_seen.as_deref().unwrap_or_default()))
+            .map(|(definition, _)| definition)
+            .ok_or_else(|| From::from(ErrorKind::SchemaDefinitionNotFound))
+    }
+
     /// This function returns the list of the versions in the provided `VersionedFile`.
     pub fn get_version_list(&self) -> &[Definition] {
         match &self {
@@ -707,6 +935,7 @@ impl Definition {
             version,
             localised_fields: vec![],
             fields: vec![],
+            provenance: None,
         }
     }
 
@@ -719,6 +948,22 @@ impl Definition {
             .collect()
     }
 
+    /// This function infers the value sets of the fields of this definition from the provided rows, returning the names of the fields inferred.
+    ///
+    /// Check the `value_domains` module for the fields that can be inferred.
+    pub fn infer_enum_values(&mut self, rows: &[Vec<DecodedData>], max_values: usize) -> Vec<String> {
+        let mut inferred = vec![];
+        for (column, field) in self.fields.iter_mut().enumerate() {
+            if value_domains::can_infer_enum_values(field) {
+                if let Some(enum_values) = value_domains::infer_enum_values(rows, column, max_values) {
+                    field.enum_values = Some(enum_values);
+                    inferred.push(field.name.to_owned());
+                }
+            }
+        }
+        inferred
+    }
+
     /// This function updates the fields in the provided definition with the data in the provided RawDefinition.
     ///
     /// Not all data is updated though, only:
@@ -727,13 +972,17 @@ impl Definition {
     /// - Default Value.
     /// - Filename Relative Path.
     /// - Is Filename.
+    /// - Description.
     /// - Is Reference.
     /// - Lookup.
     /// - CA Order.
-    pub fn update_from_raw_definition(&mut self, raw_definition: &RawDefinition) {
-        for (index, raw_field) in raw_definition.fields.iter().enumerate() {
-            for field in &mut self.fields {
-                if field.name == raw_field.name {
+    ///
+    /// Fields are matched by name, ignoring case if there is no exact match. It returns the names of the fields it couldn't match.
+    pub fn update_from_raw_definition(&mut self, raw_definition: &RawDefinition) -> Vec<String> {
+        let mut unmatched_fields = vec![];
+        for field in &mut self.fields {
+            match Self::find_raw_field(raw_definition, &field.name) {
+                Some((index, raw_field)) => {
                     if (raw_field.primary_key == "1" && !field.is_key) || (raw_field.primary_key == "0" && field.is_key) {
                         field.is_key = raw_field.primary_key == "1";
                     }
@@ -769,12 +1018,45 @@ impl Definition {
 
                     field.is_filename = raw_field.is_filename.is_some();
                     field.ca_order = index as i16;
-                    break;
+                }
+                None => unmatched_fields.push(field.name.to_owned()),
+            }
+        }
+        unmatched_fields
+    }
+
+    /// This function updates the documentation of the fields in the provided definition with the data in the provided RawDefinition.
+    ///
+    /// Unlike `update_from_raw_definition`, this is meant for older versions of a table, so it only fills descriptions and filename data
+    /// missing in the definition, as the rest of the data may have changed between versions.
+    pub fn update_docs_from_raw_definition(&mut self, raw_definition: &RawDefinition) {
+        for field in &mut self.fields {
+            if let Some((_, raw_field)) = Self::find_raw_field(raw_definition, &field.name) {
+                if field.description.is_empty() {
+                    if let Some(ref description) = raw_field.field_description {
+                        field.description = description.to_owned();
+                    }
+                }
+
+                if field.filename_relative_path.is_none() && raw_field.filename_relative_path.is_some() {
+                    field.filename_relative_path = raw_field.filename_relative_path.clone();
+                }
+
+                if !field.is_filename {
+                    field.is_filename = raw_field.is_filename.is_some();
                 }
             }
         }
     }
 
+    /// This function returns the field of the provided RawDefinition with the provided name, and its position.
+    ///
+    /// If there is no field with the exact name, it tries again ignoring case, as some Assembly Kit fields have different case than their table fields.
+    fn find_raw_field<'a>(raw_definition: &'a RawDefinition, field_name: &str) -> Option<(usize, &'a RawField)> {
+        raw_definition.fields.iter().enumerate().find(|(_, x)| x.name == field_name)
+            .or_else(|| raw_definition.fields.iter().enumerate().find(|(_, x)| x.name.eq_ignore_ascii_case(field_name)))
+    }
+
     /// This function populates the `localised_fields` of a definition with data from the assembly kit.
     pub fn update_from_raw_localisable_fields(&mut self, raw_definition: &RawDefinition, raw_localisable_fields: &[RawLocalisableField]) {
         let raw_table_name = &raw_definition.name.as_ref().unwrap()[..raw_definition.name.as_ref().unwrap().len() - 4];
@@ -902,6 +1184,7 @@ impl Definition {
             version,
             localised_fields: vec![],
             fields,
+            provenance: Some(DefinitionProvenance::new(DefinitionSource::AssemblyKit)),
         }
     }
 
@@ -991,6 +1274,7 @@ impl Definition {
             version: -1,
             localised_fields: vec![],
             fields,
+            provenance: Some(DefinitionProvenance::new(DefinitionSource::AssemblyKit)),
         }
     }
 */
@@ -1001,15 +1285,39 @@ impl Definition {
         table_name: &str,
         changes: &mut Vec<String>,
     ) {
+        self.get_diff(version_current).push_markdown(table_name, changes);
+    }
+
+    /// This function returns the differences between this definition and an older one.
+    ///
+    /// Only the properties shown in the changelogs are compared. Use `get_full_diff` to compare everything.
+    pub fn get_diff(&self, version_current: &Self) -> DefinitionDiff {
+        self.get_diff_with_properties(version_current, false)
+    }
+
+    /// This function returns all the differences between the fields of this definition and another one, including the order of the fields.
+    ///
+    /// Localised fields and provenance are not compared.
+    pub fn get_full_diff(&self, version_current: &Self) -> DefinitionDiff {
+        let mut diff = self.get_diff_with_properties(version_current, true);
+
+        let order_local = self.fields.iter().map(|x| x.name.to_owned()).filter(|x| version_current.fields.iter().any(|y| &y.name == x)).collect::<Vec<String>>();
+        let order_current = version_current.fields.iter().map(|x| x.name.to_owned()).filter(|x| self.fields.iter().any(|y| &y.name == x)).collect::<Vec<String>>();
+        if order_local != order_current {
+            diff.field_order = Some((order_current, order_local));
+        }
+
+        diff
+    }
+
+    /// This function returns the differences between this definition and an older one, comparing all the properties of the fields if `all_properties` is true.
+    fn get_diff_with_properties(&self, version_current: &Self, all_properties: bool) -> DefinitionDiff {
 
         // Here it's were things get complex. We have to get, field by field, and check:
         // - If they exists.
-        // - If they are in the same position. (TODO)
+        // - If they are in the same position. (only in full diffs, see get_full_diff)
         // - If they are different, in which case we have to check on what.
-        // Changed fields have: Vec<(field_name, vec<(changed_variant, (before, after))>)>.
-        let mut new_fields: Vec<Field> = vec![];
-        let mut changed_fields: Vec<(String, Vec<(String, (String, String))>)> = vec![];
-        let mut removed_fields: Vec<String> = vec![];
+        let mut diff = DefinitionDiff::default();
         for field_local in &self.fields {
             match version_current.fields.iter().find(|x| x.name == field_local.name) {
                 Some(field_current) => {
@@ -1019,76 +1327,78 @@ impl Definition {
                     let mut changes = vec![];
                     if field_local != field_current {
                         if field_local.field_type != field_current.field_type {
-                            changes.push(("Type".to_owned(), (format!("{}", field_current.field_type), format!("{}", field_local.field_type))));
+                            changes.push(("Type".to_owned(), format!("{}", field_current.field_type), format!("{}", field_local.field_type)));
                         }
 
                         if field_local.is_key != field_current.is_key {
-                            changes.push(("Is Key".to_owned(), (format!("{}", field_current.is_key), format!("{}", field_local.is_key))));
+                            changes.push(("Is Key".to_owned(), format!("{}", field_current.is_key), format!("{}", field_local.is_key)));
                         }
 
                         if field_local.is_reference != field_current.is_reference {
                             changes.push(("Is Reference".to_owned(),
-                                (
-                                    if let Some((ref_table, ref_column)) = &field_current.is_reference { format!("{}, {}", ref_table, ref_column) }
-                                    else { String::new() },
-                                    if let Some((ref_table, ref_column)) = &field_local.is_reference { format!("{}, {}", ref_table, ref_column) }
-                                    else { String::new() }
-                                )
+                                if let Some((ref_table, ref_column)) = &field_current.is_reference { format!("{}, {}", ref_table, ref_column) }
+                                else { String::new() },
+                                if let Some((ref_table, ref_column)) = &field_local.is_reference { format!("{}, {}", ref_table, ref_column) }
+                                else { String::new() }
                             ));
                         }
 
                         if field_local.description != field_current.description {
-                            changes.push(("Description".to_owned(), (field_current.description.to_owned(), field_local.description.to_owned())));
+                            changes.push(("Description".to_owned(), field_current.description.to_owned(), field_local.description.to_owned()));
+                        }
+
+                        if all_properties {
+                            let join = |values: &Option<Vec<String>>| values.as_ref().map(|x| x.join(", ")).unwrap_or_default();
+                            let join_map = |values: &Option<BTreeMap<String, String>>| values.as_ref().map(|x| x.iter().map(|(value, name)| format!("{}: {}", value, name)).collect::<Vec<String>>().join(", ")).unwrap_or_default();
+
+                            if field_local.default_value != field_current.default_value {
+                                changes.push(("Default Value".to_owned(), field_current.default_value.clone().unwrap_or_default(), field_local.default_value.clone().unwrap_or_default()));
+                            }
+
+                            if field_local.max_length != field_current.max_length {
+                                changes.push(("Max Length".to_owned(), field_current.max_length.to_string(), field_local.max_length.to_string()));
+                            }
+
+                            if field_local.is_filename != field_current.is_filename {
+                                changes.push(("Is Filename".to_owned(), field_current.is_filename.to_string(), field_local.is_filename.to_string()));
+                            }
+
+                            if field_local.filename_relative_path != field_current.filename_relative_path {
+                                changes.push(("Filename Relative Path".to_owned(), field_current.filename_relative_path.clone().unwrap_or_default(), field_local.filename_relative_path.clone().unwrap_or_default()));
+                            }
+
+                            if field_local.lookup != field_current.lookup {
+                                changes.push(("Lookup".to_owned(), join(&field_current.lookup), join(&field_local.lookup)));
+                            }
+
+                            if field_local.ca_order != field_current.ca_order {
+                                changes.push(("CA Order".to_owned(), field_current.ca_order.to_string(), field_local.ca_order.to_string()));
+                            }
+
+                            if field_local.enum_values != field_current.enum_values {
+                                changes.push(("Enum Values".to_owned(), join_map(&field_current.enum_values), join_map(&field_local.enum_values)));
+                            }
                         }
                     }
 
                     if !changes.is_empty() {
-                        changed_fields.push((field_local.name.to_owned(), changes));
+                        diff.changed_fields.push(FieldDiff { name: field_local.name.to_owned(), changes });
                     }
                 },
 
                 // If the field doesn't exists, it's new.
-                None => new_fields.push(field_local.clone()),
+                None => diff.new_fields.push(field_local.clone()),
             }
         }
 
         // We have to check for removed fields too.
         for field_current in &version_current.fields {
             if self.fields.iter().find(|x| x.name == field_current.name).is_none() {
-                removed_fields.push(field_current.name.to_owned());
+                diff.removed_fields.push(field_current.name.to_owned());
             }
         }
 
-        if !new_fields.is_empty() || !changed_fields.is_empty() || !removed_fields.is_empty() {
-            changes.push(format!("  - ***{}***:", table_name));
-        }
-
-        for (index, new_field) in new_fields.iter().enumerate() {
-            if index == 0 { changes.push("    - **New fields**:".to_owned()); }
-            changes.push(format!("      - ***{}***:", new_field.name));
-            changes.push(format!("        - **Type**: *{}*.", new_field.field_type));
-            changes.push(format!("        - **Is Key**: *{}*.", new_field.is_key));
-            if let Some((ref_table, ref_column)) = &new_field.is_reference {
-                changes.push(format!("        - **Is Reference**: *{}*/*{}*.", ref_table, ref_column));
-            }
-            if !new_field.description.is_empty() {
-                changes.push(format!("        - **Description**: *{}*.", new_field.description));
-            }
-        }
-
-        for (index, changed_field) in changed_fields.iter().enumerate() {
-            if index == 0 { changes.push("    - **Changed fields**:".to_owned()); }
-            changes.push(format!("      - **{}**:", changed_field.0));
-
-            for changed_variant in &changed_field.1 {
-                changes.push(format!("        - ***{}***: *{}* => *{}*.", changed_variant.0, (changed_variant.1).0, (changed_variant.1).1));
-            }
-        }
-
-        for (index, removed_field) in removed_fields.iter().enumerate() {
-            if index == 0 { changes.push("    - **Removed fields**:".to_owned()); }
-            changes.push(format!("      - *{}*.", removed_field));
-        }
+        diff
     }
 }
 
@@ -1120,7 +1430,8 @@ impl Field {
             is_reference,
             lookup,
             description,
-            ca_order
+            ca_order,
+            enum_values: None,
         }
     }
 }
@@ -1149,11 +1460,52 @@ impl Default for Field {
             is_reference: None,
             lookup: None,
             description: String::from(""),
-            ca_order: -1
+            ca_order: -1,
+            enum_values: None,
         }
     }
 }
 
+/// Serialize implementation of `Definition`.
+///
+/// Empty provenance is skipped in text formats, like the RON schemas. Binary formats, like bincode, don't support skipped fields, so they always get it.
+impl serde::Serialize for Definition {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let skip_provenance = serializer.is_human_readable() && self.provenance.is_none();
+        let mut state = serializer.serialize_struct("Definition", if skip_provenance { 3 } else { 4 })?;
+        state.serialize_field("version", &self.version)?;
+        state.serialize_field("fields", &self.fields)?;
+        state.serialize_field("localised_fields", &self.localised_fields)?;
+        if skip_provenance { state.skip_field("provenance")?; }
+        else { state.serialize_field("provenance", &self.provenance)?; }
+        state.end()
+    }
+}
+
+/// Serialize implementation of `Field`.
+///
+/// Empty enum values are skipped in text formats, like the RON schemas. Binary formats, like bincode, don't support skipped fields, so they always get them.
+impl serde::Serialize for Field {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let skip_enum_values = serializer.is_human_readable() && self.enum_values.is_none();
+        let mut state = serializer.serialize_struct("Field", if skip_enum_values { 11 } else { 12 })?;
+        state.serialize_field("name", &self.name)?;
+        state.serialize_field("field_type", &self.field_type)?;
+        state.serialize_field("is_key", &self.is_key)?;
+        state.serialize_field("default_value", &self.default_value)?;
+        state.serialize_field("max_length", &self.max_length)?;
+        state.serialize_field("is_filename", &self.is_filename)?;
+        state.serialize_field("filename_relative_path", &self.filename_relative_path)?;
+        state.serialize_field("is_reference", &self.is_reference)?;
+        state.serialize_field("lookup", &self.lookup)?;
+        state.serialize_field("description", &self.description)?;
+        state.serialize_field("ca_order", &self.ca_order)?;
+        if skip_enum_values { state.skip_field("enum_values")?; }
+        else { state.serialize_field("enum_values", &self.enum_values)?; }
+        state.end()
+    }
+}
+
 /// Display implementation of `FieldType`.
 impl Display for FieldType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -1176,6 +1528,7 @@ impl From<&RawDefinition> for Definition {
     fn from(raw_definition: &RawDefinition) -> Self {
         let mut definition = Self::new(-1);
         definition.fields = raw_definition.fields.iter().map(From::from).collect();
+        definition.provenance = Some(DefinitionProvenance::new(DefinitionSource::AssemblyKit));
         definition
     }
 }
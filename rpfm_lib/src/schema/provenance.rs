@@ -0,0 +1,157 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with the optional metadata about where a `Definition` comes from.
+
+Each `Definition` can have a `DefinitionProvenance`, with the game patches it was used in, who decoded it, and how.
+Patches are the game build or patch names, like `1.9.2` or `1.10.0 (build 1234)`. They're compared by their numbers,
+so `1.10.0` is newer than `1.9.2`.
+
+With it, we can know which version of a table was used in an specific patch, to support older versions of a game.
+!*/
+
+use serde_derive::{Serialize, Deserialize};
+
+use std::cmp::Ordering;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct contains the metadata about where a `Definition` comes from.
+#[derive(Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub struct DefinitionProvenance {
+
+    /// First patch of the game this version of the table was used in.
+    pub first_seen: Option<String>,
+
+    /// Last patch of the game this version of the table was used in. `None` if it's still used, or we don't know.
+    pub last_seen: Option<String>,
+
+    /// How this definition was made.
+    pub source: DefinitionSource,
+
+    /// Who made this definition.
+    pub author: Option<String>,
+}
+
+/// This enum represents how a `Definition` was made.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum DefinitionSource {
+
+    /// We don't know how the definition was made.
+    Unknown,
+
+    /// The definition was imported from the Assembly Kit.
+    AssemblyKit,
+
+    /// The definition was decoded by hand, with the decoder.
+    ManualDecode,
+}
+
+//---------------------------------------------------------------------------//
+//                   Implementation of DefinitionProvenance
+//---------------------------------------------------------------------------//
+
+/// Implementation of `DefinitionProvenance`.
+impl DefinitionProvenance {
+
+    /// This function creates a new `DefinitionProvenance` with the provided source, and nothing else.
+    pub fn new(source: DefinitionSource) -> Self {
+        Self {
+            source,
+            ..Default::default()
+        }
+    }
+
+    /// This function returns if the definition was used in the provided patch.
+    ///
+    /// Definitions without a first patch are never considered used, as we cannot know when they started being used.
+    pub fn is_used_in_patch(&self, patch: &str) -> bool {
+        match &self.first_seen {
+            Some(first_seen) => compare_patches(first_seen, patch) != Ordering::Greater &&
+                !matches!(&self.last_seen, Some(last_seen) if compare_patches(patch, last_seen) == Ordering::Greater),
+            None => false,
+        }
+    }
+
+    /// This function merges the provided `DefinitionProvenance` into this one.
+    ///
+    /// The patch range is widened to cover both of them, using the earliest first patch and the latest last patch.
+    /// The source and author are only taken from the other one if we don't know them.
+    pub fn merge(&mut self, other: &Self) {
+        if let Some(first_seen) = &other.first_seen {
+            if !matches!(&self.first_seen, Some(x) if compare_patches(first_seen, x) != Ordering::Less) {
+                self.first_seen = Some(first_seen.to_owned());
+            }
+        }
+
+        if let Some(last_seen) = &other.last_seen {
+            if !matches!(&self.last_seen, Some(x) if compare_patches(last_seen, x) != Ordering::Greater) {
+                self.last_seen = Some(last_seen.to_owned());
+            }
+        }
+
+        if self.source == DefinitionSource::Unknown {
+            self.source = other.source;
+        }
+
+        if self.author.is_none() {
+            self.author = other.author.clone();
+        }
+    }
+}
+
+/// Default implementation of `DefinitionSource`.
+impl Default for DefinitionSource {
+    fn default() -> Self {
+        Self::Unknown
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                              Utility functions
+//---------------------------------------------------------------------------//
+
+/// This function compares two patch names, comparing the numbers in them as numbers, and the rest as text.
+///
+/// For example, `1.9.2` is older than `1.10.0`, and `1.10.0` is older than `1.10.0 hotfix`.
+pub fn compare_patches(patch_a: &str, patch_b: &str) -> Ordering {
+    let chunks_a = get_patch_chunks(patch_a);
+    let chunks_b = get_patch_chunks(patch_b);
+    for (chunk_a, chunk_b) in chunks_a.iter().zip(chunks_b.iter()) {
+        let ordering = match (chunk_a.parse::<u64>(), chunk_b.parse::<u64>()) {
+            (Ok(number_a), Ok(number_b)) => number_a.cmp(&number_b),
+            _ => chunk_a.cmp(chunk_b),
+        };
+
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    chunks_a.len().cmp(&chunks_b.len())
+}
+
+/// This function splits a patch name in chunks of numbers and chunks of everything else.
+fn get_patch_chunks(patch: &str) -> Vec<String> {
+    let mut chunks: Vec<String> = vec![];
+    let mut last_is_digit = None;
+    for character in patch.trim().chars() {
+        let is_digit = character.is_ascii_digit();
+        match chunks.last_mut() {
+            Some(chunk) if last_is_digit == Some(is_digit) => chunk.push(character),
+            _ => chunks.push(character.to_string()),
+        }
+        last_is_digit = Some(is_digit);
+    }
+    chunks
+}
@@ -0,0 +1,367 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module containing test for the `Schema` module.
+!*/
+
+use std::path::PathBuf;
+
+use crate::assembly_kit::table_definition::{RawDefinition, RawField};
+use crate::packedfile::table::DecodedData;
+
+use super::{Definition, Field, FieldType, Schema, VersionedFile};
+use super::lint::LintIssueKind;
+use super::merge::MergeStrategy;
+use super::migration::VersionedSchema;
+use super::provenance::{DefinitionProvenance, DefinitionSource};
+use super::source::SchemaSource;
+use super::type_definitions::TypeDefinitionFormat;
+
+/// This function returns a definition with the provided version and fields.
+fn get_definition(version: i32, fields: &[(&str, FieldType)]) -> Definition {
+    let mut definition = Definition::new(version);
+    definition.fields = fields.iter().map(|(name, field_type)| Field { name: (*name).to_owned(), field_type: field_type.clone(), ..Default::default() }).collect();
+    definition
+}
+
+#[test]
+fn test_schema_diff() {
+    let mut old = Schema::default();
+    old.add_versioned_file(&VersionedFile::DB("land_units_tables".to_owned(), vec![
+        get_definition(1, &[("key", FieldType::StringU8), ("cost", FieldType::Integer)]),
+    ]));
+    old.add_versioned_file(&VersionedFile::DB("removed_tables".to_owned(), vec![get_definition(0, &[("key", FieldType::StringU8)])]));
+
+    let mut corrected = get_definition(1, &[("key", FieldType::StringU8), ("cost", FieldType::Integer)]);
+    corrected.fields[0].is_key = true;
+
+    let mut new = Schema::default();
+    new.add_versioned_file(&VersionedFile::DB("land_units_tables".to_owned(), vec![
+        get_definition(2, &[("key", FieldType::StringU8), ("cost", FieldType::Float), ("upkeep", FieldType::Integer)]),
+        corrected,
+    ]));
+    new.add_versioned_file(&VersionedFile::DB("new_tables".to_owned(), vec![get_definition(0, &[("key", FieldType::StringU8)])]));
+
+    let diff = Schema::diff(&old, &new);
+    assert_eq!(diff.new_tables, vec!["new_tables".to_owned()]);
+    assert_eq!(diff.removed_tables, vec!["removed_tables".to_owned()]);
+    assert!(diff.removed_versions.is_empty());
+
+    assert_eq!(diff.new_versions.len(), 1);
+    assert_eq!((diff.new_versions[0].version, diff.new_versions[0].compared_version), (2, Some(1)));
+    assert_eq!(diff.new_versions[0].diff.new_fields[0].name, "upkeep");
+    assert_eq!(diff.new_versions[0].diff.changed_fields[0].changes, vec![("Type".to_owned(), "Integer".to_owned(), "Float".to_owned())]);
+
+    assert_eq!(diff.corrections.len(), 1);
+    assert_eq!(diff.corrections[0].diff.changed_fields[0].name, "key");
+
+    let markdown = diff.to_markdown();
+    assert!(markdown.contains("- **New tables decoded**:\n  - *new_tables*."));
+    assert!(markdown.contains("- **Fixed Tables**:\n  - ***land_units_tables***:"));
+    assert!(diff.to_json().unwrap().contains("\"compared_version\": 1"));
+    assert!(Schema::diff(&new, &new).is_empty());
+}
+
+#[test]
+fn test_schema_source() {
+    assert_eq!(SchemaSource::new("url", "").unwrap(), SchemaSource::default());
+    assert!(SchemaSource::new("ftp", "").is_err());
+
+    // The repo itself works as both, a folder source and a git checkout source.
+    let folder = SchemaSource::new("folder", "../schemas").unwrap();
+    let git = SchemaSource::GitCheckout(PathBuf::from(".."));
+
+    let versions = folder.get_versions_file().unwrap();
+    assert_eq!(versions, git.get_versions_file().unwrap());
+    assert!(versions.get().contains_key("schema_wh.ron"));
+    assert!(folder.get_schema("schema_wh.ron").is_ok());
+    assert!(folder.get_schema("missing_schema.ron").is_err());
+}
+
+#[test]
+fn test_schema_merge() {
+    let mut ours = Schema::default();
+    ours.add_versioned_file(&VersionedFile::DB("land_units_tables".to_owned(), vec![
+        get_definition(2, &[("key", FieldType::StringU8), ("cost", FieldType::Integer)]),
+        get_definition(1, &[("key", FieldType::StringU8)]),
+    ]));
+    ours.add_versioned_file(&VersionedFile::DB("our_tables".to_owned(), vec![get_definition(0, &[("key", FieldType::StringU8)])]));
+
+    let mut theirs = Schema::default();
+    theirs.add_versioned_file(&VersionedFile::DB("land_units_tables".to_owned(), vec![
+        get_definition(3, &[("key", FieldType::StringU8), ("cost", FieldType::Integer), ("upkeep", FieldType::Integer)]),
+        get_definition(2, &[("key", FieldType::StringU8), ("cost", FieldType::Float)]),
+        get_definition(1, &[("key", FieldType::StringU8)]),
+    ]));
+    theirs.add_versioned_file(&VersionedFile::DB("their_tables".to_owned(), vec![get_definition(0, &[("key", FieldType::StringU8)])]));
+
+    // Abort leaves the schema untouched.
+    let mut merged = ours.clone();
+    assert!(merged.merge(&theirs, MergeStrategy::Abort).is_err());
+    assert_eq!(merged, ours);
+
+    // Only the version defined differently is a conflict.
+    let conflicts = merged.merge(&theirs, MergeStrategy::Ours).unwrap();
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].versioned_file, "land_units_tables");
+    assert_eq!(conflicts[0].version, 2);
+    assert_eq!(conflicts[0].diff.changed_fields[0].name, "cost");
+
+    assert!(merged.get_ref_versioned_file_db("our_tables").is_ok());
+    assert!(merged.get_ref_versioned_file_db("their_tables").is_ok());
+    let versions = merged.get_ref_versioned_file_db("land_units_tables").unwrap().get_version_list();
+    assert_eq!(versions.iter().map(|x| x.version).collect::<Vec<i32>>(), vec![3, 2, 1]);
+    assert_eq!(versions[1].fields[1].field_type, FieldType::Integer);
+
+    let mut merged = ours.clone();
+    merged.merge(&theirs, MergeStrategy::Theirs).unwrap();
+    let versions = merged.get_ref_versioned_file_db("land_units_tables").unwrap().get_version_list();
+    assert_eq!(versions[1].fields[1].field_type, FieldType::Float);
+
+    // Changes in field order or in properties not shown in changelogs are conflicts too, and are part of their diff.
+    let mut reordered = Schema::default();
+    reordered.add_versioned_file(&VersionedFile::DB("land_units_tables".to_owned(), vec![get_definition(2, &[("cost", FieldType::Integer), ("key", FieldType::StringU8)])]));
+    let conflicts = ours.clone().merge(&reordered, MergeStrategy::Ours).unwrap();
+    assert_eq!(conflicts[0].diff.field_order, Some((vec!["key".to_owned(), "cost".to_owned()], vec!["cost".to_owned(), "key".to_owned()])));
+    assert!(conflicts[0].diff.changed_fields.is_empty());
+
+    let mut max_length = theirs.clone();
+    max_length.get_ref_mut_versioned_file_db("land_units_tables").unwrap().get_ref_mut_version(1).unwrap().fields[0].max_length = 256;
+    let conflicts = ours.clone().merge(&max_length, MergeStrategy::Ours).unwrap();
+    assert_eq!(conflicts.len(), 2);
+    assert_eq!(conflicts[1].diff.changed_fields[0].changes, vec![("Max Length".to_owned(), "0".to_owned(), "256".to_owned())]);
+
+    let mut localised = theirs;
+    localised.get_ref_mut_versioned_file_db("land_units_tables").unwrap().get_ref_mut_version(1).unwrap().localised_fields = vec![Field { name: "name".to_owned(), ..Default::default() }];
+    let conflicts = ours.clone().merge(&localised, MergeStrategy::Ours).unwrap();
+    assert_eq!(conflicts.len(), 2);
+    assert!(conflicts[1].diff.is_empty());
+
+    // Definitions only different in their provenance are not conflicts, and get their patch ranges merged.
+    let mut our_provenance = ours.clone();
+    our_provenance.get_ref_mut_versioned_file_db("land_units_tables").unwrap().get_ref_mut_version(1).unwrap().provenance = Some(DefinitionProvenance { first_seen: Some("1.2".to_owned()), last_seen: Some("1.9.2".to_owned()), ..Default::default() });
+    let mut their_provenance = ours;
+    their_provenance.get_ref_mut_versioned_file_db("land_units_tables").unwrap().get_ref_mut_version(1).unwrap().provenance = Some(DefinitionProvenance { first_seen: Some("1.0".to_owned()), last_seen: Some("1.5".to_owned()), author: Some("Someone".to_owned()), source: DefinitionSource::ManualDecode });
+    assert!(our_provenance.merge(&their_provenance, MergeStrategy::Abort).unwrap().is_empty());
+    let provenance = our_provenance.get_ref_versioned_file_db("land_units_tables").unwrap().get_version(1).unwrap().provenance.clone().unwrap();
+    assert_eq!(provenance, DefinitionProvenance { first_seen: Some("1.0".to_owned()), last_seen: Some("1.9.2".to_owned()), author: Some("Someone".to_owned()), source: DefinitionSource::ManualDecode });
+}
+
+#[test]
+fn test_schema_lint() {
+    let mut units = get_definition(1, &[("key", FieldType::StringU8), ("key", FieldType::Integer), ("cost", FieldType::Integer), ("faction", FieldType::StringU8), ("category", FieldType::StringU8)]);
+    units.fields[2].max_length = 10;
+    units.fields[2].default_value = Some("cheap".to_owned());
+    units.fields[2].ca_order = 1;
+    units.fields[3].ca_order = 1;
+    units.fields[3].is_reference = Some(("factions".to_owned(), "id".to_owned()));
+    units.fields[3].lookup = Some(vec!["onscreen_name".to_owned(), "colour".to_owned()]);
+    units.fields[4].is_reference = Some(("categories".to_owned(), "key".to_owned()));
+    units.localised_fields = vec![Field { name: "cost".to_owned(), ..Default::default() }];
+
+    let mut factions = get_definition(0, &[("key", FieldType::StringU8)]);
+    factions.localised_fields = vec![Field { name: "onscreen_name".to_owned(), ..Default::default() }];
+
+    let mut schema = Schema::default();
+    schema.add_versioned_file(&VersionedFile::DB("units_tables".to_owned(), vec![units]));
+    schema.add_versioned_file(&VersionedFile::DB("factions_tables".to_owned(), vec![factions]));
+
+    let issues = schema.lint();
+    let kinds = issues.iter().map(|x| (x.field_name.as_deref().unwrap(), x.kind.clone())).collect::<Vec<(&str, LintIssueKind)>>();
+    assert_eq!(kinds, vec![
+        ("key", LintIssueKind::DuplicatedField),
+        ("cost", LintIssueKind::MaxLengthOnNonString(10)),
+        ("cost", LintIssueKind::InvalidDefaultValue("cheap".to_owned())),
+        ("faction", LintIssueKind::DuplicatedCaOrder(1)),
+        ("faction", LintIssueKind::ReferencedColumnNotFound("factions".to_owned(), "id".to_owned())),
+        ("faction", LintIssueKind::LookupColumnNotFound("factions".to_owned(), "colour".to_owned())),
+        ("category", LintIssueKind::ReferencedTableNotFound("categories".to_owned())),
+        ("cost", LintIssueKind::LocalisedFieldCollision),
+    ]);
+    assert_eq!(issues[0].to_string(), "units_tables, version 1, field 'key': there is more than one field with this name.");
+}
+
+#[test]
+fn test_schema_type_definitions() {
+    let mut definition = get_definition(5, &[("key", FieldType::StringU8), ("cost", FieldType::Integer), ("faction", FieldType::StringU16)]);
+    definition.fields[0].is_key = true;
+    definition.fields[0].max_length = 32;
+    definition.fields[2].is_reference = Some(("factions".to_owned(), "key".to_owned()));
+    definition.fields[2].description = "Faction of the unit.".to_owned();
+    definition.fields.push(Field { name: "abilities".to_owned(), field_type: FieldType::Sequence(get_definition(-1, &[("ability", FieldType::StringU8)])), ..Default::default() });
+
+    let json_schema: serde_json::Value = serde_json::from_str(&TypeDefinitionFormat::JSONSchema.generate("land_units_tables", &definition).unwrap()).unwrap();
+    assert_eq!(json_schema["properties"]["version"]["const"], 5);
+    let row = &json_schema["properties"]["entries"]["items"];
+    assert_eq!(row["required"].as_array().unwrap().len(), 4);
+    assert_eq!(row["properties"]["abilities"]["items"]["properties"]["ability"]["type"], "string");
+    assert_eq!(row["properties"]["key"]["maxLength"], 32);
+    assert_eq!(row["properties"]["key"]["x-key"], true);
+    assert_eq!(row["properties"]["cost"]["type"], "integer");
+    assert_eq!(row["properties"]["faction"]["x-reference"]["table"], "factions");
+
+    let typescript = TypeDefinitionFormat::TypeScript.generate("land_units_tables", &definition).unwrap();
+    assert!(typescript.contains("export interface LandUnitsTablesV5Row {"));
+    assert!(typescript.contains("    /** Faction of the unit. References factions/key. */\n    \"faction\": string;"));
+    assert!(typescript.contains("    entries: LandUnitsTablesV5Row[];"));
+    assert!(typescript.contains("    \"abilities\": Array<{ \"ability\": string; }>;"));
+
+    let python = TypeDefinitionFormat::Python.generate("land_units_tables", &definition).unwrap();
+    assert!(python.contains("LandUnitsTablesV5Row = TypedDict(\"LandUnitsTablesV5Row\", {"));
+    assert!(python.contains("    \"cost\": int,"));
+    assert!(python.contains("LandUnitsTablesV5RowAbilities = TypedDict(\"LandUnitsTablesV5RowAbilities\", {\n    \"ability\": str,\n})"));
+    assert!(python.contains("    \"abilities\": List[LandUnitsTablesV5RowAbilities],"));
+}
+
+#[test]
+fn test_schema_provenance() {
+    let mut definitions = vec![
+        get_definition(3, &[("key", FieldType::StringU8)]),
+        get_definition(2, &[("key", FieldType::StringU8)]),
+        get_definition(1, &[("key", FieldType::StringU8)]),
+        get_definition(0, &[("key", FieldType::StringU8)]),
+    ];
+    definitions[0].provenance = Some(DefinitionProvenance { first_seen: Some("1.10.0".to_owned()), ..Default::default() });
+    definitions[1].provenance = Some(DefinitionProvenance { first_seen: Some("1.9.2".to_owned()), last_seen: Some("1.9.5".to_owned()), author: Some("Someone".to_owned()), source: DefinitionSource::ManualDecode });
+    definitions[2].provenance = Some(DefinitionProvenance { first_seen: Some("1.0".to_owned()), ..Default::default() });
+    definitions[3].provenance = Some(DefinitionProvenance::new(DefinitionSource::AssemblyKit));
+
+    let mut schema = Schema::default();
+    schema.add_versioned_file(&VersionedFile::DB("land_units_tables".to_owned(), definitions));
+
+    assert_eq!(schema.get_ref_definition_db_for_patch("land_units_tables", "1.9.3").unwrap().version, 2);
+    assert_eq!(schema.get_ref_definition_db_for_patch("land_units_tables", "1.9.6").unwrap().version, 1);
+    assert_eq!(schema.get_ref_definition_db_for_patch("land_units_tables", "1.10.0 hotfix").unwrap().version, 3);
+    assert!(schema.get_ref_definition_db_for_patch("land_units_tables", "0.9").is_err());
+    assert_eq!(schema.get_table_versions_for_patch("1.9.5")["land_units_tables"], 2);
+
+    // Definitions with and without provenance survive the trip to RON.
+    let mut no_provenance = schema.clone();
+    no_provenance.get_ref_mut_versioned_file_db("land_units_tables").unwrap().get_ref_mut_version(1).unwrap().provenance = None;
+    let ron = ron::ser::to_string(&no_provenance).unwrap();
+    assert_eq!(ron::de::from_str::<Schema>(&ron).unwrap(), no_provenance);
+
+    // Empty metadata is left out of the RON schemas, but bincode always gets it, as it doesn't support skipped fields.
+    let no_provenance = get_definition(1, &[("key", FieldType::StringU8)]);
+    let ron = ron::ser::to_string(&no_provenance).unwrap();
+    assert!(!ron.contains("provenance") && !ron.contains("enum_values"));
+    assert_eq!(bincode::deserialize::<Definition>(&bincode::serialize(&no_provenance).unwrap()).unwrap(), no_provenance);
+}
+
+#[test]
+fn test_definition_update_from_raw_definition() {
+    let raw_field = |name: &str, description: &str| RawField {
+        name: name.to_owned(),
+        primary_key: "0".to_owned(),
+        field_description: Some(description.to_owned()),
+        is_filename: Some("1".to_owned()),
+        filename_relative_path: Some("ui/units".to_owned()),
+        default_value: Some("5".to_owned()),
+        ..Default::default()
+    };
+    let raw_definition = RawDefinition {
+        name: Some("land_units.xml".to_owned()),
+        fields: vec![raw_field("key", "The key."), raw_field("Icon", "The icon."), raw_field("only_in_ak", "")],
+    };
+
+    // Current versions get everything, and fields are matched ignoring case.
+    let mut definition = get_definition(1, &[("key", FieldType::StringU8), ("icon", FieldType::StringU8), ("only_in_schema", FieldType::Integer)]);
+    assert_eq!(definition.update_from_raw_definition(&raw_definition), vec!["only_in_schema".to_owned()]);
+    assert_eq!(definition.fields[1].description, "The icon.");
+    assert_eq!(definition.fields[1].default_value, Some("5".to_owned()));
+    assert_eq!(definition.fields[1].filename_relative_path, Some("ui/units".to_owned()));
+    assert!(definition.fields[1].is_filename);
+    assert_eq!(definition.fields[1].ca_order, 1);
+    assert_eq!(definition.fields[2].ca_order, -1);
+
+    // Older versions only get the documentation they're missing.
+    let mut definition = get_definition(0, &[("key", FieldType::StringU8), ("icon", FieldType::StringU8)]);
+    definition.fields[0].description = "Old key.".to_owned();
+    definition.update_docs_from_raw_definition(&raw_definition);
+    assert_eq!(definition.fields[0].description, "Old key.");
+    assert_eq!(definition.fields[1].description, "The icon.");
+    assert_eq!(definition.fields[1].default_value, None);
+    assert!(definition.fields[1].is_filename);
+
+    // Definitions made from the Assembly Kit are marked as such.
+    let definition = Definition::from(&raw_definition);
+    assert_eq!(definition.provenance.unwrap().source, DefinitionSource::AssemblyKit);
+}
+
+#[test]
+fn test_definition_infer_enum_values() {
+    let mut definition = get_definition(0, &[("key", FieldType::StringU8), ("class", FieldType::StringU8), ("caste", FieldType::Integer), ("cost", FieldType::Integer)]);
+    definition.fields[0].is_key = true;
+    let rows = (0..8).map(|x| vec![
+        DecodedData::StringU8(format!("unit_{}", x)),
+        DecodedData::StringU8(if x % 2 == 0 { "inf_mel".to_owned() } else { "cav_shk".to_owned() }),
+        DecodedData::Integer(x % 3),
+        DecodedData::Integer(x * 100),
+    ]).collect::<Vec<Vec<DecodedData>>>();
+
+    // Keys and columns with too many distinct values are not enums.
+    assert_eq!(definition.infer_enum_values(&rows, 4), vec!["class".to_owned(), "caste".to_owned()]);
+    assert_eq!(definition.fields[2].enum_values.as_ref().unwrap().keys().collect::<Vec<&String>>(), vec!["0", "1", "2"]);
+
+    // Value sets we already have are not inferred again.
+    definition.fields[1].enum_values.as_mut().unwrap().insert("inf_mel".to_owned(), "Melee Infantry".to_owned());
+    assert!(definition.infer_enum_values(&rows, 4).is_empty());
+    assert_eq!(definition.fields[1].enum_values.as_ref().unwrap()["inf_mel"], "Melee Infantry");
+
+    // And they're checked by the linter.
+    definition.fields[2].enum_values.as_mut().unwrap().insert("ranged".to_owned(), String::new());
+    definition.fields[2].default_value = Some("5".to_owned());
+    let mut schema = Schema::default();
+    schema.add_versioned_file(&VersionedFile::DB("land_units_tables".to_owned(), vec![definition]));
+    let issues = schema.lint().into_iter().map(|x| x.kind).collect::<Vec<LintIssueKind>>();
+    assert_eq!(issues, vec![LintIssueKind::InvalidEnumValue("ranged".to_owned()), LintIssueKind::DefaultValueNotInEnum("5".to_owned())]);
+
+    // Fields with and without a value set survive the trip to RON.
+    let ron = ron::ser::to_string(&schema).unwrap();
+    assert_eq!(ron::de::from_str::<Schema>(&ron).unwrap(), schema);
+}
+
+#[test]
+fn test_schema_migration() {
+
+    // Every structural version migrates to the same schema, one step at a time.
+    let fixtures = ["../test_files/schema_v0.json", "../test_files/schema_v1.ron", "../test_files/schema_v2.ron"];
+    let expected = Schema::load_from_path(&PathBuf::from(fixtures[2])).unwrap();
+    for (structural_version, fixture) in fixtures.iter().enumerate() {
+        let versioned_schema = VersionedSchema::read(&std::fs::read_to_string(fixture).unwrap()).unwrap();
+        assert_eq!(versioned_schema.get_structural_version() as usize, structural_version);
+
+        let (schema, steps) = versioned_schema.migrate();
+        assert_eq!(schema, expected);
+        assert_eq!(steps.len(), 2 - structural_version);
+        assert!(steps.iter().enumerate().all(|(index, step)| step.from_version as usize == structural_version + index && step.to_version == step.from_version + 1));
+    }
+
+    assert!(VersionedSchema::read("(version: 3, versioned_files: [])").is_err());
+    assert!(VersionedSchema::read("not a schema").is_err());
+
+    // Dry runs report the changes, but don't save anything.
+    let destination = std::env::temp_dir().join("rpfm_test_schema_migration.ron");
+    let _ = std::fs::remove_file(&destination);
+    let report = Schema::migrate_file(&PathBuf::from(fixtures[0]), &destination, true).unwrap();
+    assert_eq!(report.source_version, 0);
+    assert_eq!(report.steps.len(), 2);
+    assert!(report.diff.is_none());
+    assert!(!destination.exists());
+
+    Schema::migrate_file(&PathBuf::from(fixtures[1]), &destination, false).unwrap();
+    assert_eq!(Schema::load_from_path(&destination).unwrap(), expected);
+
+    // Migrating over an existing schema reports the differences with it.
+    let report = Schema::migrate_file(&PathBuf::from(fixtures[0]), &destination, true).unwrap();
+    assert!(report.diff.unwrap().is_empty());
+    std::fs::remove_file(&destination).unwrap();
+}
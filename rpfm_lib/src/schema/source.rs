@@ -0,0 +1,122 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with the code to get the schema files from where they're published.
+
+By default, schemas are downloaded from RPFM's repository, but they can be taken from any of these sources, set in the settings:
+- `url`: a folder served over HTTP(S), like a mirror or a fork of the repository.
+- `folder`: a local folder with the schema files and their `versions.ron` file.
+- `git`: a local git checkout of the repository (or a fork of it). It's updated with `git pull` before reading the `schemas` folder inside it.
+
+Other than RPFM's repository, the sources only need to have the `versions.ron` file and the schemas listed in it.
+!*/
+
+use reqwest::blocking;
+use ron::de::from_str;
+
+use std::fs::read_to_string;
+use std::path::PathBuf;
+use std::process::Command;
+
+use rpfm_error::{ErrorKind, Result};
+
+use crate::SETTINGS;
+use crate::schema::{Schema, SCHEMA_FOLDER, SCHEMA_UPDATE_URL_MASTER, SCHEMA_VERSIONS_FILE};
+use crate::schema::versions::VersionsFile;
+
+/// Types of `SchemaSource` that can be set in the settings.
+pub const SCHEMA_SOURCE_TYPES: [&str; 3] = ["url", "folder", "git"];
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This enum represents the different places we can get the schema files from.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SchemaSource {
+
+    /// URL of a folder with the schema files, served over HTTP(S).
+    Url(String),
+
+    /// Path of a local folder with the schema files.
+    Folder(PathBuf),
+
+    /// Path of a local git checkout with the schema files in its `schemas` folder.
+    GitCheckout(PathBuf),
+}
+
+//---------------------------------------------------------------------------//
+//                       Implementation of SchemaSource
+//---------------------------------------------------------------------------//
+
+/// Implementation of `SchemaSource`.
+impl SchemaSource {
+
+    /// This function returns the `SchemaSource` set in the settings.
+    pub fn from_settings() -> Result<Self> {
+        let settings = SETTINGS.read().unwrap();
+        let source_type = &settings.settings_string["schema_source_type"];
+        let location = &settings.settings_string["schema_source_location"];
+        Self::new(source_type, location)
+    }
+
+    /// This function creates a `SchemaSource` from its type (`url`, `folder` or `git`) and its location.
+    ///
+    /// An empty location on an `url` source means RPFM's repository.
+    pub fn new(source_type: &str, location: &str) -> Result<Self> {
+        match source_type {
+            "url" if location.is_empty() => Ok(Self::default()),
+            "url" => Ok(Self::Url(location.to_owned())),
+            "folder" => Ok(Self::Folder(PathBuf::from(location))),
+            "git" => Ok(Self::GitCheckout(PathBuf::from(location))),
+            _ => Err(ErrorKind::SchemaSourceInvalid(source_type.to_owned()).into()),
+        }
+    }
+
+    /// This function brings the source up to date, if it can be updated. Only git checkouts can.
+    pub fn refresh(&self) -> Result<()> {
+        if let Self::GitCheckout(path) = self {
+            let output = Command::new("git").arg("-C").arg(path).args(["pull", "--ff-only"]).output()
+                .map_err(|error| ErrorKind::SchemaSourceGitPullFailed(error.to_string()))?;
+
+            if !output.status.success() {
+                return Err(ErrorKind::SchemaSourceGitPullFailed(String::from_utf8_lossy(&output.stderr).to_string()).into());
+            }
+        }
+        Ok(())
+    }
+
+    /// This function returns the contents of the provided file of the source, as text.
+    pub fn get_file(&self, file_name: &str) -> Result<String> {
+        match self {
+            Self::Url(url) => Ok(blocking::get(&format!("{}/{}", url.trim_end_matches('/'), file_name))?.error_for_status()?.text()?),
+            Self::Folder(path) => Ok(read_to_string(path.join(file_name))?),
+            Self::GitCheckout(path) => Ok(read_to_string(path.join(SCHEMA_FOLDER).join(file_name))?),
+        }
+    }
+
+    /// This function returns the `VersionsFile` of the source.
+    pub fn get_versions_file(&self) -> Result<VersionsFile> {
+        from_str(&self.get_file(SCHEMA_VERSIONS_FILE)?).map_err(From::from)
+    }
+
+    /// This function returns the provided schema of the source.
+    pub fn get_schema(&self, schema_file: &str) -> Result<Schema> {
+        from_str(&self.get_file(schema_file)?).map_err(From::from)
+    }
+}
+
+/// Default implementation of `SchemaSource`. The master branch of RPFM's repository.
+impl Default for SchemaSource {
+    fn default() -> Self {
+        Self::Url(SCHEMA_UPDATE_URL_MASTER.to_owned())
+    }
+}
@@ -0,0 +1,306 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with the code to generate type definitions of the tables exported by RPFM, for other tools.
+
+Tables exported to JSON (see `TableFormat::JSON`) are objects with the name of the table, its version, and a list of rows keyed by field name.
+This module generates, for each version of each table, one of these:
+- A JSON Schema (draft-07) document, to validate the exported tables.
+- A TypeScript declaration file, with interfaces for the exported table and its rows.
+- A Python module, with `TypedDict`s for the exported table and its rows.
+
+Fields are typed the same way RPFM exports them: booleans, numbers, strings, and sequences as lists of rows keyed by field name.
+Keys, references and descriptions are kept as documentation.
+!*/
+
+use serde_json::{json, Map, Value};
+
+use rpfm_error::Result;
+
+use super::{Definition, Field, FieldType};
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This enum represents the formats we can generate type definitions in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TypeDefinitionFormat {
+
+    /// JSON Schema document, draft-07.
+    JSONSchema,
+
+    /// TypeScript declaration file.
+    TypeScript,
+
+    /// Python module with `TypedDict`s. Requires Python 3.8 or newer.
+    Python,
+}
+
+//---------------------------------------------------------------------------//
+//                   Implementation of TypeDefinitionFormat
+//---------------------------------------------------------------------------//
+
+/// Implementation of `TypeDefinitionFormat`.
+impl TypeDefinitionFormat {
+
+    /// This function returns the format with the provided name (`json-schema`, `typescript` or `python`), if any.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "json-schema" => Some(Self::JSONSchema),
+            "typescript" => Some(Self::TypeScript),
+            "python" => Some(Self::Python),
+            _ => None,
+        }
+    }
+
+    /// This function returns the extension of the files of this format.
+    pub fn get_file_extension(self) -> &'static str {
+        match self {
+            Self::JSONSchema => "schema.json",
+            Self::TypeScript => "d.ts",
+            Self::Python => "py",
+        }
+    }
+
+    /// This function generates the type definitions of the provided version of a table, with the name it has when exported.
+    pub fn generate(self, table_name: &str, definition: &Definition) -> Result<String> {
+        match self {
+            Self::JSONSchema => serde_json::to_string_pretty(&Self::get_json_schema(table_name, definition)).map_err(From::from),
+            Self::TypeScript => Ok(Self::get_typescript(table_name, definition)),
+            Self::Python => Ok(Self::get_python(table_name, definition)),
+        }
+    }
+
+    /// This function returns the JSON Schema of an exported table.
+    ///
+    /// Keys and references are added to the fields as `x-key` and `x-reference`, as JSON Schema has no keywords for them.
+    fn get_json_schema(table_name: &str, definition: &Definition) -> Value {
+        json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "title": format!("{}, version {}", table_name, definition.version),
+            "type": "object",
+            "properties": {
+                "table_name": { "const": table_name },
+                "version": { "const": definition.version },
+                "entries": {
+                    "type": "array",
+                    "items": Self::get_json_schema_row(definition),
+                },
+            },
+            "required": ["table_name", "version", "entries"],
+        })
+    }
+
+    /// This function returns the JSON Schema of a row of a table or sequence with the provided definition.
+    fn get_json_schema_row(definition: &Definition) -> Value {
+        let mut properties = Map::new();
+        for field in &definition.fields {
+            let mut property = Self::get_json_schema_type(&field.field_type, field.max_length);
+            if let Value::Object(property) = &mut property {
+                if !field.description.is_empty() {
+                    property.insert("description".to_owned(), Value::from(field.description.to_owned()));
+                }
+                if field.is_key {
+                    property.insert("x-key".to_owned(), Value::from(true));
+                }
+                if let Some((ref_table, ref_column)) = &field.is_reference {
+                    property.insert("x-reference".to_owned(), json!({ "table": ref_table, "column": ref_column }));
+                }
+            }
+            properties.insert(field.name.to_owned(), property);
+        }
+
+        json!({
+            "type": "object",
+            "properties": properties,
+            "required": definition.fields.iter().map(|x| x.name.to_owned()).collect::<Vec<String>>(),
+            "additionalProperties": false,
+        })
+    }
+
+    /// This function returns the JSON Schema of a field type.
+    fn get_json_schema_type(field_type: &FieldType, max_length: i32) -> Value {
+        match field_type {
+            FieldType::Boolean => json!({ "type": "boolean" }),
+            FieldType::Float => json!({ "type": "number" }),
+            FieldType::Integer => json!({ "type": "integer", "minimum": i32::MIN, "maximum": i32::MAX }),
+            FieldType::LongInteger => json!({ "type": "integer" }),
+            FieldType::StringU8 |
+            FieldType::StringU16 |
+            FieldType::OptionalStringU8 |
+            FieldType::OptionalStringU16 => if max_length > 0 { json!({ "type": "string", "maxLength": max_length }) } else { json!({ "type": "string" }) },
+            FieldType::Sequence(definition) => json!({
+                "type": "array",
+                "items": Self::get_json_schema_row(definition),
+            }),
+        }
+    }
+
+    /// This function returns the TypeScript declarations of an exported table.
+    fn get_typescript(table_name: &str, definition: &Definition) -> String {
+        let type_name = get_type_name(table_name, definition.version);
+        let mut lines = vec![
+            format!("// Type definitions of {}, version {}, as exported to JSON by RPFM.", table_name, definition.version),
+            String::new(),
+            format!("/** A row of {}, version {}. */", table_name, definition.version),
+            format!("export interface {}Row {{", type_name),
+        ];
+
+        for field in &definition.fields {
+            let docs = get_field_docs(field);
+            if !docs.is_empty() {
+                lines.push(format!("    /** {} */", docs));
+            }
+            lines.push(format!("    {}: {};", serde_json::to_string(&field.name).unwrap_or_default(), Self::get_typescript_type(&field.field_type)));
+        }
+
+        lines.push("}".to_owned());
+        lines.push(String::new());
+        lines.push(format!("/** {}, version {}. */", table_name, definition.version));
+        lines.push(format!("export interface {} {{", type_name));
+        lines.push(format!("    table_name: {};", serde_json::to_string(table_name).unwrap_or_default()));
+        lines.push(format!("    version: {};", definition.version));
+        lines.push(format!("    entries: {}Row[];", type_name));
+        lines.push("}".to_owned());
+        lines.push(String::new());
+        lines.join("\n")
+    }
+
+    /// This function returns the TypeScript type of a field type.
+    fn get_typescript_type(field_type: &FieldType) -> String {
+        match field_type {
+            FieldType::Boolean => "boolean".to_owned(),
+            FieldType::Float | FieldType::Integer | FieldType::LongInteger => "number".to_owned(),
+            FieldType::StringU8 |
+            FieldType::StringU16 |
+            FieldType::OptionalStringU8 |
+            FieldType::OptionalStringU16 => "string".to_owned(),
+            FieldType::Sequence(definition) => format!("Array<{{ {} }}>", definition.fields.iter()
+                .map(|x| format!("{}: {};", serde_json::to_string(&x.name).unwrap_or_default(), Self::get_typescript_type(&x.field_type)))
+                .collect::<Vec<String>>()
+                .join(" ")),
+        }
+    }
+
+    /// This function returns the Python module with the `TypedDict`s of an exported table.
+    ///
+    /// The functional syntax of `TypedDict` is used, as field names may not be valid Python identifiers.
+    fn get_python(table_name: &str, definition: &Definition) -> String {
+        let type_name = get_type_name(table_name, definition.version);
+        let mut lines = vec![
+            format!("# Type definitions of {}, version {}, as exported to JSON by RPFM.", table_name, definition.version),
+            String::new(),
+            "from typing import List, Literal, TypedDict".to_owned(),
+            String::new(),
+        ];
+
+        Self::add_python_row(&mut lines, &format!("{}Row", type_name), &format!("A row of {}, version {}.", table_name, definition.version), definition);
+        lines.push(format!("# {}, version {}.", table_name, definition.version));
+        lines.push(format!("{} = TypedDict(\"{}\", {{", type_name, type_name));
+        lines.push(format!("    \"table_name\": Literal[{}],", serde_json::to_string(table_name).unwrap_or_default()));
+        lines.push(format!("    \"version\": Literal[{}],", definition.version));
+        lines.push(format!("    \"entries\": List[{}Row],", type_name));
+        lines.push("})".to_owned());
+        lines.push(String::new());
+        lines.join("\n")
+    }
+
+    /// This function adds the `TypedDict` of a row with the provided definition to the provided lines.
+    ///
+    /// As Python has no inline `TypedDict`s, the rows of its sequences are added before it, named after the row and the field.
+    fn add_python_row(lines: &mut Vec<String>, row_type_name: &str, row_docs: &str, definition: &Definition) {
+        let mut row_lines = vec![
+            format!("# {}", row_docs),
+            format!("{} = TypedDict(\"{}\", {{", row_type_name, row_type_name),
+        ];
+
+        for field in &definition.fields {
+            let docs = get_field_docs(field);
+            if !docs.is_empty() {
+                row_lines.push(format!("    # {}", docs));
+            }
+
+            let field_type = match field.field_type {
+                FieldType::Sequence(ref sequence_definition) => {
+                    let sequence_type_name = format!("{}{}", row_type_name, get_pascal_case(&field.name));
+                    Self::add_python_row(lines, &sequence_type_name, &format!("A row of the field {} of {}.", field.name, row_type_name), sequence_definition);
+                    format!("List[{}]", sequence_type_name)
+                }
+                _ => Self::get_python_type(&field.field_type),
+            };
+            row_lines.push(format!("    {}: {},", serde_json::to_string(&field.name).unwrap_or_default(), field_type));
+        }
+
+        row_lines.push("})".to_owned());
+        row_lines.push(String::new());
+        lines.append(&mut row_lines);
+    }
+
+    /// This function returns the Python type of a field type, except for sequences, which need their own `TypedDict`.
+    fn get_python_type(field_type: &FieldType) -> String {
+        match field_type {
+            FieldType::Boolean => "bool".to_owned(),
+            FieldType::Float => "float".to_owned(),
+            FieldType::Integer | FieldType::LongInteger => "int".to_owned(),
+            FieldType::StringU8 |
+            FieldType::StringU16 |
+            FieldType::OptionalStringU8 |
+            FieldType::OptionalStringU16 => "str".to_owned(),
+            FieldType::Sequence(_) => "List[dict]".to_owned(),
+        }
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                              Utility functions
+//---------------------------------------------------------------------------//
+
+/// This function returns the name of the type of a version of a table, in PascalCase, like `LandUnitsTablesV5`.
+fn get_type_name(table_name: &str, version: i32) -> String {
+    let mut type_name = get_pascal_case(table_name);
+
+    // Identifiers cannot start with a number.
+    if type_name.starts_with(|x: char| x.is_ascii_digit()) {
+        type_name.insert(0, 'T');
+    }
+
+    format!("{}V{}", type_name, version)
+}
+
+/// This function returns the provided name in PascalCase, without any character that's not alphanumeric.
+fn get_pascal_case(name: &str) -> String {
+    name.split(|x: char| !x.is_ascii_alphanumeric())
+        .filter(|x| !x.is_empty())
+        .map(|x| {
+            let mut chars = x.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect::<String>()
+}
+
+/// This function returns the documentation of a field: its description, and if it's a key or a reference.
+fn get_field_docs(field: &Field) -> String {
+    let mut docs = vec![];
+    if !field.description.is_empty() {
+        docs.push(field.description.replace("*/", "* /").replace('\n', " "));
+    }
+    if field.is_key {
+        docs.push("Key field.".to_owned());
+    }
+    if let Some((ref_table, ref_column)) = &field.is_reference {
+        docs.push(format!("References {}/{}.", ref_table, ref_column));
+    }
+    docs.join(" ")
+}
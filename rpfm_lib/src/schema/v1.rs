@@ -12,7 +12,7 @@
 Module with the code to support migration operations from Schema V1 onwards.
 
 Schema V1 was the one used by RPFM during the development of 2.0. Was written in Ron, Unversioned.
-This module contains only the code needed for reading/writing Schemas V1 and for migrating them to Schemas V2.
+This module contains only the code needed for reading Schemas V1 and for migrating them to Schemas V2. The migration itself is done in the `migration` module.
 
 In case it's not clear enough, this is for supporting legacy schemas, not intended to be used externally in ANY other way.
 
@@ -55,22 +55,9 @@ The basic structure of an V1 `Schema` is:
 
 !*/
 
-use rayon::prelude::*;
-use ron::de::from_reader;
-use ron::ser::{to_string_pretty, PrettyConfig};
 use serde_derive::{Serialize, Deserialize};
 
-use std::cmp::Ordering;
-use std::collections::BTreeMap;
-use std::fs::File;
-use std::io::{BufReader, Write};
-
-use rpfm_error::Result;
-
-use crate::config::get_config_path;
 use crate::schema::*;
-use crate::schema::SCHEMA_FOLDER;
-use crate::SUPPORTED_GAMES;
 
 //---------------------------------------------------------------------------//
 //                              Enum & Structs
@@ -161,112 +148,6 @@ pub enum FieldTypeV1 {
 //                       Enum & Structs Implementations
 //---------------------------------------------------------------------------//
 
-impl SchemaV1 {
-
-    /// This function adds a new `VersionedFile` to the schema. This checks if the provided `VersionedFile`
-    /// already exists, and replace it if neccesary.
-    pub fn add_versioned_file(&mut self, versioned_file: &VersionedFileV1) {
-        match self.0.iter().position(|x| x.conflict(versioned_file)) {
-            Some(position) => { self.0.splice(position..=position, [versioned_file.clone()].iter().cloned()); },
-            None => self.0.push(versioned_file.clone()),
-        }
-    }
-
-    /// This function loads a `Schema` to memory from a file in the `schemas/` folder.
-    pub fn load(schema_file: &str) -> Result<Self> {
-        let mut file_path = get_config_path()?.join(SCHEMA_FOLDER);
-        file_path.push(schema_file);
-
-        let file = BufReader::new(File::open(&file_path)?);
-        from_reader(file).map_err(From::from)
-    }
-
-    /// This function saves a `Schema` from memory to a file in the `schemas/` folder.
-    pub fn save(&mut self, schema_file: &str) -> Result<()> {
-        let mut file_path = get_config_path()?.join(SCHEMA_FOLDER);
-        file_path.push(schema_file);
-
-        let mut file = File::create(&file_path)?;
-        let config = PrettyConfig::default();
-
-        self.sort();
-        file.write_all(to_string_pretty(&self, config)?.as_bytes())?;
-        Ok(())
-    }
-
-    /// This function sorts a `Schema` alphabetically, so the schema diffs are more or less clean.
-    pub fn sort(&mut self) {
-        self.0.sort_by(|a, b| {
-            match a {
-                VersionedFileV1::DB(table_name_a, _) => {
-                    match b {
-                        VersionedFileV1::DB(table_name_b, _) => table_name_a.cmp(&table_name_b),
-                        _ => Ordering::Less,
-                    }
-                }
-                VersionedFileV1::DepManager(_) => {
-                    match b {
-                        VersionedFileV1::DB(_,_) => Ordering::Greater,
-                        VersionedFileV1::DepManager(_) => Ordering::Equal,
-                        VersionedFileV1::Loc(_) => Ordering::Less,
-                    }
-                }
-                VersionedFileV1::Loc(_) => {
-                    match b {
-                        VersionedFileV1::Loc(_) => Ordering::Equal,
-                        _ => Ordering::Greater,
-                    }
-                }
-            }
-        });
-    }
-
-    pub fn update() {
-        println!("Importing schemas from V1 to V2");
-        let mut legacy_schemas = SUPPORTED_GAMES.par_iter().map(|(x, y)| ((*x).to_owned(), Self::load(&y.schema))).filter_map(|(x, y)| if let Ok(y) = y { Some((x, From::from(&y))) } else { None }).collect::<BTreeMap<String, Schema>>();
-        println!("Amount of SchemasV1: {:?}", legacy_schemas.len());
-        legacy_schemas.par_iter_mut().for_each(|(game, legacy_schema)| {
-            if let Some(file_name) = SUPPORTED_GAMES.iter().filter_map(|(x, y)| if x == game { Some(y.schema.to_owned()) } else { None }).find(|_| true) {
-                if legacy_schema.save(&file_name).is_ok() {
-                    println!("SchemaV1 for game {} updated to Schema.", game);
-                }
-            }
-        });
-    }
-}
-
-impl VersionedFileV1 {
-
-    /// This function returns true if both `VersionFile` are conflicting (they're the same, but their definitions may be different).
-    pub fn conflict(&self, secondary: &Self) -> bool {
-        match &self {
-            Self::DB(table_name, _) => match &secondary {
-                Self::DB(secondary_table_name, _) => table_name == secondary_table_name,
-                Self::DepManager( _) => false,
-                Self::Loc( _) => false,
-            },
-            Self::Loc(_) => secondary.is_loc(),
-            Self::DepManager( _) => secondary.is_dep_manager(),
-        }
-    }
-
-    /// This function returns true if the provided `VersionedFile` is a Dependency Manager Definition. Otherwise, it returns false.
-    pub fn is_dep_manager(&self) -> bool {
-        match *self {
-            Self::DepManager(_) => true,
-            _ => false,
-        }
-    }
-
-    /// This function returns true if the provided `VersionedFile` is a Loc Definition. Otherwise, it returns false.
-    pub fn is_loc(&self) -> bool {
-        match *self {
-            Self::Loc(_) => true,
-            _ => false,
-        }
-    }
-}
-
 impl DefinitionV1 {
     pub fn new(version: i32) -> DefinitionV1 {
         DefinitionV1 {
@@ -0,0 +1,58 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with the code to infer the value domains of the fields of a table.
+
+Many columns that are not references only take a fixed set of values, like the `class` of a unit. These columns can have their values,
+with an optional label for each one, in the `enum_values` of their `Field`. They're used like reference data: to validate the columns, and for the combos of the table view.
+
+The Assembly Kit doesn't have these sets, so they're either written by hand, or inferred from the vanilla data. A column is inferred as an enum if:
+- It's an integer or string column, and it's not a key, a reference or a filename.
+- It has between 2 and the max allowed amount of distinct values.
+- Each value is used, on average, at least twice.
+
+Inferred values have no labels.
+!*/
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::packedfile::table::DecodedData;
+use super::{Field, FieldType};
+
+/// Default max amount of distinct values a column can have to be inferred as an enum.
+pub const DEFAULT_MAX_ENUM_VALUES: usize = 16;
+
+//---------------------------------------------------------------------------//
+//                              Utility functions
+//---------------------------------------------------------------------------//
+
+/// This function returns if the provided field can have its value set inferred.
+///
+/// Fields that already have a value set are not inferred again, so hand-written labels are not lost.
+pub fn can_infer_enum_values(field: &Field) -> bool {
+    field.enum_values.is_none() &&
+        !field.is_key &&
+        !field.is_filename &&
+        field.is_reference.is_none() &&
+        matches!(field.field_type, FieldType::Integer | FieldType::LongInteger | FieldType::StringU8 | FieldType::StringU16 | FieldType::OptionalStringU8 | FieldType::OptionalStringU16)
+}
+
+/// This function returns the value set of the provided column of a table, if the column looks like an enum.
+pub fn infer_enum_values(rows: &[Vec<DecodedData>], column: usize, max_values: usize) -> Option<BTreeMap<String, String>> {
+    let values = rows.iter()
+        .filter_map(|row| row.get(column))
+        .map(|data| data.data_to_string())
+        .collect::<BTreeSet<String>>();
+
+    if values.len() >= 2 && values.len() <= max_values && rows.len() >= values.len() * 2 {
+        Some(values.into_iter().map(|value| (value, String::new())).collect())
+    } else { None }
+}
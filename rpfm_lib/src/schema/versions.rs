@@ -14,8 +14,7 @@ Module with all the code to interact with the Schema Versions File.
 This module contains all the code related with the Schema Versions File, used to keep track of Schema updates.
 !*/
 
-use reqwest::blocking;
-use ron::de::{from_str, from_reader};
+use ron::de::from_reader;
 use ron::ser::{to_string_pretty, PrettyConfig};
 use serde_derive::{Serialize, Deserialize};
 
@@ -27,6 +26,7 @@ use rpfm_error::Result;
 
 use crate::config::get_config_path;
 use crate::schema::*;
+use crate::schema::source::SchemaSource;
 
 //---------------------------------------------------------------------------//
 //                              Enum & Structs
@@ -83,20 +83,20 @@ impl VersionsFile {
     /// This function match your local schemas against the remote ones, and downloads any updated ones.
     ///
     /// If no local `VersionsFile` is found, it downloads it from the repo, along with all the schema files.
+    ///
+    /// The remote schemas are taken from the schema source set in the settings.
     pub fn check_update() -> Result<APIResponseSchema> {
+        Self::check_update_from(&SchemaSource::from_settings()?)
+    }
+
+    /// This function match your local schemas against the ones in the provided source, to check if there is an update or not.
+    pub fn check_update_from(source: &SchemaSource) -> Result<APIResponseSchema> {
+        if source.refresh().is_err() { return Ok(APIResponseSchema::Error); }
 
         // If there is a local schema, match it against the remote one, to check if there is an update or not.
-        let versions_file_url = format!("{}{}", SCHEMA_UPDATE_URL_MASTER, SCHEMA_VERSIONS_FILE);
         match Self::load() {
             Ok(local) => {
-                let remote: Self = if let Ok(string) = blocking::get(&versions_file_url) {
-                    if let Ok(string) = string.text() {
-                        if let Ok(remote) = from_str::<Self>(&string) {
-                            remote
-                        } else { return Ok(APIResponseSchema::Error); }
-                    } else { return Ok(APIResponseSchema::Error); }
-                } else { return Ok(APIResponseSchema::Error); };
-
+                let remote = if let Ok(remote) = source.get_versions_file() { remote } else { return Ok(APIResponseSchema::Error); };
                 if local == remote { return Ok(APIResponseSchema::SuccessNoUpdate); }
 
                 for (remote_file_name, remote_version) in &remote.0 {
@@ -108,12 +108,8 @@ impl VersionsFile {
 
             // If there is no local `VersionsFile`, check if we can get them from the repo.
             Err(_) => {
-                if let Ok(string) = blocking::get(&versions_file_url) {
-                    if let Ok(string) = string.text() {
-                        if from_str::<Self>(&string).is_ok() {
-                            return Ok(APIResponseSchema::SuccessNoLocalUpdate);
-                        }
-                    }
+                if source.get_versions_file().is_ok() {
+                    return Ok(APIResponseSchema::SuccessNoLocalUpdate);
                 }
                 return Ok(APIResponseSchema::Error);
             }
@@ -126,30 +122,32 @@ impl VersionsFile {
     /// This function match your local schemas against the remote ones, and downloads any updated ones.
     ///
     /// If no local `VersionsFile` is found, it downloads it from the repo, along with all the schema files.
+    ///
+    /// The remote schemas are taken from the schema source set in the settings.
     pub fn update() -> Result<()> {
+        Self::update_from(&SchemaSource::from_settings()?)
+    }
+
+    /// This function match your local schemas against the ones in the provided source, and copies any updated ones to the config folder.
+    pub fn update_from(source: &SchemaSource) -> Result<()> {
+        source.refresh()?;
 
         // If there is a local schema, match it against the remote one, download the different schemas,
         // then update our local schema with the remote one's data.
-        let versions_file_url = format!("{}{}", SCHEMA_UPDATE_URL_MASTER, SCHEMA_VERSIONS_FILE);
+        let remote = source.get_versions_file()?;
         match Self::load() {
             Ok(local) => {
-                let remote: Self = from_str(&blocking::get(&versions_file_url)?.text()?)?;
                 for (remote_file_name, remote_version) in &remote.0 {
-                    let schema_url = format!("{}{}", SCHEMA_UPDATE_URL_MASTER, remote_file_name);
                     match local.0.get(remote_file_name) {
                         Some(local_version) => {
 
                             // If it's an update over our own schema, we download it and overwrite the current schema.
                             // NOTE: Github's API has a limit of 1MB per file, so we take it directly from raw.githubusercontent.com instead.
                             if remote_version > local_version {
-                                let mut schema: Schema = from_str(&blocking::get(&schema_url)?.text()?)?;
-                                schema.save(remote_file_name)?;
+                                source.get_schema(remote_file_name)?.save(remote_file_name)?;
                             }
                         }
-                        None => {
-                            let mut schema: Schema = from_str(&blocking::get(&schema_url)?.text()?)?;
-                            schema.save(remote_file_name)?;
-                        }
+                        None => source.get_schema(remote_file_name)?.save(remote_file_name)?,
                     }
                 }
 
@@ -158,10 +156,8 @@ impl VersionsFile {
 
             // If there is no local `VersionsFile`, download all the schemas, then save the new local `VersionsFile`.
             Err(_) => {
-                let remote: Self = from_str(&blocking::get(&versions_file_url)?.text()?)?;
                 for file_name in remote.0.keys() {
-                    let mut schema: Schema = from_str(&blocking::get(&format!("{}{}", SCHEMA_UPDATE_URL_MASTER, file_name))?.text()?)?;
-                    schema.save(file_name)?;
+                    source.get_schema(file_name)?.save(file_name)?;
                 }
                 remote.save()
             }
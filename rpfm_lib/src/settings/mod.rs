@@ -63,6 +63,10 @@ impl Settings {
         settings_string.insert("font_name".to_owned(), "".to_owned());
         settings_string.insert("font_size".to_owned(), "".to_owned());
 
+        // Schema Source Settings. An empty location means RPFM's repository.
+        settings_string.insert("schema_source_type".to_owned(), "url".to_owned());
+        settings_string.insert("schema_source_location".to_owned(), "".to_owned());
+
         // UI Settings.
         settings_bool.insert("adjust_columns_to_content".to_owned(), true);
         settings_bool.insert("extend_last_column_on_tables".to_owned(), true);
@@ -80,6 +84,7 @@ impl Settings {
         settings_bool.insert("use_lazy_loading".to_owned(), true);
         settings_bool.insert("optimize_not_renamed_packedfiles".to_owned(), false);
         settings_bool.insert("disable_uuid_regeneration_on_db_tables".to_owned(), false);
+        settings_bool.insert("generate_loc_entries_on_db_edit".to_owned(), false);
 
         // Debug Settings.
         settings_bool.insert("check_for_missing_table_definitions".to_owned(), false);
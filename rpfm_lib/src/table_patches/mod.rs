@@ -0,0 +1,300 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with all the code to describe changes to vanilla DB tables in a declarative way, and apply them.
+
+Instead of copying entire vanilla tables into a PackFile and editing them, a `TablePatchSet` describes the edits
+we want to do to them (set values in rows matching some filters, add rows, remove rows), and it's applied against
+the tables of the dependency database to generate the minimal DB tables needed for the PackFile.
+As the patches don't contain the vanilla data, they can be re-applied after every game patch to update the tables.
+
+The patches are stored in RON files, like this:
+
+```ron
+(
+    name: "my_mod",
+    patches: [
+        (
+            table_name: "land_units_tables",
+            operations: [
+                Set(filters: {"key": "wh_main_emp_*"}, values: {"morale": "60"}),
+                Add(rows: [{"key": "my_mod_unit", "morale": "50"}]),
+                Remove(filters: {"key": "wh_main_emp_inf_swordsmen"}),
+            ],
+        ),
+    ],
+)
+```
+!*/
+
+use regex::Regex;
+use ron::de::from_reader;
+use ron::ser::{to_string_pretty, PrettyConfig};
+use serde_derive::{Serialize, Deserialize};
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+
+use rpfm_error::{ErrorKind, Result};
+
+use crate::DEPENDENCY_DATABASE;
+use crate::packedfile::{DecodedPackedFile, PackedFileType};
+use crate::packedfile::table::{DecodedData, Table};
+use crate::packedfile::table::db::DB;
+use crate::packfile::PackFile;
+use crate::packfile::packedfile::PackedFile;
+use crate::schema::{Definition, Schema};
+
+#[cfg(test)]
+mod table_patches_test;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct represents a set of patches for DB tables, usually loaded from a RON file.
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct TablePatchSet {
+
+    /// Name of the set. It's used as the name of the tables generated from it.
+    pub name: String,
+
+    /// List of patches of the set, one per table.
+    pub patches: Vec<TablePatch>,
+}
+
+/// This struct represents the changes to do to a specific DB table.
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct TablePatch {
+
+    /// Name of the table to patch, like `land_units_tables`.
+    pub table_name: String,
+
+    /// List of operations to do to the table, in order.
+    pub operations: Vec<TablePatchOperation>,
+}
+
+/// This enum represents the operations a `TablePatch` can do to a table.
+///
+/// Filters match a row if the values of all their fields match their patterns. Patterns can use `*` to match any
+/// amount of characters, and `?` to match a single character. Values are written as they're shown in the table view.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum TablePatchOperation {
+
+    /// Set the provided values in every row matching the filters.
+    Set {
+        filters: BTreeMap<String, String>,
+        values: BTreeMap<String, String>,
+    },
+
+    /// Add the provided rows to the table. Fields not provided get their default value.
+    ///
+    /// If a row with the same key already exists, it's replaced.
+    Add {
+        rows: Vec<BTreeMap<String, String>>,
+    },
+
+    /// Remove the rows matching the filters.
+    ///
+    /// As a mod cannot delete vanilla rows, this only drops from the generated table rows added or edited by previous operations.
+    /// If all the rows it matches are unedited vanilla rows, applying it fails, as it would do nothing.
+    Remove {
+        filters: BTreeMap<String, String>,
+    },
+}
+
+//---------------------------------------------------------------------------//
+//                       Implementation of TablePatchSet
+//---------------------------------------------------------------------------//
+
+/// Implementation of `TablePatchSet`.
+impl TablePatchSet {
+
+    /// This function loads a `TablePatchSet` from a RON file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let file = BufReader::new(File::open(path)?);
+        from_reader(file).map_err(From::from)
+    }
+
+    /// This function saves a `TablePatchSet` to a RON file.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        let config = PrettyConfig::default();
+        file.write_all(to_string_pretty(&self, config)?.as_bytes())?;
+        Ok(())
+    }
+
+    /// This function returns the path the table generated for the provided table name will have in the PackFile.
+    pub fn get_table_path(&self, table_name: &str) -> Vec<String> {
+        vec!["db".to_owned(), table_name.to_owned(), self.name.to_owned()]
+    }
+
+    /// This function applies all the patches of this set against the provided vanilla tables, returning the generated tables.
+    ///
+    /// The generated tables only contain the rows that differ from the vanilla ones, so they may be empty.
+    pub fn apply(&self, schema: &Schema, vanilla_tables: &[&DB]) -> Result<Vec<DB>> {
+        self.patches.iter().map(|patch| patch.apply(schema, vanilla_tables)).collect()
+    }
+
+    /// This function applies all the patches of this set against the tables of the dependency database, and adds the generated tables to the provided PackFile.
+    ///
+    /// Tables generated by previous runs are replaced, or removed if they end up empty. It returns the paths of the added and the removed tables.
+    pub fn apply_to_packfile(&self, pack_file: &mut PackFile, schema: &Schema) -> Result<(Vec<Vec<String>>, Vec<Vec<String>>)> {
+        let mut dependencies = DEPENDENCY_DATABASE.lock().unwrap();
+        let vanilla_tables = dependencies.iter_mut()
+            .filter(|x| x.get_packed_file_type_by_path() == PackedFileType::DB && self.patches.iter().any(|patch| patch.table_name == x.get_path()[1]))
+            .filter_map(|x| if let Ok(DecodedPackedFile::DB(table)) = x.decode_return_ref_no_locks(schema) { Some(table) } else { None })
+            .collect::<Vec<&DB>>();
+
+        let tables = self.apply(schema, &vanilla_tables)?;
+
+        let mut added_paths = vec![];
+        let mut removed_paths = vec![];
+        for table in tables {
+            let path = self.get_table_path(&table.name);
+            if table.get_ref_table_data().is_empty() {
+                if pack_file.packedfile_exists(&path) {
+                    pack_file.remove_packed_file_by_path(&path);
+                    removed_paths.push(path);
+                }
+            }
+            else {
+                let packed_file = PackedFile::new_from_decoded(&DecodedPackedFile::DB(table), path);
+                added_paths.push(pack_file.add_packed_file(&packed_file, true)?);
+            }
+        }
+
+        Ok((added_paths, removed_paths))
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                       Implementation of TablePatch
+//---------------------------------------------------------------------------//
+
+/// Implementation of `TablePatch`.
+impl TablePatch {
+
+    /// This function applies this patch against the provided vanilla tables, returning the rows that differ from them as a new table.
+    ///
+    /// The new table uses the definition of the most recent vanilla table, or the last one in the schema if there are no vanilla tables.
+    pub fn apply(&self, schema: &Schema, vanilla_tables: &[&DB]) -> Result<DB> {
+        let vanilla_tables = vanilla_tables.iter().filter(|x| x.name == self.table_name).copied().collect::<Vec<&DB>>();
+        let definition = match vanilla_tables.iter().map(|x| x.get_ref_definition()).max_by_key(|x| x.version) {
+            Some(definition) => definition.clone(),
+            None => schema.get_ref_last_definition_db(&self.table_name)?.clone(),
+        };
+
+        let mut table = DB::new(&self.table_name, None, &definition);
+        let mut rows = vanilla_tables.iter()
+            .filter(|x| x.get_ref_definition().version == definition.version)
+            .flat_map(|x| x.get_table_data())
+            .collect::<Vec<Vec<DecodedData>>>();
+
+        let vanilla_rows = rows.to_vec();
+        for operation in &self.operations {
+            operation.apply(&table, &mut rows, &vanilla_rows)?;
+        }
+
+        table.set_table_data(&rows)?;
+        table.optimize_table(&vanilla_tables);
+        Ok(table)
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                   Implementation of TablePatchOperation
+//---------------------------------------------------------------------------//
+
+/// Implementation of `TablePatchOperation`.
+impl TablePatchOperation {
+
+    /// This function applies this operation to the provided rows of the provided table.
+    ///
+    /// The vanilla rows are the rows of the table before applying any operation.
+    fn apply(&self, table: &DB, rows: &mut Vec<Vec<DecodedData>>, vanilla_rows: &[Vec<DecodedData>]) -> Result<()> {
+        match self {
+            TablePatchOperation::Set { filters, values } => {
+                let filters = Self::get_filters(table, filters)?;
+                let values = values.iter()
+                    .map(|(field, value)| Self::get_value(table, field, value))
+                    .collect::<Result<Vec<(usize, DecodedData)>>>()?;
+
+                for row in rows.iter_mut().filter(|row| Self::is_match(row, &filters)) {
+                    for (column, value) in &values {
+                        row[*column] = value.clone();
+                    }
+                }
+            }
+
+            TablePatchOperation::Add { rows: new_rows } => {
+                let definition = table.get_ref_definition();
+                for new_row in new_rows {
+                    let mut row = definition.fields.iter().map(Table::get_field_default_value).collect::<Vec<DecodedData>>();
+                    for (field, value) in new_row {
+                        let (column, value) = Self::get_value(table, field, value)?;
+                        row[column] = value;
+                    }
+
+                    let row_key = table.get_row_keys(&row);
+                    match rows.iter_mut().find(|x| !row_key.is_empty() && table.get_row_keys(x) == row_key) {
+                        Some(old_row) => *old_row = row,
+                        None => rows.push(row),
+                    }
+                }
+            }
+
+            TablePatchOperation::Remove { filters } => {
+                let filters = Self::get_filters(table, filters)?;
+                let mut matches = rows.iter().filter(|row| Self::is_match(row, &filters)).peekable();
+                if matches.peek().is_some() && matches.all(|row| vanilla_rows.contains(row)) {
+                    return Err(ErrorKind::TablePatchRemoveVanillaRows(table.name.to_owned()).into());
+                }
+
+                rows.retain(|row| !Self::is_match(row, &filters));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// This function turns the filters of an operation into a list of columns and the regexes their values have to match.
+    fn get_filters(table: &DB, filters: &BTreeMap<String, String>) -> Result<Vec<(usize, Regex)>> {
+        filters.iter().map(|(field, pattern)| {
+            let column = Self::get_column(table.get_ref_definition(), &table.name, field)?;
+            let pattern = format!("^{}$", regex::escape(pattern).replace("\\*", ".*").replace("\\?", "."));
+            Ok((column, Regex::new(&pattern).unwrap()))
+        }).collect()
+    }
+
+    /// This function returns the column of the provided field, and the provided value decoded for that field.
+    fn get_value(table: &DB, field: &str, value: &str) -> Result<(usize, DecodedData)> {
+        let definition = table.get_ref_definition();
+        let column = Self::get_column(definition, &table.name, field)?;
+        let value = DecodedData::new_from_type_and_string(&definition.fields[column].field_type, value)
+            .map_err(|_| ErrorKind::TablePatchInvalidValue(table.name.to_owned(), field.to_owned(), value.to_owned()))?;
+        Ok((column, value))
+    }
+
+    /// This function returns the column of the provided field in the provided definition.
+    fn get_column(definition: &Definition, table_name: &str, field: &str) -> Result<usize> {
+        definition.fields.iter()
+            .position(|x| x.name == field)
+            .ok_or_else(|| ErrorKind::TablePatchFieldNotFound(table_name.to_owned(), field.to_owned()).into())
+    }
+
+    /// This function checks if the provided row matches all the provided filters.
+    fn is_match(row: &[DecodedData], filters: &[(usize, Regex)]) -> bool {
+        filters.iter().all(|(column, regex)| regex.is_match(&row[*column].data_to_string()))
+    }
+}
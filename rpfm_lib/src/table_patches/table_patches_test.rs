@@ -0,0 +1,103 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module containing test for the `TablePatches` module, to make sure the patches generate only what they should.
+!*/
+
+use std::collections::BTreeMap;
+
+use crate::packedfile::table::DecodedData;
+use crate::packedfile::table::db::DB;
+use crate::schema::{Definition, Field, FieldType, Schema};
+
+use super::{TablePatch, TablePatchOperation, TablePatchSet};
+
+/// This function returns a vanilla table to apply the patches to.
+fn get_vanilla_table() -> DB {
+    let mut definition = Definition::new(1);
+    definition.fields.push(Field { name: "key".to_owned(), field_type: FieldType::StringU8, is_key: true, ..Default::default() });
+    definition.fields.push(Field { name: "morale".to_owned(), field_type: FieldType::Integer, ..Default::default() });
+    definition.fields.push(Field { name: "is_elite".to_owned(), field_type: FieldType::Boolean, ..Default::default() });
+
+    let mut table = DB::new("land_units_tables", None, &definition);
+    table.set_table_data(&[
+        vec![DecodedData::StringU8("wh_main_emp_inf_swordsmen".to_owned()), DecodedData::Integer(40), DecodedData::Boolean(false)],
+        vec![DecodedData::StringU8("wh_main_emp_inf_greatswords".to_owned()), DecodedData::Integer(60), DecodedData::Boolean(true)],
+        vec![DecodedData::StringU8("wh_main_grn_inf_orc_boyz".to_owned()), DecodedData::Integer(30), DecodedData::Boolean(false)],
+    ]).unwrap();
+    table
+}
+
+/// This function returns a map with the provided field/value pairs.
+fn map(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+    pairs.iter().map(|(field, value)| (field.to_string(), value.to_string())).collect()
+}
+
+#[test]
+fn test_table_patch_apply() {
+    let vanilla_table = get_vanilla_table();
+    let patch_set = TablePatchSet {
+        name: "test_patch".to_owned(),
+        patches: vec![TablePatch {
+            table_name: "land_units_tables".to_owned(),
+            operations: vec![
+                TablePatchOperation::Set { filters: map(&[("key", "wh_main_emp_*")]), values: map(&[("morale", "60")]) },
+                TablePatchOperation::Add { rows: vec![map(&[("key", "test_unit"), ("is_elite", "true")]), map(&[("key", "test_unit_2")])] },
+                TablePatchOperation::Remove { filters: map(&[("key", "test_unit_?")]) },
+            ],
+        }],
+    };
+
+    let tables = patch_set.apply(&Schema::default(), &[&vanilla_table]).unwrap();
+    assert_eq!(tables.len(), 1);
+    assert_eq!(tables[0].get_ref_definition(), vanilla_table.get_ref_definition());
+    assert_eq!(tables[0].get_ref_table_data(), &[
+        vec![DecodedData::StringU8("wh_main_emp_inf_swordsmen".to_owned()), DecodedData::Integer(60), DecodedData::Boolean(false)],
+        vec![DecodedData::StringU8("test_unit".to_owned()), DecodedData::Integer(0), DecodedData::Boolean(true)],
+    ]);
+
+    // Unknown fields and invalid values must fail.
+    let mut bad_patch_set = patch_set.clone();
+    bad_patch_set.patches[0].operations = vec![TablePatchOperation::Set { filters: map(&[("missing_field", "*")]), values: BTreeMap::new() }];
+    assert!(bad_patch_set.apply(&Schema::default(), &[&vanilla_table]).is_err());
+    bad_patch_set.patches[0].operations = vec![TablePatchOperation::Set { filters: BTreeMap::new(), values: map(&[("morale", "sixty")]) }];
+    assert!(bad_patch_set.apply(&Schema::default(), &[&vanilla_table]).is_err());
+
+    // Removing only vanilla rows would do nothing, so it must fail. Removing edited vanilla rows drops the edits.
+    bad_patch_set.patches[0].operations = vec![TablePatchOperation::Remove { filters: map(&[("key", "wh_main_emp_*")]) }];
+    assert!(bad_patch_set.apply(&Schema::default(), &[&vanilla_table]).is_err());
+    bad_patch_set.patches[0].operations = vec![
+        TablePatchOperation::Set { filters: map(&[("key", "wh_main_emp_*")]), values: map(&[("morale", "60")]) },
+        TablePatchOperation::Remove { filters: map(&[("key", "wh_main_emp_*")]) },
+    ];
+    assert!(bad_patch_set.apply(&Schema::default(), &[&vanilla_table]).unwrap()[0].get_ref_table_data().is_empty());
+}
+
+#[test]
+fn test_table_patch_ron() {
+    let ron = r#"(
+        name: "test_patch",
+        patches: [
+            (
+                table_name: "land_units_tables",
+                operations: [
+                    Set(filters: {"key": "wh_main_emp_*"}, values: {"morale": "60"}),
+                    Add(rows: [{"key": "test_unit"}]),
+                    Remove(filters: {"key": "test_unit"}),
+                ],
+            ),
+        ],
+    )"#;
+
+    let patch_set: TablePatchSet = ron::de::from_str(ron).unwrap();
+    assert_eq!(patch_set.patches[0].operations.len(), 3);
+    assert_eq!(patch_set.get_table_path("land_units_tables"), vec!["db".to_owned(), "land_units_tables".to_owned(), "test_patch".to_owned()]);
+}
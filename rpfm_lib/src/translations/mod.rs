@@ -0,0 +1,507 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with all the code to export/import the Loc entries of a PackFile to/from standard translation formats.
+
+This allows translators to work on a mod using their usual tools, without having to touch RPFM at all. The supported formats are:
+- `PO`: Gettext's Portable Object files.
+- `XLIFF`: XML Localisation Interchange File Format, version 1.2.
+
+In both of them, the key of each Loc entry is used as the context/id of the entry, the text in the PackFile's Locs as the source,
+and, if the key exists in the game files, the vanilla text in the target language is added as a note for the translator.
+
+Imported translations are stored as Loc PackedFiles under `text/translations/`. The game loads every Loc PackedFile of a PackFile
+no matter the language it's running in, so each PackFile should only contain the translation to one language. Translations
+to more than one language need to be imported into different copies of the PackFile.
+!*/
+
+use serde_derive::Deserialize;
+use serde_xml_rs::{Deserializer, EventReader, ParserConfig};
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use rpfm_error::{Error, ErrorKind, Result};
+
+use crate::GAME_SELECTED;
+use crate::SUPPORTED_GAMES;
+use crate::common::get_game_selected_loc_pack_path;
+use crate::packedfile::{DecodedPackedFile, PackedFileType};
+use crate::packedfile::table::loc::{self, Loc};
+use crate::packfile::PackFile;
+use crate::packfile::packedfile::PackedFile;
+use crate::schema::Schema;
+
+#[cfg(test)]
+mod translations_test;
+
+/// Folder, under `text/`, where the translated Loc PackedFiles are stored.
+pub const TRANSLATIONS_FOLDER: &str = "translations";
+
+/// Language of the source text of the translations.
+const SOURCE_LANGUAGE: &str = "en";
+
+/// Prefix of the Loc PackFiles in the game's data folder.
+const LOC_PACK_PREFIX: &str = "local_";
+
+/// Extension of the Loc PackFiles in the game's data folder.
+const LOC_PACK_EXTENSION: &str = ".pack";
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This enum represents the file formats we can export translations to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TranslationFormat {
+    PO,
+    XLIFF,
+}
+
+/// This struct represents a translation of the Loc entries of a PackFile to a specific language.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct Translation {
+
+    /// Language code of the translation, as used in the name of the game's Loc PackFiles (`local_xx.pack`).
+    pub language: String,
+
+    /// List of entries of the translation.
+    pub entries: Vec<TranslationEntry>,
+}
+
+/// This struct represents a single translatable Loc entry.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct TranslationEntry {
+
+    /// Key of the Loc entry.
+    pub key: String,
+
+    /// Source text of the entry.
+    pub source: String,
+
+    /// Translated text of the entry. Empty if the entry has not been translated yet.
+    pub target: String,
+
+    /// Vanilla text of the entry in the language of the translation, if the key exists in the game files.
+    pub hint: Option<String>,
+}
+
+/// Raw XLIFF file, used to deserialize XLIFF files.
+#[derive(Debug, Deserialize)]
+struct RawXliff {
+    file: RawXliffFile,
+}
+
+/// Raw `file` element of an XLIFF file.
+#[derive(Debug, Deserialize)]
+struct RawXliffFile {
+    #[serde(rename = "target-language", default)]
+    target_language: String,
+    body: RawXliffBody,
+}
+
+/// Raw `body` element of an XLIFF file.
+#[derive(Debug, Deserialize)]
+struct RawXliffBody {
+    #[serde(rename = "trans-unit", default)]
+    units: Vec<RawXliffUnit>,
+}
+
+/// Raw `trans-unit` element of an XLIFF file.
+#[derive(Debug, Deserialize)]
+struct RawXliffUnit {
+    id: String,
+    #[serde(default)]
+    source: Option<String>,
+    #[serde(default)]
+    target: Option<String>,
+    #[serde(default)]
+    note: Option<String>,
+}
+
+//---------------------------------------------------------------------------//
+//                      Implementation of TranslationFormat
+//---------------------------------------------------------------------------//
+
+/// Implementation of `TranslationFormat`.
+impl TranslationFormat {
+
+    /// This function returns the format of a translation file, based on its extension.
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let extension = path.extension().map(|x| x.to_string_lossy().to_lowercase()).unwrap_or_default();
+        match &*extension {
+            "po" => Ok(TranslationFormat::PO),
+            "xlf" | "xliff" => Ok(TranslationFormat::XLIFF),
+            _ => Err(ErrorKind::TranslationUnsupportedFormat(extension).into()),
+        }
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                         Implementation of Translation
+//---------------------------------------------------------------------------//
+
+/// Implementation of `Translation`.
+impl Translation {
+
+    /// This function returns the list of languages supported by the Game Selected, based on its Loc PackFiles.
+    pub fn get_supported_languages() -> Vec<String> {
+        let game_selected = GAME_SELECTED.read().unwrap();
+        match SUPPORTED_GAMES.get(&**game_selected) {
+            Some(game) => game.loc_packs.iter()
+                .filter_map(|x| x.strip_prefix(LOC_PACK_PREFIX)?.strip_suffix(LOC_PACK_EXTENSION))
+                .filter(|x| !x.contains('_'))
+                .map(|x| x.to_owned())
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    /// This function returns the texts of the game's Loc PackedFiles for the provided language, by their key.
+    ///
+    /// If the game path is not configured, or the language has no Loc PackFiles, it returns an empty list.
+    pub fn get_vanilla_texts(language: &str, schema: &Schema) -> BTreeMap<String, String> {
+        let mut texts = BTreeMap::new();
+        if let Some(paths) = get_game_selected_loc_pack_path() {
+            let name = format!("{}{}", LOC_PACK_PREFIX, language);
+            let paths = paths.into_iter()
+                .filter(|x| x.is_file())
+                .filter(|x| match x.file_stem() {
+                    Some(stem) => stem.to_string_lossy() == name || stem.to_string_lossy().starts_with(&format!("{}_", name)),
+                    None => false,
+                })
+                .collect::<Vec<_>>();
+
+            if !paths.is_empty() {
+                if let Ok(mut pack_file) = PackFile::open_packfiles(&paths, true, false, false) {
+                    for packed_file in pack_file.get_ref_mut_packed_files_by_type(PackedFileType::Loc, false) {
+                        if let Ok(DecodedPackedFile::Loc(loc)) = packed_file.decode_return_ref_no_locks(schema) {
+                            texts.extend(loc.get_texts());
+                        }
+                    }
+                }
+            }
+        }
+        texts
+    }
+
+    /// This function creates a `Translation` to the provided language with all the Loc entries of the provided PackFile.
+    ///
+    /// The translated Loc PackedFiles (the ones under `text/translations/`) are not used as source. Instead, if there is
+    /// one for the provided language, its texts are used as the already-translated texts of the entries.
+    pub fn new_from_packfile(
+        pack_file: &mut PackFile,
+        language: &str,
+        schema: &Schema,
+        vanilla_texts: &BTreeMap<String, String>,
+    ) -> Self {
+        let translation_path = Self::get_translation_path(language);
+        let translations_folder = &translation_path[..2];
+
+        let mut source_texts: Vec<(String, String)> = vec![];
+        let mut target_texts = BTreeMap::new();
+        for packed_file in pack_file.get_ref_mut_packed_files_by_type(PackedFileType::Loc, false) {
+            let path = packed_file.get_path().to_vec();
+            if let Ok(DecodedPackedFile::Loc(loc)) = packed_file.decode_return_ref_no_locks(schema) {
+                if path == translation_path { target_texts.extend(loc.get_texts()); }
+                else if !path.starts_with(translations_folder) { source_texts.extend(loc.get_texts()); }
+            }
+        }
+
+        // If a key is repeated, the last one is the one the game uses, so that's the one we keep.
+        let mut entries: Vec<TranslationEntry> = vec![];
+        let mut entries_by_key: HashMap<String, usize> = HashMap::new();
+        for (key, source) in source_texts {
+            let entry = TranslationEntry {
+                target: target_texts.get(&key).cloned().unwrap_or_default(),
+                hint: vanilla_texts.get(&key).cloned(),
+                key,
+                source,
+            };
+
+            match entries_by_key.get(&entry.key) {
+                Some(index) => entries[*index] = entry,
+                None => {
+                    entries_by_key.insert(entry.key.to_owned(), entries.len());
+                    entries.push(entry);
+                }
+            }
+        }
+
+        Self {
+            language: language.to_owned(),
+            entries,
+        }
+    }
+
+    /// This function returns the path the translated Loc PackedFile of the provided language has in a PackFile.
+    ///
+    /// Keep in mind the game loads this Loc PackedFile for every language, not only for the provided one.
+    pub fn get_translation_path(language: &str) -> Vec<String> {
+        vec!["text".to_owned(), TRANSLATIONS_FOLDER.to_owned(), format!("{}{}", language, loc::EXTENSION)]
+    }
+
+    /// This function turns this `Translation` into a Loc PackedFile and adds it to the provided PackFile, replacing any previous version of it.
+    ///
+    /// Untranslated entries are not included in the Loc PackedFile. It returns the path of the new Loc PackedFile.
+    ///
+    /// As the language is used in the name of the Loc PackedFile, languages that are not a valid file name are rejected.
+    pub fn import_to_packfile(&self, pack_file: &mut PackFile, schema: &Schema) -> Result<Vec<String>> {
+        if self.language.is_empty() || self.language.contains(&['/', '\\'][..]) || self.language.contains("..") {
+            return Err(ErrorKind::TranslationInvalidLanguage(self.language.to_owned()).into());
+        }
+
+        let mut loc = Loc::new(schema.get_ref_last_definition_loc()?);
+        let entries = self.entries.iter()
+            .filter(|x| !x.target.is_empty())
+            .map(|x| (x.key.to_owned(), x.target.to_owned()))
+            .collect::<Vec<(String, String)>>();
+        loc.add_entries(&entries);
+
+        let packed_file = PackedFile::new_from_decoded(&DecodedPackedFile::Loc(loc), Self::get_translation_path(&self.language));
+        pack_file.add_packed_file(&packed_file, true)
+    }
+
+    /// This function loads a `Translation` from a translation file. The format of the file is deduced from its extension.
+    pub fn load(path: &Path) -> Result<Self> {
+        let format = TranslationFormat::from_path(path)?;
+        let mut file = BufReader::new(File::open(path)?);
+        let mut data = String::new();
+        file.read_to_string(&mut data)?;
+
+        match format {
+            TranslationFormat::PO => Self::from_po(&data),
+            TranslationFormat::XLIFF => Self::from_xliff(&data),
+        }
+    }
+
+    /// This function saves this `Translation` to a translation file. The format of the file is deduced from its extension.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let data = match TranslationFormat::from_path(path)? {
+            TranslationFormat::PO => self.to_po(),
+            TranslationFormat::XLIFF => self.to_xliff(),
+        };
+
+        let mut file = BufWriter::new(File::create(path)?);
+        file.write_all(data.as_bytes())?;
+        Ok(())
+    }
+
+    /// This function returns this `Translation` in PO format.
+    pub fn to_po(&self) -> String {
+        let mut po = String::new();
+        po.push_str("msgid \"\"\n");
+        po.push_str("msgstr \"\"\n");
+        po.push_str(&format!("\"Language: {}\\n\"\n", escape_po(&self.language)));
+        po.push_str("\"MIME-Version: 1.0\\n\"\n");
+        po.push_str("\"Content-Type: text/plain; charset=UTF-8\\n\"\n");
+        po.push_str("\"Content-Transfer-Encoding: 8bit\\n\"\n");
+
+        for entry in &self.entries {
+            po.push('\n');
+            if let Some(ref hint) = entry.hint {
+                for line in hint.lines() {
+                    po.push_str(&format!("#. {}\n", line));
+                }
+            }
+            po.push_str(&format!("msgctxt \"{}\"\n", escape_po(&entry.key)));
+            po.push_str(&format!("msgid \"{}\"\n", escape_po(&entry.source)));
+            po.push_str(&format!("msgstr \"{}\"\n", escape_po(&entry.target)));
+        }
+
+        po
+    }
+
+    /// This function creates a `Translation` from the contents of a PO file.
+    ///
+    /// Entries without a `msgctxt` cannot be matched to a Loc key, so they're ignored.
+    pub fn from_po(data: &str) -> Result<Self> {
+        let mut translation = Self::default();
+        let mut entry = PoEntry::default();
+        let mut field: Option<PoField> = None;
+
+        for (index, untrimmed_line) in data.lines().chain(std::iter::once("")).enumerate() {
+            let line = untrimmed_line.trim();
+
+            // Empty lines mark the end of an entry.
+            if line.is_empty() {
+                entry.finish(&mut translation);
+                entry = PoEntry::default();
+                field = None;
+            }
+
+            // Only the space after the marker is ours. The spaces at the ends of the rest of the comment are part of the hint.
+            else if line.starts_with("#.") {
+                let comment = untrimmed_line.trim_start()[2..].trim_end_matches('\r');
+                entry.comments.push(comment.strip_prefix(' ').unwrap_or(comment).to_owned());
+            }
+
+            // Other comments are not useful for us.
+            else if line.starts_with('#') {}
+
+            else {
+                let (new_field, string) = if let Some(string) = line.strip_prefix("msgctxt ") { (Some(PoField::Context), string) }
+                    else if let Some(string) = line.strip_prefix("msgid ") { (Some(PoField::Source), string) }
+                    else if let Some(string) = line.strip_prefix("msgstr ") { (Some(PoField::Target), string) }
+                    else if line.starts_with('"') { (None, line) }
+                    else { return Err(ErrorKind::TranslationDecode(format!("Unexpected content in line {}: {}", index + 1, line)).into()) };
+
+                if let Some(new_field) = new_field {
+                    field = Some(new_field);
+                }
+
+                let string = unescape_po(string).ok_or_else(|| Error::from(ErrorKind::TranslationDecode(format!("Invalid string in line {}: {}", index + 1, line))))?;
+                match field {
+                    Some(PoField::Context) => entry.context.get_or_insert_with(String::new).push_str(&string),
+                    Some(PoField::Source) => entry.source.push_str(&string),
+                    Some(PoField::Target) => entry.target.push_str(&string),
+                    None => return Err(ErrorKind::TranslationDecode(format!("String without field in line {}: {}", index + 1, line)).into()),
+                }
+            }
+        }
+
+        Ok(translation)
+    }
+
+    /// This function returns this `Translation` in XLIFF 1.2 format.
+    pub fn to_xliff(&self) -> String {
+        let mut xliff = String::new();
+        xliff.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xliff.push_str("<xliff version=\"1.2\" xmlns=\"urn:oasis:names:tc:xliff:document:1.2\">\n");
+        xliff.push_str(&format!("  <file original=\"loc\" source-language=\"{}\" target-language=\"{}\" datatype=\"plaintext\">\n", SOURCE_LANGUAGE, escape_xml(&self.language)));
+        xliff.push_str("    <body>\n");
+
+        for entry in &self.entries {
+            xliff.push_str(&format!("      <trans-unit id=\"{}\" xml:space=\"preserve\">\n", escape_xml(&entry.key)));
+            xliff.push_str(&format!("        <source>{}</source>\n", escape_xml(&entry.source)));
+            xliff.push_str(&format!("        <target>{}</target>\n", escape_xml(&entry.target)));
+            if let Some(ref hint) = entry.hint {
+                xliff.push_str(&format!("        <note from=\"vanilla\">{}</note>\n", escape_xml(hint)));
+            }
+            xliff.push_str("      </trans-unit>\n");
+        }
+
+        xliff.push_str("    </body>\n");
+        xliff.push_str("  </file>\n");
+        xliff.push_str("</xliff>\n");
+        xliff
+    }
+
+    /// This function creates a `Translation` from the contents of an XLIFF 1.2 file.
+    pub fn from_xliff(data: &str) -> Result<Self> {
+
+        // The default reader trims the texts, so we need one that doesn't, or we'll lose the spaces at the start and end of the entries.
+        // The whitespace between elements is still ignored, as it's not turned into text.
+        let config = ParserConfig::new()
+            .trim_whitespace(false)
+            .whitespace_to_characters(false)
+            .cdata_to_characters(true)
+            .ignore_comments(true)
+            .coalesce_characters(true);
+
+        let mut deserializer = Deserializer::new(EventReader::new_with_config(data.as_bytes(), config));
+        let raw: RawXliff = serde::Deserialize::deserialize(&mut deserializer).map_err(|error| Error::from(ErrorKind::TranslationDecode(error.to_string())))?;
+        Ok(Self {
+            language: raw.file.target_language,
+            entries: raw.file.body.units.into_iter().map(|unit| TranslationEntry {
+                key: unit.id,
+                source: unit.source.unwrap_or_default(),
+                target: unit.target.unwrap_or_default(),
+                hint: unit.note,
+            }).collect(),
+        })
+    }
+}
+
+//---------------------------------------------------------------------------//
+//                              PO helpers
+//---------------------------------------------------------------------------//
+
+/// This enum represents the fields of a PO entry we care about.
+#[derive(Clone, Copy)]
+enum PoField {
+    Context,
+    Source,
+    Target,
+}
+
+/// This struct holds the data of a PO entry while it's being parsed.
+#[derive(Default)]
+struct PoEntry {
+    context: Option<String>,
+    source: String,
+    target: String,
+    comments: Vec<String>,
+}
+
+/// Implementation of `PoEntry`.
+impl PoEntry {
+
+    /// This function adds the entry to the provided `Translation`, or takes the language from it if it's the header entry.
+    fn finish(&mut self, translation: &mut Translation) {
+        match self.context {
+            Some(ref key) => translation.entries.push(TranslationEntry {
+                key: key.to_owned(),
+                source: self.source.to_owned(),
+                target: self.target.to_owned(),
+                hint: if self.comments.is_empty() { None } else { Some(self.comments.join("\n")) },
+            }),
+
+            // The header is the only entry without context and with an empty source.
+            None => if self.source.is_empty() {
+                if let Some(language) = self.target.lines().find_map(|x| x.strip_prefix("Language:")) {
+                    translation.language = language.trim().to_owned();
+                }
+            }
+        }
+    }
+}
+
+/// This function escapes a string to be used in a PO file.
+fn escape_po(string: &str) -> String {
+    string.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+        .replace('\t', "\\t")
+}
+
+/// This function unescapes a quoted string from a PO file. Returns `None` if the string is not properly quoted.
+fn unescape_po(string: &str) -> Option<String> {
+    let string = string.trim();
+    if string.len() < 2 || !string.starts_with('"') || !string.ends_with('"') { return None }
+
+    let mut unescaped = String::with_capacity(string.len());
+    let mut chars = string[1..string.len() - 1].chars();
+    while let Some(character) = chars.next() {
+        if character == '\\' {
+            match chars.next()? {
+                'n' => unescaped.push('\n'),
+                't' => unescaped.push('\t'),
+                'r' => unescaped.push('\r'),
+                other => unescaped.push(other),
+            }
+        }
+        else { unescaped.push(character); }
+    }
+
+    Some(unescaped)
+}
+
+/// This function escapes a string to be used in an XML file.
+fn escape_xml(string: &str) -> String {
+    string.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
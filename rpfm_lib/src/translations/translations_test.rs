@@ -0,0 +1,97 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2020 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module containing test for the `Translation` module, to make sure what we export is what we get back.
+!*/
+
+use std::path::PathBuf;
+
+use crate::packfile::{PackFile, PFHVersion};
+use crate::schema::Schema;
+
+use super::{Translation, TranslationEntry, TranslationFormat};
+
+/// This function returns a `Translation` with the most problematic cases we can think of.
+fn get_test_translation() -> Translation {
+    Translation {
+        language: "sp".to_owned(),
+        entries: vec![
+            TranslationEntry {
+                key: "land_units_onscreen_name_wh_main_emp_inf_swordsmen".to_owned(),
+                source: "Swordsmen".to_owned(),
+                target: "Espadachines".to_owned(),
+                hint: Some("Espadachines".to_owned()),
+            },
+            TranslationEntry {
+                key: "land_units_onscreen_name_padded".to_owned(),
+                source: "  Padded with spaces, and a tab at the end\t".to_owned(),
+                target: " Relleno ".to_owned(),
+                hint: Some(" Relleno".to_owned()),
+            },
+            TranslationEntry {
+                key: "land_units_tooltip_<&>".to_owned(),
+                source: "Line with \"quotes\",\ttabs,\\n escaped newlines\nand real ones <b>&</b>.".to_owned(),
+                target: String::new(),
+                hint: None,
+            },
+        ],
+    }
+}
+
+#[test]
+fn test_translation_format_from_path() {
+    assert_eq!(TranslationFormat::from_path(&PathBuf::from("mod_sp.po")).unwrap(), TranslationFormat::PO);
+    assert_eq!(TranslationFormat::from_path(&PathBuf::from("mod_sp.XLF")).unwrap(), TranslationFormat::XLIFF);
+    assert_eq!(TranslationFormat::from_path(&PathBuf::from("mod_sp.xliff")).unwrap(), TranslationFormat::XLIFF);
+    assert!(TranslationFormat::from_path(&PathBuf::from("mod_sp.tsv")).is_err());
+}
+
+#[test]
+fn test_translation_po() {
+    let translation = get_test_translation();
+    assert_eq!(Translation::from_po(&translation.to_po()).unwrap(), translation);
+}
+
+#[test]
+fn test_translation_po_multiline() {
+    let po = "msgid \"\"\nmsgstr \"\"\n\"Language: ge\\n\"\n\n#: some/reference\nmsgctxt \"key\"\nmsgid \"\"\n\"First \"\n\"second\"\nmsgstr \"Erste zweite\"\n";
+    let translation = Translation::from_po(po).unwrap();
+    assert_eq!(translation.language, "ge");
+    assert_eq!(translation.entries[0].source, "First second");
+    assert_eq!(translation.entries[0].target, "Erste zweite");
+}
+
+#[test]
+fn test_translation_xliff() {
+    let translation = get_test_translation();
+    assert_eq!(Translation::from_xliff(&translation.to_xliff()).unwrap(), translation);
+}
+
+#[test]
+fn test_translation_po_carriage_return() {
+    let mut translation = get_test_translation();
+    translation.entries[0].target = "Windows\r\nline".to_owned();
+
+    let po = translation.to_po();
+    assert!(po.contains("msgstr \"Windows\\r\\nline\""));
+    assert_eq!(Translation::from_po(&po).unwrap(), translation);
+}
+
+#[test]
+fn test_translation_invalid_language() {
+    let mut pack_file = PackFile::new_with_name("test.pack", PFHVersion::PFH5);
+    for language in &["", "../sp", "sp/../..", "sp\\fr"] {
+        let mut translation = get_test_translation();
+        translation.language = language.to_string();
+        assert!(translation.import_to_packfile(&mut pack_file, &Schema::default()).is_err());
+    }
+    assert!(pack_file.get_ref_packed_files_all().is_empty());
+}
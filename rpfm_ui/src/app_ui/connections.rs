@@ -124,6 +124,7 @@ pub unsafe fn set_connections(app_ui: &AppUI, slots: &AppUISlots) {
     //-----------------------------------------------//
     app_ui.debug_update_current_schema_from_asskit.triggered().connect(&slots.debug_update_current_schema_from_asskit);
     app_ui.debug_generate_schema_diff.triggered().connect(&slots.debug_generate_schema_diff);
+    app_ui.debug_infer_current_schema_enum_values.triggered().connect(&slots.debug_infer_current_schema_enum_values);
 
     //-----------------------------------------------//
     // `PackedFileView` connections.
@@ -247,6 +247,7 @@ pub struct AppUI {
     //-------------------------------------------------------------------------------//
     pub debug_update_current_schema_from_asskit: MutPtr<QAction>,
     pub debug_generate_schema_diff: MutPtr<QAction>,
+    pub debug_infer_current_schema_enum_values: MutPtr<QAction>,
 }
 
 /// This enum contains the data needed to create a new PackedFile.
@@ -577,6 +578,7 @@ impl AppUI {
         // Populate the `Debug` menu.
         let debug_update_current_schema_from_asskit = menu_bar_debug.add_action_q_string(&qtr("update_current_schema_from_asskit"));
         let debug_generate_schema_diff = menu_bar_debug.add_action_q_string(&qtr("generate_schema_diff"));
+        let debug_infer_current_schema_enum_values = menu_bar_debug.add_action_q_string(&qtr("infer_current_schema_enum_values"));
 
         command_palette_widget.hide();
 
@@ -750,6 +752,7 @@ impl AppUI {
             //-------------------------------------------------------------------------------//
             debug_update_current_schema_from_asskit,
             debug_generate_schema_diff,
+            debug_infer_current_schema_enum_values,
         }
     }
 }
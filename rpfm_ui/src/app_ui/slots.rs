@@ -137,6 +137,7 @@ pub struct AppUISlots {
     //-----------------------------------------------//
     pub debug_update_current_schema_from_asskit: SlotOfBool<'static>,
     pub debug_generate_schema_diff: SlotOfBool<'static>,
+    pub debug_infer_current_schema_enum_values: SlotOfBool<'static>,
 
     //-----------------------------------------------//
     // `PackedFileView` slots.
@@ -811,6 +812,7 @@ impl AppUISlots {
                     let response = CENTRAL_COMMAND.recv_message_qt_try();
                     match response {
                         Response::Success => {}
+                        Response::Error(error) => show_dialog(app_ui.main_window, error, false),
                         _ => panic!("{}{:?}", THREADS_COMMUNICATION_ERROR, response),
                     }
 
@@ -1077,7 +1079,23 @@ impl AppUISlots {
                 CENTRAL_COMMAND.send_message_qt(Command::UpdateCurrentSchemaFromAssKit(path));
                 let response = CENTRAL_COMMAND.recv_message_qt_try();
                 match response {
-                    Response::Success => show_dialog(app_ui.main_window, tr("update_current_schema_from_asskit_success"), true),
+                    Response::VecUnmatchedFields(unmatched_fields) => {
+                        if unmatched_fields.is_empty() {
+                            show_dialog(app_ui.main_window, tr("update_current_schema_from_asskit_success"), true);
+                        }
+                        else {
+                            let report = unmatched_fields.iter().map(|x| format!("{}, version {}:\n - {}: {}\n - {}: {}",
+                                x.table_name,
+                                x.version,
+                                tr("update_current_schema_from_asskit_unmatched_schema_fields"),
+                                x.schema_fields.join(", "),
+                                tr("update_current_schema_from_asskit_unmatched_raw_fields"),
+                                x.raw_fields.join(", ")
+                            )).collect::<Vec<String>>().join("\n").replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+                            let message = format!("<p>{}</p><pre>{}</pre>", tre("update_current_schema_from_asskit_success_unmatched", &[&unmatched_fields.len().to_string()]), report);
+                            show_dialog(app_ui.main_window, message, true);
+                        }
+                    }
                     Response::Error(error) => show_dialog(app_ui.main_window, error, false),
                     _ => panic!("{}{:?}", THREADS_COMMUNICATION_ERROR, response),
                 }
@@ -1102,6 +1120,25 @@ impl AppUISlots {
             }
         );
 
+        // Slot for the "Infer Value Sets" button.
+        let debug_infer_current_schema_enum_values = SlotOfBool::new(move |_| {
+                app_ui.main_window.set_enabled(false);
+                CENTRAL_COMMAND.send_message_qt(Command::InferCurrentSchemaEnumValues);
+                let response = CENTRAL_COMMAND.recv_message_qt_try();
+                match response {
+                    Response::VecString(fields) => {
+                        let report = fields.join("\n").replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+                        let message = format!("<p>{}</p><pre>{}</pre>", tre("infer_current_schema_enum_values_success", &[&fields.len().to_string()]), report);
+                        show_dialog(app_ui.main_window, message, true);
+                    }
+                    Response::Error(error) => show_dialog(app_ui.main_window, error, false),
+                    _ => panic!("{}{:?}", THREADS_COMMUNICATION_ERROR, response),
+                }
+
+                app_ui.main_window.set_enabled(true);
+            }
+        );
+
         //-----------------------------------------------//
         // `PackedFileView` logic.
         //-----------------------------------------------//
@@ -1238,6 +1275,7 @@ impl AppUISlots {
             //-----------------------------------------------//
             debug_update_current_schema_from_asskit,
             debug_generate_schema_diff,
+            debug_infer_current_schema_enum_values,
 
             //-----------------------------------------------//
             // `PackedFileView` slots.
@@ -21,7 +21,7 @@ use uuid::Uuid;
 use std::collections::BTreeMap;
 use std::env::temp_dir;
 use std::fs::File;
-use std::io::{BufWriter, Read, Write};
+use std::io::{Read, Write};
 use std::path::PathBuf;
 
 use rpfm_error::{Error, ErrorKind};
@@ -33,8 +33,8 @@ use rpfm_lib::packedfile::*;
 use rpfm_lib::packedfile::table::db::DB;
 use rpfm_lib::packedfile::table::loc::{Loc, TSV_NAME_LOC};
 use rpfm_lib::packedfile::text::{Text, TextType};
-use rpfm_lib::packfile::{PackFile, PackFileInfo, packedfile::PackedFile, PathType, PFHFlags};
-use rpfm_lib::schema::{*, versions::*};
+use rpfm_lib::packfile::{PackFile, PackFileInfo, changeset::ChangeSet, packedfile::PackedFile, PathType, PFHFlags};
+use rpfm_lib::schema::{*, decoding_report::DecodingReport, value_domains, versions::*};
 use rpfm_lib::SCHEMA;
 use rpfm_lib::SETTINGS;
 use rpfm_lib::SUPPORTED_GAMES;
@@ -42,7 +42,7 @@ use rpfm_lib::SUPPORTED_GAMES;
 use crate::app_ui::NewPackedFile;
 use crate::CENTRAL_COMMAND;
 use crate::communications::{Command, Response, THREADS_COMMUNICATION_ERROR};
-use crate::locale::tre;
+use crate::locale::{tr, tre};
 use crate::packedfile_views::table::TableType;
 use crate::RPFM_PATH;
 
@@ -61,6 +61,11 @@ pub fn background_loop() {
     let mut pack_file_decoded = PackFile::new();
     let mut pack_file_decoded_extra = PackFile::new();
 
+    // The key renames done to `pack_file_decoded`, so they can be undone/redone as a single operation each.
+    // They're lost when the PackFile is replaced, as they don't apply to any other PackFile.
+    let mut key_renames_undo: Vec<ChangeSet> = vec![];
+    let mut key_renames_redo: Vec<ChangeSet> = vec![];
+
     //---------------------------------------------------------------------------------------//
     // Looping forever and ever...
     //---------------------------------------------------------------------------------------//
@@ -72,7 +77,11 @@ pub fn background_loop() {
         match response {
 
             // In case we want to reset the PackFile to his original state (dummy)...
-            Command::ResetPackFile => pack_file_decoded = PackFile::new(),
+            Command::ResetPackFile => {
+                pack_file_decoded = PackFile::new();
+                key_renames_undo.clear();
+                key_renames_redo.clear();
+            }
 
             // In case we want to reset the Secondary PackFile to his original state (dummy)...
             Command::ResetPackFileExtra => pack_file_decoded_extra = PackFile::new(),
@@ -82,6 +91,8 @@ pub fn background_loop() {
                 let game_selected = GAME_SELECTED.read().unwrap();
                 let pack_version = SUPPORTED_GAMES.get(&**game_selected).unwrap().pfh_version[0];
                 pack_file_decoded = PackFile::new_with_name("unknown.pack", pack_version);
+                key_renames_undo.clear();
+                key_renames_redo.clear();
             }
 
             // In case we want to "Open one or more PackFiles"...
@@ -89,6 +100,8 @@ pub fn background_loop() {
                 match PackFile::open_packfiles(&paths, SETTINGS.read().unwrap().settings_bool["use_lazy_loading"], false, false) {
                     Ok(pack_file) => {
                         pack_file_decoded = pack_file;
+                        key_renames_undo.clear();
+                        key_renames_redo.clear();
                         CENTRAL_COMMAND.send_message_rust(Response::PackFileInfo(PackFileInfo::from(&pack_file_decoded)));
                     }
                     Err(error) => CENTRAL_COMMAND.send_message_rust(Response::Error(error)),
@@ -111,6 +124,8 @@ pub fn background_loop() {
                 match PackFile::open_all_ca_packfiles() {
                     Ok(pack_file) => {
                         pack_file_decoded = pack_file;
+                        key_renames_undo.clear();
+                        key_renames_redo.clear();
                         CENTRAL_COMMAND.send_message_rust(Response::PackFileInfo(PackFileInfo::from(&pack_file_decoded)));
                     }
                     Err(error) => CENTRAL_COMMAND.send_message_rust(Response::Error(error)),
@@ -208,15 +223,21 @@ pub fn background_loop() {
                 pack_file_decoded.get_ref_mut_packed_files_by_type(PackedFileType::DB, false).iter_mut().for_each(|x| { let _ = x.encode_and_clean_cache(); });
                 *SCHEMA.write().unwrap() = Schema::load(&SUPPORTED_GAMES.get(&*game_selected).unwrap().schema).ok();
 
-                // Send a response, so we can unlock the UI.
-                CENTRAL_COMMAND.send_message_rust(Response::Success);
+                // Change the `fake dependency_database` for that game. If its PAK file needs to be regenerated, we report it, but the game is still changed.
+                match DB::read_pak_file() {
+                    Ok(fake_dependency_database) => {
+                        *FAKE_DEPENDENCY_DATABASE.write().unwrap() = fake_dependency_database;
+                        CENTRAL_COMMAND.send_message_rust(Response::Success);
+                    }
+                    Err(error) => {
+                        FAKE_DEPENDENCY_DATABASE.write().unwrap().clear();
+                        CENTRAL_COMMAND.send_message_rust(Response::Error(error));
+                    }
+                }
 
                 // Change the `dependency_database` for that game.
                 *DEPENDENCY_DATABASE.lock().unwrap() = PackFile::load_all_dependency_packfiles(&pack_file_decoded.get_packfiles_list());
 
-                // Change the `fake dependency_database` for that game.
-                *FAKE_DEPENDENCY_DATABASE.write().unwrap() = DB::read_pak_file();
-
                 // If there is a PackFile open, change his id to match the one of the new `Game Selected`.
                 if !pack_file_decoded.get_file_name().is_empty() {
                     pack_file_decoded.set_pfh_version(SUPPORTED_GAMES.get(&**GAME_SELECTED.read().unwrap()).unwrap().pfh_version[0]);
@@ -226,45 +247,28 @@ pub fn background_loop() {
                 // a new patch lands and you want to know what tables you need to decode. So, unless you want
                 // to decode new tables, leave the setting as false.
                 if SETTINGS.read().unwrap().settings_bool["check_for_missing_table_definitions"] {
-                    let mut counter = 0;
-                    let mut table_list = String::new();
                     if let Some(ref schema) = *SCHEMA.read().unwrap() {
-                        for packed_file in pack_file_decoded.get_ref_mut_packed_files_by_type(PackedFileType::DB, false) {
-                            if packed_file.decode_return_ref_no_locks(schema).is_err() {
-                                if let Ok(raw_data) = packed_file.get_raw_data() {
-                                    if let Ok((_, _, _, entry_count, _)) = DB::read_header(&raw_data) {
-                                        if entry_count > 0 {
-                                            counter += 1;
-                                            table_list.push_str(&format!("{}, {:?}\n", counter, packed_file.get_path()))
-                                        }
-                                    }
-                                }
-                            }
-                        }
+                        let path = RPFM_PATH.to_path_buf().join(PathBuf::from("table_decoding_report.json"));
+                        DecodingReport::new(&pack_file_decoded, schema).save_json(&path).unwrap();
                     }
-
-                    // Try to save the file.
-                    let path = RPFM_PATH.to_path_buf().join(PathBuf::from("missing_table_definitions.txt"));
-                    let mut file = BufWriter::new(File::create(path).unwrap());
-                    file.write_all(table_list.as_bytes()).unwrap();
                 }
             }
 
             // In case we want to generate a new Pak File for our Game Selected...
             Command::GeneratePakFile(path, version) => {
-                match generate_pak_file(&path, version) {
-                    Ok(_) => CENTRAL_COMMAND.send_message_rust(Response::Success),
+                match generate_pak_file(&path, version).and_then(|_| DB::read_pak_file()) {
+                    Ok(fake_dependency_database) => {
+                        *FAKE_DEPENDENCY_DATABASE.write().unwrap() = fake_dependency_database;
+                        CENTRAL_COMMAND.send_message_rust(Response::Success);
+                    }
                     Err(error) => CENTRAL_COMMAND.send_message_rust(Response::Error(error)),
                 }
-
-                // Reload the `fake dependency_database` for that game.
-                *FAKE_DEPENDENCY_DATABASE.write().unwrap() = DB::read_pak_file();
             }
 
             // In case we want to update the Schema for our Game Selected...
             Command::UpdateCurrentSchemaFromAssKit(path) => {
                 match update_schema_from_raw_files(path) {
-                    Ok(_) => CENTRAL_COMMAND.send_message_rust(Response::Success),
+                    Ok(unmatched_fields) => CENTRAL_COMMAND.send_message_rust(Response::VecUnmatchedFields(unmatched_fields)),
                     Err(error) => CENTRAL_COMMAND.send_message_rust(Response::Error(error)),
                 }
             }
@@ -444,7 +448,23 @@ pub fn background_loop() {
                     }
                 }
                 else if let Some(packed_file) = pack_file_decoded.get_ref_mut_packed_file_by_path(&path) {
-                    *packed_file.get_ref_mut_decoded() = decoded_packed_file;
+                    let is_db = if let DecodedPackedFile::DB(_) = decoded_packed_file { true } else { false };
+                    let sync_locs = is_db && SETTINGS.read().unwrap().settings_bool["generate_loc_entries_on_db_edit"];
+
+                    // If the current version of the table is not decoded (like after saving the PackFile), decode it, so we can find its renamed keys.
+                    let decode_result = if sync_locs { packed_file.decode() } else { Ok(()) };
+                    let old_decoded_packed_file = std::mem::replace(packed_file.get_ref_mut_decoded(), decoded_packed_file);
+
+                    // If it's a DB Table and we want to keep its Loc entries in sync, do it now. If we couldn't decode
+                    // its old version, the table is still saved, but we report the error instead of syncing its Locs.
+                    if sync_locs {
+                        let old_table = if let DecodedPackedFile::DB(table) = old_decoded_packed_file { Some(table) } else { None };
+                        match decode_result.and_then(|_| pack_file_decoded.update_loc_from_db(&path, old_table.as_ref(), &tr("loc_placeholder_text"))) {
+                            Ok(paths) => CENTRAL_COMMAND.send_message_rust(Response::VecVecStringVecVecString(paths)),
+                            Err(error) => CENTRAL_COMMAND.send_message_rust(Response::Error(error)),
+                        }
+                        continue;
+                    }
                 }
                 CENTRAL_COMMAND.send_message_rust(Response::Success);
             }
@@ -470,14 +490,14 @@ pub fn background_loop() {
             // In case we want to Mass-Import TSV Files...
             Command::MassImportTSV(paths, name) => {
                 match pack_file_decoded.mass_import_tsv(&paths, name, true) {
-                    Ok(result) => CENTRAL_COMMAND.send_message_rust(Response::VecVecStringVecVecString(result)),
+                    Ok(result) => CENTRAL_COMMAND.send_message_rust(Response::VecVecStringVecVecStringVecStringVecString(result)),
                     Err(error) => CENTRAL_COMMAND.send_message_rust(Response::Error(error)),
                 }
             }
 
             // In case we want to Mass-Export TSV Files...
-            Command::MassExportTSV(path_types, path) => {
-                match pack_file_decoded.mass_export_tsv(&path_types, &path) {
+            Command::MassExportTSV(path_types, path, format) => {
+                match pack_file_decoded.mass_export_tsv(&path_types, &path, format) {
                     Ok(result) => CENTRAL_COMMAND.send_message_rust(Response::String(result)),
                     Err(error) => CENTRAL_COMMAND.send_message_rust(Response::Error(error)),
                 }
@@ -530,8 +550,8 @@ pub fn background_loop() {
                 if let PathType::File(path) = path_type {
                     if let Some(packed_file) = pack_file_decoded.get_ref_mut_packed_file_by_path(&path) {
                         match packed_file.decode_return_ref_mut() {
-                            Ok(packed_file) => match packed_file.update_table() {
-                                    Ok(data) => CENTRAL_COMMAND.send_message_rust(Response::I32I32(data)),
+                            Ok(packed_file) => match packed_file.update_table(&path) {
+                                    Ok(data) => CENTRAL_COMMAND.send_message_rust(Response::TableUpdateReport(data)),
                                     Err(error) => CENTRAL_COMMAND.send_message_rust(Response::Error(error)),
                                 }
                             Err(error) => CENTRAL_COMMAND.send_message_rust(Response::Error(error)),
@@ -540,6 +560,80 @@ pub fn background_loop() {
                 } else { CENTRAL_COMMAND.send_message_rust(Response::Error(ErrorKind::PackedFileNotFound.into())); }
             }
 
+            // In case we want to update all the tables of the PackFile...
+            Command::UpdateAllTables => {
+                match pack_file_decoded.update_all_tables() {
+                    Ok(reports) => CENTRAL_COMMAND.send_message_rust(Response::VecTableUpdateReport(reports)),
+                    Err(error) => CENTRAL_COMMAND.send_message_rust(Response::Error(error)),
+                }
+            }
+
+            // In case we want to rename a key in all the PackedFiles referencing it...
+            Command::RenameKeyCascade(table_name, column_name, old_key, new_key, rename_on_texts) => {
+                match pack_file_decoded.rename_key_cascade(&table_name, &column_name, &old_key, &new_key, rename_on_texts) {
+                    Ok(change_set) => {
+                        let paths = change_set.get_paths();
+                        if !change_set.is_empty() {
+                            key_renames_undo.push(change_set);
+                            key_renames_redo.clear();
+                        }
+                        CENTRAL_COMMAND.send_message_rust(Response::VecVecString(paths));
+                    }
+                    Err(error) => CENTRAL_COMMAND.send_message_rust(Response::Error(error)),
+                }
+            }
+
+            // In case we want to find all the rows referencing a key...
+            Command::FindReferences(table_name, key) => {
+                match &*SCHEMA.read().unwrap() {
+                    Some(ref schema) => match DB::get_references_to_key(&mut pack_file_decoded, schema, &table_name, &key, &mut DEPENDENCY_DATABASE.lock().unwrap()) {
+                        Ok(references) => CENTRAL_COMMAND.send_message_rust(Response::VecDBReference(references)),
+                        Err(error) => CENTRAL_COMMAND.send_message_rust(Response::Error(error)),
+                    }
+                    None => CENTRAL_COMMAND.send_message_rust(Response::Error(ErrorKind::SchemaNotFound.into())),
+                }
+            }
+
+            // In case we want to undo the last key rename...
+            Command::UndoKeyRename => {
+                match key_renames_undo.pop() {
+                    Some(change_set) => match change_set.undo(&mut pack_file_decoded) {
+                        Ok(paths) => {
+                            key_renames_redo.push(change_set);
+                            CENTRAL_COMMAND.send_message_rust(Response::VecVecString(paths));
+                        }
+
+                        // If the PackedFiles have been edited since the rename, the history is no longer valid.
+                        Err(error) => {
+                            key_renames_undo.clear();
+                            key_renames_redo.clear();
+                            CENTRAL_COMMAND.send_message_rust(Response::Error(error));
+                        }
+                    }
+                    None => CENTRAL_COMMAND.send_message_rust(Response::VecVecString(vec![])),
+                }
+            }
+
+            // In case we want to redo the last undone key rename...
+            Command::RedoKeyRename => {
+                match key_renames_redo.pop() {
+                    Some(change_set) => match change_set.redo(&mut pack_file_decoded) {
+                        Ok(paths) => {
+                            key_renames_undo.push(change_set);
+                            CENTRAL_COMMAND.send_message_rust(Response::VecVecString(paths));
+                        }
+
+                        // If the PackedFiles have been edited since the undo, the history is no longer valid.
+                        Err(error) => {
+                            key_renames_undo.clear();
+                            key_renames_redo.clear();
+                            CENTRAL_COMMAND.send_message_rust(Response::Error(error));
+                        }
+                    }
+                    None => CENTRAL_COMMAND.send_message_rust(Response::VecVecString(vec![])),
+                }
+            }
+
             // In case we want to replace all matches in a Global Search...
             Command::GlobalSearchReplaceMatches(mut global_search, matches) => {
                 let _ = global_search.replace_matches(&mut pack_file_decoded, &matches);
@@ -596,6 +690,14 @@ pub fn background_loop() {
                 }
             },
 
+            // In case we want to guess the definitions of a DB Table...
+            Command::GuessDefinitions(data, table_name, max_guesses) => {
+                match DB::guess_definitions(&data, &table_name, SCHEMA.read().unwrap().as_ref(), max_guesses) {
+                    Ok(guesses) => CENTRAL_COMMAND.send_message_rust(Response::VecDefinitionGuess(guesses)),
+                    Err(error) => CENTRAL_COMMAND.send_message_rust(Response::Error(error)),
+                }
+            }
+
             // In case we want to save an schema to disk...
             Command::SaveSchema(mut schema) => {
                 match schema.save(&SUPPORTED_GAMES.get(&**GAME_SELECTED.read().unwrap()).unwrap().schema) {
@@ -621,6 +723,20 @@ pub fn background_loop() {
                 }
             }
 
+            // In case we want to infer the value sets of the fields of the Schema for our Game Selected...
+            Command::InferCurrentSchemaEnumValues => {
+                match &mut *SCHEMA.write().unwrap() {
+                    Some(schema) => {
+                        let fields = schema.infer_enum_values(&mut DEPENDENCY_DATABASE.lock().unwrap(), value_domains::DEFAULT_MAX_ENUM_VALUES);
+                        match schema.save(&SUPPORTED_GAMES.get(&**GAME_SELECTED.read().unwrap()).unwrap().schema) {
+                            Ok(_) => CENTRAL_COMMAND.send_message_rust(Response::VecString(fields)),
+                            Err(error) => CENTRAL_COMMAND.send_message_rust(Response::Error(error)),
+                        }
+                    }
+                    None => CENTRAL_COMMAND.send_message_rust(Response::Error(ErrorKind::SchemaNotFound.into())),
+                }
+            }
+
             // In case we want to export a PackedFile as a TSV file...
             Command::ExportTSV((internal_path, external_path)) => {
                 match pack_file_decoded.get_ref_mut_packed_file_by_path(&internal_path) {
@@ -649,11 +765,11 @@ pub fn background_loop() {
                 match pack_file_decoded.get_ref_mut_packed_file_by_path(&internal_path) {
                     Some(packed_file) => match packed_file.get_decoded() {
                         DecodedPackedFile::DB(data) => match DB::import_tsv(&data.get_definition(), &external_path, &internal_path[1]) {
-                            Ok(data) => CENTRAL_COMMAND.send_message_rust(Response::TableType(TableType::DB(data))),
+                            Ok((data, extra_columns)) => CENTRAL_COMMAND.send_message_rust(Response::TableTypeVecString((TableType::DB(data), extra_columns))),
                             Err(error) =>  CENTRAL_COMMAND.send_message_rust(Response::Error(error)),
                         },
                         DecodedPackedFile::Loc(data) => match Loc::import_tsv(&data.get_definition(), &external_path, &TSV_NAME_LOC) {
-                            Ok(data) => CENTRAL_COMMAND.send_message_rust(Response::TableType(TableType::Loc(data))),
+                            Ok((data, extra_columns)) => CENTRAL_COMMAND.send_message_rust(Response::TableTypeVecString((TableType::Loc(data), extra_columns))),
                             Err(error) =>  CENTRAL_COMMAND.send_message_rust(Response::Error(error)),
                         },
                         /*
@@ -771,10 +887,10 @@ pub fn background_loop() {
                                     Ok(data) => {
                                         if let DecodedPackedFile::DB(ref mut data) = data {
                                             match DB::import_tsv(&data.get_definition(), &external_path, &path[1]) {
-                                                Ok(new_data) => {
+                                                Ok((new_data, extra_columns)) => {
                                                     *data = new_data;
                                                     match packed_file.encode_and_clean_cache() {
-                                                        Ok(_) => CENTRAL_COMMAND.send_message_rust(Response::Success),
+                                                        Ok(_) => CENTRAL_COMMAND.send_message_rust(Response::VecString(extra_columns)),
                                                         Err(error) => CENTRAL_COMMAND.send_message_rust(Response::Error(error)),
                                                     }
                                                 }
@@ -783,10 +899,10 @@ pub fn background_loop() {
                                         }
                                         else if let DecodedPackedFile::Loc(ref mut data) = data {
                                             match Loc::import_tsv(&data.get_definition(), &external_path, &TSV_NAME_LOC) {
-                                                Ok(new_data) => {
+                                                Ok((new_data, extra_columns)) => {
                                                     *data = new_data;
                                                     match packed_file.encode_and_clean_cache() {
-                                                        Ok(_) => CENTRAL_COMMAND.send_message_rust(Response::Success),
+                                                        Ok(_) => CENTRAL_COMMAND.send_message_rust(Response::VecString(extra_columns)),
                                                         Err(error) => CENTRAL_COMMAND.send_message_rust(Response::Error(error)),
                                                     }
                                                 }
@@ -22,16 +22,18 @@ use std::process::exit;
 
 use rpfm_error::Error;
 
+use rpfm_lib::assembly_kit::UnmatchedFields;
 use rpfm_lib::global_search::GlobalSearch;
 use rpfm_lib::global_search::MatchHolder;
 use rpfm_lib::packedfile::ca_vp8::{CaVp8, SupportedFormats};
 use rpfm_lib::packedfile::DecodedPackedFile;
 use rpfm_lib::packedfile::image::Image;
-use rpfm_lib::packedfile::table::{db::DB, loc::Loc};
+use rpfm_lib::packedfile::table::{db::{DB, DBReference}, guesser::DefinitionGuess, loc::Loc, TableFormat};
 use rpfm_lib::packedfile::text::Text;
 use rpfm_lib::packedfile::rigidmodel::RigidModel;
 use rpfm_lib::packfile::{PackFileInfo, PathType, PFHFileType};
 use rpfm_lib::packfile::packedfile::{PackedFile, PackedFileInfo};
+use rpfm_lib::packfile::table_update::TableUpdateReport;
 use rpfm_lib::schema::versions::APIResponseSchema;
 use rpfm_lib::schema::{Definition, Schema};
 use rpfm_lib::settings::*;
@@ -195,8 +197,8 @@ pub enum Command {
     /// This command is used when we want to import a large amount of table-like files from TSV files.
     MassImportTSV(Vec<PathBuf>, Option<String>),
 
-    /// This command is used when we want to export a large amount of table-like files as TSV files.
-    MassExportTSV(Vec<PathType>, PathBuf),
+    /// This command is used when we want to export a large amount of table-like files as TSV files, or any of the other supported formats.
+    MassExportTSV(Vec<PathType>, PathBuf, TableFormat),
 
     /// This command is used when we want to know if a folder exists in the currently open PackFile.
     FolderExists(Vec<String>),
@@ -222,6 +224,28 @@ pub enum Command {
     /// This command is used when we want to update a table to a newer version.
     UpdateTable(PathType),
 
+    /// This command is used when we want to update all the outdated tables of the PackFile to their newer versions.
+    UpdateAllTables,
+
+    /// This command is used when we want to rename a key of a DB Table in all the PackedFiles referencing it. The contents of this are as follows:
+    /// - String: Name of the table, like `land_units_tables`.
+    /// - String: Name of the key column.
+    /// - String: Old key.
+    /// - String: New key.
+    /// - Bool: Should we also rename it in Text PackedFiles?
+    RenameKeyCascade(String, String, String, String, bool),
+
+    /// This command is used when we want to find all the rows of the PackFile and the game files referencing a key. The contents of this are as follows:
+    /// - String: Name of the table, like `land_units_tables`.
+    /// - String: Key to find.
+    FindReferences(String, String),
+
+    /// This command is used when we want to undo the last key rename. It returns the paths of the reverted PackedFiles.
+    UndoKeyRename,
+
+    /// This command is used when we want to redo the last undone key rename. It returns the paths of the changed PackedFiles.
+    RedoKeyRename,
+
     /// This command is used when we want to replace some specific matches in a Global Search.
     GlobalSearchReplaceMatches(GlobalSearch, Vec<MatchHolder>),
 
@@ -247,6 +271,9 @@ pub enum Command {
     /// This command is used to change the format of a ca_vp8 video packedfile. Requires the path of the PackedFile and the new format.
     SetCaVp8Format((Vec<String>, SupportedFormats)),
 
+    /// This command is used to guess the definitions that can decode the provided DB data. Requires the data, the name of the table, and the maximum amount of guesses to return.
+    GuessDefinitions(Vec<u8>, String, usize),
+
     /// This command is used to save the provided schema to disk.
     SaveSchema(Schema),
 
@@ -256,6 +283,9 @@ pub enum Command {
     /// This command is used to generate a pretty diff of a schema, against the last remote version of the same schema.
     GenerateSchemaDiff,
 
+    /// This command is used to infer the value sets of the fields of the currently loaded Schema, from the data in the dependency database.
+    InferCurrentSchemaEnumValues,
+
     /// This command is used to export a table as TSV. Requires the internal and destination paths for the PackedFile.
     ExportTSV((Vec<String>, PathBuf)),
 
@@ -359,9 +389,27 @@ pub enum Response {
     /// Response to return `Vec<String>`.
     VecString(Vec<String>),
 
+    /// Response to return `(Vec<Vec<String>>, Vec<Vec<String>>, Vec<(String, Vec<String>)>)`.
+    VecVecStringVecVecStringVecStringVecString((Vec<Vec<String>>, Vec<Vec<String>>, Vec<(String, Vec<String>)>)),
+
     /// Response to return `(i32, i32)`.
     I32I32((i32, i32)),
 
+    /// Response to return `TableUpdateReport`.
+    TableUpdateReport(TableUpdateReport),
+
+    /// Response to return `Vec<TableUpdateReport>`.
+    VecTableUpdateReport(Vec<TableUpdateReport>),
+
+    /// Response to return `Vec<DefinitionGuess>`.
+    VecDefinitionGuess(Vec<DefinitionGuess>),
+
+    /// Response to return `Vec<DBReference>`.
+    VecDBReference(Vec<DBReference>),
+
+    /// Response to return `Vec<UnmatchedFields>`.
+    VecUnmatchedFields(Vec<UnmatchedFields>),
+
     /// Response to return `BTreeMap<i32, BTreeMap<String, String>>`.
     BTreeMapI32BTreeMapStringString(BTreeMap<i32, BTreeMap<String, String>>),
 
@@ -370,6 +418,9 @@ pub enum Response {
 
     /// Response to return `TableType`.
     TableType(TableType),
+
+    /// Response to return `(TableType, Vec<String>)`.
+    TableTypeVecString((TableType, Vec<String>)),
 }
 
 //-------------------------------------------------------------------------------//
@@ -45,6 +45,8 @@ pub unsafe fn set_connections(ui: &PackedFileDecoderView, slots: &PackedFileDeco
     ui.get_mut_ptr_table_view_context_menu_move_up().triggered().connect(&slots.table_view_context_menu_move_up);
     ui.get_mut_ptr_table_view_context_menu_move_down().triggered().connect(&slots.table_view_context_menu_move_down);
     ui.get_mut_ptr_table_view_context_menu_delete().triggered().connect(&slots.table_view_context_menu_delete);
+    ui.get_mut_ptr_table_view_context_menu_move_into_sequence().triggered().connect(&slots.table_view_context_menu_move_into_sequence);
+    ui.get_mut_ptr_table_view_context_menu_extract_from_sequence().triggered().connect(&slots.table_view_context_menu_extract_from_sequence);
 
     ui.get_mut_ptr_table_view().custom_context_menu_requested().connect(&slots.table_view_context_menu);
     ui.get_mut_ptr_table_view().selection_model().selection_changed().connect(&slots.table_view_context_menu_enabler);
@@ -56,6 +58,7 @@ pub unsafe fn set_connections(ui: &PackedFileDecoderView, slots: &PackedFileDeco
     ui.get_mut_ptr_table_view_old_versions_context_menu_delete().triggered().connect(&slots.table_view_old_versions_context_menu_delete);
 
     ui.get_mut_ptr_test_definition_button().released().connect(&slots.test_definition);
+    ui.get_mut_ptr_guess_definition_button().released().connect(&slots.guess_definition);
     ui.get_mut_ptr_clear_definition_button().released().connect(&slots.remove_all_fields);
     ui.get_mut_ptr_save_button().released().connect(&slots.save_definition);
 }
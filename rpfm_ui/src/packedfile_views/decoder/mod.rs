@@ -14,6 +14,8 @@ Module with all the code for managing the PackedFile decoder.
 
 use qt_widgets::q_abstract_item_view::{EditTrigger, SelectionMode};
 use qt_widgets::q_header_view::ResizeMode;
+use qt_widgets::QComboBox;
+use qt_widgets::QDialog;
 use qt_widgets::QFrame;
 use qt_widgets::QLabel;
 use qt_widgets::QLineEdit;
@@ -54,8 +56,10 @@ use rpfm_error::{ErrorKind, Result};
 use rpfm_lib::common::decoder::*;
 use rpfm_lib::packedfile::PackedFileType;
 use rpfm_lib::packedfile::table::db::DB;
-use rpfm_lib::packedfile::table::{loc, loc::Loc};
+use rpfm_lib::packedfile::table::guesser::DefinitionGuess;
+use rpfm_lib::packedfile::table::{loc, loc::Loc, DecodedData, Table};
 use rpfm_lib::schema::{Definition, Field, FieldType, Schema, VersionedFile};
+use rpfm_lib::schema::provenance::{DefinitionProvenance, DefinitionSource};
 use rpfm_lib::SCHEMA;
 use rpfm_lib::SETTINGS;
 
@@ -85,6 +89,12 @@ const SUPPORTED_PACKED_FILE_TYPES: [PackedFileType; 2] = [
     PackedFileType::Loc,
 ];
 
+/// Maximum amount of guessed definitions to offer when guessing the definition of a DB Table.
+const MAX_DEFINITION_GUESSES: usize = 5;
+
+/// This const is the key of the QVariant that holds the definition of a Sequence, serialized as JSON, in its "Field Type" item.
+const SEQUENCE_DEFINITION: i32 = 40;
+
 //-------------------------------------------------------------------------------//
 //                              Enums & Structs
 //-------------------------------------------------------------------------------//
@@ -101,6 +111,8 @@ pub struct PackedFileDecoderView {
     table_view_context_menu_move_up: AtomicPtr<QAction>,
     table_view_context_menu_move_down: AtomicPtr<QAction>,
     table_view_context_menu_delete: AtomicPtr<QAction>,
+    table_view_context_menu_move_into_sequence: AtomicPtr<QAction>,
+    table_view_context_menu_extract_from_sequence: AtomicPtr<QAction>,
 
     bool_button: AtomicPtr<QPushButton>,
     float_button: AtomicPtr<QPushButton>,
@@ -119,6 +131,7 @@ pub struct PackedFileDecoderView {
     table_view_old_versions_context_menu_delete: AtomicPtr<QAction>,
 
     test_definition_button: AtomicPtr<QPushButton>,
+    guess_definition_button: AtomicPtr<QPushButton>,
     clear_definition_button: AtomicPtr<QPushButton>,
     save_button: AtomicPtr<QPushButton>,
 
@@ -144,6 +157,8 @@ pub struct PackedFileDecoderViewRaw {
     pub table_view_context_menu_move_up: MutPtr<QAction>,
     pub table_view_context_menu_move_down: MutPtr<QAction>,
     pub table_view_context_menu_delete: MutPtr<QAction>,
+    pub table_view_context_menu_move_into_sequence: MutPtr<QAction>,
+    pub table_view_context_menu_extract_from_sequence: MutPtr<QAction>,
 
     pub bool_line_edit: MutPtr<QLineEdit>,
     pub float_line_edit: MutPtr<QLineEdit>,
@@ -174,6 +189,7 @@ pub struct PackedFileDecoderViewRaw {
     pub table_view_old_versions_context_menu_delete: MutPtr<QAction>,
 
     pub test_definition_button: MutPtr<QPushButton>,
+    pub guess_definition_button: MutPtr<QPushButton>,
     pub clear_definition_button: MutPtr<QPushButton>,
     pub save_button: MutPtr<QPushButton>,
 
@@ -263,11 +279,15 @@ impl PackedFileDecoderView {
         let mut table_view_context_menu_move_up = table_view_context_menu.add_action_q_string(&QString::from_std_str("Move &Up"));
         let mut table_view_context_menu_move_down = table_view_context_menu.add_action_q_string(&QString::from_std_str("&Move Down"));
         let mut table_view_context_menu_delete = table_view_context_menu.add_action_q_string(&QString::from_std_str("&Delete"));
+        let mut table_view_context_menu_move_into_sequence = table_view_context_menu.add_action_q_string(&QString::from_std_str("Move &Into Sequence"));
+        let mut table_view_context_menu_extract_from_sequence = table_view_context_menu.add_action_q_string(&QString::from_std_str("&Extract From Sequence"));
 
         // Disable them by default.
         table_view_context_menu_move_up.set_enabled(false);
         table_view_context_menu_move_down.set_enabled(false);
         table_view_context_menu_delete.set_enabled(false);
+        table_view_context_menu_move_into_sequence.set_enabled(false);
+        table_view_context_menu_extract_from_sequence.set_enabled(false);
 
         layout.add_widget_5a(table_view.as_mut_ptr(), 0, 1, 1, 2);
 
@@ -403,13 +423,18 @@ impl PackedFileDecoderView {
 
         // Create the bottom Buttons.
         let mut test_definition_button = QPushButton::from_q_string(&QString::from_std_str("Test Definition"));
+        let mut guess_definition_button = QPushButton::from_q_string(&QString::from_std_str("Guess Definition"));
         let mut clear_definition_button = QPushButton::from_q_string(&QString::from_std_str("Remove all fields"));
         let mut save_button = QPushButton::from_q_string(&QString::from_std_str("Finish it!"));
 
+        // Guessing only works for DB Tables, as Loc Tables always have the same structure.
+        guess_definition_button.set_enabled(packed_file_type == PackedFileType::DB);
+
         // Add them to the Dialog.
         button_box_layout.add_widget_5a(&mut test_definition_button, 0, 0, 1, 1);
-        button_box_layout.add_widget_5a(&mut clear_definition_button, 0, 1, 1, 1);
-        button_box_layout.add_widget_5a(&mut save_button, 0, 2, 1, 1);
+        button_box_layout.add_widget_5a(&mut guess_definition_button, 0, 1, 1, 1);
+        button_box_layout.add_widget_5a(&mut clear_definition_button, 0, 2, 1, 1);
+        button_box_layout.add_widget_5a(&mut save_button, 0, 3, 1, 1);
 
         layout.add_widget_5a(button_box.into_ptr(), 4, 1, 1, 2);
 
@@ -434,6 +459,8 @@ impl PackedFileDecoderView {
             table_view_context_menu_move_up,
             table_view_context_menu_move_down,
             table_view_context_menu_delete,
+            table_view_context_menu_move_into_sequence,
+            table_view_context_menu_extract_from_sequence,
 
             bool_line_edit: bool_line_edit.into_ptr(),
             float_line_edit: float_line_edit.into_ptr(),
@@ -464,6 +491,7 @@ impl PackedFileDecoderView {
             table_view_old_versions_context_menu_delete,
 
             test_definition_button: test_definition_button.into_ptr(),
+            guess_definition_button: guess_definition_button.into_ptr(),
             clear_definition_button: clear_definition_button.into_ptr(),
             save_button: save_button.into_ptr(),
 
@@ -495,6 +523,8 @@ impl PackedFileDecoderView {
             table_view_context_menu_move_up: atomic_from_mut_ptr(packed_file_decoder_view_raw.table_view_context_menu_move_up),
             table_view_context_menu_move_down: atomic_from_mut_ptr(packed_file_decoder_view_raw.table_view_context_menu_move_down),
             table_view_context_menu_delete: atomic_from_mut_ptr(packed_file_decoder_view_raw.table_view_context_menu_delete),
+            table_view_context_menu_move_into_sequence: atomic_from_mut_ptr(packed_file_decoder_view_raw.table_view_context_menu_move_into_sequence),
+            table_view_context_menu_extract_from_sequence: atomic_from_mut_ptr(packed_file_decoder_view_raw.table_view_context_menu_extract_from_sequence),
 
             bool_button: atomic_from_mut_ptr(packed_file_decoder_view_raw.bool_button),
             float_button: atomic_from_mut_ptr(packed_file_decoder_view_raw.float_button),
@@ -513,6 +543,7 @@ impl PackedFileDecoderView {
             table_view_old_versions_context_menu_delete: atomic_from_mut_ptr(packed_file_decoder_view_raw.table_view_old_versions_context_menu_delete),
 
             test_definition_button: atomic_from_mut_ptr(packed_file_decoder_view_raw.test_definition_button),
+            guess_definition_button: atomic_from_mut_ptr(packed_file_decoder_view_raw.guess_definition_button),
             clear_definition_button: atomic_from_mut_ptr(packed_file_decoder_view_raw.clear_definition_button),
             save_button: atomic_from_mut_ptr(packed_file_decoder_view_raw.save_button),
 
@@ -747,6 +778,14 @@ impl PackedFileDecoderView {
         mut_ptr_from_atomic(&self.table_view_context_menu_delete)
     }
 
+    fn get_mut_ptr_table_view_context_menu_move_into_sequence(&self) -> MutPtr<QAction> {
+        mut_ptr_from_atomic(&self.table_view_context_menu_move_into_sequence)
+    }
+
+    fn get_mut_ptr_table_view_context_menu_extract_from_sequence(&self) -> MutPtr<QAction> {
+        mut_ptr_from_atomic(&self.table_view_context_menu_extract_from_sequence)
+    }
+
     fn get_mut_ptr_table_view_old_versions_context_menu_load(&self) -> MutPtr<QAction> {
         mut_ptr_from_atomic(&self.table_view_old_versions_context_menu_load)
     }
@@ -759,6 +798,10 @@ impl PackedFileDecoderView {
         mut_ptr_from_atomic(&self.test_definition_button)
     }
 
+    fn get_mut_ptr_guess_definition_button(&self) -> MutPtr<QPushButton> {
+        mut_ptr_from_atomic(&self.guess_definition_button)
+    }
+
     fn get_mut_ptr_clear_definition_button(&self) -> MutPtr<QPushButton> {
         mut_ptr_from_atomic(&self.clear_definition_button)
     }
@@ -1005,7 +1048,11 @@ impl PackedFileDecoderViewRaw {
 
         // Create the items of the new row.
         let field_name = QStandardItem::from_q_string(&QString::from_std_str(&field.name));
-        let field_type = QStandardItem::from_q_string(&QString::from_std_str(field_type));
+        let mut field_type = QStandardItem::from_q_string(&QString::from_std_str(field_type));
+        if let FieldType::Sequence(ref definition) = field.field_type {
+            Self::set_sequence_definition(field_type.as_mut_ptr(), definition);
+        }
+
         let mut field_is_key = QStandardItem::new();
         field_is_key.set_editable(false);
         field_is_key.set_checkable(true);
@@ -1115,11 +1162,19 @@ impl PackedFileDecoderViewRaw {
                 }
             },
 
-            // TODO: Finish this.
-            FieldType::Sequence(_) => {
-                match packed_file_data.decode_packedfile_optional_string_u16(*index, &mut index) {
-                    Ok(result) => result,
-                    Err(_) => "Error".to_owned(),
+            // Sequences without fields only have their entry count decoded, so the fields after them can be used to decode their first entry.
+            FieldType::Sequence(definition) => {
+                if definition.fields.is_empty() {
+                    match packed_file_data.decode_packedfile_integer_u32(*index, &mut index) {
+                        Ok(result) => format!("{} entries", result),
+                        Err(_) => "Error".to_owned(),
+                    }
+                }
+                else {
+                    match Table::decode_sequence(definition, packed_file_data, &mut index) {
+                        Ok(result) => DecodedData::Sequence(result).data_to_string(),
+                        Err(_) => "Error".to_owned(),
+                    }
                 }
             },
         }
@@ -1145,19 +1200,7 @@ impl PackedFileDecoderViewRaw {
             if model_index.is_valid() {
 
                 // Get the row's type.
-                let row_type = self.table_model.index_2a(row, 1);
-                let field_type = match &*row_type.data_1a(0).to_string().to_std_string() {
-                    "Bool" => FieldType::Boolean,
-                    "Float" => FieldType::Float,
-                    "Integer" => FieldType::Integer,
-                    "LongInteger" => FieldType::LongInteger,
-                    "StringU8" => FieldType::StringU8,
-                    "StringU16" => FieldType::StringU16,
-                    "OptionalStringU8" => FieldType::OptionalStringU8,
-                    "OptionalStringU16" => FieldType::OptionalStringU16,
-                    "Sequence" => FieldType::Sequence(Definition::new(-1)),
-                    _ => unimplemented!()
-                };
+                let field_type = self.get_field_type_from_view(row);
 
                 // Get the decoded data using it's type...
                 let decoded_data = Self::decode_data_by_fieldtype(
@@ -1220,6 +1263,101 @@ impl PackedFileDecoderViewRaw {
     }
 
 
+    /// This function returns the type of the field in the provided row of the decoder's table.
+    ///
+    /// Sequences get the definition stored in their "Field Type" item, or an empty one if they don't have it yet.
+    unsafe fn get_field_type_from_view(&self, row: i32) -> FieldType {
+        let item = self.table_model.item_2a(row, 1);
+        match &*item.text().to_std_string() {
+            "Bool" => FieldType::Boolean,
+            "Float" => FieldType::Float,
+            "Integer" => FieldType::Integer,
+            "LongInteger" => FieldType::LongInteger,
+            "StringU8" => FieldType::StringU8,
+            "StringU16" => FieldType::StringU16,
+            "OptionalStringU8" => FieldType::OptionalStringU8,
+            "OptionalStringU16" => FieldType::OptionalStringU16,
+            "Sequence" => {
+                let definition = item.data_1a(SEQUENCE_DEFINITION).to_string().to_std_string();
+                FieldType::Sequence(serde_json::from_str(&definition).unwrap_or_else(|_| Definition::new(-1)))
+            }
+            _ => unimplemented!()
+        }
+    }
+
+    /// This function stores the provided definition in the "Field Type" item of a Sequence.
+    unsafe fn set_sequence_definition(mut item: MutPtr<QStandardItem>, definition: &Definition) {
+        let definition = serde_json::to_string(definition).unwrap_or_default();
+        item.set_data_2a(&QVariant::from_q_string(&QString::from_std_str(definition)), SEQUENCE_DEFINITION);
+    }
+
+    /// This function moves the selected fields into the definition of the Sequence field right above them.
+    ///
+    /// To author a Sequence, add it before the fields of its first entry, decode them, then move them into it.
+    pub unsafe fn move_into_sequence(&mut self, mut index: &mut usize) -> Result<()> {
+        let indexes = self.table_view.selection_model().selection().indexes();
+        let mut rows = (0..indexes.count_0a()).map(|x| indexes.at(x).row()).collect::<Vec<i32>>();
+        rows.sort();
+        rows.dedup();
+
+        let (first_row, last_row) = match (rows.first(), rows.last()) {
+            (Some(first_row), Some(last_row)) if *first_row > 0 && last_row - first_row + 1 == rows.len() as i32 => (*first_row, *last_row),
+            _ => return Err(ErrorKind::DecoderSequenceInvalidSelection.into()),
+        };
+
+        let sequence_row = first_row - 1;
+        let mut definition = match self.get_field_type_from_view(sequence_row) {
+            FieldType::Sequence(definition) => definition,
+            _ => return Err(ErrorKind::DecoderSequenceInvalidSelection.into()),
+        };
+
+        let fields = self.get_fields_from_view();
+        definition.fields.extend_from_slice(&fields[first_row as usize..=last_row as usize]);
+        for row in rows.iter().rev() {
+            self.table_model.remove_row_1a(*row);
+        }
+
+        // Block the signals of the model, so changing the "Field Type" item doesn't trigger a decoding while we're still at it.
+        let mut blocker = QSignalBlocker::from_q_object(self.table_model.static_upcast_mut::<QObject>());
+        Self::set_sequence_definition(self.table_model.item_2a(sequence_row, 1), &definition);
+        blocker.unblock();
+
+        self.update_rows_decoded(&mut index)
+    }
+
+    /// This function moves the fields of the selected Sequence field out of it, right below it, so they can be edited.
+    pub unsafe fn extract_from_sequence(&mut self, mut index: &mut usize) -> Result<()> {
+        let indexes = self.table_view.selection_model().selection().indexes();
+        let mut rows = (0..indexes.count_0a()).map(|x| indexes.at(x).row()).collect::<Vec<i32>>();
+        rows.sort();
+        rows.dedup();
+
+        let sequence_row = match rows.first() {
+            Some(row) if rows.len() == 1 => *row,
+            _ => return Err(ErrorKind::DecoderSequenceInvalidSelection.into()),
+        };
+
+        let mut definition = match self.get_field_type_from_view(sequence_row) {
+            FieldType::Sequence(definition) => definition,
+            _ => return Err(ErrorKind::DecoderSequenceInvalidSelection.into()),
+        };
+
+        // The data of the fields is decoded again once they're all in place.
+        for (offset, field) in definition.fields.iter().enumerate() {
+            self.add_field_to_view(field, &mut 0);
+            let row_data = self.table_model.take_row(self.table_model.row_count_0a() - 1);
+            self.table_model.insert_row_int_q_list_of_q_standard_item(sequence_row + 1 + offset as i32, &row_data);
+        }
+
+        definition.fields.clear();
+        // Block the signals of the model, so changing the "Field Type" item doesn't trigger a decoding while we're still at it.
+        let mut blocker = QSignalBlocker::from_q_object(self.table_model.static_upcast_mut::<QObject>());
+        Self::set_sequence_definition(self.table_model.item_2a(sequence_row, 1), &definition);
+        blocker.unblock();
+
+        self.update_rows_decoded(&mut index)
+    }
+
     /// This function gets the data from the decoder's table and returns it, so we can save it to a Definition.
     pub unsafe fn get_fields_from_view(&self) -> Vec<Field> {
         let mut fields = vec![];
@@ -1232,7 +1370,7 @@ impl PackedFileDecoderViewRaw {
 
                 // Get the data from each field of the row...
                 let field_name = self.table_model.item_2a(row, 0).text().to_std_string();
-                let field_type = self.table_model.item_2a(row, 1).text().to_std_string();
+                let field_type = self.get_field_type_from_view(row);
                 let field_is_key = self.table_model.item_2a(row, 3).check_state() == CheckState::Checked;
                 let ref_table = self.table_model.item_2a(row, 4).text().to_std_string();
                 let ref_column = self.table_model.item_2a(row, 5).text().to_std_string();
@@ -1243,20 +1381,6 @@ impl PackedFileDecoderViewRaw {
                 let field_ca_order = self.table_model.item_2a(row, 11).text().to_std_string().parse::<i16>().unwrap();
                 let field_description = self.table_model.item_2a(row, 12).text().to_std_string();
 
-                // Get the proper type of the field. If invalid, default to OptionalStringU16.
-                let field_type = match &*field_type {
-                    "Bool" => FieldType::Boolean,
-                    "Float" => FieldType::Float,
-                    "Integer" => FieldType::Integer,
-                    "LongInteger" => FieldType::LongInteger,
-                    "StringU8" => FieldType::StringU8,
-                    "StringU16" => FieldType::StringU16,
-                    "OptionalStringU8" => FieldType::OptionalStringU8,
-                    "OptionalStringU16" => FieldType::OptionalStringU16,
-                    "Sequence" => FieldType::Sequence(Definition::new(-1)),
-                    _ => unimplemented!()
-                };
-
                 let field_is_reference = if !ref_table.is_empty() && !ref_column.is_empty() {
                     Some((ref_table, ref_column))
                 } else { None };
@@ -1311,10 +1435,21 @@ impl PackedFileDecoderViewRaw {
         match versioned_file {
             Ok(versioned_file) => {
                 match versioned_file.get_ref_mut_version(version) {
-                    Ok(definition) => definition.fields = fields,
+                    Ok(definition) => {
+
+                        // Value sets cannot be edited in the decoder, so keep the ones of the fields we already had.
+                        let mut fields = fields;
+                        for field in &mut fields {
+                            if let Some(old_field) = definition.fields.iter().find(|x| x.name == field.name) {
+                                field.enum_values = old_field.enum_values.clone();
+                            }
+                        }
+                        definition.fields = fields;
+                    }
                     Err(_) => {
                         let mut definition = Definition::new(version);
                         definition.fields = fields;
+                        definition.provenance = Some(DefinitionProvenance::new(DefinitionSource::ManualDecode));
                         versioned_file.add_version(&definition);
                     }
                 }
@@ -1322,6 +1457,7 @@ impl PackedFileDecoderViewRaw {
             Err(_) => {
                 let mut definition = Definition::new(version);
                 definition.fields = fields;
+                definition.provenance = Some(DefinitionProvenance::new(DefinitionSource::ManualDecode));
 
                 let definitions = vec![definition];
                 let versioned_file = match self.packed_file_type {
@@ -1337,6 +1473,40 @@ impl PackedFileDecoderViewRaw {
 
         schema
     }
+
+    /// This function creates the dialog to choose one of the provided guessed definitions, best guesses first.
+    ///
+    /// It returns the index of the chosen guess, or None if the dialog is canceled.
+    unsafe fn create_guess_definition_dialog(&self, guesses: &[DefinitionGuess]) -> Option<usize> {
+        let mut dialog = QDialog::new_1a(self.table_view);
+        dialog.set_window_title(&QString::from_std_str("Guess Definition"));
+        dialog.set_modal(true);
+        dialog.resize_2a(500, 50);
+        let mut main_grid = create_grid_layout(dialog.as_mut_ptr().static_upcast_mut());
+
+        let mut guesses_label = QLabel::from_q_string(&QString::from_std_str("Definitions that decode the entire table, best guesses first:"));
+        let mut guesses_combobox = QComboBox::new_0a();
+        for (index, guess) in guesses.iter().enumerate() {
+            let similar_version = match guess.similar_version {
+                Some(version) => format!(", similar to version {} ({:.2})", version, guess.similarity),
+                None => String::new(),
+            };
+
+            let fields = guess.fields.iter().map(|field| field.field_type.to_string()).collect::<Vec<String>>().join(", ");
+            let text = format!("{}. Score: {:.2}, plausibility: {:.2}{}. Fields: {}", index + 1, guess.score, guess.plausibility, similar_version, fields);
+            guesses_combobox.add_item_q_string(&QString::from_std_str(&text));
+        }
+
+        let mut accept_button = QPushButton::from_q_string(&QString::from_std_str("Use this one"));
+
+        main_grid.add_widget_5a(&mut guesses_label, 0, 0, 1, 1);
+        main_grid.add_widget_5a(&mut guesses_combobox, 1, 0, 1, 1);
+        main_grid.add_widget_5a(&mut accept_button, 2, 0, 1, 1);
+
+        accept_button.released().connect(dialog.slot_accept());
+
+        if dialog.exec() == 1 { Some(guesses_combobox.current_index() as usize) } else { None }
+    }
 }
 
 /// This function returns the header size (or first byte after the header) of the provided PackedFile.
@@ -38,6 +38,7 @@ use crate::UI_STATE;
 
 use super::get_definition;
 use super::get_header_size;
+use super::MAX_DEFINITION_GUESSES;
 use super::PackedFileDecoderViewRaw;
 use super::PackedFileDecoderMutableData;
 
@@ -65,6 +66,8 @@ pub struct PackedFileDecoderViewSlots {
     pub table_view_context_menu_move_up: SlotOfBool<'static>,
     pub table_view_context_menu_move_down: SlotOfBool<'static>,
     pub table_view_context_menu_delete: SlotOfBool<'static>,
+    pub table_view_context_menu_move_into_sequence: SlotOfBool<'static>,
+    pub table_view_context_menu_extract_from_sequence: SlotOfBool<'static>,
 
     pub table_view_context_menu: SlotOfQPoint<'static>,
     pub table_view_context_menu_enabler: SlotOfQItemSelectionQItemSelection<'static>,
@@ -76,6 +79,7 @@ pub struct PackedFileDecoderViewSlots {
     pub table_view_old_versions_context_menu_delete: SlotOfBool<'static>,
 
     pub test_definition: Slot<'static>,
+    pub guess_definition: Slot<'static>,
     pub remove_all_fields: Slot<'static>,
     pub save_definition: Slot<'static>,
 }
@@ -253,6 +257,26 @@ impl PackedFileDecoderViewSlots {
             }
         ));
 
+        // Slots for the "Move Into Sequence" contextual action of the TableView.
+        let table_view_context_menu_move_into_sequence = SlotOfBool::new(clone!(
+            mut mutable_data,
+            mut view => move |_| {
+                if let Err(error) = view.move_into_sequence(&mut mutable_data.index.lock().unwrap()) {
+                    show_dialog(view.table_view, error, false);
+                }
+            }
+        ));
+
+        // Slots for the "Extract From Sequence" contextual action of the TableView.
+        let table_view_context_menu_extract_from_sequence = SlotOfBool::new(clone!(
+            mut mutable_data,
+            mut view => move |_| {
+                if let Err(error) = view.extract_from_sequence(&mut mutable_data.index.lock().unwrap()) {
+                    show_dialog(view.table_view, error, false);
+                }
+            }
+        ));
+
         // Slot to show the Contextual Menu for the fields table view.
         let table_view_context_menu = SlotOfQPoint::new(clone!(
             mut view => move |_| {
@@ -268,6 +292,8 @@ impl PackedFileDecoderViewSlots {
                     view.table_view_context_menu_move_up.set_enabled(true);
                     view.table_view_context_menu_move_down.set_enabled(true);
                     view.table_view_context_menu_delete.set_enabled(true);
+                    view.table_view_context_menu_move_into_sequence.set_enabled(true);
+                    view.table_view_context_menu_extract_from_sequence.set_enabled(true);
                 }
 
                 // Otherwise, disable everything.
@@ -275,6 +301,8 @@ impl PackedFileDecoderViewSlots {
                     view.table_view_context_menu_move_up.set_enabled(false);
                     view.table_view_context_menu_move_down.set_enabled(false);
                     view.table_view_context_menu_delete.set_enabled(false);
+                    view.table_view_context_menu_move_into_sequence.set_enabled(false);
+                    view.table_view_context_menu_extract_from_sequence.set_enabled(false);
                 }
             }
         ));
@@ -387,6 +415,36 @@ impl PackedFileDecoderViewSlots {
             }
         ));
 
+        // Slot for the "Guess Definition" button. It replaces the current definition with the guess we choose from the best ones we can find.
+        let guess_definition = Slot::new(clone!(
+            mut app_ui,
+            mut mutable_data,
+            mut view => move || {
+
+                // Guessing can take a while with big tables, so do it in the background.
+                app_ui.main_window.set_enabled(false);
+                CENTRAL_COMMAND.send_message_qt(Command::GuessDefinitions(view.packed_file_data.to_vec(), view.packed_file_path[1].to_owned(), MAX_DEFINITION_GUESSES));
+                let response = CENTRAL_COMMAND.recv_message_qt_try();
+                app_ui.main_window.set_enabled(true);
+
+                match response {
+                    Response::VecDefinitionGuess(guesses) => {
+                        if guesses.is_empty() {
+                            return show_dialog(view.table_view, "No definition found that decodes the entire table.", false);
+                        }
+
+                        if let Some(guess) = view.create_guess_definition_dialog(&guesses).and_then(|index| guesses.get(index)) {
+                            view.table_model.clear();
+                            *mutable_data.index.lock().unwrap() = get_header_size(view.packed_file_type, &view.packed_file_data).unwrap();
+                            let _ = view.update_view(&guess.fields, true, &mut mutable_data.index.lock().unwrap());
+                        }
+                    }
+                    Response::Error(error) => show_dialog(view.table_view, error, false),
+                    _ => panic!("{}{:?}", THREADS_COMMUNICATION_ERROR, response),
+                }
+            }
+        ));
+
         // Slot for the "Kill them all!" button.
         let remove_all_fields = Slot::new(clone!(
             mut mutable_data,
@@ -455,6 +513,8 @@ impl PackedFileDecoderViewSlots {
             table_view_context_menu_move_up,
             table_view_context_menu_move_down,
             table_view_context_menu_delete,
+            table_view_context_menu_move_into_sequence,
+            table_view_context_menu_extract_from_sequence,
 
             table_view_context_menu,
             table_view_context_menu_enabler,
@@ -466,6 +526,7 @@ impl PackedFileDecoderViewSlots {
             table_view_old_versions_context_menu_delete,
 
             test_definition,
+            guess_definition,
             remove_all_fields,
             save_definition,
         }
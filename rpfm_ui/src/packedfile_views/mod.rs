@@ -36,6 +36,7 @@ use crate::CENTRAL_COMMAND;
 use crate::communications::{Command, Response, THREADS_COMMUNICATION_ERROR};
 use crate::ffi::get_text_safe;
 use crate::global_search_ui::GlobalSearchUI;
+use crate::locale::tre;
 use crate::QString;
 use crate::pack_tree::*;
 use crate::packfile_contents_ui::PackFileContentsUI;
@@ -220,8 +221,8 @@ impl PackedFileView {
                                     FieldType::OptionalStringU8 => DecodedData::OptionalStringU8(QString::to_std_string(&model.item_2a(row as i32, column as i32).text())),
                                     FieldType::OptionalStringU16 => DecodedData::OptionalStringU16(QString::to_std_string(&model.item_2a(row as i32, column as i32).text())),
 
-                                    // Sequences in the UI are not yet supported.
-                                    FieldType::Sequence(_) => return Err(ErrorKind::PackedFileSaveError(self.get_path()).into()),
+                                    // Sequences are edited as text, so they need parsing, and this can fail.
+                                    FieldType::Sequence(_) => DecodedData::new_from_type_and_string(&field.field_type, &QString::to_std_string(&model.item_2a(row as i32, column as i32).text()))?,
                                 };
                                 new_row.push(item);
                             }
@@ -304,6 +305,41 @@ impl PackedFileView {
                         Ok(())
                     }
 
+                    // If we got paths back, it's the Loc PackedFiles created and edited to keep the saved table localised.
+                    Response::VecVecStringVecVecString((added_paths, edited_paths)) => {
+                        if !added_paths.is_empty() {
+                            let paths = added_paths.iter().map(|x| TreePathType::File(x.to_vec())).collect::<Vec<TreePathType>>();
+                            pack_file_contents_ui.packfile_contents_tree_view.update_treeview(true, TreeViewOperation::Add(paths.to_vec()));
+                            pack_file_contents_ui.packfile_contents_tree_view.update_treeview(true, TreeViewOperation::MarkAlwaysModified(paths));
+                        }
+
+                        if !edited_paths.is_empty() {
+                            let paths = edited_paths.iter().map(|x| TreePathType::File(x.to_vec())).collect::<Vec<TreePathType>>();
+                            pack_file_contents_ui.packfile_contents_tree_view.update_treeview(true, TreeViewOperation::Modify(paths));
+                        }
+
+                        if !added_paths.is_empty() || !edited_paths.is_empty() {
+                            UI_STATE.set_is_modified(true, app_ui, pack_file_contents_ui);
+                        }
+
+                        // If we have a GlobalSearch on, update the results for this specific PackedFile and its Locs.
+                        let global_search = UI_STATE.get_global_search();
+                        if !global_search.pattern.is_empty() {
+                            let mut path_types = vec![PathType::File(self.get_path())];
+                            path_types.extend(added_paths.iter().chain(edited_paths.iter()).map(|x| PathType::File(x.to_vec())));
+                            global_search_ui.search_on_path(&mut pack_file_contents_ui, path_types);
+                            UI_STATE.set_global_search(&global_search);
+                        }
+
+                        Ok(())
+                    }
+
+                    // If the table got saved but we failed to update its Locs, report it without failing the save.
+                    Response::Error(error) => {
+                        show_dialog(pack_file_contents_ui.packfile_contents_tree_view, error, false);
+                        Ok(())
+                    }
+
                     // In ANY other situation, it's a message problem.
                     _ => panic!("{}{:?}", THREADS_COMMUNICATION_ERROR, response),
                 }
@@ -313,6 +349,11 @@ impl PackedFileView {
                 let response = CENTRAL_COMMAND.recv_message_qt_try();
                 match response {
                     Response::Success => {},
+
+                    // Tables return the columns of the external file that are not in their definition.
+                    Response::VecString(extra_columns) => if !extra_columns.is_empty() {
+                        show_dialog(pack_file_contents_ui.packfile_contents_tree_view, tre("import_tsv_extra_columns", &[&extra_columns.join(", ")]), true);
+                    },
                     Response::Error(error) => show_dialog(pack_file_contents_ui.packfile_contents_tree_view, error, false),
                     _ => panic!("{}{:?}", THREADS_COMMUNICATION_ERROR, response),
                 }
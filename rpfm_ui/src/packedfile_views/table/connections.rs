@@ -42,6 +42,11 @@ pub unsafe fn set_connections(ui: &PackedFileTableView, slots: &PackedFileTableV
     ui.get_mut_ptr_context_menu_invert_selection().triggered().connect(&slots.invert_selection);
     ui.get_mut_ptr_context_menu_reset_selection().triggered().connect(&slots.reset_selection);
     ui.get_mut_ptr_context_menu_rewrite_selection().triggered().connect(&slots.rewrite_selection);
+    ui.get_mut_ptr_context_menu_apply_expression().triggered().connect(&slots.apply_expression);
+    ui.get_mut_ptr_context_menu_rename_key_cascade().triggered().connect(&slots.rename_key_cascade);
+    ui.get_mut_ptr_context_menu_find_references().triggered().connect(&slots.find_references);
+    ui.get_mut_ptr_context_menu_undo_key_rename().triggered().connect(&slots.undo_key_rename);
+    ui.get_mut_ptr_context_menu_redo_key_rename().triggered().connect(&slots.redo_key_rename);
     ui.get_mut_ptr_context_menu_undo().triggered().connect(&slots.undo);
     ui.get_mut_ptr_context_menu_redo().triggered().connect(&slots.redo);
     ui.get_mut_ptr_context_menu_import_tsv().triggered().connect(&slots.import_tsv);
@@ -160,6 +160,11 @@ pub struct PackedFileTableView {
     context_menu_invert_selection: AtomicPtr<QAction>,
     context_menu_reset_selection: AtomicPtr<QAction>,
     context_menu_rewrite_selection: AtomicPtr<QAction>,
+    context_menu_apply_expression: AtomicPtr<QAction>,
+    context_menu_rename_key_cascade: AtomicPtr<QAction>,
+    context_menu_find_references: AtomicPtr<QAction>,
+    context_menu_undo_key_rename: AtomicPtr<QAction>,
+    context_menu_redo_key_rename: AtomicPtr<QAction>,
     context_menu_undo: AtomicPtr<QAction>,
     context_menu_redo: AtomicPtr<QAction>,
     context_menu_import_tsv: AtomicPtr<QAction>,
@@ -311,6 +316,9 @@ impl PackedFileTableView {
         let context_menu_paste = context_menu.add_action_q_string(&qtr("context_menu_paste"));
 
         let context_menu_rewrite_selection = context_menu.add_action_q_string(&qtr("context_menu_rewrite_selection"));
+        let context_menu_apply_expression = context_menu.add_action_q_string(&qtr("context_menu_apply_expression"));
+        let context_menu_rename_key_cascade = context_menu.add_action_q_string(&qtr("context_menu_rename_key_cascade"));
+        let context_menu_find_references = context_menu.add_action_q_string(&qtr("context_menu_find_references"));
         let context_menu_invert_selection = context_menu.add_action_q_string(&qtr("context_menu_invert_selection"));
         let context_menu_reset_selection = context_menu.add_action_q_string(&qtr("context_menu_reset_selection"));
         let context_menu_resize_columns = context_menu.add_action_q_string(&qtr("context_menu_resize_columns"));
@@ -323,6 +331,8 @@ impl PackedFileTableView {
 
         let context_menu_undo = context_menu.add_action_q_string(&qtr("context_menu_undo"));
         let context_menu_redo = context_menu.add_action_q_string(&qtr("context_menu_redo"));
+        let context_menu_undo_key_rename = context_menu.add_action_q_string(&qtr("context_menu_undo_key_rename"));
+        let context_menu_redo_key_rename = context_menu.add_action_q_string(&qtr("context_menu_redo_key_rename"));
 
         // Insert some separators to space the menu, and the paste submenu.
         context_menu.insert_menu(context_menu_paste, context_menu_clone_submenu.into_ptr());
@@ -331,6 +341,7 @@ impl PackedFileTableView {
         context_menu.insert_separator(context_menu_import_tsv);
         context_menu.insert_separator(context_menu_search);
         context_menu.insert_separator(context_menu_undo);
+        context_menu.insert_separator(context_menu_undo_key_rename);
 
         //--------------------------------------------------//
         // Search Section.
@@ -463,6 +474,11 @@ impl PackedFileTableView {
             context_menu_invert_selection,
             context_menu_reset_selection,
             context_menu_rewrite_selection,
+            context_menu_apply_expression,
+            context_menu_rename_key_cascade,
+            context_menu_find_references,
+            context_menu_undo_key_rename,
+            context_menu_redo_key_rename,
             context_menu_undo,
             context_menu_redo,
             context_menu_import_tsv,
@@ -527,6 +543,11 @@ impl PackedFileTableView {
             context_menu_invert_selection: atomic_from_mut_ptr(packed_file_table_view_raw.context_menu_invert_selection),
             context_menu_reset_selection: atomic_from_mut_ptr(packed_file_table_view_raw.context_menu_reset_selection),
             context_menu_rewrite_selection: atomic_from_mut_ptr(packed_file_table_view_raw.context_menu_rewrite_selection),
+            context_menu_apply_expression: atomic_from_mut_ptr(packed_file_table_view_raw.context_menu_apply_expression),
+            context_menu_rename_key_cascade: atomic_from_mut_ptr(packed_file_table_view_raw.context_menu_rename_key_cascade),
+            context_menu_find_references: atomic_from_mut_ptr(packed_file_table_view_raw.context_menu_find_references),
+            context_menu_undo_key_rename: atomic_from_mut_ptr(packed_file_table_view_raw.context_menu_undo_key_rename),
+            context_menu_redo_key_rename: atomic_from_mut_ptr(packed_file_table_view_raw.context_menu_redo_key_rename),
             context_menu_undo: atomic_from_mut_ptr(packed_file_table_view_raw.context_menu_undo),
             context_menu_redo: atomic_from_mut_ptr(packed_file_table_view_raw.context_menu_redo),
             context_menu_import_tsv: atomic_from_mut_ptr(packed_file_table_view_raw.context_menu_import_tsv),
@@ -745,6 +766,31 @@ impl PackedFileTableView {
         mut_ptr_from_atomic(&self.context_menu_rewrite_selection)
     }
 
+    /// This function returns a pointer to the apply expression action.
+    pub fn get_mut_ptr_context_menu_apply_expression(&self) -> MutPtr<QAction> {
+        mut_ptr_from_atomic(&self.context_menu_apply_expression)
+    }
+
+    /// This function returns a pointer to the rename key cascade action.
+    pub fn get_mut_ptr_context_menu_rename_key_cascade(&self) -> MutPtr<QAction> {
+        mut_ptr_from_atomic(&self.context_menu_rename_key_cascade)
+    }
+
+    /// This function returns a mutable reference to the `Find References` action.
+    pub fn get_mut_ptr_context_menu_find_references(&self) -> MutPtr<QAction> {
+        mut_ptr_from_atomic(&self.context_menu_find_references)
+    }
+
+    /// This function returns a pointer to the undo key rename action.
+    pub fn get_mut_ptr_context_menu_undo_key_rename(&self) -> MutPtr<QAction> {
+        mut_ptr_from_atomic(&self.context_menu_undo_key_rename)
+    }
+
+    /// This function returns a pointer to the redo key rename action.
+    pub fn get_mut_ptr_context_menu_redo_key_rename(&self) -> MutPtr<QAction> {
+        mut_ptr_from_atomic(&self.context_menu_redo_key_rename)
+    }
+
     /// This function returns a pointer to the undo action.
     pub fn get_mut_ptr_context_menu_undo(&self) -> MutPtr<QAction> {
         mut_ptr_from_atomic(&self.context_menu_undo)
@@ -46,9 +46,13 @@ use std::collections::BTreeMap;
 use std::sync::{Arc, RwLock, RwLockReadGuard};
 use std::sync::atomic::{AtomicBool, Ordering};
 
+use rpfm_lib::packedfile::table::expression::Expression;
+use rpfm_lib::packfile::PathType;
 use rpfm_lib::schema::Definition;
 
+use crate::locale::{tr, tre};
 use crate::utils::{atomic_from_mut_ptr, create_grid_layout, mut_ptr_from_atomic, log_to_status_bar};
+use crate::UI_STATE;
 use crate::pack_tree::*;
 use super::*;
 
@@ -85,6 +89,11 @@ pub struct PackedFileTableViewRaw {
     pub context_menu_invert_selection: MutPtr<QAction>,
     pub context_menu_reset_selection: MutPtr<QAction>,
     pub context_menu_rewrite_selection: MutPtr<QAction>,
+    pub context_menu_apply_expression: MutPtr<QAction>,
+    pub context_menu_rename_key_cascade: MutPtr<QAction>,
+    pub context_menu_find_references: MutPtr<QAction>,
+    pub context_menu_undo_key_rename: MutPtr<QAction>,
+    pub context_menu_redo_key_rename: MutPtr<QAction>,
     pub context_menu_undo: MutPtr<QAction>,
     pub context_menu_redo: MutPtr<QAction>,
     pub context_menu_import_tsv: MutPtr<QAction>,
@@ -160,6 +169,13 @@ impl PackedFileTableViewRaw {
             self.context_menu_delete_rows.set_enabled(false);
         }
 
+        // Key renames only work with a single key cell of a DB Table selected.
+        let is_key_selected = indexes.count_0a() == 1 &&
+            self.packed_file_path.read().unwrap().get(0).map_or(false, |x| x == "db") &&
+            self.get_ref_table_definition().fields.get(indexes.at(0).column() as usize).map_or(false, |x| x.is_key);
+        self.context_menu_rename_key_cascade.set_enabled(is_key_selected);
+        self.context_menu_find_references.set_enabled(is_key_selected);
+
         if !self.undo_lock.load(Ordering::SeqCst) {
             self.context_menu_undo.set_enabled(!self.history_undo.read().unwrap().is_empty());
             self.context_menu_redo.set_enabled(!self.history_redo.read().unwrap().is_empty());
@@ -371,6 +387,182 @@ impl PackedFileTableViewRaw {
         }
     }
 
+    /// This function sets the column chosen in the "Apply Expression" dialog to the result of its expression, in every row matching its filter.
+    pub unsafe fn apply_expression(&self) {
+        if let Some((column, expression, filter)) = self.create_apply_expression_dialog() {
+            let definition = self.get_ref_table_definition().clone();
+            let expression = match Expression::parse(&expression, &definition) {
+                Ok(expression) => expression,
+                Err(error) => return show_dialog(self.table_view_primary, error, false),
+            };
+
+            let filter = if filter.trim().is_empty() { None } else {
+                match Expression::parse(&filter, &definition) {
+                    Ok(filter) => Some(filter),
+                    Err(error) => return show_dialog(self.table_view_primary, error, false),
+                }
+            };
+
+            // Evaluate all the rows before touching the model, so if the expression fails in any of them, no cell is changed.
+            let field_type = &definition.fields[column].field_type;
+            let mut new_values = vec![];
+            for row in 0..self.table_model.row_count_0a() {
+                let row_data = self.get_row_data(row, &definition);
+                if let Some(ref filter) = filter {
+                    match filter.is_match(&row_data) {
+                        Ok(true) => {},
+                        Ok(false) => continue,
+                        Err(error) => return show_dialog(self.table_view_primary, error, false),
+                    }
+                }
+
+                match expression.evaluate_as(&row_data, field_type) {
+                    Ok(value) => if value != row_data[column] { new_values.push((row, value)); },
+                    Err(error) => return show_dialog(self.table_view_primary, error, false),
+                }
+            }
+
+            let changed_cells = new_values.len();
+            for (row, value) in new_values {
+                let mut item = self.table_model.item_2a(row, column as i32);
+                match value {
+                    DecodedData::Boolean(value) => item.set_check_state(if value { CheckState::Checked } else { CheckState::Unchecked }),
+                    DecodedData::Float(value) => item.set_data_2a(&QVariant::from_float(value), 2),
+                    DecodedData::Integer(value) => item.set_data_2a(&QVariant::from_int(value), 2),
+                    DecodedData::LongInteger(value) => item.set_data_2a(&QVariant::from_i64(value), 2),
+                    _ => item.set_text(&QString::from_std_str(&value.data_to_string())),
+                }
+            }
+
+            // Fix the undo history to have all the previous changed merged into one.
+            if changed_cells > 0 {
+                {
+                    let mut history_undo = self.history_undo.write().unwrap();
+                    let mut history_redo = self.history_redo.write().unwrap();
+
+                    let len = history_undo.len();
+                    let mut edits_data = vec![];
+                    {
+                        let mut edits = history_undo.drain((len - changed_cells)..);
+                        for edit in &mut edits {
+                            if let TableOperations::Editing(mut edit) = edit {
+                                edits_data.append(&mut edit);
+                            }
+                        }
+                    }
+
+                    history_undo.push(TableOperations::Editing(edits_data));
+                    history_redo.clear();
+                }
+                update_undo_model(self.table_model, self.undo_model);
+            }
+        }
+    }
+
+    /// This function renames the selected key in every PackedFile referencing it, as a single change that can be undone with "Undo Key Rename".
+    pub unsafe fn rename_key_cascade(&self, app_ui: &mut AppUI, pack_file_contents_ui: &mut PackFileContentsUI, global_search_ui: GlobalSearchUI) {
+        if let Some((column_name, old_key, new_key, rename_on_texts)) = self.create_rename_key_cascade_dialog() {
+
+            // The rename is done in the backend, so we need to save the open PackedFiles first to not lose their changes.
+            if let Err(error) = Self::save_open_packedfiles(app_ui, pack_file_contents_ui, global_search_ui) {
+                return show_dialog(self.table_view_primary, error, false);
+            }
+
+            let table_name = self.packed_file_path.read().unwrap()[1].to_owned();
+            CENTRAL_COMMAND.send_message_qt(Command::RenameKeyCascade(table_name, column_name, old_key.to_owned(), new_key, rename_on_texts));
+            let response = CENTRAL_COMMAND.recv_message_qt();
+            match response {
+                Response::VecVecString(paths) => {
+                    if paths.is_empty() { show_dialog(self.table_view_primary, tre("rename_key_cascade_nothing_renamed", &[&old_key]), false) }
+                    else { Self::reload_after_key_rename(&paths, app_ui, pack_file_contents_ui, global_search_ui) }
+                }
+                Response::Error(error) => show_dialog(self.table_view_primary, error, false),
+                _ => panic!("{}{:?}", THREADS_COMMUNICATION_ERROR, response),
+            }
+        }
+    }
+
+    /// This function shows the rows of the PackFile and the game files referencing the selected key.
+    pub unsafe fn find_references(&self, app_ui: &mut AppUI, pack_file_contents_ui: &mut PackFileContentsUI, global_search_ui: GlobalSearchUI) {
+        let indexes = self.table_filter.map_selection_to_source(&self.table_view_primary.selection_model().selection()).indexes();
+        if indexes.count_0a() != 1 { return }
+        let index = indexes.at(0);
+        let definition = self.get_ref_table_definition().clone();
+        let key = match self.get_row_data(index.row(), &definition).get(index.column() as usize) {
+            Some(data) => data.data_to_string(),
+            None => return,
+        };
+
+        // The search is done in the backend, so we need to save the open PackedFiles first to search on their current data.
+        if let Err(error) = Self::save_open_packedfiles(app_ui, pack_file_contents_ui, global_search_ui) {
+            return show_dialog(self.table_view_primary, error, false);
+        }
+
+        let table_name = self.packed_file_path.read().unwrap()[1].to_owned();
+        CENTRAL_COMMAND.send_message_qt(Command::FindReferences(table_name, key.to_owned()));
+        let response = CENTRAL_COMMAND.recv_message_qt();
+        match response {
+            Response::VecDBReference(references) => {
+                if references.is_empty() { show_dialog(self.table_view_primary, tre("find_references_none", &[&key]), true) }
+                else {
+                    let references = references.iter().map(|reference| {
+                        let entry = if reference.is_from_game_files { "find_references_entry_game_files" } else { "find_references_entry" };
+                        format!("<li>{}</li>", tre(entry, &[&reference.path.join("/"), &reference.column_name, &(reference.row_number + 1).to_string()]))
+                    }).collect::<String>();
+                    show_dialog(self.table_view_primary, format!("<p>{}</p><ul>{}</ul>", tre("find_references_found", &[&key]), references), true)
+                }
+            }
+            Response::Error(error) => show_dialog(self.table_view_primary, error, false),
+            _ => panic!("{}{:?}", THREADS_COMMUNICATION_ERROR, response),
+        }
+    }
+
+    /// This function undoes the last key rename or, if `undo` is false, redoes the last undone one.
+    ///
+    /// Each key rename is undone as a whole, restoring all the PackedFiles it changed to their data before the rename.
+    pub unsafe fn undo_redo_key_rename(&self, undo: bool, app_ui: &mut AppUI, pack_file_contents_ui: &mut PackFileContentsUI, global_search_ui: GlobalSearchUI) {
+        if let Err(error) = Self::save_open_packedfiles(app_ui, pack_file_contents_ui, global_search_ui) {
+            return show_dialog(self.table_view_primary, error, false);
+        }
+
+        CENTRAL_COMMAND.send_message_qt(if undo { Command::UndoKeyRename } else { Command::RedoKeyRename });
+        let response = CENTRAL_COMMAND.recv_message_qt();
+        match response {
+            Response::VecVecString(paths) => {
+                if paths.is_empty() { show_dialog(self.table_view_primary, tr(if undo { "key_rename_nothing_to_undo" } else { "key_rename_nothing_to_redo" }), false) }
+                else { Self::reload_after_key_rename(&paths, app_ui, pack_file_contents_ui, global_search_ui) }
+            }
+            Response::Error(error) => show_dialog(self.table_view_primary, error, false),
+            _ => panic!("{}{:?}", THREADS_COMMUNICATION_ERROR, response),
+        }
+    }
+
+    /// This function saves all the open PackedFiles to the backend.
+    unsafe fn save_open_packedfiles(app_ui: &mut AppUI, pack_file_contents_ui: &mut PackFileContentsUI, global_search_ui: GlobalSearchUI) -> Result<()> {
+        UI_STATE.get_open_packedfiles().iter().try_for_each(|packed_file| packed_file.save(app_ui, global_search_ui, pack_file_contents_ui))
+    }
+
+    /// This function updates the UI after a key rename changed the provided PackedFiles, reloading those that are open.
+    unsafe fn reload_after_key_rename(paths: &[Vec<String>], app_ui: &mut AppUI, pack_file_contents_ui: &mut PackFileContentsUI, mut global_search_ui: GlobalSearchUI) {
+        let item_types = paths.iter().map(|x| TreePathType::File(x.to_vec())).collect::<Vec<TreePathType>>();
+        pack_file_contents_ui.packfile_contents_tree_view.update_treeview(true, TreeViewOperation::Modify(item_types.to_vec()));
+        pack_file_contents_ui.packfile_contents_tree_view.update_treeview(true, TreeViewOperation::MarkAlwaysModified(item_types));
+        UI_STATE.set_is_modified(true, app_ui, pack_file_contents_ui);
+
+        let path_types = paths.iter().map(|x| PathType::File(x.to_vec())).collect::<Vec<PathType>>();
+        global_search_ui.search_on_path(pack_file_contents_ui, path_types);
+
+        // Try to reload all open files which data we altered, and close those that failed.
+        let mut open_packedfiles = UI_STATE.set_open_packedfiles();
+        for path in paths {
+            if let Some(packed_file_view) = open_packedfiles.iter_mut().find(|x| *x.get_ref_path() == *path) {
+                if packed_file_view.reload(path, pack_file_contents_ui).is_err() {
+                    app_ui.purge_that_one_specifically(global_search_ui, *pack_file_contents_ui, path, false);
+                }
+            }
+        }
+    }
+
     /// This function copies the selected cells into the clipboard as a TSV file, so you can paste them in other programs.
     pub unsafe fn copy_selection(&self) {
 
@@ -748,8 +940,8 @@ impl PackedFileTableViewRaw {
                         FieldType::OptionalStringU8 |
                         FieldType::OptionalStringU16 => true,
 
-                        // Ignore sequences.
-                        FieldType::Sequence(_) => false,
+                        // Sequences need to be valid for their definition.
+                        FieldType::Sequence(_) => DecodedData::new_from_type_and_string(&field.field_type, text).is_ok(),
                     };
 
                     // If it's valid, add it to the real_cells list.
@@ -1164,12 +1356,12 @@ impl PackedFileTableViewRaw {
             FieldType::Integer |
             FieldType::LongInteger => format!("{}", item.data_1a(2).to_long_long_0a()),
 
-            // All these are Strings, so they need to escape certain chars and include commas in Lua.
+            // All these are Strings (Sequences are stored as text), so they need to escape certain chars and include commas in Lua.
             FieldType::StringU8 |
             FieldType::StringU16 |
             FieldType::OptionalStringU8 |
-            FieldType::OptionalStringU16 => format!("\"{}\"", item.text().to_std_string().escape_default().to_string()),
-            FieldType::Sequence(_) => "\"Sequence\"".to_owned(),
+            FieldType::OptionalStringU16 |
+            FieldType::Sequence(_) => format!("\"{}\"", item.text().to_std_string().escape_default().to_string()),
         }
     }
 
@@ -1353,4 +1545,130 @@ impl PackedFileTableViewRaw {
             if new_text.is_empty() { None } else { Some((is_math_op.is_checked(), rewrite_sequence_line_edit.text().to_std_string())) }
         } else { None }
     }
+
+    /// This function returns the data of a row of the model, decoded using the provided definition.
+    ///
+    /// Sequences that are not valid are returned empty.
+    unsafe fn get_row_data(&self, row: i32, definition: &Definition) -> Vec<DecodedData> {
+        definition.fields.iter().enumerate().map(|(column, field)| {
+            let item = self.table_model.item_2a(row, column as i32);
+            match field.field_type {
+                FieldType::Boolean => DecodedData::Boolean(item.check_state() == CheckState::Checked),
+                FieldType::Float => DecodedData::Float(item.data_1a(2).to_float_0a()),
+                FieldType::Integer => DecodedData::Integer(item.data_1a(2).to_int_0a()),
+                FieldType::LongInteger => DecodedData::LongInteger(item.data_1a(2).to_long_long_0a()),
+                FieldType::StringU8 => DecodedData::StringU8(item.text().to_std_string()),
+                FieldType::StringU16 => DecodedData::StringU16(item.text().to_std_string()),
+                FieldType::OptionalStringU8 => DecodedData::OptionalStringU8(item.text().to_std_string()),
+                FieldType::OptionalStringU16 => DecodedData::OptionalStringU16(item.text().to_std_string()),
+                FieldType::Sequence(_) => DecodedData::new_from_type_and_string(&field.field_type, &item.text().to_std_string())
+                    .unwrap_or_else(|_| DecodedData::default(&field.field_type)),
+            }
+        }).collect()
+    }
+
+    /// This function creates the entire "Apply Expression" dialog for tables. It returns the column, the expression and the filter, or None.
+    pub unsafe fn create_apply_expression_dialog(&self) -> Option<(usize, String, String)> {
+
+        // Create and configure the dialog.
+        let mut dialog = QDialog::new_1a(self.table_view_primary);
+        dialog.set_window_title(&qtr("apply_expression_title"));
+        dialog.set_modal(true);
+        dialog.resize_2a(500, 50);
+        let mut main_grid = create_grid_layout(dialog.as_mut_ptr().static_upcast_mut());
+
+        // Create a little frame with some instructions.
+        let instructions_frame = QGroupBox::from_q_string(&qtr("apply_expression_instructions_title")).into_ptr();
+        let mut instructions_grid = create_grid_layout(instructions_frame.static_upcast_mut());
+        let mut instructions_label = QLabel::from_q_string(&qtr("apply_expression_instructions"));
+        instructions_grid.add_widget_5a(&mut instructions_label, 0, 0, 1, 1);
+
+        // The column defaults to the one of the first selected cell.
+        let mut column_label = QLabel::from_q_string(&qtr("apply_expression_column"));
+        let mut column_combobox = QComboBox::new_0a();
+        for field in &self.get_ref_table_definition().fields {
+            column_combobox.add_item_q_string(&QString::from_std_str(&field.name));
+        }
+
+        let indexes = self.table_view_primary.selection_model().selection().indexes();
+        if indexes.count_0a() > 0 {
+            column_combobox.set_current_index(indexes.at(0).column());
+        }
+
+        let mut expression_label = QLabel::from_q_string(&qtr("apply_expression_expression"));
+        let mut expression_line_edit = QLineEdit::new();
+        expression_line_edit.set_placeholder_text(&qtr("apply_expression_expression_placeholder"));
+
+        let mut filter_label = QLabel::from_q_string(&qtr("apply_expression_filter"));
+        let mut filter_line_edit = QLineEdit::new();
+        filter_line_edit.set_placeholder_text(&qtr("apply_expression_filter_placeholder"));
+
+        let mut accept_button = QPushButton::from_q_string(&qtr("apply_expression_accept"));
+
+        main_grid.add_widget_5a(instructions_frame, 0, 0, 1, 2);
+        main_grid.add_widget_5a(&mut column_label, 1, 0, 1, 1);
+        main_grid.add_widget_5a(&mut column_combobox, 1, 1, 1, 1);
+        main_grid.add_widget_5a(&mut expression_label, 2, 0, 1, 1);
+        main_grid.add_widget_5a(&mut expression_line_edit, 2, 1, 1, 1);
+        main_grid.add_widget_5a(&mut filter_label, 3, 0, 1, 1);
+        main_grid.add_widget_5a(&mut filter_line_edit, 3, 1, 1, 1);
+        main_grid.add_widget_5a(&mut accept_button, 4, 1, 1, 1);
+
+        accept_button.released().connect(dialog.slot_accept());
+
+        if dialog.exec() == 1 {
+            let expression = expression_line_edit.text().to_std_string();
+            if expression.is_empty() || column_combobox.current_index() < 0 { None }
+            else { Some((column_combobox.current_index() as usize, expression, filter_line_edit.text().to_std_string())) }
+        } else { None }
+    }
+
+    /// This function creates the entire "Rename Key Everywhere" dialog for tables. It returns the column, the old key, the new key,
+    /// and if the key should be renamed in Text PackedFiles too, or None.
+    pub unsafe fn create_rename_key_cascade_dialog(&self) -> Option<(String, String, String, bool)> {
+
+        // The key to rename is the one in the selected cell.
+        let indexes = self.table_filter.map_selection_to_source(&self.table_view_primary.selection_model().selection()).indexes();
+        if indexes.count_0a() != 1 { return None }
+
+        let index = indexes.at(0);
+        let column = index.column() as usize;
+        let definition = self.get_ref_table_definition().clone();
+        let column_name = definition.fields.get(column)?.name.to_owned();
+        let old_key = self.get_row_data(index.row(), &definition).get(column)?.data_to_string();
+
+        // Create and configure the dialog.
+        let mut dialog = QDialog::new_1a(self.table_view_primary);
+        dialog.set_window_title(&qtr("rename_key_cascade_title"));
+        dialog.set_modal(true);
+        dialog.resize_2a(500, 50);
+        let mut main_grid = create_grid_layout(dialog.as_mut_ptr().static_upcast_mut());
+
+        // Create a little frame with some instructions.
+        let instructions_frame = QGroupBox::from_q_string(&qtr("rename_key_cascade_instructions_title")).into_ptr();
+        let mut instructions_grid = create_grid_layout(instructions_frame.static_upcast_mut());
+        let mut instructions_label = QLabel::from_q_string(&qtr("rename_key_cascade_instructions"));
+        instructions_grid.add_widget_5a(&mut instructions_label, 0, 0, 1, 1);
+
+        let mut new_key_label = QLabel::from_q_string(&qtr("rename_key_cascade_new_key"));
+        let mut new_key_line_edit = QLineEdit::new();
+        new_key_line_edit.set_text(&QString::from_std_str(&old_key));
+
+        let mut rename_on_texts_checkbox = QCheckBox::from_q_string(&qtr("rename_key_cascade_rename_on_texts"));
+        let mut accept_button = QPushButton::from_q_string(&qtr("rename_key_cascade_accept"));
+
+        main_grid.add_widget_5a(instructions_frame, 0, 0, 1, 2);
+        main_grid.add_widget_5a(&mut new_key_label, 1, 0, 1, 1);
+        main_grid.add_widget_5a(&mut new_key_line_edit, 1, 1, 1, 1);
+        main_grid.add_widget_5a(&mut rename_on_texts_checkbox, 2, 0, 1, 2);
+        main_grid.add_widget_5a(&mut accept_button, 3, 1, 1, 1);
+
+        accept_button.released().connect(dialog.slot_accept());
+
+        if dialog.exec() == 1 {
+            let new_key = new_key_line_edit.text().to_std_string();
+            if new_key.is_empty() || new_key == old_key { None }
+            else { Some((column_name, old_key, new_key, rename_on_texts_checkbox.is_checked())) }
+        } else { None }
+    }
 }
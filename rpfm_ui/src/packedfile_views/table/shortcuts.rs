@@ -36,6 +36,11 @@ pub unsafe fn set_shortcuts(ui: &mut PackedFileTableView) {
     ui.get_mut_ptr_context_menu_copy_as_lua_table().set_shortcut(&QKeySequence::from_q_string(&QString::from_std_str(&shortcuts.packed_file_table["copy_as_lua_table"])));
     ui.get_mut_ptr_context_menu_paste().set_shortcut(&QKeySequence::from_q_string(&QString::from_std_str(&shortcuts.packed_file_table["paste"])));
     ui.get_mut_ptr_context_menu_rewrite_selection().set_shortcut(&QKeySequence::from_q_string(&QString::from_std_str(&shortcuts.packed_file_table["rewrite_selection"])));
+    ui.get_mut_ptr_context_menu_apply_expression().set_shortcut(&QKeySequence::from_q_string(&QString::from_std_str(&shortcuts.packed_file_table["apply_expression"])));
+    ui.get_mut_ptr_context_menu_rename_key_cascade().set_shortcut(&QKeySequence::from_q_string(&QString::from_std_str(&shortcuts.packed_file_table["rename_key_cascade"])));
+    ui.get_mut_ptr_context_menu_find_references().set_shortcut(&QKeySequence::from_q_string(&QString::from_std_str(&shortcuts.packed_file_table["find_references"])));
+    ui.get_mut_ptr_context_menu_undo_key_rename().set_shortcut(&QKeySequence::from_q_string(&QString::from_std_str(&shortcuts.packed_file_table["undo_key_rename"])));
+    ui.get_mut_ptr_context_menu_redo_key_rename().set_shortcut(&QKeySequence::from_q_string(&QString::from_std_str(&shortcuts.packed_file_table["redo_key_rename"])));
     ui.get_mut_ptr_context_menu_invert_selection().set_shortcut(&QKeySequence::from_q_string(&QString::from_std_str(&shortcuts.packed_file_table["selection_invert"])));
     ui.get_mut_ptr_context_menu_reset_selection().set_shortcut(&QKeySequence::from_q_string(&QString::from_std_str(&shortcuts.packed_file_table["revert_selection"])));
     ui.get_mut_ptr_context_menu_resize_columns().set_shortcut(&QKeySequence::from_q_string(&QString::from_std_str(&shortcuts.packed_file_table["resize_columns"])));
@@ -57,6 +62,11 @@ pub unsafe fn set_shortcuts(ui: &mut PackedFileTableView) {
     ui.get_mut_ptr_context_menu_copy_as_lua_table().set_shortcut_context(ShortcutContext::WidgetShortcut);
     ui.get_mut_ptr_context_menu_paste().set_shortcut_context(ShortcutContext::WidgetShortcut);
     ui.get_mut_ptr_context_menu_rewrite_selection().set_shortcut_context(ShortcutContext::WidgetShortcut);
+    ui.get_mut_ptr_context_menu_apply_expression().set_shortcut_context(ShortcutContext::WidgetShortcut);
+    ui.get_mut_ptr_context_menu_rename_key_cascade().set_shortcut_context(ShortcutContext::WidgetShortcut);
+    ui.get_mut_ptr_context_menu_find_references().set_shortcut_context(ShortcutContext::WidgetShortcut);
+    ui.get_mut_ptr_context_menu_undo_key_rename().set_shortcut_context(ShortcutContext::WidgetShortcut);
+    ui.get_mut_ptr_context_menu_redo_key_rename().set_shortcut_context(ShortcutContext::WidgetShortcut);
     ui.get_mut_ptr_context_menu_invert_selection().set_shortcut_context(ShortcutContext::WidgetShortcut);
     ui.get_mut_ptr_context_menu_reset_selection().set_shortcut_context(ShortcutContext::WidgetShortcut);
     ui.get_mut_ptr_context_menu_search().set_shortcut_context(ShortcutContext::WidgetShortcut);
@@ -78,6 +88,11 @@ pub unsafe fn set_shortcuts(ui: &mut PackedFileTableView) {
     ui.get_mut_ptr_table_view_primary().add_action(ui.get_mut_ptr_context_menu_copy_as_lua_table());
     ui.get_mut_ptr_table_view_primary().add_action(ui.get_mut_ptr_context_menu_paste());
     ui.get_mut_ptr_table_view_primary().add_action(ui.get_mut_ptr_context_menu_rewrite_selection());
+    ui.get_mut_ptr_table_view_primary().add_action(ui.get_mut_ptr_context_menu_apply_expression());
+    ui.get_mut_ptr_table_view_primary().add_action(ui.get_mut_ptr_context_menu_rename_key_cascade());
+    ui.get_mut_ptr_table_view_primary().add_action(ui.get_mut_ptr_context_menu_find_references());
+    ui.get_mut_ptr_table_view_primary().add_action(ui.get_mut_ptr_context_menu_undo_key_rename());
+    ui.get_mut_ptr_table_view_primary().add_action(ui.get_mut_ptr_context_menu_redo_key_rename());
     ui.get_mut_ptr_table_view_primary().add_action(ui.get_mut_ptr_context_menu_invert_selection());
     ui.get_mut_ptr_table_view_primary().add_action(ui.get_mut_ptr_context_menu_reset_selection());
     ui.get_mut_ptr_table_view_primary().add_action(ui.get_mut_ptr_context_menu_resize_columns());
@@ -37,6 +37,7 @@ use std::path::PathBuf;
 use crate::app_ui::AppUI;
 use crate::ffi::*;
 use crate::global_search_ui::GlobalSearchUI;
+use crate::locale::tre;
 use crate::packfile_contents_ui::PackFileContentsUI;
 use crate::packedfile_views::table::PackedFileTableViewRaw;
 use crate::packedfile_views::utils::*;
@@ -72,6 +73,11 @@ pub struct PackedFileTableViewSlots {
     pub invert_selection: Slot<'static>,
     pub reset_selection: Slot<'static>,
     pub rewrite_selection: Slot<'static>,
+    pub apply_expression: Slot<'static>,
+    pub rename_key_cascade: SlotOfBool<'static>,
+    pub find_references: SlotOfBool<'static>,
+    pub undo_key_rename: SlotOfBool<'static>,
+    pub redo_key_rename: SlotOfBool<'static>,
     pub save: Slot<'static>,
     pub undo: Slot<'static>,
     pub redo: Slot<'static>,
@@ -204,7 +210,9 @@ impl PackedFileTableViewSlots {
                 // If we have the dependency stuff enabled, check if it's a valid reference.
                 if SETTINGS.read().unwrap().settings_bool["use_dependency_checker"] {
                     let column = item.column();
-                    if packed_file_view.get_ref_table_definition().fields[column as usize].is_reference.is_some() {
+                    let definition = packed_file_view.get_ref_table_definition();
+                    let field = &definition.fields[column as usize];
+                    if field.is_reference.is_some() || field.enum_values.is_some() {
                         check_references(column, item, &packed_file_view.dependency_data.read().unwrap());
                     }
                 }
@@ -319,6 +327,40 @@ impl PackedFileTableViewSlots {
             packed_file_view.rewrite_selection();
         }));
 
+        // When we want to set a column to the result of an expression.
+        let apply_expression = Slot::new(clone!(
+            mut packed_file_view => move || {
+            packed_file_view.apply_expression();
+        }));
+
+        // When we want to rename a key in all the PackedFiles referencing it.
+        let rename_key_cascade = SlotOfBool::new(clone!(
+            mut pack_file_contents_ui,
+            packed_file_view => move |_| {
+            packed_file_view.rename_key_cascade(&mut app_ui, &mut pack_file_contents_ui, global_search_ui);
+        }));
+
+        // When we want to find all the rows referencing a key.
+        let find_references = SlotOfBool::new(clone!(
+            mut pack_file_contents_ui,
+            packed_file_view => move |_| {
+            packed_file_view.find_references(&mut app_ui, &mut pack_file_contents_ui, global_search_ui);
+        }));
+
+        // When we want to undo the last key rename.
+        let undo_key_rename = SlotOfBool::new(clone!(
+            mut pack_file_contents_ui,
+            packed_file_view => move |_| {
+            packed_file_view.undo_redo_key_rename(true, &mut app_ui, &mut pack_file_contents_ui, global_search_ui);
+        }));
+
+        // When we want to redo the last undone key rename.
+        let redo_key_rename = SlotOfBool::new(clone!(
+            mut pack_file_contents_ui,
+            packed_file_view => move |_| {
+            packed_file_view.undo_redo_key_rename(false, &mut app_ui, &mut pack_file_contents_ui, global_search_ui);
+        }));
+
         // When we want to save the contents of the UI to the backend...
         //
         // NOTE: in-edition saves to backend are only triggered when the GlobalSearch has search data, to keep it updated.
@@ -377,7 +419,7 @@ impl PackedFileTableViewSlots {
                     CENTRAL_COMMAND.send_message_qt(Command::ImportTSV((packed_file_view.packed_file_path.read().unwrap().to_vec(), path)));
                     let response = CENTRAL_COMMAND.recv_message_qt_try();
                     match response {
-                        Response::TableType(data) => {
+                        Response::TableTypeVecString((data, extra_columns)) => {
                             let old_data = packed_file_view.get_copy_of_table();
 
                             packed_file_view.undo_lock.store(true, Ordering::SeqCst);
@@ -407,6 +449,10 @@ impl PackedFileTableViewSlots {
                             packed_file_view.history_redo.write().unwrap().clear();
                             update_undo_model(packed_file_view.table_model, packed_file_view.undo_model);
                             set_modified(true, &packed_file_view.packed_file_path.read().unwrap(), &mut app_ui, &mut pack_file_contents_ui);
+
+                            if !extra_columns.is_empty() {
+                                show_dialog(packed_file_view.table_view_primary, tre("import_tsv_extra_columns", &[&extra_columns.join(", ")]), true);
+                            }
                         },
                         Response::Error(error) => return show_dialog(packed_file_view.table_view_primary, error, false),
                         _ => panic!("{}{:?}", THREADS_COMMUNICATION_ERROR, response),
@@ -691,6 +737,11 @@ impl PackedFileTableViewSlots {
             invert_selection,
             reset_selection,
             rewrite_selection,
+            apply_expression,
+            rename_key_cascade,
+            find_references,
+            undo_key_rename,
+            redo_key_rename,
             save,
             undo,
             redo,
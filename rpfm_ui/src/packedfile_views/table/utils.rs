@@ -258,7 +258,7 @@ unsafe fn get_default_item_from_field(field: &Field) -> CppBox<QStandardItem> {
                 QStandardItem::from_q_string(&QString::new())
             }
         },
-        FieldType::Sequence(_) => QStandardItem::from_q_string(&qtr("packedfile_noneditable_sequence")),
+        FieldType::Sequence(_) => QStandardItem::from_q_string(&QString::from_std_str(&DecodedData::default(&field.field_type).data_to_string())),
     }
 }
 
@@ -368,7 +368,7 @@ pub unsafe fn load_data(
                 let mut item = get_item_from_decoded_data(field);
 
                 // If we have the dependency stuff enabled, check if it's a valid reference.
-                if SETTINGS.read().unwrap().settings_bool["use_dependency_checker"] && definition.fields[index].is_reference.is_some() {
+                if SETTINGS.read().unwrap().settings_bool["use_dependency_checker"] && (definition.fields[index].is_reference.is_some() || definition.fields[index].enum_values.is_some()) {
                     check_references(index as i32, item.as_mut_ptr(), &dependency_data.read().unwrap());
                 }
 
@@ -459,9 +459,14 @@ unsafe fn get_item_from_decoded_data(data: &DecodedData) -> CppBox<QStandardItem
             item.set_data_2a(&QVariant::from_q_string(&QString::from_std_str(data)), ITEM_SOURCE_VALUE);
             item
         },
+
+        // Sequences are shown and edited as text.
         DecodedData::Sequence(_) => {
-            let mut item = QStandardItem::from_q_string(&qtr("packedfile_noneditable_sequence"));
-            item.set_editable(false);
+            let data = data.data_to_string();
+            let mut item = QStandardItem::from_q_string(&QString::from_std_str(&data));
+            item.set_tool_tip(&QString::from_std_str(&tre("original_data", &[&data])));
+            item.set_data_2a(&QVariant::from_bool(true), ITEM_HAS_SOURCE_VALUE);
+            item.set_data_2a(&QVariant::from_q_string(&QString::from_std_str(&data)), ITEM_SOURCE_VALUE);
             item
         }
     }
@@ -551,6 +556,8 @@ unsafe fn set_column_tooltip(schema: &Option<Schema>, field: &Field, table_name:
 
     // If we passed it a table name, build the tooltip based on it. The logic is simple:
     // - If we have a description, we add it to the tooltip.
+    // - If we have a default value or the column contains paths of files, we add it to the tooltip.
+    // - If the column only takes a fixed set of values, we add them to the tooltip.
     // - If the column references another column, we add it to the tooltip.
     // - If the column is referenced by another column, we add it to the tooltip.
     if !table_name.is_empty() {
@@ -559,6 +566,29 @@ unsafe fn set_column_tooltip(schema: &Option<Schema>, field: &Field, table_name:
             tooltip_text.push_str(&format!("<p>{}</p>", field.description));
         }
 
+        if let Some(ref default_value) = field.default_value {
+            tooltip_text.push_str(&format!("<p>{} <i>\"{}\"</i></p>", tr("column_tooltip_default_value"), default_value));
+        }
+
+        if field.is_filename {
+            match field.filename_relative_path {
+                Some(ref filename_relative_path) => tooltip_text.push_str(&format!("<p>{}</p><p><i>\"{}\"</i></p>", tr("column_tooltip_filename"), filename_relative_path)),
+                None => tooltip_text.push_str(&format!("<p>{}</p>", tr("column_tooltip_filename_unknown"))),
+            }
+        }
+
+        if let Some(ref enum_values) = field.enum_values {
+            tooltip_text.push_str(&format!("<p>{}</p><p>", tr("column_tooltip_enum_values")));
+            for (value, label) in enum_values {
+                if label.is_empty() {
+                    tooltip_text.push_str(&format!("<i>\"{}\"</i><br>", value));
+                } else {
+                    tooltip_text.push_str(&format!("<i>\"{}\"</i>: {}<br>", value, label));
+                }
+            }
+            tooltip_text.push_str("</p>");
+        }
+
         if let Some(ref reference) = field.is_reference {
             tooltip_text.push_str(&format!("<p>{}</p><p><i>\"{}/{}\"</i></p>", tr("column_tooltip_1"), reference.0, reference.1));
         }
@@ -737,7 +767,7 @@ pub unsafe fn check_table_for_error(
 ) {
     let _blocker = QSignalBlocker::from_q_object(model.static_upcast_mut::<QObject>());
     for (column, field) in definition.fields.iter().enumerate() {
-        if field.is_reference.is_some() {
+        if field.is_reference.is_some() || field.enum_values.is_some() {
             for row in 0..model.row_count_0a() {
                 let item = model.item_2a(row, column as i32);
                 check_references(column as i32, item, dependency_data);
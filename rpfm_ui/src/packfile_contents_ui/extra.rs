@@ -240,7 +240,7 @@ impl PackFileContentsUI {
                     &qtr("mass_import_select"),
                 );
 
-                file_dialog.set_name_filter(&QString::from_std_str("TSV Files (*.tsv)"));
+                file_dialog.set_name_filter(&QString::from_std_str("Table Files (*.tsv *.csv *.json *.ron)"));
                 file_dialog.set_file_mode(FileMode::ExistingFiles);
 
                 if file_dialog.exec() == 1 {
@@ -12,7 +12,7 @@
 Module with all the code related to the main `PackFileContentsSlots`.
 !*/
 
-use qt_widgets::{QFileDialog, q_file_dialog::FileMode};
+use qt_widgets::{QFileDialog, q_file_dialog::{FileMode, Option as QFileDialogOption}};
 use qt_widgets::SlotOfQPoint;
 use qt_widgets::QTreeView;
 
@@ -20,6 +20,7 @@ use qt_gui::QCursor;
 use qt_gui::SlotOfQStandardItem;
 
 use qt_core::{SlotOfBool, Slot, SlotOfQString};
+use qt_core::QFlags;
 use qt_core::QSignalBlocker;
 use qt_core::QObject;
 
@@ -33,8 +34,10 @@ use std::rc::Rc;
 use rpfm_error::ErrorKind;
 use rpfm_lib::common::get_files_from_subdir;
 use rpfm_lib::packedfile::PackedFileType;
+use rpfm_lib::packedfile::table::TableFormat;
 use rpfm_lib::packedfile::text::TextType;
 use rpfm_lib::packfile::PathType;
+use rpfm_lib::packfile::table_update::TableUpdateReport;
 use rpfm_lib::SETTINGS;
 
 use crate::app_ui::AppUI;
@@ -260,7 +263,7 @@ impl PackFileContentsSlots {
                         pack_file_contents_ui.context_menu_open_containing_folder.set_enabled(true);
                         pack_file_contents_ui.context_menu_open_with_external_program.set_enabled(false);
                         pack_file_contents_ui.context_menu_open_notes.set_enabled(true);
-                        pack_file_contents_ui.context_menu_update_table.set_enabled(false);
+                        pack_file_contents_ui.context_menu_update_table.set_enabled(true);
                     },
 
                     // PackFile and one or more files selected.
@@ -1021,8 +1024,12 @@ impl PackFileContentsSlots {
                     CENTRAL_COMMAND.send_message_qt(Command::UpdateTable(path_type.clone()));
                     let response = CENTRAL_COMMAND.recv_message_qt();
                     match response {
-                        Response::I32I32((old_version, new_version)) => {
-                            let message = tre("update_table_success", &[&old_version.to_string(), &new_version.to_string()]);
+                        Response::TableUpdateReport(report) => {
+                            let message = tre("update_table_success", &[&report.old_version.to_string(), &report.new_version.to_string()]);
+                            let message = if report.has_warnings() {
+                                let report = TableUpdateReport::to_markdown(&[report]).replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+                                format!("<p>{}</p><pre>{}</pre>", message, report)
+                            } else { message };
                             show_dialog(app_ui.main_window, message, true);
 
                             pack_file_contents_ui.packfile_contents_tree_view.update_treeview(true, TreeViewOperation::Modify(vec![item_type.clone(); 1]));
@@ -1036,6 +1043,37 @@ impl PackFileContentsSlots {
                         _ => panic!("{}{:?}", THREADS_COMMUNICATION_ERROR, response),
                     }
                 }
+
+                // If the PackFile is selected, we update all the outdated tables in it.
+                TreePathType::PackFile => {
+                    app_ui.purge_them_all(global_search_ui, pack_file_contents_ui, &slot_holder);
+
+                    CENTRAL_COMMAND.send_message_qt(Command::UpdateAllTables);
+                    let response = CENTRAL_COMMAND.recv_message_qt();
+                    match response {
+                        Response::VecTableUpdateReport(reports) => {
+                            if reports.is_empty() {
+                                return show_dialog(app_ui.main_window, tr("update_all_tables_up_to_date"), true);
+                            }
+
+                            let reports_with_warnings = reports.iter().filter(|x| x.has_warnings()).count();
+                            let report = TableUpdateReport::to_markdown(&reports).replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+                            let message = format!("<p>{}</p><pre>{}</pre>", tre("update_all_tables_success", &[&reports.len().to_string(), &reports_with_warnings.to_string()]), report);
+                            show_dialog(app_ui.main_window, message, true);
+
+                            let item_types = reports.iter().map(|x| TreePathType::File(x.path.to_vec())).collect::<Vec<TreePathType>>();
+                            pack_file_contents_ui.packfile_contents_tree_view.update_treeview(true, TreeViewOperation::Modify(item_types.to_vec()));
+                            pack_file_contents_ui.packfile_contents_tree_view.update_treeview(true, TreeViewOperation::MarkAlwaysModified(item_types));
+                            UI_STATE.set_is_modified(true, &mut app_ui, &mut pack_file_contents_ui);
+
+                            let path_types = reports.iter().map(|x| PathType::File(x.path.to_vec())).collect::<Vec<PathType>>();
+                            global_search_ui.search_on_path(&mut pack_file_contents_ui, path_types);
+                        }
+
+                        Response::Error(error) => show_dialog(app_ui.main_window, error, false),
+                        _ => panic!("{}{:?}", THREADS_COMMUNICATION_ERROR, response),
+                    }
+                }
                 _ => unimplemented!()
             }
         }));
@@ -1065,7 +1103,7 @@ impl PackFileContentsSlots {
                         match response {
 
                             // If it's success....
-                            Response::VecVecStringVecVecString(paths) => {
+                            Response::VecVecStringVecVecStringVecStringVecString(paths) => {
 
                                 // Get the list of paths to add, removing those we "replaced".
                                 let mut paths_to_add = paths.1.to_vec();
@@ -1079,6 +1117,12 @@ impl PackFileContentsSlots {
 
                                 // Update the global search stuff, if needed.
                                 global_search_ui.search_on_path(&mut pack_file_contents_ui, paths_to_add.iter().map(|x| PathType::File(x.to_vec())).collect::<Vec<PathType>>());
+
+                                // If any of the files had columns we don't know about, report them.
+                                if !paths.2.is_empty() {
+                                    let extra_columns = paths.2.iter().map(|(path, columns)| format!("<li>{}: {}</li>", path, columns.join(", "))).collect::<String>();
+                                    show_dialog(app_ui.main_window, tre("mass_import_tsv_extra_columns", &[&format!("<ul>{}</ul>", extra_columns)]), true);
+                                }
                             }
 
                             Response::Error(error) => show_dialog(app_ui.main_window, error, false),
@@ -1095,20 +1139,30 @@ impl PackFileContentsSlots {
         // What happens when we trigger the "Mass-Export TSV" Action.
         let contextual_menu_mass_export_tsv = SlotOfBool::new(move |_| {
 
-                // Get a "Folder-only" FileDialog.
-                let export_path = QFileDialog::get_existing_directory_2a(
+                // Get a "Folder-only" FileDialog, with a filter per format to choose the format of the exported files.
+                // Native folder dialogs don't show filters, so we have to use Qt's own dialog.
+                let mut file_dialog = QFileDialog::from_q_widget_q_string(
                     app_ui.main_window,
                     &qtr("context_menu_mass_export_tsv_folder")
                 );
 
+                file_dialog.set_file_mode(FileMode::Directory);
+                file_dialog.set_options(QFlags::from(QFileDialogOption::DontUseNativeDialog));
+                file_dialog.set_name_filter(&QString::from_std_str("TSV Files (*.tsv);;CSV Files (*.csv);;JSON Files (*.json);;RON Files (*.ron)"));
+
                 // If we got an export path and it's not empty, try to export all selected files there.
-                if !export_path.is_empty() {
-                    let export_path = PathBuf::from(export_path.to_std_string());
+                if file_dialog.exec() == 1 {
+                    let export_path = PathBuf::from(file_dialog.selected_files().at(0).to_std_string());
+                    let name_filter = file_dialog.selected_name_filter().to_std_string();
+                    let format = name_filter.rsplit("*.").next()
+                        .and_then(|extension| TableFormat::from_extension(extension.trim_end_matches(')')).ok())
+                        .unwrap_or(TableFormat::TSV);
+
                     if export_path.is_dir() {
                         app_ui.main_window.set_enabled(false);
                         let selected_items = <MutPtr<QTreeView> as PackTree>::get_item_types_from_main_treeview_selection(&pack_file_contents_ui);
                         let selected_items = selected_items.iter().map(From::from).collect::<Vec<PathType>>();
-                        CENTRAL_COMMAND.send_message_qt(Command::MassExportTSV(selected_items, export_path));
+                        CENTRAL_COMMAND.send_message_qt(Command::MassExportTSV(selected_items, export_path, format));
                         let response = CENTRAL_COMMAND.recv_message_qt();
                         match response {
                             Response::String(response) => show_dialog(app_ui.main_window, response, true),
@@ -36,6 +36,7 @@ use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 use rpfm_lib::SUPPORTED_GAMES;
+use rpfm_lib::schema::source::SCHEMA_SOURCE_TYPES;
 use rpfm_lib::settings::Settings;
 
 use crate::AppUI;
@@ -103,6 +104,9 @@ pub struct SettingsUI {
     pub extra_packfile_use_dependency_checker_label: MutPtr<QLabel>,
     pub extra_packfile_use_lazy_loading_label: MutPtr<QLabel>,
     pub extra_disable_uuid_regeneration_on_db_tables_label: MutPtr<QLabel>,
+    pub extra_generate_loc_entries_on_db_edit_label: MutPtr<QLabel>,
+    pub extra_network_schema_source_type_label: MutPtr<QLabel>,
+    pub extra_network_schema_source_location_label: MutPtr<QLabel>,
 
     pub extra_global_default_game_combobox: MutPtr<QComboBox>,
     pub extra_network_check_updates_on_start_checkbox: MutPtr<QCheckBox>,
@@ -112,6 +116,9 @@ pub struct SettingsUI {
     pub extra_packfile_use_dependency_checker_checkbox: MutPtr<QCheckBox>,
     pub extra_packfile_use_lazy_loading_checkbox: MutPtr<QCheckBox>,
     pub extra_disable_uuid_regeneration_on_db_tables_checkbox: MutPtr<QCheckBox>,
+    pub extra_generate_loc_entries_on_db_edit_checkbox: MutPtr<QCheckBox>,
+    pub extra_network_schema_source_type_combobox: MutPtr<QComboBox>,
+    pub extra_network_schema_source_location_line_edit: MutPtr<QLineEdit>,
 
     //-------------------------------------------------------------------------------//
     // `Debug` section of the `Settings` dialog.
@@ -300,6 +307,9 @@ impl SettingsUI {
         let mut extra_packfile_use_dependency_checker_label = QLabel::from_q_string(&qtr("settings_use_dependency_checker"));
         let mut extra_packfile_use_lazy_loading_label = QLabel::from_q_string(&qtr("settings_use_lazy_loading"));
         let mut extra_disable_uuid_regeneration_on_db_tables_label = QLabel::from_q_string(&qtr("settings_disable_uuid_regeneration_tables"));
+        let mut extra_generate_loc_entries_on_db_edit_label = QLabel::from_q_string(&qtr("settings_generate_loc_entries_on_db_edit"));
+        let mut extra_network_schema_source_type_label = QLabel::from_q_string(&qtr("settings_schema_source_type"));
+        let mut extra_network_schema_source_location_label = QLabel::from_q_string(&qtr("settings_schema_source_location"));
 
         let mut extra_network_check_updates_on_start_checkbox = QCheckBox::new();
         let mut extra_network_check_schema_updates_on_start_checkbox = QCheckBox::new();
@@ -308,6 +318,12 @@ impl SettingsUI {
         let mut extra_packfile_use_dependency_checker_checkbox = QCheckBox::new();
         let mut extra_packfile_use_lazy_loading_checkbox = QCheckBox::new();
         let mut extra_disable_uuid_regeneration_on_db_tables_checkbox = QCheckBox::new();
+        let mut extra_generate_loc_entries_on_db_edit_checkbox = QCheckBox::new();
+
+        // Create the "Schema Source" ComboBox and LineEdit. The items of the ComboBox are the source types of the lib.
+        let mut extra_network_schema_source_type_combobox = QComboBox::new_0a();
+        let mut extra_network_schema_source_location_line_edit = QLineEdit::new();
+        for source_type in &SCHEMA_SOURCE_TYPES { extra_network_schema_source_type_combobox.add_item_q_string(&QString::from_std_str(source_type)); }
 
         extra_grid.add_widget_5a(&mut extra_global_default_game_label, 0, 0, 1, 1);
         extra_grid.add_widget_5a(&mut extra_global_default_game_combobox, 0, 1, 1, 1);
@@ -333,6 +349,15 @@ impl SettingsUI {
         extra_grid.add_widget_5a(&mut extra_disable_uuid_regeneration_on_db_tables_label, 7, 0, 1, 1);
         extra_grid.add_widget_5a(&mut extra_disable_uuid_regeneration_on_db_tables_checkbox, 7, 1, 1, 1);
 
+        extra_grid.add_widget_5a(&mut extra_generate_loc_entries_on_db_edit_label, 8, 0, 1, 1);
+        extra_grid.add_widget_5a(&mut extra_generate_loc_entries_on_db_edit_checkbox, 8, 1, 1, 1);
+
+        extra_grid.add_widget_5a(&mut extra_network_schema_source_type_label, 9, 0, 1, 1);
+        extra_grid.add_widget_5a(&mut extra_network_schema_source_type_combobox, 9, 1, 1, 1);
+
+        extra_grid.add_widget_5a(&mut extra_network_schema_source_location_label, 10, 0, 1, 1);
+        extra_grid.add_widget_5a(&mut extra_network_schema_source_location_line_edit, 10, 1, 1, 1);
+
         main_grid.add_widget_5a(extra_frame, 1, 1, 1, 1);
 
         //-----------------------------------------------//
@@ -425,6 +450,9 @@ impl SettingsUI {
             extra_packfile_use_dependency_checker_label: extra_packfile_use_dependency_checker_label.into_ptr(),
             extra_packfile_use_lazy_loading_label: extra_packfile_use_lazy_loading_label.into_ptr(),
             extra_disable_uuid_regeneration_on_db_tables_label: extra_disable_uuid_regeneration_on_db_tables_label.into_ptr(),
+            extra_generate_loc_entries_on_db_edit_label: extra_generate_loc_entries_on_db_edit_label.into_ptr(),
+            extra_network_schema_source_type_label: extra_network_schema_source_type_label.into_ptr(),
+            extra_network_schema_source_location_label: extra_network_schema_source_location_label.into_ptr(),
 
             extra_global_default_game_combobox: extra_global_default_game_combobox.into_ptr(),
             extra_network_check_updates_on_start_checkbox: extra_network_check_updates_on_start_checkbox.into_ptr(),
@@ -434,6 +462,9 @@ impl SettingsUI {
             extra_packfile_use_dependency_checker_checkbox: extra_packfile_use_dependency_checker_checkbox.into_ptr(),
             extra_packfile_use_lazy_loading_checkbox: extra_packfile_use_lazy_loading_checkbox.into_ptr(),
             extra_disable_uuid_regeneration_on_db_tables_checkbox: extra_disable_uuid_regeneration_on_db_tables_checkbox.into_ptr(),
+            extra_generate_loc_entries_on_db_edit_checkbox: extra_generate_loc_entries_on_db_edit_checkbox.into_ptr(),
+            extra_network_schema_source_type_combobox: extra_network_schema_source_type_combobox.into_ptr(),
+            extra_network_schema_source_location_line_edit: extra_network_schema_source_location_line_edit.into_ptr(),
 
             //-------------------------------------------------------------------------------//
             // `Debug` section of the `Settings` dialog.
@@ -498,6 +529,13 @@ impl SettingsUI {
         self.extra_packfile_use_dependency_checker_checkbox.set_checked(settings.settings_bool["use_dependency_checker"]);
         self.extra_packfile_use_lazy_loading_checkbox.set_checked(settings.settings_bool["use_lazy_loading"]);
         self.extra_disable_uuid_regeneration_on_db_tables_checkbox.set_checked(settings.settings_bool["disable_uuid_regeneration_on_db_tables"]);
+        self.extra_generate_loc_entries_on_db_edit_checkbox.set_checked(settings.settings_bool["generate_loc_entries_on_db_edit"]);
+
+        // Load the Schema Source.
+        if let Some(index) = SCHEMA_SOURCE_TYPES.iter().position(|x| *x == settings.settings_string["schema_source_type"]) {
+            self.extra_network_schema_source_type_combobox.set_current_index(index as i32);
+        }
+        self.extra_network_schema_source_location_line_edit.set_text(&QString::from_std_str(&settings.settings_string["schema_source_location"]));
 
         // Load the Debug Stuff.
         self.debug_check_for_missing_table_definitions_checkbox.set_checked(settings.settings_bool["check_for_missing_table_definitions"]);
@@ -555,6 +593,9 @@ impl SettingsUI {
         settings.settings_bool.insert("use_dependency_checker".to_owned(), self.extra_packfile_use_dependency_checker_checkbox.is_checked());
         settings.settings_bool.insert("use_lazy_loading".to_owned(), self.extra_packfile_use_lazy_loading_checkbox.is_checked());
         settings.settings_bool.insert("disable_uuid_regeneration_on_db_tables".to_owned(), self.extra_disable_uuid_regeneration_on_db_tables_checkbox.is_checked());
+        settings.settings_bool.insert("generate_loc_entries_on_db_edit".to_owned(), self.extra_generate_loc_entries_on_db_edit_checkbox.is_checked());
+        settings.settings_string.insert("schema_source_type".to_owned(), self.extra_network_schema_source_type_combobox.current_text().to_std_string());
+        settings.settings_string.insert("schema_source_location".to_owned(), self.extra_network_schema_source_location_line_edit.text().to_std_string());
 
         // Get the Debug Settings.
         settings.settings_bool.insert("check_for_missing_table_definitions".to_owned(), self.debug_check_for_missing_table_definitions_checkbox.is_checked());
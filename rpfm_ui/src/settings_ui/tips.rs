@@ -54,6 +54,8 @@ pub unsafe fn set_tips(settings_ui: &mut SettingsUI) {
     let extra_packfile_use_dependency_checker_tip = qtr("tt_extra_packfile_use_dependency_checker_tip");
     let extra_packfile_use_lazy_loading_tip = qtr("tt_extra_packfile_use_lazy_loading_tip");
     let extra_disable_uuid_regeneration_on_db_tables_label_tip = qtr("tt_extra_disable_uuid_regeneration_on_db_tables_label_tip");
+    let extra_generate_loc_entries_on_db_edit_tip = qtr("tt_extra_generate_loc_entries_on_db_edit_tip");
+    let extra_network_schema_source_tip = qtr("tt_extra_network_schema_source_tip");
 
     settings_ui.extra_network_check_updates_on_start_label.set_tool_tip(&extra_network_check_updates_on_start_tip);
     settings_ui.extra_network_check_updates_on_start_checkbox.set_tool_tip(&extra_network_check_updates_on_start_tip);
@@ -69,6 +71,12 @@ pub unsafe fn set_tips(settings_ui: &mut SettingsUI) {
     settings_ui.extra_packfile_use_lazy_loading_checkbox.set_tool_tip(&extra_packfile_use_lazy_loading_tip);
     settings_ui.extra_disable_uuid_regeneration_on_db_tables_label.set_tool_tip(&extra_disable_uuid_regeneration_on_db_tables_label_tip);
     settings_ui.extra_disable_uuid_regeneration_on_db_tables_checkbox.set_tool_tip(&extra_disable_uuid_regeneration_on_db_tables_label_tip);
+    settings_ui.extra_generate_loc_entries_on_db_edit_label.set_tool_tip(&extra_generate_loc_entries_on_db_edit_tip);
+    settings_ui.extra_generate_loc_entries_on_db_edit_checkbox.set_tool_tip(&extra_generate_loc_entries_on_db_edit_tip);
+    settings_ui.extra_network_schema_source_type_label.set_tool_tip(&extra_network_schema_source_tip);
+    settings_ui.extra_network_schema_source_type_combobox.set_tool_tip(&extra_network_schema_source_tip);
+    settings_ui.extra_network_schema_source_location_label.set_tool_tip(&extra_network_schema_source_tip);
+    settings_ui.extra_network_schema_source_location_line_edit.set_tool_tip(&extra_network_schema_source_tip);
 
     //-----------------------------------------------//
     // `Debug` tips.
@@ -80,7 +80,7 @@ const SHORTCUTS_MENU_BAR_ABOUT: [(&str, &str); 6] = [
 ];
 
 /// List of shortcuts for the PackFile Contents Contextual Menu.
-const SHORTCUTS_PACKFILE_CONTENTS_TREE_VIEW: [(&str, &str); 23] = [
+const SHORTCUTS_PACKFILE_CONTENTS_TREE_VIEW: [(&str, &str); 24] = [
     ("add_file", "Ctrl+A"),
     ("add_folder", "Ctrl+Shift+A"),
     ("add_from_packfile", "Ctrl+Alt+A"),
@@ -107,7 +107,7 @@ const SHORTCUTS_PACKFILE_CONTENTS_TREE_VIEW: [(&str, &str); 23] = [
 ];
 
 /// List of shortcuts for the Table PackedFile's Contextual Menu.
-const SHORTCUTS_PACKED_FILE_TABLE: [(&str, &str); 19] = [
+const SHORTCUTS_PACKED_FILE_TABLE: [(&str, &str); 23] = [
     ("add_row", "Ctrl+Shift+A"),
     ("insert_row", "Ctrl+I"),
     ("delete_row", "Ctrl+Del"),
@@ -117,6 +117,11 @@ const SHORTCUTS_PACKED_FILE_TABLE: [(&str, &str); 19] = [
     ("copy_as_lua_table", "Ctrl+Shift+C"),
     ("paste", "Ctrl+V"),
     ("rewrite_selection", "Ctrl+Y"),
+    ("apply_expression", ""),
+    ("rename_key_cascade", ""),
+    ("find_references", ""),
+    ("undo_key_rename", ""),
+    ("redo_key_rename", ""),
     ("selection_invert", "Ctrl+-"),
     ("revert_selection", ""),
     ("import_tsv", ""),